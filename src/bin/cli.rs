@@ -2,7 +2,7 @@
 //!
 //! Provides feature parity with the GUI for automation and headless operation.
 
-use clap::{Parser, Subcommand, ValueEnum};
+use clap::{Args, CommandFactory, Parser, Subcommand, ValueEnum};
 use std::io::{self, Read, Write};
 use std::path::PathBuf;
 use std::time::Duration;
@@ -44,6 +44,34 @@ impl LineEnding {
     }
 }
 
+/// One-shot mode: connect, send, expect, exit
+#[derive(Args, Debug, Default)]
+struct OneShotArgs {
+    /// Serial port for one-shot mode
+    #[arg(long, conflicts_with = "tcp")]
+    serial: Option<String>,
+    
+    /// TCP target (host:port) for one-shot mode
+    #[arg(long)]
+    tcp: Option<String>,
+    
+    /// Baud rate for --serial
+    #[arg(long, default_value = "115200")]
+    baud: u32,
+    
+    /// Data to send (supports \r, \n, \xHH escapes)
+    #[arg(long)]
+    send: Option<String>,
+    
+    /// Regex to wait for in the reply
+    #[arg(long)]
+    expect: Option<String>,
+    
+    /// Seconds to wait for the reply
+    #[arg(long, default_value = "5")]
+    timeout: u64,
+}
+
 /// Termicon CLI
 #[derive(Parser, Debug)]
 #[command(
@@ -66,8 +94,11 @@ struct Cli {
     #[arg(short, long)]
     quiet: bool,
     
+    #[command(flatten)]
+    oneshot: OneShotArgs,
+    
     #[command(subcommand)]
-    command: Commands,
+    command: Option<Commands>,
 }
 
 #[derive(Subcommand, Debug)]
@@ -93,7 +124,7 @@ enum Commands {
         #[arg(long, default_value = "8")]
         data_bits: u8,
         
-        /// Parity (none, odd, even)
+        /// Parity (none, odd, even, mark, space)
         #[arg(long, default_value = "none")]
         parity: String,
         
@@ -329,7 +360,11 @@ async fn main() -> anyhow::Result<()> {
     
     let cli = Cli::parse();
     
-    match &cli.command {
+    let Some(command) = &cli.command else {
+        return run_oneshot(&cli).await;
+    };
+    
+    match command {
         Commands::ListPorts { detailed } => {
             list_ports(&cli, *detailed)?;
         }
@@ -365,6 +400,82 @@ async fn main() -> anyhow::Result<()> {
     Ok(())
 }
 
+async fn run_oneshot(cli: &Cli) -> anyhow::Result<()> {
+    use termicon_core::cli::{unescape, OneShot, PipeMode};
+    use termicon_core::core::transport::{SerialConfig, TcpConfig, Transport};
+    
+    let args = &cli.oneshot;
+    let transport = if let Some(port) = &args.serial {
+        Transport::Serial(SerialConfig::new(port, args.baud))
+    } else if let Some(target) = &args.tcp {
+        let (host, port) = target
+            .rsplit_once(':')
+            .and_then(|(host, port)| Some((host, port.parse().ok()?)))
+            .ok_or_else(|| anyhow::anyhow!("Expected host:port, got {}", target))?;
+        Transport::Tcp(TcpConfig::new(host, port))
+    } else {
+        Cli::command().print_help()?;
+        std::process::exit(i32::from(termicon_core::ExitCodes::INVALID_ARGS));
+    };
+    
+    if args.send.is_none() && args.expect.is_none() && PipeMode::detect().has_stdin() {
+        return run_transparent(cli, transport).await;
+    }
+    
+    let mut oneshot = OneShot::new(transport).timeout(Duration::from_secs(args.timeout));
+    if let Some(send) = &args.send {
+        oneshot = oneshot.send(unescape(send));
+    }
+    if let Some(expect) = &args.expect {
+        oneshot = oneshot.expect(expect.as_str());
+    }
+    
+    let report = oneshot.run().await;
+    if !report.received.is_empty() {
+        output_data(cli, &report.received);
+    }
+    if !report.result.is_success() && !cli.quiet {
+        if let Some(message) = report.result.message() {
+            eprintln!("{}", message);
+        }
+    }
+    
+    std::process::exit(i32::from(report.result.code()));
+}
+
+/// Bridge piped stdin to the transport and its RX to stdout
+async fn run_transparent(cli: &Cli, transport: termicon_core::core::transport::Transport) -> anyhow::Result<()> {
+    use termicon_core::cli::pipe::DEFAULT_DRAIN_TIMEOUT;
+    use termicon_core::cli::{CliResult, PipeProcessor};
+    use termicon_core::core::transport::create_transport;
+    
+    let format = match cli.format {
+        OutputFormat::Text => termicon_core::OutputFormat::Raw,
+        OutputFormat::Json => termicon_core::OutputFormat::Json,
+        OutputFormat::Csv => termicon_core::OutputFormat::Csv,
+        OutputFormat::Hex => termicon_core::OutputFormat::Hex,
+    };
+    
+    let result = match create_transport(transport).await {
+        Ok(mut transport) => match transport.connect().await {
+            Ok(()) => PipeProcessor::transparent(format)
+                .bridge(transport.as_mut(), DEFAULT_DRAIN_TIMEOUT)
+                .await
+                .map_or_else(CliResult::from, |()| CliResult::success()),
+            Err(e) => CliResult::from(e),
+        },
+        Err(e) => CliResult::from(e),
+    };
+    
+    if !result.is_success() && !cli.quiet {
+        if let Some(message) = result.message() {
+            eprintln!("{}", message);
+        }
+    }
+    
+    std::process::exit(i32::from(result.code()));
+}
+
 fn list_ports(cli: &Cli, detailed: bool) -> anyhow::Result<()> {
     let ports = serialport::available_ports()?;
     
@@ -483,7 +594,8 @@ async fn connect_serial(
                         format,
                         verbose: false,
                         quiet,
-                        command: Commands::Info,
+                        oneshot: OneShotArgs::default(),
+                        command: None,
                     };
                     output_data(&cli_ref, &buf[..n]);
                 }
@@ -687,7 +799,9 @@ async fn ble_scan(cli: &Cli, duration: u64, filter: Option<&str>) -> anyhow::Res
                     "name": d.name,
                     "address": d.address,
                     "rssi": d.rssi,
-                    "services": d.services
+                    "services": d.services,
+                    "manufacturer": d.manufacturer_name(),
+                    "tx_power": d.tx_power
                 })
             }).collect();
             println!("{}", serde_json::to_string_pretty(&json)?);
@@ -63,6 +63,18 @@ impl ExitCodes {
     /// Data validation failed
     pub const VALIDATION_FAILED: u8 = 17;
     
+    /// Transfer timed out waiting for the other side
+    pub const TRANSFER_TIMEOUT: u8 = 18;
+    
+    /// Transfer cancelled by either side
+    pub const TRANSFER_CANCELLED: u8 = 19;
+    
+    /// Transfer aborted after repeated CRC/checksum errors
+    pub const TRANSFER_CHECKSUM: u8 = 20;
+    
+    /// SSH host key unknown or changed
+    pub const HOST_KEY_REJECTED: u8 = 21;
+    
     /// Internal error
     pub const INTERNAL_ERROR: u8 = 127;
 }
@@ -154,6 +166,70 @@ impl From<std::io::Error> for CliResult {
     }
 }
 
+impl From<crate::core::transport::TransportError> for CliResult {
+    fn from(err: crate::core::transport::TransportError) -> Self {
+        use crate::core::transport::TransportError;
+        
+        let code = match err {
+            TransportError::ConnectionFailed(_) | TransportError::Disconnected | TransportError::NotConnected => {
+                ExitCodes::CONNECTION_FAILED
+            }
+            TransportError::Timeout(_) => ExitCodes::TIMEOUT,
+            TransportError::PortNotFound(_) => ExitCodes::PORT_NOT_FOUND,
+            TransportError::AuthFailed(_) => ExitCodes::AUTH_FAILED,
+            TransportError::Ssh(_) => ExitCodes::HOST_KEY_REJECTED,
+            TransportError::PermissionDenied(_) => ExitCodes::PERMISSION_DENIED,
+            TransportError::PortInUse(_) => ExitCodes::DEVICE_BUSY,
+            TransportError::ConfigError(_) | TransportError::InvalidConfiguration(_) | TransportError::Unsupported(_) => {
+                ExitCodes::CONFIG_ERROR
+            }
+            TransportError::IoError(ref e) => return Self::from(std::io::Error::new(e.kind(), err.to_string())),
+            TransportError::SendError(_) | TransportError::ReceiveError(_) => ExitCodes::ERROR,
+        };
+        
+        Self::Error(code, err.to_string())
+    }
+}
+
+impl From<crate::core::transfer::TransferState> for CliResult {
+    fn from(state: crate::core::transfer::TransferState) -> Self {
+        use crate::core::transfer::TransferState;
+        
+        match state {
+            TransferState::Complete => Self::success(),
+            TransferState::Cancelled => Self::Error(ExitCodes::TRANSFER_CANCELLED, "Transfer cancelled".to_string()),
+            // The other side never started
+            TransferState::Idle | TransferState::WaitingForStart => {
+                Self::Error(ExitCodes::TRANSFER_TIMEOUT, "Transfer did not start".to_string())
+            }
+            TransferState::InProgress | TransferState::Error => {
+                Self::Error(ExitCodes::TRANSFER_FAILED, "Transfer failed".to_string())
+            }
+        }
+    }
+}
+
+impl From<&crate::core::transfer::TransferError> for CliResult {
+    fn from(err: &crate::core::transfer::TransferError) -> Self {
+        use crate::core::transfer::TransferError;
+
+        let code = match err {
+            TransferError::Cancelled(_) => ExitCodes::TRANSFER_CANCELLED,
+            TransferError::Timeout(_) => ExitCodes::TRANSFER_TIMEOUT,
+            TransferError::Checksum(_) => ExitCodes::TRANSFER_CHECKSUM,
+            TransferError::Io(_) | TransferError::Transport(_) | TransferError::Protocol(_) => ExitCodes::TRANSFER_FAILED,
+        };
+
+        Self::Error(code, err.to_string())
+    }
+}
+
+impl From<crate::core::transfer::TransferError> for CliResult {
+    fn from(err: crate::core::transfer::TransferError) -> Self {
+        Self::from(&err)
+    }
+}
+
 /// Exit code description
 pub fn exit_code_description(code: u8) -> &'static str {
     match code {
@@ -175,6 +251,10 @@ pub fn exit_code_description(code: u8) -> &'static str {
         15 => "Script error",
         16 => "Pattern not found",
         17 => "Validation failed",
+        18 => "Transfer timeout",
+        19 => "Transfer cancelled",
+        20 => "Transfer checksum error",
+        21 => "Host key rejected",
         127 => "Internal error",
         _ => "Unknown error",
     }
@@ -184,7 +264,7 @@ pub fn exit_code_description(code: u8) -> &'static str {
 pub fn print_exit_codes() {
     println!("Exit Codes:");
     println!("  {:>3}  {}", 0, exit_code_description(0));
-    for code in [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 127] {
+    for code in [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 127] {
         println!("  {:>3}  {}", code, exit_code_description(code));
     }
 }
@@ -211,8 +291,34 @@ mod tests {
         let result = CliResult::from(err);
         assert_eq!(result.code(), ExitCodes::FILE_NOT_FOUND);
     }
+    
+    #[test]
+    fn test_from_transport_error() {
+        use crate::core::transport::{SshError, TransportError};
+        
+        assert_eq!(CliResult::from(TransportError::AuthFailed("root".into())).code(), ExitCodes::AUTH_FAILED);
+        let host_key = TransportError::Ssh(SshError::UnknownHost {
+            host: "example.com".into(),
+            fingerprint: "SHA256:abc".into(),
+        });
+        assert_eq!(CliResult::from(host_key).code(), ExitCodes::HOST_KEY_REJECTED);
+    }
+    
+    #[test]
+    fn test_transfer_error_codes() {
+        use crate::core::transfer::{TransferError, TransferState};
+        
+        assert_eq!(CliResult::from(TransferError::Cancelled("sender")).code(), ExitCodes::TRANSFER_CANCELLED);
+        assert_eq!(CliResult::from(TransferError::Timeout("waiting for block")).code(), ExitCodes::TRANSFER_TIMEOUT);
+        let checksum = CliResult::from(&TransferError::Checksum(10));
+        assert_eq!(checksum.code(), ExitCodes::TRANSFER_CHECKSUM);
+        assert_eq!(checksum.message(), Some("Too many corrupted blocks (10 retries)"));
+        let io = std::io::Error::new(std::io::ErrorKind::Other, "disk full");
+        assert_eq!(CliResult::from(TransferError::Io(io)).code(), ExitCodes::TRANSFER_FAILED);
+        
+        assert!(CliResult::from(TransferState::Complete).is_success());
+        assert_eq!(CliResult::from(TransferState::Cancelled).code(), ExitCodes::TRANSFER_CANCELLED);
+        assert_eq!(CliResult::from(TransferState::WaitingForStart).code(), ExitCodes::TRANSFER_TIMEOUT);
+        assert_eq!(exit_code_description(ExitCodes::TRANSFER_CHECKSUM), "Transfer checksum error");
+    }
 }
-
-
-
-
@@ -2,13 +2,16 @@
 //!
 //! Provides command-line interface functionality including:
 //! - Exit codes for automation
-//! - Pipe support for stdin/stdout
+//! - Pipe support for stdin/stdout, including a transparent stdin/transport/stdout bridge
+//! - One-shot connect/send/expect mode
 
 pub mod exit_codes;
+pub mod oneshot;
 pub mod pipe;
 
 pub use exit_codes::{ExitCodes, CliResult, exit_code_description, print_exit_codes};
-pub use pipe::{PipeMode, StdinPipe, StdinLineReader, StdoutPipe, PipeProcessor, OutputFormat, format_output};
+pub use oneshot::{unescape, ExpectMachine, ExpectState, OneShot, OneShotReport};
+pub use pipe::{PipeMode, StdinPipe, StdinRead, StdinLineReader, StdoutPipe, PipeProcessor, OutputFormat, format_output};
 
 
 
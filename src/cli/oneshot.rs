@@ -0,0 +1,240 @@
+//! One-shot CLI mode
+//!
+//! Connect, send a command, wait for an expected reply and exit. Intended for
+//! shell scripts and CI jobs where the exit code is the result.
+
+use std::time::Duration;
+
+use regex::bytes::Regex;
+use tokio::time::Instant;
+
+use super::exit_codes::{CliResult, ExitCodes};
+use crate::core::transport::{create_transport, Transport};
+
+/// Default time to wait for the expected reply
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Expect state
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExpectState {
+    /// Still waiting for the pattern
+    Waiting,
+    /// Pattern matched; holds the matched text
+    Matched(String),
+    /// Deadline passed without a match
+    TimedOut,
+}
+
+/// Matches a pattern against accumulated RX data
+#[derive(Debug)]
+pub struct ExpectMachine {
+    pattern: Regex,
+    buffer: Vec<u8>,
+    state: ExpectState,
+}
+
+impl ExpectMachine {
+    /// Create a machine waiting for `pattern` (a regex)
+    pub fn new(pattern: &str) -> Result<Self, String> {
+        let pattern = Regex::new(pattern).map_err(|e| format!("Invalid expect pattern: {}", e))?;
+        Ok(Self {
+            pattern,
+            buffer: Vec::new(),
+            state: ExpectState::Waiting,
+        })
+    }
+
+    /// Feed received data
+    pub fn feed(&mut self, data: &[u8]) -> &ExpectState {
+        if self.state == ExpectState::Waiting {
+            self.buffer.extend_from_slice(data);
+            if let Some(m) = self.pattern.find(&self.buffer) {
+                self.state = ExpectState::Matched(String::from_utf8_lossy(m.as_bytes()).into_owned());
+            }
+        }
+        &self.state
+    }
+
+    /// Mark the deadline as passed
+    pub fn time_out(&mut self) {
+        if self.state == ExpectState::Waiting {
+            self.state = ExpectState::TimedOut;
+        }
+    }
+
+    /// Current state
+    pub fn state(&self) -> &ExpectState {
+        &self.state
+    }
+}
+
+/// Outcome of a one-shot run
+#[derive(Debug)]
+pub struct OneShotReport {
+    /// Everything received before exiting
+    pub received: Vec<u8>,
+    /// Result to exit with
+    pub result: CliResult,
+}
+
+/// Connect, send and expect
+#[derive(Debug, Clone)]
+pub struct OneShot {
+    transport: Transport,
+    send: Vec<u8>,
+    expect: Option<String>,
+    timeout: Duration,
+}
+
+impl OneShot {
+    /// Create a one-shot run over the given transport
+    pub fn new(transport: Transport) -> Self {
+        Self {
+            transport,
+            send: Vec::new(),
+            expect: None,
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+
+    /// Data to send after connecting
+    #[must_use]
+    pub fn send(mut self, data: impl Into<Vec<u8>>) -> Self {
+        self.send = data.into();
+        self
+    }
+
+    /// Regex to wait for; without one the run collects RX until the timeout
+    #[must_use]
+    pub fn expect(mut self, pattern: impl Into<String>) -> Self {
+        self.expect = Some(pattern.into());
+        self
+    }
+
+    /// How long to wait for the reply
+    #[must_use]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Run to completion
+    pub async fn run(self) -> OneShotReport {
+        let mut received = Vec::new();
+        let result = self.run_inner(&mut received).await;
+        OneShotReport { received, result }
+    }
+
+    async fn run_inner(self, received: &mut Vec<u8>) -> CliResult {
+        let mut machine = match self.expect.as_deref().map(ExpectMachine::new).transpose() {
+            Ok(machine) => machine,
+            Err(e) => return CliResult::error(ExitCodes::INVALID_ARGS, e),
+        };
+
+        let mut transport = match create_transport(self.transport).await {
+            Ok(transport) => transport,
+            Err(e) => return e.into(),
+        };
+        if let Err(e) = transport.connect().await {
+            return e.into();
+        }
+        if !self.send.is_empty() {
+            if let Err(e) = transport.send(&self.send).await {
+                return e.into();
+            }
+        }
+
+        let deadline = Instant::now() + self.timeout;
+        let result = loop {
+            let data = match tokio::time::timeout_at(deadline, transport.receive()).await {
+                Ok(Ok(data)) => data,
+                Ok(Err(e)) => break e.into(),
+                Err(_) => break Self::finish(machine.as_mut()),
+            };
+            received.extend_from_slice(&data);
+
+            if let Some(ref mut machine) = machine {
+                if let ExpectState::Matched(text) = machine.feed(&data) {
+                    break CliResult::success_with_message(text.clone());
+                }
+            }
+            if Instant::now() >= deadline {
+                break Self::finish(machine.as_mut());
+            }
+        };
+
+        let _ = transport.disconnect().await;
+        result
+    }
+
+    fn finish(machine: Option<&mut ExpectMachine>) -> CliResult {
+        match machine {
+            Some(machine) => {
+                machine.time_out();
+                CliResult::timeout("Expected reply not received")
+            }
+            None => CliResult::success(),
+        }
+    }
+}
+
+/// Decode `\r`, `\n`, `\t`, `\0`, `\e`, `\\` and `\xHH` escapes in a command line argument
+pub fn unescape(input: &str) -> Vec<u8> {
+    let mut out = Vec::with_capacity(input.len());
+    let mut bytes = input.bytes();
+
+    while let Some(b) = bytes.next() {
+        if b != b'\\' {
+            out.push(b);
+            continue;
+        }
+        match bytes.next() {
+            Some(b'r') => out.push(b'\r'),
+            Some(b'n') => out.push(b'\n'),
+            Some(b't') => out.push(b'\t'),
+            Some(b'0') => out.push(0),
+            Some(b'e') => out.push(0x1b),
+            Some(b'x') => {
+                let digits: Vec<u8> = bytes.clone().take(2).collect();
+                match std::str::from_utf8(&digits).ok().and_then(|s| u8::from_str_radix(s, 16).ok()) {
+                    Some(value) if digits.len() == 2 => {
+                        out.push(value);
+                        bytes.nth(1);
+                    }
+                    _ => out.extend_from_slice(b"\\x"),
+                }
+            }
+            Some(other) => {
+                if other != b'\\' {
+                    out.push(b'\\');
+                }
+                out.push(other);
+            }
+            None => out.push(b'\\'),
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expect_machine() {
+        let mut machine = ExpectMachine::new("OK|ERROR").unwrap();
+        assert_eq!(machine.feed(b"AT\r\nO"), &ExpectState::Waiting);
+        assert_eq!(machine.feed(b"K\r\n"), &ExpectState::Matched("OK".to_string()));
+
+        let mut machine = ExpectMachine::new("OK").unwrap();
+        machine.time_out();
+        assert_eq!(machine.state(), &ExpectState::TimedOut);
+    }
+
+    #[test]
+    fn test_unescape() {
+        assert_eq!(unescape(r"AT\r\n"), b"AT\r\n");
+        assert_eq!(unescape(r"\x41\\\xzz"), b"A\\\\xzz");
+    }
+}
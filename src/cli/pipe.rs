@@ -5,7 +5,15 @@
 use std::io::{self, BufRead, BufReader, Read, Write};
 use std::sync::mpsc::{self, Receiver, Sender};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+
+use crate::core::transport::{TransportError, TransportTrait};
+
+/// How long to keep reading RX after stdin reaches EOF
+pub const DEFAULT_DRAIN_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Poll interval for the transparent bridge
+const BRIDGE_POLL_INTERVAL: Duration = Duration::from_millis(20);
 
 /// Pipe mode
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -18,6 +26,8 @@ pub enum PipeMode {
     StdoutOnly,
     /// Full pipe mode (stdin -> process -> stdout)
     Full,
+    /// Transparent bridge (stdin -> transport -> stdout), closes on stdin EOF
+    Transparent,
 }
 
 impl PipeMode {
@@ -36,12 +46,12 @@ impl PipeMode {
     
     /// Is receiving from stdin?
     pub fn has_stdin(&self) -> bool {
-        matches!(self, Self::StdinOnly | Self::Full)
+        matches!(self, Self::StdinOnly | Self::Full | Self::Transparent)
     }
     
     /// Is sending to stdout?
     pub fn has_stdout(&self) -> bool {
-        matches!(self, Self::StdoutOnly | Self::Full)
+        matches!(self, Self::StdoutOnly | Self::Full | Self::Transparent)
     }
     
     /// Is interactive?
@@ -50,6 +60,17 @@ impl PipeMode {
     }
 }
 
+/// Result of polling stdin
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StdinRead {
+    /// Data read from stdin
+    Data(Vec<u8>),
+    /// Nothing available yet
+    Empty,
+    /// Stdin reached EOF (or failed)
+    Eof,
+}
+
 /// Pipe handler for stdin
 pub struct StdinPipe {
     receiver: Receiver<Vec<u8>>,
@@ -98,6 +119,15 @@ impl StdinPipe {
     pub fn try_recv(&self) -> Option<Vec<u8>> {
         self.receiver.try_recv().ok()
     }
+    
+    /// Poll stdin (non-blocking), distinguishing "no data yet" from EOF
+    pub fn poll(&self) -> StdinRead {
+        match self.receiver.try_recv() {
+            Ok(data) => StdinRead::Data(data),
+            Err(mpsc::TryRecvError::Empty) => StdinRead::Empty,
+            Err(mpsc::TryRecvError::Disconnected) => StdinRead::Eof,
+        }
+    }
 }
 
 impl Default for StdinPipe {
@@ -316,6 +346,16 @@ impl PipeProcessor {
         }
     }
     
+    /// Create a processor for transparent stdin <-> transport <-> stdout bridging
+    pub fn transparent(output_format: OutputFormat) -> Self {
+        Self {
+            mode: PipeMode::Transparent,
+            stdin_pipe: Some(StdinPipe::new()),
+            stdout_pipe: StdoutPipe::new(false),
+            output_format,
+        }
+    }
+    
     /// Get pipe mode
     pub fn mode(&self) -> PipeMode {
         self.mode
@@ -345,6 +385,83 @@ impl PipeProcessor {
     pub fn flush(&mut self) -> io::Result<()> {
         self.stdout_pipe.flush()
     }
+    
+    /// Bridge stdin to `transport` and its RX to stdout until stdin hits EOF
+    /// or the transport disconnects.
+    ///
+    /// After stdin EOF, RX is still forwarded until the transport stays quiet
+    /// for `drain`. The transport is disconnected before returning.
+    pub async fn bridge(
+        &mut self,
+        transport: &mut dyn TransportTrait,
+        drain: Duration,
+    ) -> Result<(), TransportError> {
+        let result = self.bridge_inner(transport, drain).await;
+        let _ = transport.disconnect().await;
+        self.flush()?;
+        result
+    }
+    
+    async fn bridge_inner(
+        &mut self,
+        transport: &mut dyn TransportTrait,
+        drain: Duration,
+    ) -> Result<(), TransportError> {
+        let Some(stdin) = self.stdin_pipe.take() else {
+            return Err(TransportError::ConfigError("stdin is not piped".to_string()));
+        };
+        
+        // Transports return empty reads when idle, so poll with a short sleep
+        loop {
+            loop {
+                match stdin.poll() {
+                    StdinRead::Data(data) => {
+                        transport.send(&data).await?;
+                    }
+                    StdinRead::Empty => break,
+                    StdinRead::Eof => return self.drain(transport, drain).await,
+                }
+            }
+            
+            match transport.receive().await {
+                Ok(data) if !data.is_empty() => self.forward(&data)?,
+                Ok(_) => tokio::time::sleep(BRIDGE_POLL_INTERVAL).await,
+                Err(TransportError::Disconnected) => return Ok(()),
+                Err(e) => return Err(e),
+            }
+        }
+    }
+    
+    /// Forward RX until the transport has been quiet for `drain`
+    async fn drain(
+        &mut self,
+        transport: &mut dyn TransportTrait,
+        drain: Duration,
+    ) -> Result<(), TransportError> {
+        let mut last_rx = Instant::now();
+        
+        while last_rx.elapsed() < drain {
+            match transport.receive().await {
+                Ok(data) if !data.is_empty() => {
+                    self.forward(&data)?;
+                    last_rx = Instant::now();
+                }
+                Ok(_) => tokio::time::sleep(BRIDGE_POLL_INTERVAL).await,
+                Err(TransportError::Disconnected) => break,
+                Err(e) => return Err(e),
+            }
+        }
+        
+        Ok(())
+    }
+    
+    fn forward(&mut self, data: &[u8]) -> io::Result<()> {
+        self.write(data)?;
+        if self.output_format == OutputFormat::Raw {
+            self.stdout_pipe.flush()?;
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -365,6 +482,14 @@ mod tests {
         assert_eq!(escaped, "Hello\\n");
     }
     
+    #[test]
+    fn test_transparent_mode() {
+        let mode = PipeMode::Transparent;
+        assert!(mode.has_stdin());
+        assert!(mode.has_stdout());
+        assert!(!mode.is_interactive());
+    }
+    
     #[test]
     fn test_pipe_mode_detection() {
         // In tests, stdio is typically not a TTY
@@ -3,8 +3,10 @@
 //! Handles application settings and connection profiles
 
 mod settings;
+mod watcher;
 
-pub use settings::{AppConfig, ConnectionProfile, ProfileType};
+pub use settings::{AppConfig, ConnectionProfile, LineEnding, ProfileType};
+pub use watcher::{ConfigEvent, ConfigWatcher};
 
 use directories::ProjectDirs;
 use std::path::PathBuf;
@@ -2,17 +2,31 @@
 
 use crate::core::codec::CodecType;
 use crate::core::logger::LogFormat;
-use crate::core::transport::{SerialConfig, SerialFlowControl, SerialParity, TcpConfig, TelnetConfig};
+use crate::core::session::SessionConfig;
+use crate::core::transport::{SerialConfig, SerialFlowControl, SerialParity, TcpConfig, TelnetConfig, Transport};
 use crate::i18n::Locale;
+use crate::utils::notifier::DEFAULT_NOTIFY_INTERVAL;
+use super::watcher::ConfigWatcher;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+pub use crate::core::session::LineEnding;
 
 /// Application configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppConfig {
     /// Application language
     pub locale: String,
+    /// Theme (light/dark)
+    #[serde(default = "default_theme")]
+    pub theme: String,
+    /// Display codec for new sessions
+    #[serde(default)]
+    pub default_codec: CodecType,
+    /// Line ending for new sessions
+    #[serde(default)]
+    pub default_line_ending: LineEnding,
     /// Window state
     pub window: WindowConfig,
     /// Terminal settings
@@ -33,6 +47,9 @@ impl Default for AppConfig {
     fn default() -> Self {
         Self {
             locale: "en".to_string(),
+            theme: default_theme(),
+            default_codec: CodecType::Text,
+            default_line_ending: LineEnding::CrLf,
             window: WindowConfig::default(),
             terminal: TerminalConfig::default(),
             logging: LoggingConfig::default(),
@@ -45,31 +62,57 @@ impl Default for AppConfig {
 }
 
 impl AppConfig {
+    /// Path of the config file
+    pub fn path() -> Option<PathBuf> {
+        super::config_dir().map(|dir| dir.join("config.toml"))
+    }
+
     /// Load config from file
     pub fn load() -> Result<Self, Box<dyn std::error::Error>> {
-        let config_path = super::config_dir()
-            .ok_or("Could not determine config directory")?
-            .join("config.toml");
+        let config_path = Self::path().ok_or("Could not determine config directory")?;
+        Self::load_from(&config_path)
+    }
 
-        if config_path.exists() {
-            let content = std::fs::read_to_string(&config_path)?;
-            Ok(toml::from_str(&content)?)
+    /// Load config from a specific file, falling back to defaults if it does not exist
+    pub fn load_from(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        if path.exists() {
+            let content = std::fs::read_to_string(path)?;
+            Ok(Self::from_toml(&content)?)
         } else {
             Ok(Self::default())
         }
     }
 
+    /// Parse config file contents, migrating settings from older layouts
+    pub fn from_toml(content: &str) -> Result<Self, toml::de::Error> {
+        let mut value: toml::Value = toml::from_str(content)?;
+        migrate_legacy_keys(&mut value);
+        value.try_into()
+    }
+
     /// Save config to file
     pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
-        let config_path = super::config_dir()
-            .ok_or("Could not determine config directory")?
-            .join("config.toml");
+        let config_path = Self::path().ok_or("Could not determine config directory")?;
+        self.save_to(&config_path)
+    }
+
+    /// Save config to a specific file
+    pub fn save_to(&self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
 
         let content = toml::to_string_pretty(self)?;
-        std::fs::write(config_path, content)?;
+        std::fs::write(path, content)?;
         Ok(())
     }
 
+    /// Watch the config file and reload it on change
+    pub fn watch() -> Result<ConfigWatcher, Box<dyn std::error::Error>> {
+        let config_path = Self::path().ok_or("Could not determine config directory")?;
+        ConfigWatcher::new(&config_path)
+    }
+
     /// Get locale
     pub fn locale(&self) -> Locale {
         Locale::from_code(&self.locale).unwrap_or_default()
@@ -79,6 +122,47 @@ impl AppConfig {
     pub fn set_locale(&mut self, locale: Locale) {
         self.locale = locale.code().to_string();
     }
+
+    /// Is the dark theme selected?
+    pub fn is_dark_theme(&self) -> bool {
+        self.theme != "light"
+    }
+
+    /// Session configuration inheriting the configured defaults
+    pub fn session_config(&self, name: &str, transport: Transport) -> SessionConfig {
+        let mut config = SessionConfig::new(name, transport);
+        config.codec = self.default_codec;
+        config.line_ending = self.default_line_ending;
+        config.log_format = self.logging.format;
+        config
+    }
+}
+
+/// Settings that used to live under `[window]` and `[terminal]`, with their top-level keys
+const LEGACY_KEYS: &[(&str, &str, &str)] = &[
+    ("window", "theme", "theme"),
+    ("terminal", "display_mode", "default_codec"),
+    ("terminal", "line_ending", "default_line_ending"),
+];
+
+/// Move settings from their old sections to the top level unless already set there
+fn migrate_legacy_keys(value: &mut toml::Value) {
+    let Some(root) = value.as_table_mut() else {
+        return;
+    };
+    for (section, old_key, new_key) in LEGACY_KEYS {
+        let old = root
+            .get_mut(*section)
+            .and_then(toml::Value::as_table_mut)
+            .and_then(|table| table.remove(*old_key));
+        if let Some(old) = old {
+            root.entry(*new_key).or_insert(old);
+        }
+    }
+}
+
+fn default_theme() -> String {
+    "dark".to_string()
 }
 
 /// Window configuration
@@ -98,8 +182,6 @@ pub struct WindowConfig {
     pub show_toolbar: bool,
     /// Show status bar
     pub show_status_bar: bool,
-    /// Theme (light/dark)
-    pub theme: String,
 }
 
 impl Default for WindowConfig {
@@ -112,7 +194,6 @@ impl Default for WindowConfig {
             maximized: false,
             show_toolbar: true,
             show_status_bar: true,
-            theme: "dark".to_string(),
         }
     }
 }
@@ -128,16 +209,21 @@ pub struct TerminalConfig {
     pub line_height: f32,
     /// Local echo
     pub local_echo: bool,
-    /// Line ending type
-    pub line_ending: LineEnding,
-    /// Default display mode
-    pub display_mode: CodecType,
     /// Scroll buffer size (lines)
     pub scroll_buffer: usize,
+    /// Move lines past the scroll buffer to a temp file instead of dropping them
+    #[serde(default)]
+    pub spill_to_disk: bool,
     /// Scroll on output
     pub scroll_on_output: bool,
     /// Show timestamps
     pub show_timestamps: bool,
+    /// Post a desktop notification when the remote rings the bell
+    #[serde(default)]
+    pub bell_notifications: bool,
+    /// Minimum seconds between two bell notifications
+    #[serde(default = "default_bell_interval")]
+    pub bell_interval_secs: u64,
     /// Highlight patterns (pattern -> color)
     pub highlights: HashMap<String, String>,
 }
@@ -149,37 +235,19 @@ impl Default for TerminalConfig {
             font_size: 13.0,
             line_height: 1.2,
             local_echo: false,
-            line_ending: LineEnding::CrLf,
-            display_mode: CodecType::Text,
             scroll_buffer: 10000,
+            spill_to_disk: false,
             scroll_on_output: true,
             show_timestamps: false,
+            bell_notifications: false,
+            bell_interval_secs: default_bell_interval(),
             highlights: HashMap::new(),
         }
     }
 }
 
-/// Line ending type
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
-pub enum LineEnding {
-    /// Carriage Return only
-    Cr,
-    /// Line Feed only
-    Lf,
-    /// Both CR and LF
-    #[default]
-    CrLf,
-}
-
-impl LineEnding {
-    /// Get the byte sequence for this line ending
-    pub fn bytes(&self) -> &'static [u8] {
-        match self {
-            Self::Cr => b"\r",
-            Self::Lf => b"\n",
-            Self::CrLf => b"\r\n",
-        }
-    }
+fn default_bell_interval() -> u64 {
+    DEFAULT_NOTIFY_INTERVAL.as_secs()
 }
 
 /// Logging settings
@@ -307,6 +375,16 @@ impl ConnectionProfile {
     }
 }
 
+impl From<&ConnectionProfile> for Transport {
+    fn from(profile: &ConnectionProfile) -> Self {
+        match &profile.connection {
+            ProfileType::Serial(cfg) => Transport::Serial(cfg.clone()),
+            ProfileType::Tcp(cfg) => Transport::Tcp(cfg.clone()),
+            ProfileType::Telnet(cfg) => Transport::Telnet(cfg.clone()),
+        }
+    }
+}
+
 /// Macro definition
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MacroDefinition {
@@ -368,10 +446,60 @@ fn default_macros() -> Vec<MacroDefinition> {
     ]
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_save_and_load_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join("config.toml");
+
+        let mut config = AppConfig::default();
+        config.theme = "light".to_string();
+        config.default_codec = CodecType::Hex;
+        config.default_line_ending = LineEnding::Lf;
+        config.save_to(&path).unwrap();
+
+        let loaded = AppConfig::load_from(&path).unwrap();
+        assert!(!loaded.is_dark_theme());
+        assert_eq!(loaded.default_codec, CodecType::Hex);
+        assert_eq!(loaded.default_line_ending, LineEnding::Lf);
+
+        let session = loaded.session_config("test", Transport::Tcp(TcpConfig::new("localhost", 23)));
+        assert_eq!(session.codec, CodecType::Hex);
+        assert_eq!(session.line_ending, LineEnding::Lf);
+    }
 
-
-
-
-
-
-
+    #[test]
+    fn test_legacy_keys_are_migrated() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+
+        let mut value = toml::Value::try_from(AppConfig::default()).unwrap();
+        let root = value.as_table_mut().unwrap();
+        root.remove("theme");
+        root.remove("default_codec");
+        root.remove("default_line_ending");
+        let window = root.get_mut("window").unwrap().as_table_mut().unwrap();
+        window.insert("theme".to_string(), "light".into());
+        let terminal = root.get_mut("terminal").unwrap().as_table_mut().unwrap();
+        terminal.insert("display_mode".to_string(), "Hex".into());
+        terminal.insert("line_ending".to_string(), "Lf".into());
+        std::fs::write(&path, toml::to_string(&value).unwrap()).unwrap();
+
+        let loaded = AppConfig::load_from(&path).unwrap();
+        assert_eq!(loaded.theme, "light");
+        assert_eq!(loaded.default_codec, CodecType::Hex);
+        assert_eq!(loaded.default_line_ending, LineEnding::Lf);
+
+        // Keys already at the top level win over stale legacy ones
+        let mut config = loaded;
+        config.theme = "dark".to_string();
+        let mut value = toml::Value::try_from(&config).unwrap();
+        let window = value.get_mut("window").unwrap().as_table_mut().unwrap();
+        window.insert("theme".to_string(), "light".into());
+        std::fs::write(&path, toml::to_string(&value).unwrap()).unwrap();
+        assert!(AppConfig::load_from(&path).unwrap().is_dark_theme());
+    }
+}
@@ -0,0 +1,103 @@
+//! Config file watcher
+//!
+//! Reloads `config.toml` when it changes on disk so running windows pick up
+//! edits without a restart.
+
+use super::AppConfig;
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, Sender};
+
+/// Config change notification
+#[derive(Debug, Clone)]
+pub enum ConfigEvent {
+    /// Config file changed and was reloaded
+    Changed(Box<AppConfig>),
+    /// Config file changed but could not be parsed
+    Error(String),
+}
+
+/// Watches the config file and emits a [`ConfigEvent`] per change
+pub struct ConfigWatcher {
+    _watcher: RecommendedWatcher,
+    receiver: Receiver<ConfigEvent>,
+}
+
+impl ConfigWatcher {
+    /// Start watching `path`
+    ///
+    /// The parent directory is watched rather than the file itself, so editors
+    /// that save by replacing the file are still picked up.
+    pub fn new(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let dir = path.parent().ok_or("Config path has no parent directory")?;
+        std::fs::create_dir_all(dir)?;
+
+        let (sender, receiver) = mpsc::channel();
+        let mut state = ReloadState {
+            path: path.to_path_buf(),
+            last_content: std::fs::read_to_string(path).ok(),
+            sender,
+        };
+
+        let mut watcher = notify::recommended_watcher(move |res: notify::Result<Event>| {
+            if let Ok(event) = res {
+                state.handle(&event);
+            }
+        })?;
+        watcher.watch(dir, RecursiveMode::NonRecursive)?;
+
+        Ok(Self {
+            _watcher: watcher,
+            receiver,
+        })
+    }
+
+    /// Next pending change (non-blocking)
+    pub fn try_recv(&self) -> Option<ConfigEvent> {
+        self.receiver.try_recv().ok()
+    }
+
+    /// Most recent pending change, discarding older ones (non-blocking)
+    pub fn latest(&self) -> Option<ConfigEvent> {
+        self.receiver.try_iter().last()
+    }
+}
+
+impl std::fmt::Debug for ConfigWatcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConfigWatcher").finish_non_exhaustive()
+    }
+}
+
+/// Reload bookkeeping owned by the notify callback
+struct ReloadState {
+    path: PathBuf,
+    last_content: Option<String>,
+    sender: Sender<ConfigEvent>,
+}
+
+impl ReloadState {
+    fn handle(&mut self, event: &Event) {
+        if !matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+            return;
+        }
+        if !event.paths.iter().any(|p| p.file_name() == self.path.file_name()) {
+            return;
+        }
+
+        // Editors often emit several events per save; only reload real changes
+        let Ok(content) = std::fs::read_to_string(&self.path) else {
+            return;
+        };
+        if self.last_content.as_deref() == Some(content.as_str()) {
+            return;
+        }
+
+        let event = match AppConfig::from_toml(&content) {
+            Ok(config) => ConfigEvent::Changed(Box::new(config)),
+            Err(e) => ConfigEvent::Error(format!("Failed to reload config: {}", e)),
+        };
+        self.last_content = Some(content);
+        let _ = self.sender.send(event);
+    }
+}
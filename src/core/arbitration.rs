@@ -5,10 +5,15 @@
 //! - Bandwidth/rate limiting
 //! - Fairness policies
 //! - Resource allocation
+//! - Serial port locks shared with other processes
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
+use thiserror::Error;
 
 /// Session priority level
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
@@ -349,6 +354,159 @@ pub struct ArbiterStats {
     pub total_bytes_received: u64,
 }
 
+/// Port lock errors
+#[derive(Error, Debug)]
+pub enum PortLockError {
+    /// Another live process holds the lock
+    #[error("{port} is locked by process {pid}")]
+    Held { port: String, pid: u32 },
 
+    /// A lock file exists but names no process
+    #[error("{port} is locked ({path} holds no process ID)")]
+    Unreadable { port: String, path: PathBuf },
 
+    /// The lock file could not be created or read
+    #[error("Lock file error: {0}")]
+    Io(#[from] io::Error),
+}
+
+/// Advisory lock on a serial port, shared with other processes
+///
+/// A UUCP-style `LCK..<device>` file holding the owner's PID, as used by
+/// minicom and friends. The PID is written to a temporary file first and
+/// hard-linked into place, so the lock never appears without its owner.
+/// Locks of processes that no longer exist are taken over; a lock without
+/// a readable PID is left alone. The file is removed on drop.
+#[derive(Debug)]
+pub struct PortLock {
+    path: PathBuf,
+}
+
+impl PortLock {
+    /// Lock `port` in the default lock directory
+    pub fn acquire(port: &str) -> Result<Self, PortLockError> {
+        Self::acquire_in(&default_lock_dir(), port)
+    }
+
+    /// Lock `port` with a lock file in `dir`
+    pub fn acquire_in(dir: &Path, port: &str) -> Result<Self, PortLockError> {
+        fs::create_dir_all(dir)?;
+        let name = Path::new(port).file_name().map_or_else(|| port.into(), |name| name.to_string_lossy());
+        let path = dir.join(format!("LCK..{}", name));
+
+        let tmp = dir.join(format!("LTMP.{}", std::process::id()));
+        {
+            let mut file = fs::File::create(&tmp)?;
+            writeln!(file, "{:>10}", std::process::id())?;
+        }
+        let result = Self::link_lock(&tmp, &path, port);
+        let _ = fs::remove_file(&tmp);
+        result
+    }
 
+    /// Hard-link the prepared `tmp` to `path`, taking over a stale lock
+    fn link_lock(tmp: &Path, path: &Path, port: &str) -> Result<Self, PortLockError> {
+        // A second attempt follows removing a stale lock
+        for _ in 0..2 {
+            match fs::hard_link(tmp, path) {
+                Ok(()) => return Ok(Self { path: path.to_path_buf() }),
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
+                    let Some(pid) = fs::read_to_string(path)?.trim().parse::<u32>().ok() else {
+                        return Err(PortLockError::Unreadable { port: port.to_string(), path: path.to_path_buf() });
+                    };
+                    if process_alive(pid) {
+                        return Err(PortLockError::Held { port: port.to_string(), pid });
+                    }
+                    tracing::info!("Removing stale lock {}", path.display());
+                    fs::remove_file(path)?;
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Err(PortLockError::Io(io::Error::new(io::ErrorKind::AlreadyExists, "lock file keeps reappearing")))
+    }
+
+    /// Path of the lock file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for PortLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Directory for port lock files
+///
+/// The system lock directory when it is writable, so other serial tools
+/// see the locks; otherwise a per-user directory under the temp dir.
+pub fn default_lock_dir() -> PathBuf {
+    #[cfg(unix)]
+    for dir in ["/run/lock", "/var/lock"] {
+        let Ok(c_dir) = std::ffi::CString::new(dir) else {
+            continue;
+        };
+        // SAFETY: `c_dir` is a valid NUL-terminated path
+        if unsafe { libc::access(c_dir.as_ptr(), libc::W_OK) } == 0 {
+            return PathBuf::from(dir);
+        }
+    }
+    #[cfg(unix)]
+    // SAFETY: getuid cannot fail
+    let user = unsafe { libc::getuid() }.to_string();
+    #[cfg(not(unix))]
+    let user = std::env::var("USERNAME").unwrap_or_default();
+    std::env::temp_dir().join(format!("termicon-locks-{}", user))
+}
+
+/// Check if a process with `pid` exists
+#[cfg(unix)]
+fn process_alive(pid: u32) -> bool {
+    let Ok(pid) = libc::pid_t::try_from(pid) else {
+        return false;
+    };
+    // SAFETY: signal 0 only checks for existence and permission
+    let result = unsafe { libc::kill(pid, 0) };
+    result == 0 || io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+/// Check if a process with `pid` exists
+#[cfg(not(unix))]
+fn process_alive(_pid: u32) -> bool {
+    // No cheap check; treat the lock as live
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_port_lock() {
+        let dir = tempfile::tempdir().unwrap();
+        let lock = PortLock::acquire_in(dir.path(), "/dev/ttyUSB0").unwrap();
+        assert_eq!(lock.path(), dir.path().join("LCK..ttyUSB0"));
+
+        match PortLock::acquire_in(dir.path(), "/dev/ttyUSB0") {
+            Err(PortLockError::Held { pid, .. }) => assert_eq!(pid, std::process::id()),
+            other => panic!("unexpected {:?}", other),
+        }
+        drop(lock);
+        assert!(!dir.path().join("LCK..ttyUSB0").exists());
+
+        // A lock left by a process that is gone is taken over
+        fs::write(dir.path().join("LCK..ttyACM0"), format!("{:>10}\n", u32::MAX - 1)).unwrap();
+        assert!(PortLock::acquire_in(dir.path(), "/dev/ttyACM0").is_ok());
+
+        // A lock whose PID cannot be read may be mid-creation by another tool
+        fs::write(dir.path().join("LCK..ttyS0"), "").unwrap();
+        assert!(matches!(
+            PortLock::acquire_in(dir.path(), "/dev/ttyS0"),
+            Err(PortLockError::Unreadable { .. })
+        ));
+        assert!(dir.path().join("LCK..ttyS0").exists());
+        assert!(!dir.path().join(format!("LTMP.{}", std::process::id())).exists());
+    }
+}
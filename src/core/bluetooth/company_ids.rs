@@ -0,0 +1,71 @@
+//! Bluetooth SIG company identifiers
+//!
+//! A subset of the assigned numbers used in manufacturer-specific
+//! advertising data, covering vendors commonly seen in scans.
+
+/// Company identifiers and names, sorted by identifier
+const COMPANY_IDS: &[(u16, &str)] = &[
+    (0x0000, "Ericsson"),
+    (0x0001, "Nokia"),
+    (0x0002, "Intel"),
+    (0x0003, "IBM"),
+    (0x0004, "Toshiba"),
+    (0x0006, "Microsoft"),
+    (0x0008, "Motorola"),
+    (0x0009, "Infineon Technologies"),
+    (0x000A, "Qualcomm Technologies International"),
+    (0x000D, "Texas Instruments"),
+    (0x000F, "Broadcom"),
+    (0x001D, "Qualcomm"),
+    (0x0025, "NXP Semiconductors"),
+    (0x0030, "STMicroelectronics"),
+    (0x0046, "MediaTek"),
+    (0x004C, "Apple"),
+    (0x0057, "Harman International"),
+    (0x0059, "Nordic Semiconductor"),
+    (0x005D, "Realtek Semiconductor"),
+    (0x0065, "HP"),
+    (0x0075, "Samsung"),
+    (0x0078, "Nike"),
+    (0x0087, "Garmin"),
+    (0x009E, "Bose"),
+    (0x00C4, "LG Electronics"),
+    (0x00D2, "Dialog Semiconductor"),
+    (0x00E0, "Google"),
+    (0x012D, "Sony"),
+    (0x0131, "Cypress Semiconductor"),
+    (0x0171, "Amazon"),
+    (0x02D5, "Xiaomi"),
+    (0x02E5, "Espressif"),
+    (0x0499, "Ruuvi Innovations"),
+];
+
+/// Name of the company with Bluetooth SIG identifier `id`
+pub fn company_name(id: u16) -> Option<&'static str> {
+    COMPANY_IDS
+        .binary_search_by_key(&id, |&(company, _)| company)
+        .ok()
+        .map(|index| COMPANY_IDS[index].1)
+}
+
+/// Manufacturer named by advertised company IDs
+///
+/// If several are advertised, the lowest known one is used.
+pub fn manufacturer_name<'a>(ids: impl IntoIterator<Item = &'a u16>) -> Option<&'static str> {
+    let mut ids: Vec<u16> = ids.into_iter().copied().collect();
+    ids.sort_unstable();
+    ids.into_iter().find_map(company_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table_is_sorted() {
+        assert!(COMPANY_IDS.windows(2).all(|pair| pair[0].0 < pair[1].0));
+        assert_eq!(company_name(0x004C), Some("Apple"));
+        assert_eq!(company_name(0x0059), Some("Nordic Semiconductor"));
+        assert_eq!(company_name(0xFFFF), None);
+    }
+}
@@ -1,5 +1,6 @@
 //! Bluetooth device representation
 
+use super::company_ids::manufacturer_name;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use uuid::Uuid;
@@ -71,6 +72,32 @@ pub enum ConnectionState {
     Disconnecting,
 }
 
+// Advertising data (AD) types, Bluetooth Core Supplement part A
+const AD_UUID16_PARTIAL: u8 = 0x02;
+const AD_UUID16_COMPLETE: u8 = 0x03;
+const AD_UUID128_PARTIAL: u8 = 0x06;
+const AD_UUID128_COMPLETE: u8 = 0x07;
+const AD_NAME_SHORT: u8 = 0x08;
+const AD_NAME_COMPLETE: u8 = 0x09;
+const AD_TX_POWER: u8 = 0x0A;
+const AD_SERVICE_DATA16: u8 = 0x16;
+const AD_APPEARANCE: u8 = 0x19;
+const AD_SERVICE_DATA128: u8 = 0x21;
+const AD_MANUFACTURER: u8 = 0xFF;
+
+/// Full UUID of a 16-bit SIG-assigned UUID
+fn uuid16(short: u16) -> Uuid {
+    Uuid::from_u128(0x0000_0000_0000_1000_8000_0080_5f9b_34fb | ((short as u128) << 96))
+}
+
+/// UUID from 16 little-endian bytes
+fn uuid128(bytes: &[u8]) -> Uuid {
+    let mut be = [0u8; 16];
+    be.copy_from_slice(bytes);
+    be.reverse();
+    Uuid::from_bytes(be)
+}
+
 /// Bluetooth device information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BluetoothDevice {
@@ -100,6 +127,9 @@ pub struct BluetoothDevice {
     pub service_data: HashMap<Uuid, Vec<u8>>,
     /// TX power level (if advertised)
     pub tx_power: Option<i8>,
+    /// GAP appearance value (if advertised)
+    #[serde(default)]
+    pub appearance: Option<u16>,
     /// Last seen timestamp
     pub last_seen: Option<chrono::DateTime<chrono::Utc>>,
     /// User-defined label
@@ -129,6 +159,7 @@ impl BluetoothDevice {
             manufacturer_data: HashMap::new(),
             service_data: HashMap::new(),
             tx_power: None,
+            appearance: None,
             last_seen: Some(chrono::Utc::now()),
             label: None,
             notes: None,
@@ -157,23 +188,58 @@ impl BluetoothDevice {
         self.service_uuids.contains(uuid)
     }
 
-    /// Get manufacturer name from manufacturer data
+    /// Name of the manufacturer in the advertising data
     pub fn manufacturer_name(&self) -> Option<&'static str> {
-        // Check common manufacturer IDs
-        for &company_id in self.manufacturer_data.keys() {
-            match company_id {
-                0x004C => return Some("Apple"),
-                0x0006 => return Some("Microsoft"),
-                0x000D => return Some("Texas Instruments"),
-                0x000F => return Some("Broadcom"),
-                0x0059 => return Some("Nordic Semiconductor"),
-                0x00E0 => return Some("Google"),
-                0x0075 => return Some("Samsung"),
-                0x02D5 => return Some("Xiaomi"),
+        manufacturer_name(self.manufacturer_data.keys())
+    }
+
+    /// Update fields from raw advertising or scan response data
+    ///
+    /// Unknown and malformed AD structures are skipped.
+    pub fn apply_advertising(&mut self, data: &[u8]) {
+        let mut rest = data;
+        while let [len, tail @ ..] = rest {
+            let len = *len as usize;
+            if len == 0 || len > tail.len() {
+                break;
+            }
+            let (ad_type, value) = (tail[0], &tail[1..len]);
+            rest = &tail[len..];
+
+            match (ad_type, value) {
+                (AD_UUID16_PARTIAL | AD_UUID16_COMPLETE, _) => {
+                    for chunk in value.chunks_exact(2) {
+                        self.add_service(uuid16(u16::from_le_bytes([chunk[0], chunk[1]])));
+                    }
+                }
+                (AD_UUID128_PARTIAL | AD_UUID128_COMPLETE, _) => {
+                    for chunk in value.chunks_exact(16) {
+                        self.add_service(uuid128(chunk));
+                    }
+                }
+                (AD_NAME_SHORT | AD_NAME_COMPLETE, _) => {
+                    self.name = Some(String::from_utf8_lossy(value).into_owned());
+                }
+                (AD_TX_POWER, &[power]) => self.tx_power = Some(power as i8),
+                (AD_SERVICE_DATA16, [a, b, data @ ..]) => {
+                    self.service_data.insert(uuid16(u16::from_le_bytes([*a, *b])), data.to_vec());
+                }
+                (AD_SERVICE_DATA128, _) if value.len() >= 16 => {
+                    self.service_data.insert(uuid128(&value[..16]), value[16..].to_vec());
+                }
+                (AD_APPEARANCE, &[a, b]) => self.appearance = Some(u16::from_le_bytes([a, b])),
+                (AD_MANUFACTURER, [a, b, data @ ..]) => {
+                    self.manufacturer_data.insert(u16::from_le_bytes([*a, *b]), data.to_vec());
+                }
                 _ => {}
             }
         }
-        None
+    }
+
+    fn add_service(&mut self, uuid: Uuid) {
+        if !self.service_uuids.contains(&uuid) {
+            self.service_uuids.push(uuid);
+        }
     }
 
     /// Update from another device (merge data)
@@ -209,6 +275,9 @@ impl BluetoothDevice {
         if other.tx_power.is_some() {
             self.tx_power = other.tx_power;
         }
+        if other.appearance.is_some() {
+            self.appearance = other.appearance;
+        }
         
         self.last_seen = Some(chrono::Utc::now());
         
@@ -227,10 +296,31 @@ impl Default for BluetoothDevice {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
 
+    #[test]
+    fn test_apply_advertising() {
+        let mut device = BluetoothDevice::new("AA:BB:CC:DD:EE:FF");
+        device.apply_advertising(&[
+            0x02, 0x01, 0x06, // flags
+            0x05, 0x09, b'T', b'a', b'g', b'1', // complete name
+            0x02, 0x0A, 0xF4, // TX power -12 dBm
+            0x03, 0x19, 0xC1, 0x03, // appearance 0x03C1 (keyboard)
+            0x03, 0x03, 0x0F, 0x18, // battery service
+            0x04, 0x16, 0x0F, 0x18, 0x55, // battery level 85
+            0x05, 0xFF, 0x59, 0x00, 0x01, 0x02, // Nordic
+            0x09, 0xFF, 0x00, // truncated
+        ]);
 
-
-
-
-
-
+        let battery = Uuid::parse_str("0000180f-0000-1000-8000-00805f9b34fb").unwrap();
+        assert_eq!(device.name.as_deref(), Some("Tag1"));
+        assert_eq!(device.tx_power, Some(-12));
+        assert_eq!(device.appearance, Some(0x03C1));
+        assert!(device.has_service(&battery));
+        assert_eq!(device.service_data[&battery], [0x55]);
+        assert_eq!(device.manufacturer_data[&0x0059], [0x01, 0x02]);
+        assert_eq!(device.manufacturer_name(), Some("Nordic Semiconductor"));
+    }
+}
@@ -4,13 +4,16 @@
 //! - BLE GATT client (scan, connect, services/characteristics)
 //! - RFCOMM/SPP serial terminal
 //! - Device management (pairing, bonding, trust)
+//! - Advertising data parsing
 //! - HCI logging and debugging
 
 pub mod ble;
+pub mod company_ids;
 pub mod device;
 pub mod rfcomm;
 
 pub use ble::{BleClient, BleConfig, GattCharacteristic, GattService};
+pub use company_ids::{company_name, manufacturer_name};
 pub use device::{BluetoothDevice, BluetoothDeviceClass, BluetoothDeviceType};
 pub use rfcomm::{RfcommConfig, RfcommTransport};
 
@@ -3,13 +3,17 @@
 //! Provides serial port emulation over Bluetooth Classic
 
 use super::BluetoothError;
-use crate::core::transport::{TransportError, TransportStats, TransportTrait, TransportType};
+use crate::core::transport::{BluetoothConfig, TransportError, TransportStats, TransportTrait, TransportType};
 use async_trait::async_trait;
 use bytes::Bytes;
 use parking_lot::RwLock;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::broadcast;
+#[cfg(target_os = "linux")]
+use std::os::fd::{AsRawFd, OwnedFd};
+#[cfg(target_os = "linux")]
+use tokio::io::unix::AsyncFd;
 
 /// RFCOMM channel configuration
 #[derive(Debug, Clone)]
@@ -60,6 +64,15 @@ impl RfcommConfig {
     }
 }
 
+impl From<&BluetoothConfig> for RfcommConfig {
+    fn from(config: &BluetoothConfig) -> Self {
+        Self::new(&config.device)
+            .channel(config.channel)
+            .timeout(Duration::from_secs(config.timeout_secs))
+            .auto_reconnect(config.auto_reconnect)
+    }
+}
+
 impl Default for RfcommConfig {
     fn default() -> Self {
         Self::new("00:00:00:00:00:00")
@@ -73,9 +86,9 @@ pub struct RfcommTransport {
     stats: Arc<RwLock<TransportStats>>,
     connected_at: Option<Instant>,
     tx: broadcast::Sender<Bytes>,
-    // Platform-specific socket/stream would go here
+    /// Connected BlueZ RFCOMM socket (non-blocking)
     #[cfg(target_os = "linux")]
-    socket: Option<std::os::unix::io::RawFd>,
+    socket: Option<AsyncFd<OwnedFd>>,
 }
 
 impl RfcommTransport {
@@ -94,59 +107,68 @@ impl RfcommTransport {
         }
     }
 
-    /// Discover SPP service and get RFCOMM channel
-    #[allow(dead_code)]
-    async fn discover_spp_channel(&self) -> Result<u8, BluetoothError> {
-        // Platform-specific SDP query to find SPP service
-        // This would use bluez-rs or similar on Linux
+    /// Look up the Serial Port service's RFCOMM channel over SDP
+    #[cfg(target_os = "linux")]
+    async fn discover_spp_channel(&self, address: [u8; 6]) -> Result<u8, BluetoothError> {
         tracing::info!("Discovering SPP service on {}", self.config.address);
 
-        // Default to channel 1 as fallback
-        Ok(1)
+        let query = tokio::task::spawn_blocking(move || bluez::sdp_search(address, bluez::SPP_UUID));
+        let attributes = tokio::time::timeout(self.config.timeout, query)
+            .await
+            .map_err(|_| BluetoothError::Timeout)?
+            .map_err(|e| BluetoothError::ConnectionFailed(e.to_string()))?
+            .map_err(|e| BluetoothError::ConnectionFailed(format!("SDP query failed: {}", e)))?;
+
+        bluez::rfcomm_channel(&attributes)
+            .ok_or_else(|| BluetoothError::ServiceNotFound(format!("Serial Port on {}", self.config.address)))
     }
-}
 
-#[async_trait]
-impl TransportTrait for RfcommTransport {
-    async fn connect(&mut self) -> Result<(), TransportError> {
-        tracing::info!(
-            "Connecting to Bluetooth device {} on channel {}",
-            self.config.address,
-            self.config.channel
-        );
+    /// Open the RFCOMM socket, discovering the channel if none is set
+    #[cfg(target_os = "linux")]
+    async fn open(&mut self) -> Result<u8, TransportError> {
+        let address = parse_mac_address(&self.config.address)
+            .map_err(|e| TransportError::InvalidConfiguration(e.to_string()))?;
 
-        // Discover channel if not specified
         let channel = if self.config.channel == 0 {
-            self.discover_spp_channel()
+            self.discover_spp_channel(address)
                 .await
                 .map_err(|e| TransportError::ConnectionFailed(e.to_string()))?
         } else {
             self.config.channel
         };
 
-        // Platform-specific RFCOMM connection
-        #[cfg(target_os = "linux")]
-        {
-            // On Linux, we would use socket(AF_BLUETOOTH, SOCK_STREAM, BTPROTO_RFCOMM)
-            // and connect to the Bluetooth address + channel
-            // This requires the bluez-rs crate or manual libc calls
+        let connect = tokio::task::spawn_blocking(move || bluez::connect(address, channel));
+        let fd = tokio::time::timeout(self.config.timeout, connect)
+            .await
+            .map_err(|_| TransportError::Timeout(self.config.timeout.as_secs()))?
+            .map_err(|e| TransportError::ConnectionFailed(e.to_string()))?
+            .map_err(|e| match e.kind() {
+                std::io::ErrorKind::PermissionDenied => TransportError::PermissionDenied(e.to_string()),
+                _ => TransportError::ConnectionFailed(format!("{}: {}", self.config.address, e)),
+            })?;
+        self.socket = Some(AsyncFd::new(fd)?);
+        Ok(channel)
+    }
 
-            // Placeholder for now
-            tokio::time::sleep(Duration::from_millis(500)).await;
-        }
+    /// RFCOMM sockets are only implemented for BlueZ
+    #[cfg(not(target_os = "linux"))]
+    async fn open(&mut self) -> Result<u8, TransportError> {
+        Err(TransportError::Unsupported(
+            "Bluetooth SPP is only available on Linux".to_string(),
+        ))
+    }
+}
 
-        #[cfg(target_os = "windows")]
-        {
-            // On Windows, we would use Winsock with RFCOMM
-            // or the Windows.Devices.Bluetooth.Rfcomm API
-            tokio::time::sleep(Duration::from_millis(500)).await;
-        }
+#[async_trait]
+impl TransportTrait for RfcommTransport {
+    async fn connect(&mut self) -> Result<(), TransportError> {
+        tracing::info!(
+            "Connecting to Bluetooth device {} on channel {}",
+            self.config.address,
+            self.config.channel
+        );
 
-        #[cfg(target_os = "macos")]
-        {
-            // On macOS, we would use IOBluetoothRFCOMMChannel
-            tokio::time::sleep(Duration::from_millis(500)).await;
-        }
+        let channel = self.open().await?;
 
         *self.connected.write() = true;
         self.connected_at = Some(Instant::now());
@@ -167,11 +189,8 @@ impl TransportTrait for RfcommTransport {
         // Platform-specific disconnect
         #[cfg(target_os = "linux")]
         {
-            if let Some(fd) = self.socket.take() {
-                unsafe {
-                    libc::close(fd);
-                }
-            }
+            // Dropping the descriptor closes the socket
+            self.socket = None;
         }
 
         *self.connected.write() = false;
@@ -189,9 +208,23 @@ impl TransportTrait for RfcommTransport {
             return Err(TransportError::Disconnected);
         }
 
-        // Platform-specific send
         tracing::debug!("Sending {} bytes over RFCOMM", data.len());
 
+        #[cfg(target_os = "linux")]
+        {
+            if let Some(socket) = &self.socket {
+                let mut written = 0;
+                while written < data.len() {
+                    let mut guard = socket.writable().await?;
+                    match guard.try_io(|fd| bluez::write(fd.as_raw_fd(), &data[written..])) {
+                        Ok(Ok(n)) => written += n,
+                        Ok(Err(e)) => return Err(TransportError::SendError(e.to_string())),
+                        Err(_would_block) => continue,
+                    }
+                }
+            }
+        }
+
         let mut stats = self.stats.write();
         stats.bytes_sent += data.len() as u64;
         stats.packets_sent += 1;
@@ -204,10 +237,33 @@ impl TransportTrait for RfcommTransport {
             return Err(TransportError::Disconnected);
         }
 
-        // Platform-specific receive
-        // This would be non-blocking read from the RFCOMM socket
+        #[cfg(target_os = "linux")]
+        {
+            if let Some(socket) = &self.socket {
+                let mut buffer = vec![0u8; 4096];
+                return match bluez::read(socket.as_raw_fd(), &mut buffer) {
+                    Ok(0) => {
+                        *self.connected.write() = false;
+                        Err(TransportError::Disconnected)
+                    }
+                    Ok(n) => {
+                        buffer.truncate(n);
+                        let bytes = Bytes::from(buffer);
+
+                        let mut stats = self.stats.write();
+                        stats.bytes_received += n as u64;
+                        stats.packets_received += 1;
+
+                        let _ = self.tx.send(bytes.clone());
+                        Ok(bytes)
+                    }
+                    Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => Ok(Bytes::new()),
+                    Err(e) => Err(TransportError::ReceiveError(e.to_string())),
+                };
+            }
+        }
 
-        // For now, return empty (no data available)
+        // No platform socket: nothing to read
         Ok(Bytes::new())
     }
 
@@ -236,6 +292,191 @@ impl TransportTrait for RfcommTransport {
     }
 }
 
+/// BlueZ RFCOMM sockets
+#[cfg(target_os = "linux")]
+mod bluez {
+    use std::io;
+    use std::os::fd::{AsRawFd, FromRawFd, OwnedFd, RawFd};
+
+    /// `BTPROTO_L2CAP` from `<bluetooth/bluetooth.h>`
+    const BTPROTO_L2CAP: libc::c_int = 0;
+    /// `BTPROTO_RFCOMM` from `<bluetooth/bluetooth.h>`
+    const BTPROTO_RFCOMM: libc::c_int = 3;
+    /// L2CAP PSM of the SDP server
+    const SDP_PSM: u16 = 0x0001;
+    /// Serial Port service class UUID
+    pub const SPP_UUID: u16 = 0x1101;
+    /// SDP `ServiceSearchAttribute` request and response PDU IDs
+    const SDP_SEARCH_ATTR_REQ: u8 = 0x06;
+    const SDP_SEARCH_ATTR_RSP: u8 = 0x07;
+    /// `ProtocolDescriptorList` attribute ID
+    const SDP_PROTOCOL_DESCRIPTORS: u16 = 0x0004;
+    /// Upper bound on continuation round trips for one query
+    const SDP_MAX_ROUNDS: u16 = 32;
+
+    /// `struct sockaddr_rc` from `<bluetooth/rfcomm.h>`
+    #[repr(C)]
+    struct SockaddrRc {
+        rc_family: libc::sa_family_t,
+        rc_bdaddr: [u8; 6],
+        rc_channel: u8,
+    }
+
+    /// `struct sockaddr_l2` from `<bluetooth/l2cap.h>`
+    #[repr(C)]
+    struct SockaddrL2 {
+        l2_family: libc::sa_family_t,
+        l2_psm: u16,
+        l2_bdaddr: [u8; 6],
+        l2_cid: u16,
+        l2_bdaddr_type: u8,
+    }
+
+    /// `bdaddr_t` is stored least significant byte first
+    fn bdaddr(address: [u8; 6]) -> [u8; 6] {
+        let mut bdaddr = address;
+        bdaddr.reverse();
+        bdaddr
+    }
+
+    /// Open a Bluetooth socket and connect it to `addr`, blocking
+    fn open<A>(kind: libc::c_int, protocol: libc::c_int, addr: &A) -> io::Result<OwnedFd> {
+        // SAFETY: plain socket(2) call; the descriptor is owned immediately
+        let fd = unsafe { libc::socket(libc::AF_BLUETOOTH, kind | libc::SOCK_CLOEXEC, protocol) };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        // SAFETY: `fd` is a fresh descriptor owned by nobody else
+        let socket = unsafe { OwnedFd::from_raw_fd(fd) };
+
+        // SAFETY: `addr` is a valid sockaddr of the length passed
+        let ret = unsafe {
+            libc::connect(
+                socket.as_raw_fd(),
+                addr as *const A as *const libc::sockaddr,
+                std::mem::size_of::<A>() as libc::socklen_t,
+            )
+        };
+        if ret < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(socket)
+    }
+
+    /// Ask the SDP server at `address` for the protocol descriptor lists of
+    /// services of class `uuid`; blocks, returns the raw attribute lists
+    pub fn sdp_search(address: [u8; 6], uuid: u16) -> io::Result<Vec<u8>> {
+        let addr = SockaddrL2 {
+            l2_family: libc::AF_BLUETOOTH as libc::sa_family_t,
+            l2_psm: SDP_PSM.to_le(),
+            l2_bdaddr: bdaddr(address),
+            l2_cid: 0,
+            l2_bdaddr_type: 0,
+        };
+        let socket = open(libc::SOCK_SEQPACKET, BTPROTO_L2CAP, &addr)?;
+
+        let mut attributes = Vec::new();
+        let mut continuation = Vec::new();
+        // Default L2CAP MTU is 672; leave room for larger negotiated ones
+        let mut buffer = [0u8; 4096];
+        for transaction in 1..=SDP_MAX_ROUNDS {
+            write(socket.as_raw_fd(), &sdp_request(transaction, uuid, &continuation))?;
+            let n = read(socket.as_raw_fd(), &mut buffer)?;
+            let (chunk, state) = sdp_response(&buffer[..n])
+                .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "malformed SDP response"))?;
+            attributes.extend_from_slice(chunk);
+            if state.is_empty() {
+                return Ok(attributes);
+            }
+            continuation = state.to_vec();
+        }
+        Err(io::Error::new(io::ErrorKind::InvalidData, "SDP response never completed"))
+    }
+
+    /// `ServiceSearchAttribute` request for the protocol descriptors of `uuid`
+    pub(super) fn sdp_request(transaction: u16, uuid: u16, continuation: &[u8]) -> Vec<u8> {
+        // Search pattern: sequence of one UUID16
+        let mut params = vec![0x35, 0x03, 0x19];
+        params.extend_from_slice(&uuid.to_be_bytes());
+        // Maximum attribute byte count per response
+        params.extend_from_slice(&0xFFFFu16.to_be_bytes());
+        // Attribute ID list: sequence of one UINT16
+        params.extend_from_slice(&[0x35, 0x03, 0x09]);
+        params.extend_from_slice(&SDP_PROTOCOL_DESCRIPTORS.to_be_bytes());
+        params.push(continuation.len() as u8);
+        params.extend_from_slice(continuation);
+
+        let mut pdu = vec![SDP_SEARCH_ATTR_REQ];
+        pdu.extend_from_slice(&transaction.to_be_bytes());
+        pdu.extend_from_slice(&(params.len() as u16).to_be_bytes());
+        pdu.extend_from_slice(&params);
+        pdu
+    }
+
+    /// Attribute bytes and continuation state of a `ServiceSearchAttribute`
+    /// response; `None` for error responses and malformed PDUs
+    pub(super) fn sdp_response(pdu: &[u8]) -> Option<(&[u8], &[u8])> {
+        if *pdu.first()? != SDP_SEARCH_ATTR_RSP {
+            return None;
+        }
+        let count = usize::from(u16::from_be_bytes([*pdu.get(5)?, *pdu.get(6)?]));
+        let attributes = pdu.get(7..7 + count)?;
+        let state_len = usize::from(*pdu.get(7 + count)?);
+        let state = pdu.get(8 + count..8 + count + state_len)?;
+        Some((attributes, state))
+    }
+
+    /// RFCOMM channel in SDP protocol descriptor lists
+    ///
+    /// The RFCOMM descriptor is encoded as `UUID16 0x0003, UINT8 channel`.
+    pub fn rfcomm_channel(attributes: &[u8]) -> Option<u8> {
+        attributes
+            .windows(5)
+            .find(|w| w[..4] == [0x19, 0x00, 0x03, 0x08])
+            .map(|w| w[4])
+    }
+
+    /// Open a stream socket to `address` (as written, most significant byte
+    /// first) on `channel`; blocks until connected, then turns non-blocking
+    pub fn connect(address: [u8; 6], channel: u8) -> io::Result<OwnedFd> {
+        let addr = SockaddrRc {
+            rc_family: libc::AF_BLUETOOTH as libc::sa_family_t,
+            rc_bdaddr: bdaddr(address),
+            rc_channel: channel,
+        };
+        let socket = open(libc::SOCK_STREAM, BTPROTO_RFCOMM, &addr)?;
+
+        // SAFETY: fcntl on a descriptor we own
+        let flags = unsafe { libc::fcntl(socket.as_raw_fd(), libc::F_GETFL) };
+        if flags < 0 || unsafe { libc::fcntl(socket.as_raw_fd(), libc::F_SETFL, flags | libc::O_NONBLOCK) } < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(socket)
+    }
+
+    /// Non-blocking read; `WouldBlock` when no data is pending
+    pub fn read(fd: RawFd, buf: &mut [u8]) -> io::Result<usize> {
+        // SAFETY: `buf` is valid for writes of its length
+        let n = unsafe { libc::read(fd, buf.as_mut_ptr().cast(), buf.len()) };
+        if n < 0 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(n as usize)
+        }
+    }
+
+    /// Non-blocking write; `WouldBlock` when the socket buffer is full
+    pub fn write(fd: RawFd, data: &[u8]) -> io::Result<usize> {
+        // SAFETY: `data` is valid for reads of its length
+        let n = unsafe { libc::write(fd, data.as_ptr().cast(), data.len()) };
+        if n < 0 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(n as usize)
+        }
+    }
+}
+
 /// Helper to format Bluetooth MAC address
 pub fn format_mac_address(addr: &[u8; 6]) -> String {
     format!(
@@ -270,6 +511,40 @@ mod tests {
         assert_eq!(format_mac_address(&addr), "AA:BB:CC:DD:EE:FF");
     }
 
+    #[test]
+    fn test_config_from_bluetooth() {
+        let config = RfcommConfig::from(&BluetoothConfig::rfcomm("00:11:22:33:44:55", 3));
+        assert_eq!(config.address, "00:11:22:33:44:55");
+        assert_eq!(config.channel, 3);
+        assert_eq!(config.timeout, Duration::from_secs(10));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_sdp_spp_lookup() {
+        let request = bluez::sdp_request(1, bluez::SPP_UUID, &[]);
+        assert_eq!(
+            request,
+            [0x06, 0x00, 0x01, 0x00, 0x0D, 0x35, 0x03, 0x19, 0x11, 0x01, 0xFF, 0xFF, 0x35, 0x03, 0x09, 0x00, 0x04, 0x00]
+        );
+
+        // One record: L2CAP, then RFCOMM on channel 5
+        let attributes = [
+            0x35, 0x11, 0x35, 0x0F, 0x09, 0x00, 0x04, 0x35, 0x0A, 0x35, 0x03, 0x19, 0x01, 0x00, 0x35, 0x05, 0x19,
+            0x00, 0x03, 0x08, 0x05,
+        ];
+        let mut response = vec![0x07, 0x00, 0x01, 0x00, 0x18, 0x00, attributes.len() as u8];
+        response.extend_from_slice(&attributes);
+        response.push(0x00);
+
+        let (found, continuation) = bluez::sdp_response(&response).unwrap();
+        assert!(continuation.is_empty());
+        assert_eq!(bluez::rfcomm_channel(found), Some(5));
+        assert_eq!(bluez::rfcomm_channel(&attributes[..10]), None);
+        // Error response
+        assert!(bluez::sdp_response(&[0x01, 0x00, 0x01, 0x00, 0x02, 0x00, 0x03]).is_none());
+    }
+
     #[test]
     fn test_parse_mac() {
         let addr = parse_mac_address("AA:BB:CC:DD:EE:FF").unwrap();
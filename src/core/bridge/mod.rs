@@ -11,8 +11,59 @@ use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::thread;
 use std::time::Duration;
-use std::io::{Read, Write};
+use std::io::{BufWriter, Read, Write};
+use std::fs::File;
+use std::path::PathBuf;
 use parking_lot::Mutex;
+use crate::core::packet::Packet;
+use crate::core::pcap::PcapWriter;
+use thiserror::Error;
+
+/// Bridge error
+#[derive(Debug, Error)]
+pub enum BridgeError {
+    /// `start` was called on a running bridge
+    #[error("Bridge already running")]
+    AlreadyRunning,
+
+    /// The traffic capture file could not be created
+    #[error("Capture failed: {0}")]
+    Capture(String),
+
+    /// Binding the TCP listener failed
+    #[error("Failed to bind port {port}: {source}")]
+    Bind {
+        /// Requested TCP port
+        port: u16,
+        /// Underlying error
+        #[source]
+        source: std::io::Error,
+    },
+
+    /// I/O error
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Shared handle to the bridged serial port
+type SerialHandle = Arc<Mutex<Box<dyn serialport::SerialPort>>>;
+
+/// Shared traffic capture, present when `log_traffic` is set
+type Capture = Option<Arc<Mutex<PcapWriter<BufWriter<File>>>>>;
+
+/// Initial delay before trying to reopen a lost serial port
+const SERIAL_REOPEN_INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+/// Upper bound for the serial reopen backoff
+const SERIAL_REOPEN_MAX_BACKOFF: Duration = Duration::from_secs(10);
+
+/// Why a bridged TCP connection ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ConnectionEnd {
+    /// Bridge stopped or TCP peer went away
+    Closed,
+    /// Serial port failed (e.g. USB adapter unplugged)
+    SerialLost,
+}
 
 /// Bridge mode
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -48,6 +99,8 @@ pub struct BridgeConfig {
     pub local_echo: bool,
     /// Log traffic
     pub log_traffic: bool,
+    /// PCAP file traffic is written to when `log_traffic` is set
+    pub capture_path: Option<PathBuf>,
 }
 
 impl Default for BridgeConfig {
@@ -62,6 +115,7 @@ impl Default for BridgeConfig {
             rfc2217: false,
             local_echo: false,
             log_traffic: false,
+            capture_path: None,
         }
     }
 }
@@ -108,9 +162,9 @@ impl Bridge {
     }
 
     /// Start the bridge
-    pub fn start(&mut self) -> Result<(), String> {
+    pub fn start(&mut self) -> Result<(), BridgeError> {
         if self.running.load(Ordering::Relaxed) {
-            return Err("Bridge already running".to_string());
+            return Err(BridgeError::AlreadyRunning);
         }
 
         *self.state.lock() = BridgeState::Starting;
@@ -121,15 +175,30 @@ impl Bridge {
         let state = self.state.clone();
         let stats = self.stats.clone();
 
+        let capture: Capture = match (&config.capture_path, config.log_traffic) {
+            (Some(path), true) => match PcapWriter::create(path) {
+                Ok(writer) => Some(Arc::new(Mutex::new(writer))),
+                Err(e) => {
+                    self.running.store(false, Ordering::Relaxed);
+                    *self.state.lock() = BridgeState::Error;
+                    return Err(BridgeError::Capture(e));
+                }
+            },
+            _ => None,
+        };
+
         let handle = thread::spawn(move || {
             match config.mode {
                 BridgeMode::SerialToTcpServer | BridgeMode::Bidirectional => {
-                    Self::run_server_mode(&config, &running, &state, &stats);
+                    Self::run_server_mode(&config, &running, &state, &stats, &capture);
                 }
                 BridgeMode::SerialToTcpClient | BridgeMode::TcpClientToSerial => {
-                    Self::run_client_mode(&config, &running, &state, &stats);
+                    Self::run_client_mode(&config, &running, &state, &stats, &capture);
                 }
             }
+            if let Some(capture) = &capture {
+                let _ = capture.lock().flush();
+            }
         });
 
         self.handle = Some(handle);
@@ -171,17 +240,14 @@ impl Bridge {
         running: &Arc<AtomicBool>,
         state: &Arc<Mutex<BridgeState>>,
         stats: &Arc<Mutex<BridgeStats>>,
+        capture: &Capture,
     ) {
         // Open serial port
-        let serial: Arc<Mutex<Box<dyn serialport::SerialPort + Send>>> = 
-            match serialport::new(&config.serial_port, config.baud_rate)
-                .timeout(Duration::from_millis(100))
-                .open()
-        {
-            Ok(s) => Arc::new(Mutex::new(s)),
+        let mut serial = match Self::open_serial(config) {
+            Ok(s) => s,
             Err(e) => {
                 *state.lock() = BridgeState::Error;
-                eprintln!("Failed to open serial port: {}", e);
+                tracing::error!("Failed to open serial port: {}", e);
                 return;
             }
         };
@@ -195,7 +261,7 @@ impl Bridge {
             Ok(l) => l,
             Err(e) => {
                 *state.lock() = BridgeState::Error;
-                eprintln!("Failed to bind TCP: {}", e);
+                tracing::error!("Failed to bind TCP: {}", e);
                 return;
             }
         };
@@ -209,13 +275,23 @@ impl Bridge {
                 stats.lock().connections += 1;
                 stream.set_nonblocking(true).ok();
                 
-                Self::handle_connection(
+                let end = Self::handle_connection(
                     stream,
                     serial.clone(),
                     config,
                     running,
+                    state,
                     stats,
+                    capture,
                 );
+
+                if end == ConnectionEnd::SerialLost {
+                    // Drop the TCP client and wait for the device to come back
+                    match Self::reopen_serial(config, running, state) {
+                        Some(s) => serial = s,
+                        None => return,
+                    }
+                }
             }
 
             thread::sleep(Duration::from_millis(10));
@@ -228,17 +304,14 @@ impl Bridge {
         running: &Arc<AtomicBool>,
         state: &Arc<Mutex<BridgeState>>,
         stats: &Arc<Mutex<BridgeStats>>,
+        capture: &Capture,
     ) {
         // Open serial port
-        let serial: Arc<Mutex<Box<dyn serialport::SerialPort + Send>>> = 
-            match serialport::new(&config.serial_port, config.baud_rate)
-                .timeout(Duration::from_millis(100))
-                .open()
-        {
-            Ok(s) => Arc::new(Mutex::new(s)),
+        let mut serial = match Self::open_serial(config) {
+            Ok(s) => s,
             Err(e) => {
                 *state.lock() = BridgeState::Error;
-                eprintln!("Failed to open serial port: {}", e);
+                tracing::error!("Failed to open serial port: {}", e);
                 return;
             }
         };
@@ -256,13 +329,22 @@ impl Bridge {
                     stats.lock().connections += 1;
                     stream.set_nonblocking(true).ok();
                     
-                    Self::handle_connection(
+                    let end = Self::handle_connection(
                         stream,
                         serial.clone(),
                         config,
                         running,
+                        state,
                         stats,
+                        capture,
                     );
+
+                    if end == ConnectionEnd::SerialLost {
+                        match Self::reopen_serial(config, running, state) {
+                            Some(s) => serial = s,
+                            None => return,
+                        }
+                    }
                 }
                 Err(_) => {
                     // Retry after delay
@@ -272,31 +354,126 @@ impl Bridge {
         }
     }
 
+    /// Open the configured serial port
+    fn open_serial(config: &BridgeConfig) -> Result<SerialHandle, serialport::Error> {
+        serialport::new(&config.serial_port, config.baud_rate)
+            .timeout(Duration::from_millis(100))
+            .open()
+            .map(|s| Arc::new(Mutex::new(s)))
+    }
+
+    /// Check whether a serial I/O error means the device is gone
+    ///
+    /// Timeouts and interruptions are part of normal polling; anything else
+    /// (broken pipe, no such device, ...) is treated as unrecoverable.
+    fn is_fatal_serial_error(err: &std::io::Error) -> bool {
+        !matches!(
+            err.kind(),
+            std::io::ErrorKind::TimedOut
+                | std::io::ErrorKind::WouldBlock
+                | std::io::ErrorKind::Interrupted
+        )
+    }
+
+    /// Mark the serial side as failed
+    fn report_serial_lost(
+        err: &std::io::Error,
+        state: &Arc<Mutex<BridgeState>>,
+        stats: &Arc<Mutex<BridgeStats>>,
+    ) {
+        *state.lock() = BridgeState::Error;
+        stats.lock().errors += 1;
+        tracing::warn!("Bridge serial port lost ({}), bridge degraded", err);
+    }
+
+    /// Try to reopen the serial port with exponential backoff
+    ///
+    /// Returns `None` if the bridge was stopped before the port came back.
+    fn reopen_serial(
+        config: &BridgeConfig,
+        running: &Arc<AtomicBool>,
+        state: &Arc<Mutex<BridgeState>>,
+    ) -> Option<SerialHandle> {
+        let mut backoff = SERIAL_REOPEN_INITIAL_BACKOFF;
+
+        while running.load(Ordering::Relaxed) {
+            // Sleep in small steps so stop() is not delayed by the backoff
+            let deadline = std::time::Instant::now() + backoff;
+            while running.load(Ordering::Relaxed) && std::time::Instant::now() < deadline {
+                thread::sleep(Duration::from_millis(50));
+            }
+            if !running.load(Ordering::Relaxed) {
+                break;
+            }
+
+            match Self::open_serial(config) {
+                Ok(serial) => {
+                    *state.lock() = BridgeState::Running;
+                    tracing::info!("Bridge serial port {} reopened", config.serial_port);
+                    return Some(serial);
+                }
+                Err(e) => {
+                    tracing::debug!(
+                        "Reopening {} failed: {}, retrying in {:?}",
+                        config.serial_port,
+                        e,
+                        backoff
+                    );
+                    backoff = (backoff * 2).min(SERIAL_REOPEN_MAX_BACKOFF);
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Record a chunk in the traffic capture, if enabled
+    ///
+    /// Direction is from the serial device's point of view: TCP → serial is
+    /// TX, serial → TCP is RX.
+    fn capture_packet(capture: &Capture, packet: Packet) {
+        if let Some(capture) = capture {
+            if let Err(e) = capture.lock().write_packet(&packet) {
+                tracing::warn!("Bridge capture write failed: {}", e);
+            }
+        }
+    }
+
     /// Handle a single TCP connection
     fn handle_connection(
         mut stream: TcpStream,
-        serial: Arc<Mutex<Box<dyn serialport::SerialPort + Send>>>,
+        serial: SerialHandle,
         config: &BridgeConfig,
         running: &Arc<AtomicBool>,
+        state: &Arc<Mutex<BridgeState>>,
         stats: &Arc<Mutex<BridgeStats>>,
-    ) {
+        capture: &Capture,
+    ) -> ConnectionEnd {
         let mut tcp_buf = vec![0u8; config.buffer_size];
         let mut serial_buf = vec![0u8; config.buffer_size];
 
         while running.load(Ordering::Relaxed) {
             // TCP -> Serial
             match stream.read(&mut tcp_buf) {
-                Ok(0) => break, // Connection closed
+                Ok(0) => return ConnectionEnd::Closed, // Connection closed
                 Ok(n) => {
                     let mut serial_guard = serial.lock();
-                    if serial_guard.write_all(&tcp_buf[..n]).is_ok() {
-                        let mut stats_guard = stats.lock();
-                        stats_guard.bytes_tcp_to_serial += n as u64;
-                        stats_guard.packets_tcp_to_serial += 1;
+                    match serial_guard.write_all(&tcp_buf[..n]) {
+                        Ok(()) => {
+                            let mut stats_guard = stats.lock();
+                            stats_guard.bytes_tcp_to_serial += n as u64;
+                            stats_guard.packets_tcp_to_serial += 1;
+                            Self::capture_packet(capture, Packet::tx(tcp_buf[..n].to_vec()));
+                        }
+                        Err(ref e) if Self::is_fatal_serial_error(e) => {
+                            Self::report_serial_lost(e, state, stats);
+                            return ConnectionEnd::SerialLost;
+                        }
+                        Err(_) => {}
                     }
                 }
                 Err(ref e) if e.kind() == std::io::ErrorKind::WouldBlock => {}
-                Err(_) => break,
+                Err(_) => return ConnectionEnd::Closed,
             }
 
             // Serial -> TCP
@@ -305,6 +482,7 @@ impl Bridge {
                 match serial_guard.read(&mut serial_buf) {
                     Ok(n) if n > 0 => {
                         drop(serial_guard); // Release lock before writing to TCP
+                        Self::capture_packet(capture, Packet::rx(serial_buf[..n].to_vec()));
                         if stream.write_all(&serial_buf[..n]).is_ok() {
                             let mut stats_guard = stats.lock();
                             stats_guard.bytes_serial_to_tcp += n as u64;
@@ -312,13 +490,18 @@ impl Bridge {
                         }
                     }
                     Ok(_) => {}
-                    Err(ref e) if e.kind() == std::io::ErrorKind::TimedOut => {}
+                    Err(ref e) if Self::is_fatal_serial_error(e) => {
+                        Self::report_serial_lost(e, state, stats);
+                        return ConnectionEnd::SerialLost;
+                    }
                     Err(_) => {}
                 }
             }
 
             thread::sleep(Duration::from_millis(1));
         }
+
+        ConnectionEnd::Closed
     }
 }
 
@@ -346,10 +529,9 @@ impl TcpServer {
     }
 
     /// Start listening
-    pub fn start(&mut self, port: u16) -> Result<(), String> {
-        let addr: SocketAddr = format!("0.0.0.0:{}", port).parse().unwrap();
-        let listener = TcpListener::bind(addr)
-            .map_err(|e| format!("Failed to bind: {}", e))?;
+    pub fn start(&mut self, port: u16) -> Result<(), BridgeError> {
+        let addr = SocketAddr::from(([0, 0, 0, 0], port));
+        let listener = TcpListener::bind(addr).map_err(|source| BridgeError::Bind { port, source })?;
         
         self.listener = Some(listener);
         self.running.store(true, Ordering::Relaxed);
@@ -390,4 +572,30 @@ mod tests {
         let stats = BridgeStats::default();
         assert_eq!(stats.bytes_serial_to_tcp, 0);
     }
+
+    #[test]
+    fn test_bridge_errors() {
+        let occupied = TcpListener::bind("0.0.0.0:0").unwrap();
+        let port = occupied.local_addr().unwrap().port();
+        let err = TcpServer::new().start(port).unwrap_err();
+        assert!(matches!(err, BridgeError::Bind { port: p, .. } if p == port));
+
+        let mut bridge = Bridge::new(BridgeConfig {
+            capture_path: Some(PathBuf::from("/nonexistent-dir/capture.pcap")),
+            log_traffic: true,
+            ..BridgeConfig::default()
+        });
+        assert!(matches!(bridge.start(), Err(BridgeError::Capture(_))));
+        assert_eq!(bridge.state(), BridgeState::Error);
+    }
+
+    #[test]
+    fn test_serial_error_classification() {
+        use std::io::{Error, ErrorKind};
+
+        assert!(!Bridge::is_fatal_serial_error(&Error::from(ErrorKind::TimedOut)));
+        assert!(!Bridge::is_fatal_serial_error(&Error::from(ErrorKind::Interrupted)));
+        assert!(Bridge::is_fatal_serial_error(&Error::from(ErrorKind::BrokenPipe)));
+        assert!(Bridge::is_fatal_serial_error(&Error::from(ErrorKind::NotFound)));
+    }
 }
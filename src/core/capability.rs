@@ -3,6 +3,7 @@
 //! Provides a unified way to query what features each transport supports.
 //! This enables dynamic UI enable/disable based on transport capabilities.
 
+use crate::core::transport::TransportType;
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 
@@ -200,6 +201,17 @@ impl CapabilitySet {
 pub struct CapabilityRegistry;
 
 impl CapabilityRegistry {
+    /// Get capabilities for a transport type (Bluetooth as BLE)
+    pub fn for_transport(transport: TransportType) -> CapabilitySet {
+        match transport {
+            TransportType::Serial => Self::serial(),
+            TransportType::Tcp => Self::tcp(),
+            TransportType::Telnet => Self::telnet(),
+            TransportType::Ssh => Self::ssh(),
+            TransportType::Bluetooth => Self::bluetooth_le(),
+        }
+    }
+
     /// Get capabilities for Serial transport
     pub fn serial() -> CapabilitySet {
         let mut caps = CapabilitySet::with_description("Serial Port (RS-232/RS-485/USB)");
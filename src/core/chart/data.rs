@@ -73,6 +73,10 @@ pub struct ChartChannel {
     pub visible: bool,
     /// Unit label
     pub unit: String,
+    /// Multiplier applied to raw values as they are added
+    pub scale: f64,
+    /// Added to raw values after scaling
+    pub offset: f64,
 }
 
 impl ChartChannel {
@@ -85,11 +89,43 @@ impl ChartChannel {
             max_points: 10000,
             visible: true,
             unit: String::new(),
+            scale: 1.0,
+            offset: 0.0,
         }
     }
 
-    /// Add a data point
+    /// Convert raw values to engineering units (`raw * scale + offset`)
+    ///
+    /// Applies to points added from now on; existing points keep their values.
+    pub fn with_scale(mut self, scale: f64, offset: f64, unit: &str) -> Self {
+        self.set_scale(scale, offset, unit);
+        self
+    }
+
+    /// Set the raw-to-unit conversion
+    pub fn set_scale(&mut self, scale: f64, offset: f64, unit: &str) {
+        self.scale = scale;
+        self.offset = offset;
+        self.unit = unit.to_string();
+    }
+
+    /// Apply the channel's scale and offset to a raw value
+    pub fn transform(&self, raw: f64) -> f64 {
+        raw * self.scale + self.offset
+    }
+
+    /// Name with unit, e.g. `vbat (V)`
+    pub fn label(&self) -> String {
+        if self.unit.is_empty() {
+            self.name.clone()
+        } else {
+            format!("{} ({})", self.name, self.unit)
+        }
+    }
+
+    /// Add a raw data point; `y` is scaled into the channel's unit
     pub fn add_point(&mut self, x: f64, y: f64) {
+        let y = self.transform(y);
         self.points.push_back(DataPoint::new(x, y));
         
         // Limit size
@@ -269,4 +305,19 @@ mod tests {
 
         assert_eq!(channel.len(), 3);
     }
+
+    #[test]
+    fn test_channel_scale() {
+        let mut channel = ChartChannel::new("vbat", Color32::RED).with_scale(3.3 / 4095.0, 0.0, "V");
+        channel.add_point(1.0, 4095.0);
+        channel.add_point(2.0, 0.0);
+
+        assert!((channel.last_value().unwrap() - 0.0).abs() < 1e-9);
+        let (_, max) = channel.y_range().unwrap();
+        assert!((max - 3.3).abs() < 1e-9);
+        assert_eq!(channel.label(), "vbat (V)");
+
+        let celsius = ChartChannel::new("t", Color32::RED).with_scale(0.1, -40.0, "°C");
+        assert!((celsius.transform(650.0) - 25.0).abs() < 1e-9);
+    }
 }
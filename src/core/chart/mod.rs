@@ -15,7 +15,7 @@ pub mod parser;
 pub use data::{ChartData, DataPoint, ChartConfig, ChartChannel};
 pub use export::{SvgExporter, DataExporter, ExportFormat, ExportConfig, ExportSeries};
 pub use markers::{DataMarker, MarkerType, MarkerShape, MarkerManager};
-pub use parser::{DataParser, ParserConfig};
+pub use parser::{DataParser, ParserConfig, ParserMode};
 
 use std::collections::HashMap;
 use eframe::egui::Color32;
@@ -148,9 +148,9 @@ impl ChartManager {
 
         // Header
         csv.push_str("Timestamp");
-        for name in self.channels.keys() {
+        for channel in self.channels.values() {
             csv.push(',');
-            csv.push_str(name);
+            csv.push_str(&channel.label());
         }
         csv.push('\n');
 
@@ -220,6 +220,8 @@ pub struct ChannelStats {
     pub max: f64,
     pub avg: f64,
     pub last: f64,
+    /// Unit of the values above
+    pub unit: String,
 }
 
 impl ChartChannel {
@@ -227,7 +229,10 @@ impl ChartChannel {
     pub fn stats(&self) -> ChannelStats {
         let points = self.points();
         if points.is_empty() {
-            return ChannelStats::default();
+            return ChannelStats {
+                unit: self.unit.clone(),
+                ..Default::default()
+            };
         }
 
         let mut min = f64::MAX;
@@ -246,6 +251,7 @@ impl ChartChannel {
             max,
             avg: sum / points.len() as f64,
             last: points.back().map(|p| p.y).unwrap_or(0.0),
+            unit: self.unit.clone(),
         }
     }
 }
@@ -275,4 +281,16 @@ mod tests {
         assert!(csv.contains("sensor1"));
         assert!(csv.contains("sensor2"));
     }
+
+    #[test]
+    fn test_scaled_channel_stats_and_csv() {
+        let mut manager = ChartManager::new();
+        manager.channel("adc").set_scale(3.3 / 4095.0, 0.0, "V");
+        manager.add_value("adc", 2048.0);
+
+        let stats = manager.channel_stats("adc").unwrap();
+        assert_eq!(stats.unit, "V");
+        assert!((stats.last - 2048.0 * 3.3 / 4095.0).abs() < 1e-9);
+        assert!(manager.export_csv().starts_with("Timestamp,adc (V)\n"));
+    }
 }
@@ -1,10 +1,12 @@
 //! Data parsing for chart visualization
 //!
-//! Supports multiple input formats: CSV, JSON, key=value, regex
+//! Supports multiple input formats: CSV, JSON, key=value, regex, NMEA 0183
 
 use std::collections::HashMap;
 use regex::Regex;
 
+use crate::core::protocol::{NmeaParser, NmeaSentence};
+
 /// Parser configuration
 #[derive(Debug, Clone)]
 pub struct ParserConfig {
@@ -41,6 +43,17 @@ impl Default for ParserConfig {
     }
 }
 
+impl ParserConfig {
+    /// Regex extraction config; named capture groups become channel names
+    pub fn regex(pattern: &str) -> Self {
+        Self {
+            mode: ParserMode::Regex,
+            regex_pattern: Some(pattern.to_string()),
+            ..Self::default()
+        }
+    }
+}
+
 /// Parser mode
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ParserMode {
@@ -58,6 +71,8 @@ pub enum ParserMode {
     Regex,
     /// Single numeric value
     SingleValue,
+    /// NMEA 0183 GPS sentences (speed_kn, altitude_m, sats, hdop)
+    Nmea,
 }
 
 impl ParserMode {
@@ -71,6 +86,7 @@ impl ParserMode {
             ParserMode::ArduinoPlotter,
             ParserMode::Regex,
             ParserMode::SingleValue,
+            ParserMode::Nmea,
         ]
     }
 
@@ -84,6 +100,7 @@ impl ParserMode {
             ParserMode::ArduinoPlotter => "Arduino Plotter",
             ParserMode::Regex => "Regex",
             ParserMode::SingleValue => "Single Value",
+            ParserMode::Nmea => "NMEA",
         }
     }
 }
@@ -93,6 +110,7 @@ pub struct DataParser {
     config: ParserConfig,
     compiled_regex: Option<Regex>,
     value_counter: usize,
+    nmea: NmeaParser,
 }
 
 impl Default for DataParser {
@@ -108,6 +126,7 @@ impl DataParser {
             config: ParserConfig::default(),
             compiled_regex: None,
             value_counter: 0,
+            nmea: NmeaParser::new(),
         }
     }
 
@@ -122,6 +141,15 @@ impl DataParser {
         self.config = config;
     }
 
+    /// Set configuration, rejecting an invalid regex pattern
+    pub fn try_set_config(&mut self, config: ParserConfig) -> Result<(), String> {
+        if let Some(ref pattern) = config.regex_pattern {
+            Regex::new(pattern).map_err(|e| format!("Invalid regex: {}", e))?;
+        }
+        self.set_config(config);
+        Ok(())
+    }
+
     /// Get configuration
     pub fn config(&self) -> &ParserConfig {
         &self.config
@@ -148,6 +176,7 @@ impl DataParser {
             ParserMode::ArduinoPlotter => self.parse_arduino(line),
             ParserMode::Regex => self.parse_regex(line),
             ParserMode::SingleValue => self.parse_single(line),
+            ParserMode::Nmea => self.parse_nmea(line),
         };
 
         // Apply prefix
@@ -166,6 +195,11 @@ impl DataParser {
     fn detect_mode(line: &str) -> ParserMode {
         let line = line.trim();
 
+        // Check for NMEA ($GPGGA,... / $GNRMC,...)
+        if Self::looks_like_nmea(line) {
+            return ParserMode::Nmea;
+        }
+
         // Check for JSON
         if line.starts_with('{') && line.ends_with('}') {
             return ParserMode::Json;
@@ -207,6 +241,7 @@ impl DataParser {
             ParserMode::KeyValue => self.parse_key_value(line),
             ParserMode::ArduinoPlotter => self.parse_arduino(line),
             ParserMode::SingleValue => self.parse_single(line),
+            ParserMode::Nmea => self.parse_nmea(line),
             _ => None,
         }
     }
@@ -299,7 +334,7 @@ impl DataParser {
         }
     }
 
-    /// Parse using regex
+    /// Parse using regex; captures that are not valid numbers are skipped
     fn parse_regex(&self, line: &str) -> Option<Vec<(String, f64)>> {
         let regex = self.compiled_regex.as_ref()?;
         let caps = regex.captures(line)?;
@@ -341,6 +376,52 @@ impl DataParser {
         self.value_counter += 1;
         Some(vec![("value".to_string(), value)])
     }
+
+    /// Check for a `$` followed by a five-character talker/sentence id
+    fn looks_like_nmea(line: &str) -> bool {
+        line.strip_prefix('$')
+            .and_then(|rest| rest.split(',').next())
+            .is_some_and(|id| id.len() == 5 && id.bytes().all(|b| b.is_ascii_alphanumeric()))
+    }
+
+    /// Parse an NMEA sentence into GPS channels
+    fn parse_nmea(&mut self, line: &str) -> Option<Vec<(String, f64)>> {
+        let mut result = Vec::new();
+
+        match self.nmea.parse(line).ok()? {
+            NmeaSentence::Gga(gga) => {
+                if let Some(altitude) = gga.altitude {
+                    result.push(("altitude_m".to_string(), f64::from(altitude)));
+                }
+                result.push(("sats".to_string(), f64::from(gga.satellites_used)));
+                if let Some(hdop) = gga.hdop {
+                    result.push(("hdop".to_string(), f64::from(hdop)));
+                }
+            }
+            NmeaSentence::Rmc(rmc) => {
+                if let Some(speed) = rmc.speed_knots {
+                    result.push(("speed_kn".to_string(), f64::from(speed)));
+                }
+            }
+            NmeaSentence::Vtg(vtg) => {
+                if let Some(speed) = vtg.speed_knots {
+                    result.push(("speed_kn".to_string(), f64::from(speed)));
+                }
+            }
+            NmeaSentence::Gsa(gsa) => {
+                if let Some(hdop) = gsa.hdop {
+                    result.push(("hdop".to_string(), f64::from(hdop)));
+                }
+            }
+            _ => {}
+        }
+
+        if result.is_empty() {
+            None
+        } else {
+            Some(result)
+        }
+    }
 }
 
 #[cfg(test)]
@@ -376,6 +457,23 @@ mod tests {
         assert_eq!(result.len(), 3);
     }
 
+    #[test]
+    fn test_parse_regex_named_captures() {
+        let mut parser = DataParser::new();
+        parser
+            .try_set_config(ParserConfig::regex(r"temp=(?P<temp>[\d.]+).*hum=(?P<hum>[\d.]+)"))
+            .unwrap();
+
+        let result = parser.parse_line("temp=21.5 hum=40").unwrap();
+        assert_eq!(result, vec![("temp".to_string(), 21.5), ("hum".to_string(), 40.0)]);
+
+        // Malformed capture is dropped, the rest of the line still charts
+        let result = parser.parse_line("temp=1.2.3 hum=41").unwrap();
+        assert_eq!(result, vec![("hum".to_string(), 41.0)]);
+
+        assert!(parser.try_set_config(ParserConfig::regex("(unclosed")).is_err());
+    }
+
     #[test]
     fn test_detect_mode() {
         assert_eq!(DataParser::detect_mode(r#"{"a":1}"#), ParserMode::Json);
@@ -383,5 +481,22 @@ mod tests {
         assert_eq!(DataParser::detect_mode("1,2,3"), ParserMode::Csv);
         assert_eq!(DataParser::detect_mode("1 2 3"), ParserMode::ArduinoPlotter);
         assert_eq!(DataParser::detect_mode("42"), ParserMode::SingleValue);
+        assert_eq!(DataParser::detect_mode("$GPGGA,123519,4807.038,N"), ParserMode::Nmea);
+    }
+
+    #[test]
+    fn test_parse_nmea() {
+        let mut parser = DataParser::new();
+        let gga = parser
+            .parse_line("$GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47")
+            .unwrap();
+        assert!(gga.contains(&("altitude_m".to_string(), 545.4f32 as f64)));
+        assert!(gga.contains(&("sats".to_string(), 8.0)));
+        assert!(gga.contains(&("hdop".to_string(), 0.9f32 as f64)));
+
+        let rmc = parser
+            .parse_line("$GPRMC,123519,A,4807.038,N,01131.000,E,022.4,084.4,230394,003.1,W*6A")
+            .unwrap();
+        assert_eq!(rmc, vec![("speed_kn".to_string(), 22.4f32 as f64)]);
     }
 }
@@ -2,6 +2,7 @@
 //!
 //! Supports different display formats:
 //! - Text (ASCII/UTF-8)
+//! - Control-safe text (`^C`, `\x1b`)
 //! - Hexadecimal
 //! - Binary
 //! - Mixed (hex + text)
@@ -10,7 +11,7 @@ mod hex;
 mod text;
 
 pub use self::hex::HexCodec;
-pub use text::TextCodec;
+pub use text::{TextCodec, Utf8Stream};
 
 use bytes::Bytes;
 use serde::{Deserialize, Serialize};
@@ -21,6 +22,8 @@ pub enum CodecType {
     /// Plain text (ASCII/UTF-8)
     #[default]
     Text,
+    /// UTF-8 text with control characters in caret/escape notation
+    TextSafe,
     /// Hexadecimal display
     Hex,
     /// Mixed hex and text
@@ -61,6 +64,7 @@ pub enum CodecError {
 pub fn create_codec(codec_type: CodecType) -> Box<dyn Codec> {
     match codec_type {
         CodecType::Text => Box::new(TextCodec::new()),
+        CodecType::TextSafe => Box::new(TextCodec::safe()),
         CodecType::Hex => Box::new(HexCodec::new()),
         CodecType::Mixed => Box::new(HexCodec::mixed()),
         CodecType::Binary => Box::new(HexCodec::binary()),
@@ -115,10 +119,116 @@ pub fn hexdump(data: &[u8], bytes_per_line: usize) -> String {
     output
 }
 
+/// Default longest line a text view shows before breaking it
+pub const DEFAULT_MAX_LINE_LEN: usize = 4096;
+
+/// Default printable share below which received data counts as binary
+pub const DEFAULT_BINARY_THRESHOLD: f32 = 0.7;
+
+/// Chunks shorter than this are too small to judge
+const BINARY_MIN_CHUNK: usize = 32;
+
+/// Share of the characters in `data` that display as text
+///
+/// Printable characters, whitespace and ESC count as text; other control
+/// characters and invalid UTF-8 do not.
+pub fn printable_ratio(data: &[u8]) -> f32 {
+    let text = String::from_utf8_lossy(data);
+    let (mut printable, mut total) = (0usize, 0usize);
+    for c in text.chars() {
+        total += 1;
+        let is_text = match c {
+            '\t' | '\r' | '\n' | '\x1b' => true,
+            char::REPLACEMENT_CHARACTER => false,
+            c => !c.is_control(),
+        };
+        printable += usize::from(is_text);
+    }
+    if total == 0 {
+        1.0
+    } else {
+        printable as f32 / total as f32
+    }
+}
+
+/// Break `line` into pieces of at most `max_chars` characters (0 = no limit)
+pub fn split_long_line(line: &str, max_chars: usize) -> Vec<&str> {
+    if max_chars == 0 {
+        return vec![line];
+    }
+    let mut pieces = Vec::new();
+    let mut rest = line;
+    while let Some((split, _)) = rest.char_indices().nth(max_chars) {
+        pieces.push(&rest[..split]);
+        rest = &rest[split..];
+    }
+    pieces.push(rest);
+    pieces
+}
+
+/// Notices when data shown as text turns binary
+///
+/// Reports once per binary stretch; a chunk that reads as text re-arms it.
+#[derive(Debug, Clone)]
+pub struct BinaryDetector {
+    threshold: f32,
+    in_binary: bool,
+}
+
+impl BinaryDetector {
+    /// Detector flagging chunks less than `threshold` printable (0 = off)
+    pub fn new(threshold: f32) -> Self {
+        Self {
+            threshold,
+            in_binary: false,
+        }
+    }
+
+    /// Check a chunk; returns its printable ratio when binary data starts
+    pub fn check(&mut self, data: &[u8]) -> Option<f32> {
+        if self.threshold <= 0.0 || data.len() < BINARY_MIN_CHUNK {
+            return None;
+        }
+        let ratio = printable_ratio(data);
+        let was_binary = std::mem::replace(&mut self.in_binary, ratio < self.threshold);
+        (self.in_binary && !was_binary).then_some(ratio)
+    }
+}
+
+impl Default for BinaryDetector {
+    fn default() -> Self {
+        Self::new(DEFAULT_BINARY_THRESHOLD)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_binary_detection() {
+        assert_eq!(printable_ratio(b"ls -la\r\n\x1b[0m"), 1.0);
+        assert!(printable_ratio(&[0x00, 0x01, 0xFF, b'a']) < 0.5);
+
+        let mut detector = BinaryDetector::default();
+        let text = b"temperature=21.5 humidity=40 ok\r\n".repeat(2);
+        let binary: Vec<u8> = (0u8..64).map(|b| b.wrapping_mul(37)).collect();
+        assert_eq!(detector.check(&text), None);
+        assert!(detector.check(&binary).is_some());
+        // Reported once until text resumes
+        assert_eq!(detector.check(&binary), None);
+        assert_eq!(detector.check(&text), None);
+        assert!(detector.check(&binary).is_some());
+    }
+
+    #[test]
+    fn test_split_long_line() {
+        assert_eq!(split_long_line("abcdefg", 3), vec!["abc", "def", "g"]);
+        assert_eq!(split_long_line("äöü", 2), vec!["äö", "ü"]);
+        assert_eq!(split_long_line("abc", 3), vec!["abc"]);
+        assert_eq!(split_long_line("abc", 0), vec!["abc"]);
+    }
+
     #[test]
     fn test_hexdump() {
         let data = b"Hello, World!";
@@ -12,6 +12,10 @@ pub struct TextCodecConfig {
     pub show_escape_sequences: bool,
     /// Character encoding
     pub encoding: TextEncoding,
+    /// Render control bytes as `^C`/`\x1b` and keep printable UTF-8
+    pub control_notation: bool,
+    /// With `control_notation`, show CR/LF as `\r`/`\n` (a newline still follows `\n`)
+    pub show_line_endings: bool,
 }
 
 impl Default for TextCodecConfig {
@@ -20,6 +24,8 @@ impl Default for TextCodecConfig {
             non_printable_char: '·',
             show_escape_sequences: false,
             encoding: TextEncoding::Utf8,
+            control_notation: false,
+            show_line_endings: false,
         }
     }
 }
@@ -54,6 +60,70 @@ impl TextCodec {
         Self { config }
     }
 
+    /// Control-safe text, as `minicom`/`screen` render it
+    ///
+    /// Printable UTF-8 is kept, C0 controls become caret notation (`^C`),
+    /// ESC is `\x1b`, and invalid bytes are `\xNN`. Tabs, CR and LF keep
+    /// their layout unless [`TextCodec::show_line_endings`] is set.
+    pub fn safe() -> Self {
+        Self::with_config(TextCodecConfig {
+            control_notation: true,
+            ..TextCodecConfig::default()
+        })
+    }
+
+    /// Show CR/LF explicitly in control-safe mode
+    #[must_use]
+    pub fn show_line_endings(mut self, show: bool) -> Self {
+        self.config.show_line_endings = show;
+        self
+    }
+
+    /// Append `c` in control-safe notation
+    fn push_safe(&self, output: &mut String, c: char) {
+        match c {
+            '\t' => output.push('\t'),
+            '\r' if self.config.show_line_endings => output.push_str("\\r"),
+            '\n' if self.config.show_line_endings => output.push_str("\\n\n"),
+            '\r' | '\n' => output.push(c),
+            '\x1b' => output.push_str("\\x1b"),
+            '\x7f' => output.push_str("^?"),
+            c if (c as u32) < 0x20 => {
+                output.push('^');
+                output.push((c as u8 + b'@') as char);
+            }
+            c if c.is_control() => output.push_str(&format!("\\u{{{:x}}}", c as u32)),
+            c => output.push(c),
+        }
+    }
+
+    /// Encode in control-safe notation
+    fn encode_safe(&self, data: &[u8]) -> String {
+        let mut output = String::with_capacity(data.len() + data.len() / 4);
+        let mut rest = data;
+
+        while !rest.is_empty() {
+            let (valid, invalid) = match std::str::from_utf8(rest) {
+                Ok(text) => (text, 0),
+                Err(e) => {
+                    let valid = std::str::from_utf8(&rest[..e.valid_up_to()]).unwrap_or_default();
+                    // A sequence cut off at the end of the chunk has no error_len
+                    (valid, e.error_len().unwrap_or(rest.len() - e.valid_up_to()))
+                }
+            };
+            for c in valid.chars() {
+                self.push_safe(&mut output, c);
+            }
+            let start = valid.len();
+            for byte in &rest[start..start + invalid] {
+                output.push_str(&format!("\\x{:02x}", byte));
+            }
+            rest = &rest[start + invalid..];
+        }
+
+        output
+    }
+
     /// Set non-printable character
     #[must_use]
     pub fn non_printable_char(mut self, c: char) -> Self {
@@ -77,6 +147,10 @@ impl Default for TextCodec {
 
 impl Codec for TextCodec {
     fn encode(&self, data: &[u8]) -> String {
+        if self.config.control_notation {
+            return self.encode_safe(data);
+        }
+
         let mut output = String::with_capacity(data.len() * 2);
 
         for &byte in data {
@@ -147,10 +221,63 @@ impl Codec for TextCodec {
     }
 
     fn codec_type(&self) -> CodecType {
-        CodecType::Text
+        if self.config.control_notation {
+            CodecType::TextSafe
+        } else {
+            CodecType::Text
+        }
     }
 }
 
+/// Splits a received byte stream at UTF-8 character boundaries
+///
+/// A multi-byte sequence cut off at the end of one chunk is held back and
+/// prepended to the next, so decoding each chunk on its own never renders
+/// half a character as `\xNN` or U+FFFD.
+#[derive(Debug, Clone, Default)]
+pub struct Utf8Stream {
+    pending: Vec<u8>,
+}
+
+impl Utf8Stream {
+    /// Create an empty stream
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Bytes of `data`, after any held-back prefix, up to the last complete character
+    pub fn push(&mut self, data: &[u8]) -> Vec<u8> {
+        let mut bytes = std::mem::take(&mut self.pending);
+        bytes.extend_from_slice(data);
+        let keep = incomplete_utf8_tail(&bytes);
+        self.pending = bytes.split_off(bytes.len() - keep);
+        bytes
+    }
+
+    /// Release the held-back bytes, e.g. when the connection closes
+    pub fn flush(&mut self) -> Vec<u8> {
+        std::mem::take(&mut self.pending)
+    }
+}
+
+/// Length of a UTF-8 sequence started but not finished at the end of `data`
+fn incomplete_utf8_tail(data: &[u8]) -> usize {
+    for back in 1..=data.len().min(3) {
+        let byte = data[data.len() - back];
+        if byte & 0xC0 == 0x80 {
+            continue;
+        }
+        let needed = match byte {
+            0xC2..=0xDF => 2,
+            0xE0..=0xEF => 3,
+            0xF0..=0xF4 => 4,
+            _ => return 0,
+        };
+        return if needed > back { back } else { 0 };
+    }
+    0
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -167,6 +294,35 @@ mod tests {
         assert_eq!(codec.encode(b"Hello\r\n"), "Hello\\r\\n");
     }
 
+    #[test]
+    fn test_encode_control_safe() {
+        let codec = TextCodec::safe();
+        assert_eq!(codec.encode(b"ok\x03\x1b[0m\x7f"), "ok^C\\x1b[0m^?");
+        assert_eq!(codec.encode("héllo\tµ\r\n".as_bytes()), "héllo\tµ\r\n");
+        assert_eq!(codec.encode(b"\x00bad\xff"), "^@bad\\xff");
+        assert_eq!(codec.codec_type(), CodecType::TextSafe);
+
+        let explicit = TextCodec::safe().show_line_endings(true);
+        assert_eq!(explicit.encode(b"a\r\nb"), "a\\r\\n\nb");
+    }
+
+    #[test]
+    fn test_utf8_stream_carries_split_characters() {
+        let codec = TextCodec::safe();
+        let mut stream = Utf8Stream::new();
+        let text = "é€😀".as_bytes();
+
+        assert_eq!(codec.encode(&stream.push(&text[..1])), "");
+        assert_eq!(codec.encode(&stream.push(&text[1..4])), "é");
+        assert_eq!(codec.encode(&stream.push(&text[4..6])), "€");
+        assert_eq!(codec.encode(&stream.push(&text[6..])), "😀");
+
+        // Invalid bytes are not held back
+        assert_eq!(codec.encode(&stream.push(b"a\xff")), "a\\xff");
+        assert_eq!(stream.push(b"\xe2\x82"), b"");
+        assert_eq!(stream.flush(), b"\xe2\x82");
+    }
+
     #[test]
     fn test_decode_escape_sequences() {
         let codec = TextCodec::new();
@@ -0,0 +1,305 @@
+//! Capture comparison
+//!
+//! Line-based diff of two captured sessions, e.g. a known-good firmware
+//! run against a new one. Packets are turned into transcript lines (sent
+//! lines prefixed with `> `), so timestamps and packet boundaries never
+//! count as differences.
+
+use crate::core::packet::Packet;
+use crate::core::transcript;
+
+/// Edit distance past which the diff stops looking for common lines
+///
+/// Keeps time and memory bounded for captures that have little in
+/// common; the rest is reported as removed and added lines.
+pub const MAX_DIFF_EDITS: usize = 2000;
+
+/// What counts as a difference
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DiffOptions {
+    /// Compare with leading/trailing whitespace trimmed and runs collapsed
+    pub ignore_whitespace: bool,
+    /// Compare with escape sequences (colors, cursor movement) removed
+    pub ignore_ansi: bool,
+}
+
+impl Default for DiffOptions {
+    fn default() -> Self {
+        Self {
+            ignore_whitespace: false,
+            ignore_ansi: true,
+        }
+    }
+}
+
+impl DiffOptions {
+    /// Ignore whitespace differences
+    #[must_use]
+    pub fn ignore_whitespace(mut self, ignore: bool) -> Self {
+        self.ignore_whitespace = ignore;
+        self
+    }
+
+    /// Ignore escape sequences
+    #[must_use]
+    pub fn ignore_ansi(mut self, ignore: bool) -> Self {
+        self.ignore_ansi = ignore;
+        self
+    }
+
+    /// Text the comparison is made on
+    fn key(&self, line: &str) -> String {
+        if self.ignore_whitespace {
+            line.split_whitespace().collect::<Vec<_>>().join(" ")
+        } else {
+            line.to_string()
+        }
+    }
+}
+
+/// One line of a diff; line numbers are 1-based
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffLine {
+    /// Line in both captures
+    Same { a: usize, b: usize, text: String },
+    /// Line only in the first capture
+    Removed { a: usize, text: String },
+    /// Line only in the second capture
+    Added { b: usize, text: String },
+    /// Line replaced by another at the same place
+    Changed { a: usize, b: usize, old: String, new: String },
+}
+
+impl DiffLine {
+    /// Check if the line is a difference
+    pub fn is_change(&self) -> bool {
+        !matches!(self, Self::Same { .. })
+    }
+}
+
+/// Diff the transcripts of two captures
+pub fn diff_transcripts(a: &[Packet], b: &[Packet], opts: DiffOptions) -> Vec<DiffLine> {
+    let old = transcript::lines(a, opts.ignore_ansi);
+    let new = transcript::lines(b, opts.ignore_ansi);
+    diff_lines(&old, &new, opts)
+}
+
+/// Diff two lists of lines
+pub fn diff_lines(old: &[String], new: &[String], opts: DiffOptions) -> Vec<DiffLine> {
+    let old_keys: Vec<String> = old.iter().map(|line| opts.key(line)).collect();
+    let new_keys: Vec<String> = new.iter().map(|line| opts.key(line)).collect();
+
+    let mut diff = Vec::new();
+    let (mut a, mut b) = (0, 0);
+    let mut removed = Vec::new();
+    let mut added = Vec::new();
+    for edit in edit_script(&old_keys, &new_keys) {
+        match edit {
+            Edit::Same => {
+                pair_changes(&mut diff, &mut removed, &mut added);
+                diff.push(DiffLine::Same {
+                    a: a + 1,
+                    b: b + 1,
+                    text: new[b].clone(),
+                });
+                a += 1;
+                b += 1;
+            }
+            Edit::Remove => {
+                removed.push(a);
+                a += 1;
+            }
+            Edit::Add => {
+                added.push(b);
+                b += 1;
+            }
+        }
+    }
+    pair_changes(&mut diff, &mut removed, &mut added);
+
+    // Pairing needs the text; fill it in from the line numbers
+    for line in &mut diff {
+        match line {
+            DiffLine::Removed { a, text } => *text = old[*a - 1].clone(),
+            DiffLine::Added { b, text } => *text = new[*b - 1].clone(),
+            DiffLine::Changed { a, b, old: old_text, new: new_text } => {
+                *old_text = old[*a - 1].clone();
+                *new_text = new[*b - 1].clone();
+            }
+            DiffLine::Same { .. } => {}
+        }
+    }
+    diff
+}
+
+/// Render a diff as text: `  ` same, `- ` removed, `+ ` added
+pub fn format_diff(diff: &[DiffLine]) -> String {
+    let mut out = String::new();
+    for line in diff {
+        match line {
+            DiffLine::Same { text, .. } => out.push_str(&format!("  {}\n", text)),
+            DiffLine::Removed { text, .. } => out.push_str(&format!("- {}\n", text)),
+            DiffLine::Added { text, .. } => out.push_str(&format!("+ {}\n", text)),
+            DiffLine::Changed { old, new, .. } => out.push_str(&format!("- {}\n+ {}\n", old, new)),
+        }
+    }
+    out
+}
+
+/// Turn a run of removed and added lines into changed pairs plus the rest
+fn pair_changes(diff: &mut Vec<DiffLine>, removed: &mut Vec<usize>, added: &mut Vec<usize>) {
+    let pairs = removed.len().min(added.len());
+    for (&a, &b) in removed.iter().zip(added.iter()) {
+        diff.push(DiffLine::Changed {
+            a: a + 1,
+            b: b + 1,
+            old: String::new(),
+            new: String::new(),
+        });
+    }
+    for &a in &removed[pairs..] {
+        diff.push(DiffLine::Removed { a: a + 1, text: String::new() });
+    }
+    for &b in &added[pairs..] {
+        diff.push(DiffLine::Added { b: b + 1, text: String::new() });
+    }
+    removed.clear();
+    added.clear();
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Edit {
+    Same,
+    Remove,
+    Add,
+}
+
+/// Shortest edit script from `a` to `b` (Myers' O(ND) algorithm)
+fn edit_script<T: PartialEq>(a: &[T], b: &[T]) -> Vec<Edit> {
+    let prefix = a.iter().zip(b).take_while(|(x, y)| x == y).count();
+    let suffix = a[prefix..]
+        .iter()
+        .rev()
+        .zip(b[prefix..].iter().rev())
+        .take_while(|(x, y)| x == y)
+        .count();
+
+    let mut script = vec![Edit::Same; prefix];
+    script.extend(middle_script(&a[prefix..a.len() - suffix], &b[prefix..b.len() - suffix]));
+    script.extend(std::iter::repeat(Edit::Same).take(suffix));
+    script
+}
+
+fn middle_script<T: PartialEq>(a: &[T], b: &[T]) -> Vec<Edit> {
+    let (n, m) = (a.len() as isize, b.len() as isize);
+    let max = (a.len() + b.len()).min(MAX_DIFF_EDITS) as isize;
+    let offset = max + 1;
+    let mut v = vec![0isize; 2 * offset as usize + 1];
+    // v[k] before step d, for k in -d..=d
+    let mut trace: Vec<Vec<isize>> = Vec::new();
+
+    let mut found = None;
+    'search: for d in 0..=max {
+        trace.push(v[(offset - d) as usize..=(offset + d) as usize].to_vec());
+        for k in (-d..=d).step_by(2) {
+            let index = (offset + k) as usize;
+            let mut x = if k == -d || (k != d && v[index - 1] < v[index + 1]) {
+                v[index + 1]
+            } else {
+                v[index - 1] + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && a[x as usize] == b[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            v[index] = x;
+            if x >= n && y >= m {
+                found = Some(d);
+                break 'search;
+            }
+        }
+    }
+
+    let Some(distance) = found else {
+        let mut script = vec![Edit::Remove; a.len()];
+        script.extend(std::iter::repeat(Edit::Add).take(b.len()));
+        return script;
+    };
+
+    let mut script = Vec::new();
+    let (mut x, mut y) = (n, m);
+    for d in (0..=distance).rev() {
+        let row = &trace[d as usize];
+        let at = |k: isize| row[(k + d) as usize];
+        let k = x - y;
+        let prev_k = if k == -d || (k != d && at(k - 1) < at(k + 1)) { k + 1 } else { k - 1 };
+        let prev_x = if d == 0 { 0 } else { at(prev_k) };
+        let prev_y = prev_x - prev_k;
+        while x > prev_x && y > prev_y {
+            script.push(Edit::Same);
+            x -= 1;
+            y -= 1;
+        }
+        if d > 0 {
+            script.push(if x == prev_x { Edit::Add } else { Edit::Remove });
+        }
+        x = prev_x;
+        y = prev_y;
+    }
+    script.reverse();
+    script
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(text: &str) -> Vec<String> {
+        text.lines().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn test_marks_added_removed_changed() {
+        let old = lines("boot\nversion 1.0\nmem ok\nready");
+        let new = lines("boot\nversion 1.1\nready\nwarn: fan");
+        let diff = diff_lines(&old, &new, DiffOptions::default());
+        assert_eq!(
+            diff,
+            [
+                DiffLine::Same { a: 1, b: 1, text: "boot".to_string() },
+                DiffLine::Changed { a: 2, b: 2, old: "version 1.0".to_string(), new: "version 1.1".to_string() },
+                DiffLine::Removed { a: 3, text: "mem ok".to_string() },
+                DiffLine::Same { a: 4, b: 3, text: "ready".to_string() },
+                DiffLine::Added { b: 4, text: "warn: fan".to_string() },
+            ]
+        );
+        assert_eq!(format_diff(&diff[3..]), "  ready\n+ warn: fan\n");
+    }
+
+    #[test]
+    fn test_transcripts_ignore_timing_and_escapes() {
+        let a = [Packet::tx(b"ver\r\n".to_vec()), Packet::rx(b"v1.2\r\nOK\r\n".to_vec())];
+        let b = [
+            Packet::tx(b"ver\r\n".to_vec()),
+            Packet::rx(b"\x1b[1mv1".to_vec()),
+            Packet::rx(b".2\x1b[0m  \r\nOK\r\n".to_vec()),
+        ];
+        let opts = DiffOptions::default();
+        assert!(diff_transcripts(&a, &b, opts).iter().any(DiffLine::is_change));
+        let opts = opts.ignore_whitespace(true);
+        assert!(!diff_transcripts(&a, &b, opts).iter().any(DiffLine::is_change));
+        let opts = opts.ignore_ansi(false);
+        assert!(diff_transcripts(&a, &b, opts).iter().any(DiffLine::is_change));
+    }
+
+    #[test]
+    fn test_edit_script_is_minimal() {
+        let a: Vec<char> = "ABCABBA".chars().collect();
+        let b: Vec<char> = "CBABAC".chars().collect();
+        let script = edit_script(&a, &b);
+        assert_eq!(script.iter().filter(|&&edit| edit != Edit::Same).count(), 5);
+        assert_eq!(script.iter().filter(|&&edit| edit != Edit::Add).count(), a.len());
+        assert_eq!(script.iter().filter(|&&edit| edit != Edit::Remove).count(), b.len());
+    }
+}
@@ -0,0 +1,631 @@
+//! Transport Settings Schema
+//!
+//! Describes each transport's configurable fields (name, type, default,
+//! allowed values) so frontends can render settings forms generically and
+//! validate input. Fields read and write the real config structs
+//! (`SerialConfig`, `SshConfig`, ...), and which optional fields appear is
+//! decided by the transport's capabilities.
+
+use crate::core::capability::{Capability, CapabilityRegistry, CapabilitySet};
+use crate::core::transport::{
+    BluetoothConfig, BluetoothType, SerialConfig, SerialFlowControl, SerialParity, SshAuth, SshConfig, TcpConfig,
+    TelnetConfig, Transport, TransportType,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use thiserror::Error;
+
+/// Type of a settings field
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub enum FieldKind {
+    /// Free text
+    Text,
+    /// Text that should be masked when shown
+    Secret,
+    /// File system path
+    Path,
+    /// Whole number within an inclusive range
+    Integer {
+        /// Smallest allowed value
+        min: i64,
+        /// Largest allowed value
+        max: i64,
+    },
+    /// On/off switch
+    Bool,
+    /// One of a fixed set of values
+    Choice(Vec<&'static str>),
+}
+
+/// Value of a settings field
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum FieldValue {
+    /// Text, secret, path or choice value
+    Text(String),
+    /// Integer value
+    Integer(i64),
+    /// Boolean value
+    Bool(bool),
+}
+
+impl FieldValue {
+    fn text(value: &str) -> Self {
+        Self::Text(value.to_string())
+    }
+
+    fn as_text(&self) -> &str {
+        match self {
+            Self::Text(text) => text,
+            _ => "",
+        }
+    }
+
+    fn as_int<T: TryFrom<i64> + Default>(&self) -> T {
+        match self {
+            Self::Integer(value) => T::try_from(*value).unwrap_or_default(),
+            _ => T::default(),
+        }
+    }
+
+    fn as_bool(&self) -> bool {
+        matches!(self, Self::Bool(true))
+    }
+}
+
+/// Settings schema error
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum SchemaError {
+    /// No field with this name
+    #[error("Unknown field: {0}")]
+    UnknownField(String),
+    /// Value has the wrong type for the field
+    #[error("Field {field} expects {expected}")]
+    WrongType {
+        /// Field name
+        field: String,
+        /// Expected type
+        expected: &'static str,
+    },
+    /// Integer outside the allowed range
+    #[error("Field {field} must be between {min} and {max}")]
+    OutOfRange {
+        /// Field name
+        field: String,
+        /// Smallest allowed value
+        min: i64,
+        /// Largest allowed value
+        max: i64,
+    },
+    /// Value not in the field's choices
+    #[error("Field {field} does not allow '{value}'")]
+    InvalidChoice {
+        /// Field name
+        field: String,
+        /// Rejected value
+        value: String,
+    },
+    /// Required field left empty
+    #[error("Field {0} is required")]
+    Required(String),
+    /// Config belongs to another transport
+    #[error("Expected a {0} configuration")]
+    TransportMismatch(TransportType),
+}
+
+type Getter = fn(&Transport) -> Option<FieldValue>;
+type Setter = fn(&mut Transport, &FieldValue) -> bool;
+
+/// One configurable field
+#[derive(Debug, Clone, Serialize)]
+pub struct FieldSchema {
+    /// Field name (stable key)
+    pub name: &'static str,
+    /// Human-readable label
+    pub label: &'static str,
+    /// Field type and constraints
+    pub kind: FieldKind,
+    /// Value in the transport's default config
+    pub default: FieldValue,
+    /// Must not be empty
+    pub required: bool,
+    #[serde(skip)]
+    get: Getter,
+    #[serde(skip)]
+    set: Setter,
+}
+
+impl FieldSchema {
+    /// Check a value against the field's type and constraints
+    pub fn validate(&self, value: &FieldValue) -> Result<(), SchemaError> {
+        let wrong_type = |expected| SchemaError::WrongType {
+            field: self.name.to_string(),
+            expected,
+        };
+
+        match (&self.kind, value) {
+            (FieldKind::Text | FieldKind::Secret | FieldKind::Path, FieldValue::Text(text)) => {
+                if self.required && text.trim().is_empty() {
+                    return Err(SchemaError::Required(self.name.to_string()));
+                }
+            }
+            (FieldKind::Integer { min, max }, FieldValue::Integer(n)) => {
+                if n < min || n > max {
+                    return Err(SchemaError::OutOfRange {
+                        field: self.name.to_string(),
+                        min: *min,
+                        max: *max,
+                    });
+                }
+            }
+            (FieldKind::Bool, FieldValue::Bool(_)) => {}
+            (FieldKind::Choice(choices), FieldValue::Text(text)) => {
+                if !choices.contains(&text.as_str()) {
+                    return Err(SchemaError::InvalidChoice {
+                        field: self.name.to_string(),
+                        value: text.clone(),
+                    });
+                }
+            }
+            (FieldKind::Text | FieldKind::Secret | FieldKind::Path | FieldKind::Choice(_), _) => {
+                return Err(wrong_type("text"));
+            }
+            (FieldKind::Integer { .. }, _) => return Err(wrong_type("an integer")),
+            (FieldKind::Bool, _) => return Err(wrong_type("a boolean")),
+        }
+        Ok(())
+    }
+}
+
+/// Settings schema for one transport type
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigSchema {
+    /// Transport described
+    pub transport: TransportType,
+    /// Fields in display order
+    pub fields: Vec<FieldSchema>,
+}
+
+/// Build a field bound to one `Transport` variant
+macro_rules! field {
+    ($variant:ident, $name:literal, $label:literal, $kind:expr,
+     get |$c:ident| $get:expr, set |$s:ident, $v:ident| $set:expr) => {
+        FieldSchema {
+            name: $name,
+            label: $label,
+            kind: $kind,
+            default: FieldValue::Bool(false),
+            required: false,
+            get: |transport| match transport {
+                Transport::$variant($c) => Some($get),
+                _ => None,
+            },
+            set: |transport, $v| match transport {
+                Transport::$variant($s) => {
+                    $set;
+                    true
+                }
+                _ => false,
+            },
+        }
+    };
+}
+
+const PORT_RANGE: FieldKind = FieldKind::Integer { min: 1, max: 65535 };
+const TIMEOUT_RANGE: FieldKind = FieldKind::Integer { min: 1, max: 3600 };
+
+fn parity_name(parity: SerialParity) -> &'static str {
+    match parity {
+        SerialParity::None => "none",
+        SerialParity::Odd => "odd",
+        SerialParity::Even => "even",
+        SerialParity::Mark => "mark",
+        SerialParity::Space => "space",
+    }
+}
+
+fn flow_name(flow: SerialFlowControl) -> &'static str {
+    match flow {
+        SerialFlowControl::None => "none",
+        SerialFlowControl::Hardware => "hardware",
+        SerialFlowControl::Software => "software",
+    }
+}
+
+fn auth_name(auth: &SshAuth) -> &'static str {
+    match auth {
+        SshAuth::Password(_) => "password",
+        SshAuth::PublicKey { .. } => "publickey",
+        SshAuth::Agent => "agent",
+        SshAuth::KeyboardInteractive => "keyboard-interactive",
+        SshAuth::None => "none",
+    }
+}
+
+fn set_auth(auth: &mut SshAuth, method: &str) {
+    if auth_name(auth) == method {
+        return;
+    }
+    *auth = match method {
+        "password" => SshAuth::Password(String::new()),
+        "publickey" => SshAuth::PublicKey {
+            private_key: PathBuf::new(),
+            passphrase: None,
+        },
+        "keyboard-interactive" => SshAuth::KeyboardInteractive,
+        "none" => SshAuth::None,
+        _ => SshAuth::Agent,
+    };
+}
+
+impl ConfigSchema {
+    /// Schema for a transport type, with defaults taken from its config struct
+    pub fn for_transport(transport: TransportType) -> Self {
+        let caps = CapabilityRegistry::for_transport(transport);
+        let fields = match transport {
+            TransportType::Serial => Self::serial_fields(&caps),
+            TransportType::Tcp => Self::tcp_fields(&caps),
+            TransportType::Telnet => Self::telnet_fields(),
+            TransportType::Ssh => Self::ssh_fields(&caps),
+            TransportType::Bluetooth => Self::bluetooth_fields(&caps),
+        };
+
+        let mut schema = Self { transport, fields };
+        let defaults = Self::default_config(transport);
+        for field in &mut schema.fields {
+            if let Some(value) = (field.get)(&defaults) {
+                field.default = value;
+            }
+        }
+        schema
+    }
+
+    /// Default config for a transport type
+    pub fn default_config(transport: TransportType) -> Transport {
+        match transport {
+            TransportType::Serial => Transport::Serial(SerialConfig::default()),
+            TransportType::Tcp => Transport::Tcp(TcpConfig::default()),
+            TransportType::Telnet => Transport::Telnet(TelnetConfig::default()),
+            TransportType::Ssh => Transport::Ssh(SshConfig::default()),
+            TransportType::Bluetooth => Transport::Bluetooth(BluetoothConfig::default()),
+        }
+    }
+
+    /// Look up a field by name
+    pub fn field(&self, name: &str) -> Option<&FieldSchema> {
+        self.fields.iter().find(|f| f.name == name)
+    }
+
+    /// Validate a single value
+    pub fn validate(&self, name: &str, value: &FieldValue) -> Result<(), SchemaError> {
+        self.field(name)
+            .ok_or_else(|| SchemaError::UnknownField(name.to_string()))?
+            .validate(value)
+    }
+
+    /// Current field values of a config
+    pub fn values(&self, config: &Transport) -> Result<HashMap<&'static str, FieldValue>, SchemaError> {
+        self.fields
+            .iter()
+            .map(|field| {
+                (field.get)(config)
+                    .map(|value| (field.name, value))
+                    .ok_or(SchemaError::TransportMismatch(self.transport))
+            })
+            .collect()
+    }
+
+    /// Validate `values` and write them into `config`
+    ///
+    /// Nothing is written unless every value is valid. Fields are applied in
+    /// schema order, so e.g. the SSH auth method is set before its password.
+    pub fn apply(&self, config: &mut Transport, values: &HashMap<String, FieldValue>) -> Result<(), SchemaError> {
+        if self.values(config).is_err() {
+            return Err(SchemaError::TransportMismatch(self.transport));
+        }
+        for (name, value) in values {
+            self.validate(name, value)?;
+        }
+        for field in &self.fields {
+            if let Some(value) = values.get(field.name) {
+                (field.set)(config, value);
+            }
+        }
+        Ok(())
+    }
+
+    /// Build a config from the defaults plus `values`
+    pub fn build(&self, values: &HashMap<String, FieldValue>) -> Result<Transport, SchemaError> {
+        let mut config = Self::default_config(self.transport);
+        self.apply(&mut config, values)?;
+
+        for field in self.fields.iter().filter(|f| f.required) {
+            if let Some(FieldValue::Text(text)) = (field.get)(&config) {
+                if text.trim().is_empty() {
+                    return Err(SchemaError::Required(field.name.to_string()));
+                }
+            }
+        }
+        Ok(config)
+    }
+
+    fn serial_fields(caps: &CapabilitySet) -> Vec<FieldSchema> {
+        let mut fields = vec![
+            FieldSchema {
+                required: true,
+                ..field!(Serial, "port", "Port", FieldKind::Text,
+                    get |c| FieldValue::Text(c.port.clone()),
+                    set |c, v| c.port = v.as_text().to_string())
+            },
+        ];
+        if caps.supports(Capability::BaudRate) {
+            fields.push(field!(Serial, "baud_rate", "Baud rate", FieldKind::Integer { min: 50, max: 4_000_000 },
+                get |c| FieldValue::Integer(c.baud_rate.into()),
+                set |c, v| c.baud_rate = v.as_int()));
+        }
+        if caps.supports(Capability::DataBits) {
+            fields.push(field!(Serial, "data_bits", "Data bits", FieldKind::Integer { min: 5, max: 8 },
+                get |c| FieldValue::Integer(c.data_bits.into()),
+                set |c, v| c.data_bits = v.as_int()));
+        }
+        if caps.supports(Capability::Parity) {
+            fields.push(field!(Serial, "parity", "Parity", FieldKind::Choice(vec!["none", "odd", "even", "mark", "space"]),
+                get |c| FieldValue::text(parity_name(c.parity)),
+                set |c, v| c.parity = v.as_text().parse().unwrap_or_default()));
+        }
+        if caps.supports(Capability::StopBits) {
+            fields.push(field!(Serial, "stop_bits", "Stop bits", FieldKind::Integer { min: 1, max: 2 },
+                get |c| FieldValue::Integer(c.stop_bits.into()),
+                set |c, v| c.stop_bits = v.as_int()));
+        }
+        if caps.supports_any(&[Capability::HardwareFlowControl, Capability::SoftwareFlowControl]) {
+            let mut choices = vec!["none"];
+            if caps.supports(Capability::HardwareFlowControl) {
+                choices.push("hardware");
+            }
+            if caps.supports(Capability::SoftwareFlowControl) {
+                choices.push("software");
+            }
+            fields.push(field!(Serial, "flow_control", "Flow control", FieldKind::Choice(choices),
+                get |c| FieldValue::text(flow_name(c.flow_control)),
+                set |c, v| c.flow_control = v.as_text().parse().unwrap_or_default()));
+        }
+        if caps.can_reconnect() {
+            fields.push(field!(Serial, "auto_reconnect", "Auto-reconnect", FieldKind::Bool,
+                get |c| FieldValue::Bool(c.auto_reconnect),
+                set |c, v| c.auto_reconnect = v.as_bool()));
+        }
+        fields
+    }
+
+    fn tcp_fields(caps: &CapabilitySet) -> Vec<FieldSchema> {
+        let mut fields = vec![
+            FieldSchema {
+                required: true,
+                ..field!(Tcp, "host", "Host", FieldKind::Text,
+                    get |c| FieldValue::Text(c.host.clone()),
+                    set |c, v| c.host = v.as_text().to_string())
+            },
+            field!(Tcp, "port", "Port", PORT_RANGE,
+                get |c| FieldValue::Integer(c.port.into()),
+                set |c, v| c.port = v.as_int()),
+        ];
+        if caps.supports(Capability::ConfigurableTimeout) {
+            fields.push(field!(Tcp, "timeout_secs", "Timeout (s)", TIMEOUT_RANGE,
+                get |c| FieldValue::Integer(i64::try_from(c.timeout_secs).unwrap_or(i64::MAX)),
+                set |c, v| c.timeout_secs = v.as_int()));
+        }
+        fields
+    }
+
+    fn telnet_fields() -> Vec<FieldSchema> {
+        vec![
+            FieldSchema {
+                required: true,
+                ..field!(Telnet, "host", "Host", FieldKind::Text,
+                    get |c| FieldValue::Text(c.host.clone()),
+                    set |c, v| c.host = v.as_text().to_string())
+            },
+            field!(Telnet, "port", "Port", PORT_RANGE,
+                get |c| FieldValue::Integer(c.port.into()),
+                set |c, v| c.port = v.as_int()),
+            field!(Telnet, "terminal_type", "Terminal type", FieldKind::Text,
+                get |c| FieldValue::Text(c.terminal_type.clone()),
+                set |c, v| c.terminal_type = v.as_text().to_string()),
+        ]
+    }
+
+    fn ssh_fields(caps: &CapabilitySet) -> Vec<FieldSchema> {
+        let mut methods = vec![];
+        if caps.supports(Capability::PasswordAuth) {
+            methods.extend(["password", "keyboard-interactive"]);
+        }
+        if caps.supports(Capability::KeyAuth) {
+            methods.push("publickey");
+        }
+        if caps.supports(Capability::AgentAuth) {
+            methods.push("agent");
+        }
+        methods.push("none");
+
+        let mut fields = vec![
+            FieldSchema {
+                required: true,
+                ..field!(Ssh, "host", "Host", FieldKind::Text,
+                    get |c| FieldValue::Text(c.host.clone()),
+                    set |c, v| c.host = v.as_text().to_string())
+            },
+            field!(Ssh, "port", "Port", PORT_RANGE,
+                get |c| FieldValue::Integer(c.port.into()),
+                set |c, v| c.port = v.as_int()),
+            FieldSchema {
+                required: true,
+                ..field!(Ssh, "username", "Username", FieldKind::Text,
+                    get |c| FieldValue::Text(c.username.clone()),
+                    set |c, v| c.username = v.as_text().to_string())
+            },
+            field!(Ssh, "auth", "Authentication", FieldKind::Choice(methods),
+                get |c| FieldValue::text(auth_name(&c.auth)),
+                set |c, v| set_auth(&mut c.auth, v.as_text())),
+            field!(Ssh, "password", "Password", FieldKind::Secret,
+                get |c| FieldValue::Text(match &c.auth {
+                    SshAuth::Password(password) => password.clone(),
+                    _ => String::new(),
+                }),
+                set |c, v| if let SshAuth::Password(password) = &mut c.auth {
+                    *password = v.as_text().to_string();
+                }),
+            field!(Ssh, "private_key", "Private key", FieldKind::Path,
+                get |c| FieldValue::Text(match &c.auth {
+                    SshAuth::PublicKey { private_key, .. } => private_key.display().to_string(),
+                    _ => String::new(),
+                }),
+                set |c, v| if let SshAuth::PublicKey { private_key, .. } = &mut c.auth {
+                    *private_key = PathBuf::from(v.as_text());
+                }),
+            field!(Ssh, "passphrase", "Key passphrase", FieldKind::Secret,
+                get |c| FieldValue::Text(match &c.auth {
+                    SshAuth::PublicKey { passphrase, .. } => passphrase.clone().unwrap_or_default(),
+                    _ => String::new(),
+                }),
+                set |c, v| if let SshAuth::PublicKey { passphrase, .. } = &mut c.auth {
+                    *passphrase = Some(v.as_text().to_string()).filter(|p| !p.is_empty());
+                }),
+        ];
+        if caps.supports(Capability::ConfigurableTimeout) {
+            fields.push(field!(Ssh, "timeout_secs", "Timeout (s)", TIMEOUT_RANGE,
+                get |c| FieldValue::Integer(i64::try_from(c.timeout_secs).unwrap_or(i64::MAX)),
+                set |c, v| c.timeout_secs = v.as_int()));
+        }
+        if caps.supports(Capability::KeepAlive) {
+            fields.push(field!(Ssh, "keepalive_secs", "Keepalive (s, 0 = off)", FieldKind::Integer { min: 0, max: 3600 },
+                get |c| FieldValue::Integer(i64::try_from(c.keepalive_secs).unwrap_or(i64::MAX)),
+                set |c, v| c.keepalive_secs = v.as_int()));
+        }
+        if caps.supports(Capability::Pty) {
+            fields.push(field!(Ssh, "request_pty", "Request PTY", FieldKind::Bool,
+                get |c| FieldValue::Bool(c.request_pty),
+                set |c, v| c.request_pty = v.as_bool()));
+            fields.push(field!(Ssh, "term_type", "Terminal type", FieldKind::Text,
+                get |c| FieldValue::Text(c.term_type.clone()),
+                set |c, v| c.term_type = v.as_text().to_string()));
+        }
+        fields.push(field!(Ssh, "compression", "Compression", FieldKind::Bool,
+            get |c| FieldValue::Bool(c.compression),
+            set |c, v| c.compression = v.as_bool()));
+        if caps.can_reconnect() {
+            fields.push(field!(Ssh, "auto_reconnect", "Auto-reconnect", FieldKind::Bool,
+                get |c| FieldValue::Bool(c.auto_reconnect),
+                set |c, v| c.auto_reconnect = v.as_bool()));
+        }
+        fields
+    }
+
+    fn bluetooth_fields(caps: &CapabilitySet) -> Vec<FieldSchema> {
+        let mut fields = vec![
+            FieldSchema {
+                required: true,
+                ..field!(Bluetooth, "device", "Device", FieldKind::Text,
+                    get |c| FieldValue::Text(c.device.clone()),
+                    set |c, v| c.device = v.as_text().to_string())
+            },
+            field!(Bluetooth, "bt_type", "Type", FieldKind::Choice(vec!["ble", "spp"]),
+                get |c| FieldValue::text(match c.bt_type {
+                    BluetoothType::Ble => "ble",
+                    BluetoothType::Spp => "spp",
+                }),
+                set |c, v| c.bt_type = if v.as_text() == "spp" { BluetoothType::Spp } else { BluetoothType::Ble }),
+            field!(Bluetooth, "channel", "RFCOMM channel", FieldKind::Integer { min: 0, max: 30 },
+                get |c| FieldValue::Integer(c.channel.into()),
+                set |c, v| c.channel = v.as_int()),
+        ];
+        if caps.supports(Capability::ConfigurableTimeout) {
+            fields.push(field!(Bluetooth, "timeout_secs", "Timeout (s)", TIMEOUT_RANGE,
+                get |c| FieldValue::Integer(i64::try_from(c.timeout_secs).unwrap_or(i64::MAX)),
+                set |c, v| c.timeout_secs = v.as_int()));
+        }
+        if caps.supports(Capability::BleGatt) {
+            fields.push(field!(Bluetooth, "mtu", "MTU", FieldKind::Integer { min: 23, max: 517 },
+                get |c| FieldValue::Integer(c.mtu.into()),
+                set |c, v| c.mtu = v.as_int()));
+            fields.push(field!(Bluetooth, "service_uuid", "Service UUID", FieldKind::Text,
+                get |c| FieldValue::Text(c.ble_service.service_uuid.clone()),
+                set |c, v| c.ble_service.service_uuid = v.as_text().to_string()));
+            fields.push(field!(Bluetooth, "tx_characteristic", "TX characteristic", FieldKind::Text,
+                get |c| FieldValue::Text(c.ble_service.tx_characteristic.clone()),
+                set |c, v| c.ble_service.tx_characteristic = v.as_text().to_string()));
+            fields.push(field!(Bluetooth, "rx_characteristic", "RX characteristic", FieldKind::Text,
+                get |c| FieldValue::Text(c.ble_service.rx_characteristic.clone()),
+                set |c, v| c.ble_service.rx_characteristic = v.as_text().to_string()));
+        }
+        if caps.can_reconnect() {
+            fields.push(field!(Bluetooth, "auto_reconnect", "Auto-reconnect", FieldKind::Bool,
+                get |c| FieldValue::Bool(c.auto_reconnect),
+                set |c, v| c.auto_reconnect = v.as_bool()));
+        }
+        fields
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(pairs: &[(&str, FieldValue)]) -> HashMap<String, FieldValue> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.clone())).collect()
+    }
+
+    #[test]
+    fn test_serial_schema_defaults_and_build() {
+        let schema = ConfigSchema::for_transport(TransportType::Serial);
+        assert_eq!(schema.field("baud_rate").unwrap().default, FieldValue::Integer(115200));
+        assert!(schema.field("port").unwrap().required);
+
+        let built = schema
+            .build(&values(&[
+                ("port", FieldValue::text("/dev/ttyUSB0")),
+                ("baud_rate", FieldValue::Integer(9600)),
+                ("parity", FieldValue::text("even")),
+            ]))
+            .unwrap();
+        let Transport::Serial(cfg) = built else { panic!("not serial") };
+        assert_eq!((cfg.port.as_str(), cfg.baud_rate, cfg.parity), ("/dev/ttyUSB0", 9600, SerialParity::Even));
+    }
+
+    #[test]
+    fn test_validation_errors() {
+        let schema = ConfigSchema::for_transport(TransportType::Tcp);
+        assert!(matches!(
+            schema.validate("port", &FieldValue::Integer(70000)),
+            Err(SchemaError::OutOfRange { .. })
+        ));
+        assert!(matches!(
+            schema.validate("port", &FieldValue::text("23")),
+            Err(SchemaError::WrongType { .. })
+        ));
+        assert_eq!(
+            schema.validate("baud_rate", &FieldValue::Integer(9600)),
+            Err(SchemaError::UnknownField("baud_rate".to_string()))
+        );
+        assert_eq!(schema.build(&values(&[("host", FieldValue::text(" "))])).unwrap_err(), SchemaError::Required("host".to_string()));
+
+        let mut serial = ConfigSchema::default_config(TransportType::Serial);
+        assert_eq!(schema.apply(&mut serial, &HashMap::new()), Err(SchemaError::TransportMismatch(TransportType::Tcp)));
+    }
+
+    #[test]
+    fn test_ssh_auth_applied_before_password() {
+        let schema = ConfigSchema::for_transport(TransportType::Ssh);
+        let built = schema
+            .build(&values(&[
+                ("host", FieldValue::text("router")),
+                ("password", FieldValue::text("hunter2")),
+                ("auth", FieldValue::text("password")),
+            ]))
+            .unwrap();
+        let Transport::Ssh(cfg) = built else { panic!("not ssh") };
+        assert!(matches!(cfg.auth, SshAuth::Password(ref p) if p == "hunter2"));
+        assert_eq!(schema.values(&Transport::Ssh(cfg)).unwrap()["auth"], FieldValue::text("password"));
+    }
+}
@@ -0,0 +1,257 @@
+//! Connection Event Log
+//!
+//! Appends per-profile connect/disconnect events to a JSON Lines file in the
+//! data directory for auditing and connection statistics
+
+use crate::core::session::{Session, SessionEvent, SessionState};
+use crate::core::storage::StorageError;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+
+/// What happened to the connection
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ConnectionEventKind {
+    /// Connection established (or re-established)
+    Connected,
+    /// Connection ended
+    Disconnected,
+    /// Waiting to make a reconnect attempt (1-based)
+    Reconnecting(u32),
+}
+
+/// A logged connection state change
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConnectionEvent {
+    /// Profile the session was opened from
+    pub profile_id: String,
+    /// What happened
+    pub kind: ConnectionEventKind,
+    /// When it happened
+    pub timestamp: DateTime<Utc>,
+    /// Why the connection ended, for `Disconnected`
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+impl ConnectionEvent {
+    /// Event stamped with the current time
+    pub fn now(profile_id: &str, kind: ConnectionEventKind, reason: Option<String>) -> Self {
+        Self {
+            profile_id: profile_id.to_string(),
+            kind,
+            timestamp: Utc::now(),
+            reason,
+        }
+    }
+}
+
+/// Connection history of one profile
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProfileActivity {
+    /// Number of times the profile connected
+    pub sessions: usize,
+    /// Most recent connection
+    pub last_connected: Option<DateTime<Utc>>,
+    /// Time spent connected, over sessions that have ended
+    pub connected_time: Duration,
+    /// Reason the most recent session ended
+    pub last_disconnect_reason: Option<String>,
+}
+
+/// Append-only connection event log
+#[derive(Debug, Clone)]
+pub struct EventLog {
+    path: PathBuf,
+}
+
+impl EventLog {
+    /// Log in the application data directory
+    pub fn new() -> Self {
+        let path = crate::config::data_dir()
+            .map(|dir| dir.join("connections.jsonl"))
+            .unwrap_or_else(|| PathBuf::from("connections.jsonl"));
+        Self::with_path(path)
+    }
+
+    /// Log stored at `path`
+    pub fn with_path(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    /// Log file path
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append an event
+    pub fn append(&self, event: &ConnectionEvent) -> Result<(), StorageError> {
+        let io_error = |source| StorageError::Io {
+            path: self.path.clone(),
+            source,
+        };
+        let mut line = serde_json::to_string(event).map_err(StorageError::Serialize)?;
+        line.push('\n');
+
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).map_err(io_error)?;
+        }
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path).map_err(io_error)?;
+        file.write_all(line.as_bytes()).map_err(io_error)
+    }
+
+    /// Events for `profile_id` at or after `since`, oldest first
+    pub fn query(&self, profile_id: &str, since: DateTime<Utc>) -> Result<Vec<ConnectionEvent>, StorageError> {
+        Ok(self
+            .read_all()?
+            .into_iter()
+            .filter(|event| event.profile_id == profile_id && event.timestamp >= since)
+            .collect())
+    }
+
+    /// Totals over all logged events for `profile_id`
+    pub fn activity(&self, profile_id: &str) -> Result<ProfileActivity, StorageError> {
+        let mut activity = ProfileActivity::default();
+        let mut connected_at = None;
+        for event in self.query(profile_id, DateTime::<Utc>::MIN_UTC)? {
+            match event.kind {
+                ConnectionEventKind::Connected => {
+                    activity.sessions += 1;
+                    activity.last_connected = Some(event.timestamp);
+                    connected_at = Some(event.timestamp);
+                }
+                ConnectionEventKind::Disconnected => {
+                    if let Some(start) = connected_at.take() {
+                        activity.connected_time = activity.connected_time + (event.timestamp - start);
+                    }
+                    activity.last_disconnect_reason = event.reason;
+                }
+                ConnectionEventKind::Reconnecting(_) => {}
+            }
+        }
+        Ok(activity)
+    }
+
+    /// Log `session`'s state changes under `profile_id` until it is dropped
+    ///
+    /// A session that is already connected is logged as connecting now.
+    pub fn attach(&self, profile_id: &str, session: &Session) -> JoinHandle<()> {
+        let log = self.clone();
+        let profile_id = profile_id.to_string();
+        let mut events = session.subscribe();
+        if session.is_connected() {
+            log.record(&profile_id, ConnectionEventKind::Connected, None);
+        }
+
+        tokio::spawn(async move {
+            loop {
+                let (kind, reason) = match events.recv().await {
+                    Ok(SessionEvent::StateChanged(SessionState::Connected)) => (ConnectionEventKind::Connected, None),
+                    Ok(SessionEvent::StateChanged(SessionState::Reconnecting { attempt, .. })) => {
+                        (ConnectionEventKind::Reconnecting(attempt), None)
+                    }
+                    Ok(SessionEvent::Disconnected(reason)) => {
+                        (ConnectionEventKind::Disconnected, Some(reason.to_string()))
+                    }
+                    Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => break,
+                };
+                log.record(&profile_id, kind, reason);
+            }
+        })
+    }
+
+    fn record(&self, profile_id: &str, kind: ConnectionEventKind, reason: Option<String>) {
+        if let Err(e) = self.append(&ConnectionEvent::now(profile_id, kind, reason)) {
+            tracing::warn!("Could not write connection event: {}", e);
+        }
+    }
+
+    fn read_all(&self) -> Result<Vec<ConnectionEvent>, StorageError> {
+        let content = match fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(source) => {
+                return Err(StorageError::Io {
+                    path: self.path.clone(),
+                    source,
+                })
+            }
+        };
+
+        // A torn or hand-edited line must not hide the rest of the history
+        Ok(content
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .filter_map(|(index, line)| match serde_json::from_str(line) {
+                Ok(event) => Some(event),
+                Err(e) => {
+                    tracing::warn!("Skipping bad line {} in {}: {}", index + 1, self.path.display(), e);
+                    None
+                }
+            })
+            .collect())
+    }
+}
+
+impl Default for EventLog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(kind: ConnectionEventKind, minutes: i64, reason: Option<&str>) -> ConnectionEvent {
+        ConnectionEvent {
+            profile_id: "board".to_string(),
+            kind,
+            timestamp: DateTime::from_timestamp(1_700_000_000 + minutes * 60, 0).unwrap(),
+            reason: reason.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_query_and_activity() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = EventLog::with_path(dir.path().join("events").join("connections.jsonl"));
+        assert!(log.query("board", DateTime::<Utc>::MIN_UTC).unwrap().is_empty());
+
+        log.append(&event(ConnectionEventKind::Connected, 0, None)).unwrap();
+        log.append(&event(ConnectionEventKind::Disconnected, 10, Some("Connection timed out"))).unwrap();
+        log.append(&ConnectionEvent { profile_id: "other".to_string(), ..event(ConnectionEventKind::Connected, 15, None) }).unwrap();
+        log.append(&event(ConnectionEventKind::Connected, 20, None)).unwrap();
+        log.append(&event(ConnectionEventKind::Disconnected, 25, Some("Disconnected by user"))).unwrap();
+
+        let since = DateTime::from_timestamp(1_700_000_000 + 20 * 60, 0).unwrap();
+        assert_eq!(log.query("board", since).unwrap().len(), 2);
+
+        let activity = log.activity("board").unwrap();
+        assert_eq!(activity.sessions, 2);
+        assert_eq!(activity.connected_time, Duration::minutes(15));
+        assert_eq!(activity.last_connected, Some(since));
+        assert_eq!(activity.last_disconnect_reason.as_deref(), Some("Disconnected by user"));
+    }
+
+    #[test]
+    fn test_bad_lines_are_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("connections.jsonl");
+        let log = EventLog::with_path(path.clone());
+        log.append(&event(ConnectionEventKind::Connected, 0, None)).unwrap();
+        // A line mangled by hand or by a bad write
+        let mut content = fs::read_to_string(&path).unwrap();
+        content.push_str("{\"profile_id\":\"board\",\"ki\n");
+        fs::write(&path, content).unwrap();
+        log.append(&event(ConnectionEventKind::Disconnected, 5, None)).unwrap();
+
+        assert_eq!(log.query("board", DateTime::<Utc>::MIN_UTC).unwrap().len(), 2);
+    }
+}
@@ -6,8 +6,11 @@
 //! - Heatmap generation
 //! - Automated optimization
 
+use crate::core::session::{Session, SessionError};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 /// Parameter definition
@@ -117,6 +120,43 @@ pub struct ExperimentResult {
     pub notes: String,
 }
 
+/// Safety bound on measured values during a sweep
+#[derive(Clone)]
+pub struct SafetyLimit {
+    check: Arc<dyn Fn(f64) -> bool + Send + Sync>,
+    /// Command that puts the equipment in a safe state
+    pub on_abort: Vec<u8>,
+}
+
+impl SafetyLimit {
+    /// Whether a measured value is within bounds
+    pub fn allows(&self, value: f64) -> bool {
+        (self.check)(value)
+    }
+}
+
+impl fmt::Debug for SafetyLimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SafetyLimit").field("on_abort", &self.on_abort).finish_non_exhaustive()
+    }
+}
+
+/// A sweep halted by its safety check
+#[derive(Debug, Clone, PartialEq)]
+pub struct SafetyAbort {
+    /// Which value broke the bound
+    pub reason: String,
+    /// Safe-state command to send right away
+    pub command: Vec<u8>,
+}
+
+impl SafetyAbort {
+    /// Send the safe-state command, bypassing the session's guard bytes
+    pub async fn send(&self, session: &Session) -> Result<(), SessionError> {
+        session.send_forced(&self.command).await
+    }
+}
+
 /// Experiment definition
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Experiment {
@@ -140,6 +180,12 @@ pub struct Experiment {
     pub created: String,
     /// Completed timestamp
     pub completed: Option<String>,
+    /// Safety bound checked against every measured metric (not saved)
+    #[serde(skip)]
+    pub safety: Option<SafetyLimit>,
+    /// Why the safety check stopped the sweep
+    #[serde(default)]
+    pub abort_reason: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -150,6 +196,8 @@ pub enum ExperimentStatus {
     Completed,
     Failed,
     Cancelled,
+    /// Stopped by the safety check
+    Aborted,
 }
 
 impl Experiment {
@@ -165,9 +213,38 @@ impl Experiment {
             status: ExperimentStatus::Pending,
             created: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
             completed: None,
+            safety: None,
+            abort_reason: None,
         }
     }
 
+    /// Halt the sweep and send `on_abort` when a measured value fails `limit_check`
+    ///
+    /// `limit_check` returns true for safe values. It is applied to every
+    /// metric of each result passed to [`ExperimentRunner::record_result`].
+    #[must_use]
+    pub fn with_safety(mut self, limit_check: impl Fn(f64) -> bool + Send + Sync + 'static, on_abort: Vec<u8>) -> Self {
+        self.safety = Some(SafetyLimit {
+            check: Arc::new(limit_check),
+            on_abort,
+        });
+        self
+    }
+
+    /// First metric of `result` outside the safety bound, as an abort reason
+    pub fn safety_violation(&self, result: &ExperimentResult) -> Option<String> {
+        let safety = self.safety.as_ref()?;
+        let mut metrics: Vec<_> = result.metrics.iter().collect();
+        metrics.sort_by(|a, b| a.0.cmp(b.0));
+        metrics
+            .into_iter()
+            .find(|(_, &value)| !safety.allows(value))
+            .map(|(name, value)| {
+                let unit = self.metrics.iter().find(|m| &m.name == name).map_or("", |m| m.unit.as_str());
+                format!("{} = {}{} out of safe range at {:?}", name, value, unit, result.parameters)
+            })
+    }
+
     /// Add a parameter
     pub fn add_parameter(&mut self, param: Parameter) {
         self.parameters.push(param);
@@ -406,6 +483,29 @@ impl ExperimentRunner {
         Some(config)
     }
 
+    /// Record a run's result for the current experiment
+    ///
+    /// If a measured value breaks the experiment's safety bound the sweep
+    /// stops at once (no further configurations are handed out), the reason
+    /// is recorded, and the safe-state command is returned for the caller to
+    /// send immediately.
+    pub fn record_result(&mut self, result: ExperimentResult) -> Option<SafetyAbort> {
+        let idx = self.current_experiment?;
+        let experiment = self.experiments.get_mut(idx)?;
+        let violation = experiment.safety_violation(&result);
+        experiment.add_result(result);
+
+        let reason = violation?;
+        tracing::warn!("Experiment '{}' aborted: {}", experiment.name, reason);
+        experiment.status = ExperimentStatus::Aborted;
+        experiment.abort_reason = Some(reason.clone());
+        experiment.completed = Some(chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string());
+        self.current_experiment = None;
+
+        let command = experiment.safety.as_ref().map(|s| s.on_abort.clone()).unwrap_or_default();
+        Some(SafetyAbort { reason, command })
+    }
+
     /// Complete current experiment
     pub fn complete(&mut self) {
         if let Some(idx) = self.current_experiment {
@@ -418,6 +518,44 @@ impl ExperimentRunner {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(current: f64) -> ExperimentResult {
+        ExperimentResult {
+            parameters: HashMap::from([("voltage".to_string(), current * 10.0)]),
+            metrics: HashMap::from([("current".to_string(), current)]),
+            duration_ms: 5,
+            success: true,
+            error: None,
+            notes: String::new(),
+        }
+    }
 
-
-
+    #[test]
+    fn test_safety_abort_halts_sweep() {
+        let mut experiment = Experiment::new("PSU sweep").with_safety(|amps| amps <= 2.0, b"OUTP OFF\n".to_vec());
+        experiment.add_parameter(Parameter::range("voltage", 0.0, 50.0, 10.0, "V"));
+        experiment.add_metric("current", "A", false);
+        experiment.repetitions = 1;
+
+        let mut runner = ExperimentRunner::new();
+        let idx = runner.add_experiment(experiment);
+        runner.start(idx).unwrap();
+
+        assert!(runner.next_configuration().is_some());
+        assert!(runner.record_result(result(1.5)).is_none());
+
+        assert!(runner.next_configuration().is_some());
+        let abort = runner.record_result(result(2.5)).unwrap();
+        assert_eq!(abort.command, b"OUTP OFF\n");
+        assert!(abort.reason.starts_with("current = 2.5A"));
+
+        assert!(runner.next_configuration().is_none());
+        let experiment = &runner.experiments[idx];
+        assert_eq!(experiment.status, ExperimentStatus::Aborted);
+        assert_eq!(experiment.results.len(), 2);
+        assert_eq!(experiment.abort_reason.as_deref(), Some(abort.reason.as_str()));
+    }
+}
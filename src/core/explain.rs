@@ -6,6 +6,8 @@
 //! - Troubleshooting suggestions
 //! - Rule-based diagnostic engine
 
+use crate::core::session::ConnectDiagnostics;
+use crate::core::transport::TransportError;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -16,6 +18,10 @@ pub enum Symptom {
     ConnectionTimeout,
     /// Connection refused
     ConnectionRefused,
+    /// Host name did not resolve
+    DnsFailure,
+    /// TCP opened but the protocol handshake never completed
+    HandshakeTimeout,
     /// CRC/checksum error
     ChecksumError,
     /// Frame error
@@ -36,6 +42,8 @@ pub enum Symptom {
     AuthenticationFailed,
     /// Permission denied
     PermissionDenied,
+    /// Device or port not present
+    DeviceNotFound,
     /// Resource busy
     ResourceBusy,
     /// Buffer overflow
@@ -227,6 +235,32 @@ impl ExplainEngine {
                 .suggest("Check device documentation for correct baud rate")
         );
 
+        self.add_rule(
+            DiagnosticRule::new("Serial Port Missing")
+                .when_symptom(Symptom::DeviceNotFound)
+                .when_context("transport", "serial")
+                .cause(
+                    "The serial device is not connected or has a different name",
+                    0.85,
+                    CauseCategory::Hardware
+                )
+                .suggest("Check the USB cable and that the device is powered")
+                .suggest("Refresh the port list; USB adapters can change names when replugged")
+        );
+
+        self.add_rule(
+            DiagnosticRule::new("Serial Permission Denied")
+                .when_symptom(Symptom::PermissionDenied)
+                .when_context("transport", "serial")
+                .cause(
+                    "Your user may not access the serial device",
+                    0.90,
+                    CauseCategory::Security
+                )
+                .suggest("Add your user to the dialout (or uucp) group and log in again")
+                .suggest("Check the device node permissions")
+        );
+
         self.add_rule(
             DiagnosticRule::new("Wrong Parity/Stop Bits")
                 .when_symptom(Symptom::FrameError)
@@ -299,7 +333,48 @@ impl ExplainEngine {
                 .suggest("Use netstat to verify listening ports")
         );
 
+        self.add_rule(
+            DiagnosticRule::new("Unknown Host")
+                .when_symptom(Symptom::DnsFailure)
+                .cause(
+                    "Host name could not be resolved",
+                    0.90,
+                    CauseCategory::Network
+                )
+                .suggest("Check the host name for typos")
+                .suggest("Try the IP address directly")
+                .suggest("Verify DNS settings or VPN connection")
+        );
+
+        self.add_rule(
+            DiagnosticRule::new("Port Unreachable")
+                .when_symptom(Symptom::ConnectionTimeout)
+                .when_context("tcp_open", "false")
+                .cause(
+                    "Host resolved but the port never answered",
+                    0.85,
+                    CauseCategory::Network
+                )
+                .suggest("Check firewall rules between you and the host")
+                .suggest("Verify the port number")
+                .suggest("Check the host is powered and on the network")
+        );
+
         // SSH rules
+        self.add_rule(
+            DiagnosticRule::new("SSH Handshake Stalled")
+                .when_symptom(Symptom::HandshakeTimeout)
+                .when_context("transport", "ssh")
+                .cause(
+                    "TCP connected but the SSH server did not complete key exchange",
+                    0.80,
+                    CauseCategory::Protocol
+                )
+                .suggest("Check the port really runs an SSH server")
+                .suggest("Check the server's MaxStartups / connection limits")
+                .suggest("Look for reverse DNS delays on the server (UseDNS)")
+        );
+
         self.add_rule(
             DiagnosticRule::new("SSH Auth Failed")
                 .when_symptom(Symptom::AuthenticationFailed)
@@ -476,6 +551,57 @@ impl ExplainEngine {
 
         self.diagnose(&context)
     }
+
+    /// First suggestion for `symptom` with the given context, if any rule matches
+    pub fn hint(&self, symptom: Symptom, context: &[(&str, &str)]) -> Option<String> {
+        let mut diagnostic = DiagnosticContext::new();
+        diagnostic.add_symptom(symptom);
+        for (key, value) in context {
+            diagnostic.set_context(key, value);
+        }
+        self.diagnose(&diagnostic)
+            .recommended_actions
+            .into_iter()
+            .next()
+    }
+
+    /// Explain a failed connection attempt using how far it got
+    pub fn explain_connect(&self, diagnostics: &ConnectDiagnostics, error: &TransportError) -> DiagnosticResult {
+        let mut context = DiagnosticContext::new();
+        context.set_context("transport", &diagnostics.transport.to_string().to_lowercase());
+        context.add_error(&error.to_string());
+
+        let flags = [
+            ("dns_resolved", diagnostics.dns_resolved),
+            ("tcp_open", diagnostics.tcp_open),
+            ("handshake_reached", diagnostics.handshake_reached),
+        ];
+        for (key, value) in flags {
+            if let Some(value) = value {
+                context.set_context(key, &value.to_string());
+            }
+        }
+
+        let symptom = match error {
+            _ if diagnostics.dns_resolved == Some(false) => Some(Symptom::DnsFailure),
+            TransportError::AuthFailed(_) => Some(Symptom::AuthenticationFailed),
+            TransportError::PermissionDenied(_) => Some(Symptom::PermissionDenied),
+            TransportError::PortNotFound(_) => Some(Symptom::DeviceNotFound),
+            TransportError::PortInUse(_) => Some(Symptom::ResourceBusy),
+            TransportError::IoError(e) if e.kind() == std::io::ErrorKind::ConnectionRefused => {
+                Some(Symptom::ConnectionRefused)
+            }
+            _ if diagnostics.timed_out && diagnostics.tcp_open == Some(true) => Some(Symptom::HandshakeTimeout),
+            _ if diagnostics.timed_out => Some(Symptom::ConnectionTimeout),
+            _ if diagnostics.tcp_open == Some(false) => Some(Symptom::ConnectionRefused),
+            _ => None,
+        };
+        if let Some(symptom) = symptom {
+            context.add_symptom(symptom);
+        }
+
+        self.diagnose(&context)
+    }
 }
 
 
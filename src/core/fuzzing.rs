@@ -5,6 +5,7 @@
 //! - Timing Fuzzer  
 //! - Boundary value testing
 //! - Protocol stress testing
+//! - Parser fuzz targets with seed corpora
 
 use serde::{Deserialize, Serialize};
 use std::time::{Duration, Instant};
@@ -476,6 +477,158 @@ pub mod patterns {
     }
 }
 
+/// Parser fuzz targets
+///
+/// Each target feeds arbitrary bytes to a parser that reads untrusted data
+/// off the wire. Parsers must reject bad input with an error, never panic.
+pub mod targets {
+    use crate::core::protocol::checksum::crc16_modbus;
+    use crate::core::protocol::modbus::{
+        build_rtu_request, build_rtu_write_multiple_registers, build_rtu_write_single_register, build_tcp_request,
+        parse_rtu_frame, parse_rtu_request, parse_tcp_frame, FunctionCode,
+    };
+    use crate::core::protocol::nmea::NmeaParser;
+
+    /// A parser entry point and valid inputs to mutate
+    #[derive(Debug, Clone)]
+    pub struct FuzzTarget {
+        pub name: &'static str,
+        pub run: fn(&[u8]),
+        pub corpus: Vec<Vec<u8>>,
+    }
+
+    /// Modbus RTU frames, as responses and as requests
+    ///
+    /// Also parses the input with its CRC fixed, so mutations reach past
+    /// the CRC check.
+    pub fn modbus_rtu(data: &[u8]) {
+        let _ = parse_rtu_frame(data);
+        let _ = parse_rtu_request(data);
+        if data.len() >= 2 {
+            let fixed = with_crc(data[..data.len() - 2].to_vec());
+            let _ = parse_rtu_frame(&fixed);
+            let _ = parse_rtu_request(&fixed);
+        }
+    }
+
+    /// Modbus TCP frames
+    pub fn modbus_tcp(data: &[u8]) {
+        let _ = parse_tcp_frame(data);
+    }
+
+    /// NMEA sentences, one per line
+    ///
+    /// Each line is also parsed without its checksum, which is optional,
+    /// so mutations reach the field parsers.
+    pub fn nmea(data: &[u8]) {
+        let mut parser = NmeaParser::new();
+        for line in String::from_utf8_lossy(data).lines() {
+            let _ = parser.parse(line);
+            if let Some((unchecked, _)) = line.rsplit_once('*') {
+                let _ = parser.parse(unchecked);
+            }
+        }
+    }
+
+    fn with_crc(mut frame: Vec<u8>) -> Vec<u8> {
+        let crc = crc16_modbus(&frame);
+        frame.extend_from_slice(&crc.to_le_bytes());
+        frame
+    }
+
+    /// All parser targets with their seed corpora
+    pub fn all() -> Vec<FuzzTarget> {
+        vec![
+            FuzzTarget {
+                name: "modbus_rtu",
+                run: modbus_rtu,
+                corpus: vec![
+                    build_rtu_request(1, FunctionCode::ReadHoldingRegisters, 0, 10),
+                    build_rtu_write_single_register(1, 0x10, 0x1234),
+                    build_rtu_write_multiple_registers(7, 0x10, &[1, 2, 3]),
+                    with_crc(vec![0x01, 0x03, 0x04, 0x00, 0x64, 0x01, 0x2C]),
+                    with_crc(vec![0x01, 0x01, 0x01, 0x05]),
+                    with_crc(vec![0x01, 0x83, 0x02]),
+                ],
+            },
+            FuzzTarget {
+                name: "modbus_tcp",
+                run: modbus_tcp,
+                corpus: vec![
+                    build_tcp_request(1, 1, FunctionCode::ReadHoldingRegisters, 0, 10),
+                    vec![0x00, 0x01, 0x00, 0x00, 0x00, 0x07, 0x01, 0x03, 0x04, 0x00, 0x64, 0x01, 0x2C],
+                    vec![0x00, 0x02, 0x00, 0x00, 0x00, 0x03, 0x01, 0x83, 0x02],
+                ],
+            },
+            FuzzTarget {
+                name: "nmea",
+                run: nmea,
+                corpus: [
+                    "$GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47",
+                    "$GPRMC,123519,A,4807.038,N,01131.000,E,022.4,084.4,230394,003.1,W*6A",
+                    "$GPGSV,2,1,08,01,40,083,46,02,17,308,41,12,07,344,39,14,22,228,45*75",
+                    "$GPGSA,A,3,04,05,,09,12,,,24,,,,,2.5,1.3,2.1*39",
+                    "$GPVTG,054.7,T,034.4,M,005.5,N,010.2,K*48",
+                    "$GPGLL,4916.45,N,12311.12,W,225444,A*31",
+                    "$GPZDA,201530.00,04,07,2002,00,00*60",
+                    "$HEHDT,274.07,T*03",
+                    "$SDDBT,7.8,f,2.4,M,1.3,F*0D",
+                ]
+                .iter()
+                .map(|sentence| sentence.as_bytes().to_vec())
+                .collect(),
+            },
+        ]
+    }
+}
 
+/// Outcome of running a target over its corpus
+#[derive(Debug, Clone, Default)]
+pub struct CorpusReport {
+    /// Inputs tried
+    pub runs: usize,
+    /// Inputs that made the parser panic
+    pub panics: Vec<Vec<u8>>,
+}
 
+/// Run `target` on its corpus, every truncation of it and `iterations`
+/// mutated inputs
+pub fn run_corpus(target: &targets::FuzzTarget, fuzzer: &mut PacketFuzzer, iterations: usize) -> CorpusReport {
+    let mut report = CorpusReport::default();
+    let mut check = |input: &[u8]| {
+        report.runs += 1;
+        if std::panic::catch_unwind(|| (target.run)(input)).is_err() {
+            report.panics.push(input.to_vec());
+        }
+    };
+
+    for seed in &target.corpus {
+        for len in 0..=seed.len() {
+            check(&seed[..len]);
+        }
+    }
+    if !target.corpus.is_empty() {
+        for i in 0..iterations {
+            let input = fuzzer.fuzz_packet(&target.corpus[i % target.corpus.len()]);
+            check(&input);
+        }
+    }
+    report
+}
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parser_targets_do_not_panic() {
+        let mut fuzzer = PacketFuzzer::with_seed(0x5eed);
+        fuzzer.add_strategy(FuzzStrategy::LengthFuzz { min_length: 0, max_length: 64 });
+        fuzzer.add_strategy(FuzzStrategy::Dictionary { patterns: patterns::modbus_fuzz() });
+        for target in targets::all() {
+            let report = run_corpus(&target, &mut fuzzer, 5000);
+            assert!(report.runs > 5000);
+            assert!(report.panics.is_empty(), "{} panicked on {:02X?}", target.name, report.panics[0]);
+        }
+    }
+}
@@ -0,0 +1,338 @@
+//! Persistent Command History
+//!
+//! Keeps typed commands per profile/transport across restarts. The file is
+//! an append-only JSON Lines log replayed on load; it is rewritten only when
+//! compacted.
+
+use crate::core::storage::StorageError;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+
+/// Default number of commands kept per session key
+pub const DEFAULT_HISTORY_SIZE: usize = 1000;
+
+/// Command history store, keyed by profile ID or connection string
+pub struct HistoryStore {
+    sessions: HashMap<String, VecDeque<String>>,
+    /// Keys whose commands are never recorded
+    excluded: HashSet<String>,
+    max_entries: usize,
+    config_path: PathBuf,
+}
+
+impl HistoryStore {
+    /// Create a store backed by the default history file
+    pub fn new() -> Self {
+        let mut store = Self::with_path(Self::get_config_path());
+        if let Err(e) = store.load() {
+            tracing::error!("Failed to load history: {}", e);
+        }
+        store
+    }
+
+    /// Create an empty store saving to `path`
+    pub fn with_path(path: PathBuf) -> Self {
+        Self {
+            sessions: HashMap::new(),
+            excluded: HashSet::new(),
+            max_entries: DEFAULT_HISTORY_SIZE,
+            config_path: path,
+        }
+    }
+
+    /// Get config path
+    fn get_config_path() -> PathBuf {
+        if let Some(proj_dirs) = directories::ProjectDirs::from("com", "termicon", "Termicon") {
+            let config_dir = proj_dirs.config_dir();
+            let _ = fs::create_dir_all(config_dir);
+            config_dir.join("history.jsonl")
+        } else {
+            PathBuf::from("history.jsonl")
+        }
+    }
+
+    /// Load history from disk, compacting the log if it has grown
+    pub fn load(&mut self) -> Result<(), StorageError> {
+        let content = match fs::read_to_string(&self.config_path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(source) => return Err(self.io_error(source)),
+        };
+
+        self.sessions.clear();
+        self.excluded.clear();
+        let mut records = 0;
+        // A torn or hand-edited line must not hide the rest of the history
+        for (index, line) in content.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()) {
+            match serde_json::from_str(line) {
+                Ok(record) => {
+                    self.apply(record);
+                    records += 1;
+                }
+                Err(e) => tracing::warn!("Skipping bad line {} in {}: {}", index + 1, self.config_path.display(), e),
+            }
+        }
+        self.trim_all();
+
+        if records > 2 * self.records().len() + COMPACT_SLACK {
+            self.save()?;
+        }
+        Ok(())
+    }
+
+    /// Rewrite the history file with only the current state
+    pub fn save(&self) -> Result<(), StorageError> {
+        let mut content = String::new();
+        for record in self.records() {
+            content.push_str(&serde_json::to_string(&record).map_err(StorageError::Serialize)?);
+            content.push('\n');
+        }
+        fs::write(&self.config_path, content).map_err(|source| self.io_error(source))
+    }
+
+    /// Record a command and append it to the history file
+    ///
+    /// Returns false if nothing was recorded (blank command or excluded key).
+    pub fn append(&mut self, key: &str, command: &str) -> Result<bool, StorageError> {
+        if !self.push(key, command) {
+            return Ok(false);
+        }
+
+        self.append_record(&HistoryRecord::Push {
+            key: key.to_string(),
+            command: command.to_string(),
+        })?;
+        Ok(true)
+    }
+
+    /// Record a command; a repeated command moves to the newest position
+    ///
+    /// Returns false if nothing was recorded (blank command or excluded key).
+    /// Only changes memory; see [`HistoryStore::append`].
+    pub fn push(&mut self, key: &str, command: &str) -> bool {
+        if command.trim().is_empty() || self.is_excluded(key) {
+            return false;
+        }
+
+        let entries = self.sessions.entry(key.to_string()).or_default();
+        entries.retain(|c| c != command);
+        entries.push_back(command.to_string());
+        while entries.len() > self.max_entries {
+            entries.pop_front();
+        }
+        true
+    }
+
+    /// Commands for a key, oldest first
+    pub fn entries(&self, key: &str) -> Vec<String> {
+        self.sessions
+            .get(key)
+            .map(|entries| entries.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Commands starting with `prefix`, newest first (reverse-i-search)
+    pub fn search(&self, key: &str, prefix: &str) -> Vec<&str> {
+        self.sessions
+            .get(key)
+            .map(|entries| {
+                entries
+                    .iter()
+                    .rev()
+                    .filter(|c| c.starts_with(prefix))
+                    .map(String::as_str)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Stop (or resume) recording a key; excluding also forgets its history
+    pub fn set_excluded(&mut self, key: &str, excluded: bool) {
+        if excluded {
+            self.excluded.insert(key.to_string());
+            self.sessions.remove(key);
+        } else {
+            self.excluded.remove(key);
+        }
+    }
+
+    /// Whether a key is excluded from history
+    pub fn is_excluded(&self, key: &str) -> bool {
+        self.excluded.contains(key)
+    }
+
+    /// Forget a key's history, recording that in the history file
+    pub fn clear(&mut self, key: &str) -> Result<(), StorageError> {
+        self.sessions.remove(key);
+        self.append_record(&HistoryRecord::Clear { key: key.to_string() })
+    }
+
+    /// Set the number of commands kept per key
+    pub fn set_max_entries(&mut self, max: usize) {
+        self.max_entries = max.max(1);
+        self.trim_all();
+    }
+
+    /// Number of commands kept per key
+    pub fn max_entries(&self) -> usize {
+        self.max_entries
+    }
+
+    /// Replay one line of the history file
+    fn apply(&mut self, record: HistoryRecord) {
+        match record {
+            HistoryRecord::Push { key, command } => {
+                self.push(&key, &command);
+            }
+            HistoryRecord::Exclude { key } => self.set_excluded(&key, true),
+            HistoryRecord::Clear { key } => {
+                self.sessions.remove(&key);
+            }
+            HistoryRecord::MaxEntries { max } => self.max_entries = max.max(1),
+        }
+    }
+
+    /// The current state as history file lines
+    fn records(&self) -> Vec<HistoryRecord> {
+        let mut records = vec![HistoryRecord::MaxEntries { max: self.max_entries }];
+        records.extend(self.excluded.iter().map(|key| HistoryRecord::Exclude { key: key.clone() }));
+        for (key, entries) in &self.sessions {
+            records.extend(entries.iter().map(|command| HistoryRecord::Push {
+                key: key.clone(),
+                command: command.clone(),
+            }));
+        }
+        records
+    }
+
+    /// Add one line to the end of the history file
+    fn append_record(&self, record: &HistoryRecord) -> Result<(), StorageError> {
+        let mut line = serde_json::to_string(record).map_err(StorageError::Serialize)?;
+        line.push('\n');
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.config_path)
+            .map_err(|source| self.io_error(source))?;
+        file.write_all(line.as_bytes()).map_err(|source| self.io_error(source))
+    }
+
+    fn io_error(&self, source: std::io::Error) -> StorageError {
+        StorageError::Io {
+            path: self.config_path.clone(),
+            source,
+        }
+    }
+
+    fn trim_all(&mut self) {
+        for entries in self.sessions.values_mut() {
+            while entries.len() > self.max_entries {
+                entries.pop_front();
+            }
+        }
+    }
+}
+
+impl Default for HistoryStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Superseded lines tolerated in the history file before it is compacted
+const COMPACT_SLACK: usize = 256;
+
+/// One line of the history file
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+enum HistoryRecord {
+    /// A command was recorded
+    Push { key: String, command: String },
+    /// A key stopped being recorded
+    Exclude { key: String },
+    /// A key's commands were forgotten
+    Clear { key: String },
+    /// Per-key size limit
+    MaxEntries { max: usize },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Store in a fresh directory, removed when the guard drops
+    fn temp_store() -> (tempfile::TempDir, HistoryStore) {
+        let dir = tempfile::tempdir().unwrap();
+        let store = HistoryStore::with_path(dir.path().join("history.jsonl"));
+        (dir, store)
+    }
+
+    #[test]
+    fn test_dedup_and_max_size() {
+        let (_dir, mut store) = temp_store();
+        store.set_max_entries(3);
+        for cmd in ["ls", "pwd", "ls", "whoami", "uptime"] {
+            store.push("serial:/dev/ttyUSB0", cmd);
+        }
+        assert_eq!(store.entries("serial:/dev/ttyUSB0"), ["ls", "whoami", "uptime"]);
+        assert!(!store.push("serial:/dev/ttyUSB0", "   "));
+    }
+
+    #[test]
+    fn test_search_newest_first() {
+        let (_dir, mut store) = temp_store();
+        for cmd in ["AT+GMR", "ATI", "show version", "AT+CSQ"] {
+            store.push("modem", cmd);
+        }
+        assert_eq!(store.search("modem", "AT+"), ["AT+CSQ", "AT+GMR"]);
+        assert!(store.search("other", "AT").is_empty());
+    }
+
+    #[test]
+    fn test_excluded_keys_and_persistence() {
+        let (_dir, mut store) = temp_store();
+        store.push("router", "show run");
+        store.push("ssh:admin@host", "secret");
+        store.set_excluded("ssh:admin@host", true);
+        assert!(!store.push("ssh:admin@host", "password123"));
+        store.save().unwrap();
+
+        let mut loaded = HistoryStore::with_path(store.config_path.clone());
+        loaded.load().unwrap();
+        assert_eq!(loaded.entries("router"), ["show run"]);
+        assert!(loaded.entries("ssh:admin@host").is_empty());
+        assert!(loaded.is_excluded("ssh:admin@host"));
+    }
+
+    #[test]
+    fn test_append_replays_and_compacts() {
+        let (_dir, mut store) = temp_store();
+        for _ in 0..200 {
+            store.append("modem", "AT").unwrap();
+            store.append("modem", "ATI").unwrap();
+        }
+        assert!(!store.append("modem", "").unwrap());
+        let lines = |store: &HistoryStore| fs::read_to_string(&store.config_path).unwrap().lines().count();
+        assert_eq!(lines(&store), 400);
+
+        let mut loaded = HistoryStore::with_path(store.config_path.clone());
+        loaded.load().unwrap();
+        assert_eq!(loaded.entries("modem"), ["AT", "ATI"]);
+        // Superseded lines were dropped: the size limit plus two commands
+        assert_eq!(lines(&loaded), 3);
+
+        // Clearing survives a reload
+        loaded.clear("modem").unwrap();
+        let mut reloaded = HistoryStore::with_path(store.config_path.clone());
+        reloaded.load().unwrap();
+        assert!(reloaded.entries("modem").is_empty());
+
+        // A torn last line does not lose the rest
+        fs::write(&store.config_path, "{\"op\":\"push\",\"key\":\"modem\",\"command\":\"AT\"}\n{\"op\":\"pu").unwrap();
+        loaded.load().unwrap();
+        assert_eq!(loaded.entries("modem"), ["AT"]);
+    }
+}
@@ -2,11 +2,17 @@
 //!
 //! Supports multiple output formats and real-time logging
 
+use std::borrow::Cow;
 use std::fs::{File, OpenOptions};
 use std::io::{BufWriter, Write};
 use std::path::PathBuf;
+use std::sync::mpsc::{self, SyncSender, TrySendError};
 use std::sync::Arc;
-use chrono::{DateTime, Local};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, Local, Utc};
+use std::fmt::Write as _;
 use parking_lot::Mutex;
 
 /// Type alias for backwards compatibility
@@ -76,10 +82,107 @@ pub enum Direction {
     Info,
 }
 
+/// Default timestamp format (local time, milliseconds)
+pub const DEFAULT_TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S%.3f";
+
+/// How log timestamps are rendered
+///
+/// `format` is a chrono `strftime` string applied to the wall-clock time,
+/// in UTC or local time. With `monotonic_relative` the stamp is instead the
+/// seconds since logging started (e.g. `12.345678`), measured with a
+/// monotonic clock, which is what you want for timing analysis.
+///
+/// The `strftime` string is re-parsed for every line, so long custom
+/// formats cost a few hundred nanoseconds per entry; relative stamps skip
+/// that entirely. In background-writer mode the formatting happens on the
+/// writer thread. An invalid format renders with
+/// [`DEFAULT_TIMESTAMP_FORMAT`] instead; check user input with
+/// [`validate`](Self::validate).
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct TimestampConfig {
+    /// `strftime` format for wall-clock stamps
+    pub format: String,
+    /// Render in UTC instead of local time
+    pub utc: bool,
+    /// Seconds since logging started instead of wall-clock time
+    pub monotonic_relative: bool,
+}
+
+impl Default for TimestampConfig {
+    fn default() -> Self {
+        Self::local()
+    }
+}
+
+impl TimestampConfig {
+    /// Local time, e.g. `2024-05-01 13:45:12.345`
+    pub fn local() -> Self {
+        Self::custom(DEFAULT_TIMESTAMP_FORMAT)
+    }
+
+    /// ISO-8601 UTC, e.g. `2024-05-01T11:45:12.345Z`
+    pub fn iso8601_utc() -> Self {
+        Self {
+            format: "%Y-%m-%dT%H:%M:%S%.3fZ".to_string(),
+            utc: true,
+            monotonic_relative: false,
+        }
+    }
+
+    /// Custom local-time `strftime` format
+    pub fn custom(format: &str) -> Self {
+        Self {
+            format: format.to_string(),
+            utc: false,
+            monotonic_relative: false,
+        }
+    }
+
+    /// Seconds since logging started
+    pub fn relative() -> Self {
+        Self {
+            monotonic_relative: true,
+            ..Self::local()
+        }
+    }
+
+    /// Check that `format` is a valid `strftime` string
+    pub fn validate(&self) -> Result<(), String> {
+        if StrftimeItems::new(&self.format).any(|item| matches!(item, Item::Error)) {
+            return Err(format!("Invalid timestamp format: {}", self.format));
+        }
+        Ok(())
+    }
+
+    /// Render a stamp for a wall-clock time and the elapsed time since start
+    pub fn render(&self, time: &DateTime<Local>, elapsed: Duration) -> String {
+        if self.monotonic_relative {
+            return format!("{:.6}", elapsed.as_secs_f64());
+        }
+
+        let render = |format: &str| {
+            // Formatting (not parsing) reports a bad format, as fmt::Error
+            let mut out = String::new();
+            let written = if self.utc {
+                write!(out, "{}", time.with_timezone(&Utc).format(format))
+            } else {
+                write!(out, "{}", time.format(format))
+            };
+            written.map(|()| out)
+        };
+        render(&self.format)
+            .or_else(|_| render(DEFAULT_TIMESTAMP_FORMAT))
+            .unwrap_or_default()
+    }
+}
+
 /// A single log entry
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct LogEntry {
     pub timestamp: DateTime<Local>,
+    /// Time since the logger started (monotonic; zero outside a logger)
+    #[serde(skip)]
+    pub elapsed: Duration,
     pub direction: Direction,
     pub data: Vec<u8>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -91,6 +194,7 @@ impl LogEntry {
     pub fn new(direction: Direction, data: Vec<u8>) -> Self {
         Self {
             timestamp: Local::now(),
+            elapsed: Duration::ZERO,
             direction,
             data,
             note: None,
@@ -101,58 +205,63 @@ impl LogEntry {
     pub fn with_note(direction: Direction, data: Vec<u8>, note: &str) -> Self {
         Self {
             timestamp: Local::now(),
+            elapsed: Duration::ZERO,
             direction,
             data,
             note: Some(note.to_string()),
         }
     }
 
-    /// Format as text
-    pub fn to_text(&self, show_timestamp: bool) -> String {
-        let dir = match self.direction {
+    /// Render this entry's timestamp
+    pub fn timestamp_string(&self, config: &TimestampConfig) -> String {
+        config.render(&self.timestamp, self.elapsed)
+    }
+
+    fn direction_label(&self) -> &'static str {
+        match self.direction {
             Direction::Received => "RX",
             Direction::Sent => "TX",
             Direction::Info => "##",
-        };
+        }
+    }
 
+    /// Format as text
+    pub fn to_text(&self, show_timestamp: bool) -> String {
+        self.to_text_with(show_timestamp.then(TimestampConfig::default).as_ref())
+    }
+
+    /// Format as text, stamped with `timestamps` if given
+    pub fn to_text_with(&self, timestamps: Option<&TimestampConfig>) -> String {
         let text = String::from_utf8_lossy(&self.data);
 
-        if show_timestamp {
-            format!(
-                "[{}] {} {}",
-                self.timestamp.format("%Y-%m-%d %H:%M:%S%.3f"),
-                dir,
-                text
-            )
-        } else {
-            format!("{} {}", dir, text)
+        match timestamps {
+            Some(config) => format!("[{}] {} {}", self.timestamp_string(config), self.direction_label(), text),
+            None => format!("{} {}", self.direction_label(), text),
         }
     }
 
     /// Format as hex
     pub fn to_hex(&self, show_timestamp: bool) -> String {
-        let dir = match self.direction {
-            Direction::Received => "RX",
-            Direction::Sent => "TX",
-            Direction::Info => "##",
-        };
+        self.to_hex_with(show_timestamp.then(TimestampConfig::default).as_ref())
+    }
 
+    /// Format as hex, stamped with `timestamps` if given
+    pub fn to_hex_with(&self, timestamps: Option<&TimestampConfig>) -> String {
         let hex: String = self.data.iter().map(|b| format!("{:02X} ", b)).collect();
 
-        if show_timestamp {
-            format!(
-                "[{}] {} {}",
-                self.timestamp.format("%Y-%m-%d %H:%M:%S%.3f"),
-                dir,
-                hex
-            )
-        } else {
-            format!("{} {}", dir, hex)
+        match timestamps {
+            Some(config) => format!("[{}] {} {}", self.timestamp_string(config), self.direction_label(), hex),
+            None => format!("{} {}", self.direction_label(), hex),
         }
     }
 
     /// Format as CSV
     pub fn to_csv(&self) -> String {
+        self.to_csv_with(&TimestampConfig::default())
+    }
+
+    /// Format as CSV with the given timestamp style
+    pub fn to_csv_with(&self, timestamps: &TimestampConfig) -> String {
         let dir = match self.direction {
             Direction::Received => "RX",
             Direction::Sent => "TX",
@@ -164,7 +273,7 @@ impl LogEntry {
 
         format!(
             "\"{}\",\"{}\",\"{}\",\"{}\"",
-            self.timestamp.format("%Y-%m-%d %H:%M:%S%.3f"),
+            self.timestamp_string(timestamps),
             dir,
             hex,
             text
@@ -175,6 +284,195 @@ impl LogEntry {
     pub fn to_json(&self) -> String {
         serde_json::to_string(self).unwrap_or_default()
     }
+
+    /// Format as JSON line with an extra `time` field rendered by `timestamps`
+    pub fn to_json_with(&self, timestamps: Option<&TimestampConfig>) -> String {
+        let Some(config) = timestamps else {
+            return self.to_json();
+        };
+        let mut value = serde_json::to_value(self).unwrap_or_default();
+        if let Some(object) = value.as_object_mut() {
+            object.insert("time".to_string(), self.timestamp_string(config).into());
+        }
+        value.to_string()
+    }
+
+    /// Format as one line of `format`; `None` for raw binary output
+    ///
+    /// CSV always carries a timestamp, falling back to the default style.
+    pub fn to_line(&self, format: LogFormat, timestamps: Option<&TimestampConfig>) -> Option<String> {
+        let line = match format {
+            LogFormat::Text => self.to_text_with(timestamps),
+            LogFormat::Hex => self.to_hex_with(timestamps),
+            LogFormat::Mixed => {
+                if self.data.iter().all(|&b| b >= 32 && b < 127 || b == b'\n' || b == b'\r' || b == b'\t') {
+                    self.to_text_with(timestamps)
+                } else {
+                    self.to_hex_with(timestamps)
+                }
+            }
+            LogFormat::Csv => match timestamps {
+                Some(config) => self.to_csv_with(config),
+                None => self.to_csv(),
+            },
+            LogFormat::Raw => return None,
+            LogFormat::JsonLines => self.to_json_with(timestamps),
+        };
+        Some(line)
+    }
+}
+
+/// Replacement for redacted secrets
+const REDACTED: &[u8] = b"***";
+
+/// Default inline secret patterns; the `secret` group is what gets masked
+const DEFAULT_REDACTION_RULES: &[&str] = &[
+    r"(?i)\b(?:token|api_?key|secret|password|passwd)=(?P<secret>[^&\s]+)",
+    r"(?i)\bauthorization:\s*(?:bearer|basic)\s+(?P<secret>\S+)",
+];
+
+/// Default prompt after which the next sent line is a secret
+const DEFAULT_SECRET_PROMPT: &str = r"(?i)(?:password|passphrase|pin)[^:\n]*:\s*$";
+
+/// Masks secrets before they reach a log sink
+///
+/// Inline rules are regexes; if a rule has a `secret` named group only
+/// that part is replaced, otherwise the whole match is. After received
+/// data matches the secret prompt (e.g. `Password:`), the next sent line
+/// is masked too.
+#[derive(Debug, Clone)]
+pub struct LogRedactor {
+    rules: Vec<regex::bytes::Regex>,
+    prompt: Option<regex::bytes::Regex>,
+    /// A secret prompt was seen; mask sent data up to the next line end
+    masking_input: bool,
+}
+
+impl Default for LogRedactor {
+    fn default() -> Self {
+        let mut redactor = Self::new();
+        for rule in DEFAULT_REDACTION_RULES {
+            redactor.add_rule(rule).expect("default redaction rule is valid");
+        }
+        redactor.set_secret_prompt(DEFAULT_SECRET_PROMPT).expect("default prompt pattern is valid");
+        redactor
+    }
+}
+
+impl LogRedactor {
+    /// Create a redactor with no rules (see `default()` for the built-in rules)
+    pub fn new() -> Self {
+        Self {
+            rules: Vec::new(),
+            prompt: None,
+            masking_input: false,
+        }
+    }
+
+    /// Add an inline redaction rule
+    pub fn add_rule(&mut self, pattern: &str) -> Result<(), String> {
+        let re = regex::bytes::Regex::new(pattern).map_err(|e| format!("Invalid redaction rule: {}", e))?;
+        self.rules.push(re);
+        Ok(())
+    }
+
+    /// Set the prompt pattern that marks the next sent line as secret
+    pub fn set_secret_prompt(&mut self, pattern: &str) -> Result<(), String> {
+        let re = regex::bytes::Regex::new(pattern).map_err(|e| format!("Invalid prompt pattern: {}", e))?;
+        self.prompt = Some(re);
+        Ok(())
+    }
+
+    /// Redact a chunk of logged data
+    pub fn redact<'a>(&mut self, direction: Direction, data: &'a [u8]) -> Cow<'a, [u8]> {
+        let mut out = Cow::Borrowed(data);
+
+        if direction == Direction::Sent && self.masking_input {
+            let end = data.iter().position(|&b| b == b'\r' || b == b'\n');
+            let mut masked = REDACTED.to_vec();
+            if let Some(end) = end {
+                masked.extend_from_slice(&data[end..]);
+                self.masking_input = false;
+            }
+            out = Cow::Owned(masked);
+        }
+
+        for rule in &self.rules {
+            if !rule.is_match(&out) {
+                continue;
+            }
+            let mut replaced = Vec::with_capacity(out.len());
+            let mut last = 0;
+            for caps in rule.captures_iter(&out) {
+                let Some(secret) = caps.name("secret").or_else(|| caps.get(0)) else {
+                    continue;
+                };
+                replaced.extend_from_slice(&out[last..secret.start()]);
+                replaced.extend_from_slice(REDACTED);
+                last = secret.end();
+            }
+            replaced.extend_from_slice(&out[last..]);
+            out = Cow::Owned(replaced);
+        }
+
+        if direction == Direction::Received {
+            if let Some(prompt) = &self.prompt {
+                if prompt.is_match(data) {
+                    self.masking_input = true;
+                }
+            }
+        }
+
+        out
+    }
+}
+
+/// What a background-writer logger does when its queue is full
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OverflowPolicy {
+    /// Drop the entry (counted in `dropped_entries`); never stalls the caller
+    #[default]
+    Drop,
+    /// Wait for the writer to catch up
+    Block,
+}
+
+/// Default queue length for background-writer mode
+pub const DEFAULT_WRITER_QUEUE: usize = 4096;
+
+/// Message to the background writer thread
+enum WriterMsg {
+    /// Entry and its timestamp style (None = no timestamp)
+    Entry(LogEntry, Option<Arc<TimestampConfig>>),
+    /// Flush the file, then acknowledge
+    Flush(mpsc::Sender<()>),
+}
+
+/// Handle to the thread writing log entries to disk
+struct BackgroundWriter {
+    tx: SyncSender<WriterMsg>,
+    handle: JoinHandle<()>,
+    policy: OverflowPolicy,
+}
+
+/// Format one entry into the log file; returns the lines written
+fn write_entry(
+    file: &mut BufWriter<File>,
+    format: LogFormat,
+    timestamps: Option<&TimestampConfig>,
+    entry: &LogEntry,
+) -> usize {
+    match entry.to_line(format, timestamps) {
+        Some(line) => {
+            let _ = writeln!(file, "{}", line);
+            1
+        }
+        None => {
+            // Write raw bytes
+            let _ = file.write_all(&entry.data);
+            0
+        }
+    }
 }
 
 /// Session logger
@@ -187,6 +485,10 @@ pub struct SessionLogger {
     path: Option<PathBuf>,
     /// Include timestamps
     timestamps: bool,
+    /// How timestamps are rendered
+    timestamp_config: Arc<TimestampConfig>,
+    /// Reference point for relative timestamps
+    started: Instant,
     /// Buffer for in-memory log
     buffer: Vec<LogEntry>,
     /// Max buffer size
@@ -195,6 +497,12 @@ pub struct SessionLogger {
     bytes_logged: usize,
     /// Lines logged
     lines_logged: usize,
+    /// Secret masking for this sink
+    redactor: Option<LogRedactor>,
+    /// Writer thread, when file output is in background mode
+    writer: Option<BackgroundWriter>,
+    /// Entries dropped because the writer queue was full
+    dropped: u64,
 }
 
 impl Default for SessionLogger {
@@ -211,19 +519,87 @@ impl SessionLogger {
             format: LogFormat::Text,
             path: None,
             timestamps: true,
+            timestamp_config: Arc::new(TimestampConfig::default()),
+            started: Instant::now(),
             buffer: Vec::new(),
             max_buffer: 10000,
             bytes_logged: 0,
             lines_logged: 0,
+            redactor: None,
+            writer: None,
+            dropped: 0,
         }
     }
 
     /// Start logging to file
     pub fn start(&mut self, path: PathBuf, format: LogFormat) -> Result<(), String> {
+        self.stop();
+        self.file = Some(Self::open(&path, format)?);
+        self.format = format;
+        self.path = Some(path);
+        self.bytes_logged = 0;
+        self.lines_logged = 0;
+        self.started = Instant::now();
+
+        Ok(())
+    }
+
+    /// Start logging to file from a dedicated writer thread
+    ///
+    /// `log_*` only queues entries (up to `queue_len`), so a slow disk
+    /// cannot stall the caller; `policy` decides what happens when the
+    /// queue is full. [`SessionLogger::flush`] and [`SessionLogger::stop`]
+    /// wait for queued entries to be written.
+    pub fn start_background(
+        &mut self,
+        path: PathBuf,
+        format: LogFormat,
+        queue_len: usize,
+        policy: OverflowPolicy,
+    ) -> Result<(), String> {
+        self.stop();
+        let mut file = Self::open(&path, format)?;
+
+        let (tx, rx) = mpsc::sync_channel::<WriterMsg>(queue_len.max(1));
+        let handle = std::thread::Builder::new()
+            .name("log-writer".to_string())
+            .spawn(move || {
+                let mut lines = 0usize;
+                for msg in rx {
+                    match msg {
+                        WriterMsg::Entry(entry, timestamps) => {
+                            lines += write_entry(&mut file, format, timestamps.as_deref(), &entry);
+                            if lines % 100 == 0 {
+                                let _ = file.flush();
+                            }
+                        }
+                        WriterMsg::Flush(ack) => {
+                            let _ = file.flush();
+                            let _ = ack.send(());
+                        }
+                    }
+                }
+                let _ = file.flush();
+            })
+            .map_err(|e| format!("Failed to start log writer: {}", e))?;
+
+        self.writer = Some(BackgroundWriter { tx, handle, policy });
+        self.format = format;
+        self.path = Some(path);
+        self.bytes_logged = 0;
+        self.lines_logged = 0;
+        self.dropped = 0;
+        self.started = Instant::now();
+
+        Ok(())
+    }
+
+    /// Open a log file, writing the CSV header if needed
+    fn open(path: &PathBuf, format: LogFormat) -> Result<BufWriter<File>, String> {
         let file = OpenOptions::new()
             .create(true)
             .append(true)
-            .open(&path)
+            .open(path)
             .map_err(|e| format!("Failed to open log file: {}", e))?;
 
         let mut writer = BufWriter::new(file);
@@ -234,26 +610,30 @@ impl SessionLogger {
                 .map_err(|e| format!("Failed to write header: {}", e))?;
         }
 
-        self.file = Some(writer);
-        self.format = format;
-        self.path = Some(path);
-        self.bytes_logged = 0;
-        self.lines_logged = 0;
-
-        Ok(())
+        Ok(writer)
     }
 
-    /// Stop logging
+    /// Stop logging; in background mode, waits for queued entries to be written
     pub fn stop(&mut self) {
         if let Some(ref mut file) = self.file {
             let _ = file.flush();
         }
         self.file = None;
+
+        if let Some(writer) = self.writer.take() {
+            drop(writer.tx);
+            let _ = writer.handle.join();
+        }
     }
 
     /// Is currently logging
     pub fn is_logging(&self) -> bool {
-        self.file.is_some()
+        self.file.is_some() || self.writer.is_some()
+    }
+
+    /// Entries dropped because the background writer's queue was full
+    pub fn dropped_entries(&self) -> u64 {
+        self.dropped
     }
 
     /// Get log path
@@ -261,40 +641,51 @@ impl SessionLogger {
         self.path.as_ref()
     }
 
+    /// Set secret redaction for this logger (None = log raw data)
+    pub fn set_redactor(&mut self, redactor: Option<LogRedactor>) {
+        self.redactor = redactor;
+    }
+
     /// Log data
     pub fn log(&mut self, direction: Direction, data: &[u8]) {
-        let entry = LogEntry::new(direction, data.to_vec());
+        let data = match self.redactor.as_mut() {
+            Some(redactor) => redactor.redact(direction, data),
+            None => Cow::Borrowed(data),
+        };
+        let data = data.as_ref();
+        let mut entry = LogEntry::new(direction, data.to_vec());
+        entry.elapsed = self.started.elapsed();
+        let timestamps = self.timestamps.then(|| Arc::clone(&self.timestamp_config));
 
         // Write to file if logging
         if let Some(ref mut file) = self.file {
-            let line = match self.format {
-                LogFormat::Text => entry.to_text(self.timestamps),
-                LogFormat::Hex => entry.to_hex(self.timestamps),
-                LogFormat::Mixed => {
-                    if data.iter().all(|&b| b >= 32 && b < 127 || b == b'\n' || b == b'\r' || b == b'\t') {
-                        entry.to_text(self.timestamps)
-                    } else {
-                        entry.to_hex(self.timestamps)
-                    }
-                }
-                LogFormat::Csv => entry.to_csv(),
-                LogFormat::Raw => {
-                    // Write raw bytes
-                    let _ = file.write_all(data);
-                    self.bytes_logged += data.len();
-                    return;
-                }
-                LogFormat::JsonLines => entry.to_json(),
-            };
-
-            let _ = writeln!(file, "{}", line);
+            let lines = write_entry(file, self.format, timestamps.as_deref(), &entry);
             self.bytes_logged += data.len();
-            self.lines_logged += 1;
+            self.lines_logged += lines;
 
             // Flush periodically
-            if self.lines_logged % 100 == 0 {
+            if lines > 0 && self.lines_logged % 100 == 0 {
                 let _ = file.flush();
             }
+        } else if let Some(ref writer) = self.writer {
+            let msg = WriterMsg::Entry(entry.clone(), timestamps);
+            let queued = match writer.policy {
+                OverflowPolicy::Block => writer.tx.send(msg).is_ok(),
+                OverflowPolicy::Drop => match writer.tx.try_send(msg) {
+                    Ok(()) => true,
+                    Err(TrySendError::Full(_)) => {
+                        self.dropped += 1;
+                        false
+                    }
+                    Err(TrySendError::Disconnected(_)) => false,
+                },
+            };
+            if queued {
+                self.bytes_logged += data.len();
+                if self.format != LogFormat::Raw {
+                    self.lines_logged += 1;
+                }
+            }
         }
 
         // Add to buffer
@@ -343,20 +734,9 @@ impl SessionLogger {
         }
 
         for entry in &self.buffer {
-            let line = match format {
-                LogFormat::Text => entry.to_text(true),
-                LogFormat::Hex => entry.to_hex(true),
-                LogFormat::Mixed => {
-                    if entry.data.iter().all(|&b| b >= 32 && b < 127 || b == b'\n' || b == b'\r' || b == b'\t') {
-                        entry.to_text(true)
-                    } else {
-                        entry.to_hex(true)
-                    }
-                }
-                LogFormat::Csv => entry.to_csv(),
-                LogFormat::Raw => hex::encode(&entry.data),
-                LogFormat::JsonLines => entry.to_json(),
-            };
+            let line = entry
+                .to_line(format, Some(&self.timestamp_config))
+                .unwrap_or_else(|| hex::encode(&entry.data));
             result.push_str(&line);
             result.push('\n');
         }
@@ -369,16 +749,32 @@ impl SessionLogger {
         self.timestamps = show;
     }
 
+    /// Set how timestamps are rendered in every format
+    pub fn set_timestamp_config(&mut self, config: TimestampConfig) {
+        self.timestamp_config = Arc::new(config);
+    }
+
+    /// How timestamps are rendered
+    pub fn timestamp_config(&self) -> &TimestampConfig {
+        &self.timestamp_config
+    }
+
     /// Set max buffer size
     pub fn set_max_buffer(&mut self, size: usize) {
         self.max_buffer = size;
     }
 
-    /// Flush to disk
+    /// Flush to disk; in background mode, waits until the queue has drained
     pub fn flush(&mut self) {
         if let Some(ref mut file) = self.file {
             let _ = file.flush();
         }
+        if let Some(ref writer) = self.writer {
+            let (ack_tx, ack_rx) = mpsc::channel();
+            if writer.tx.send(WriterMsg::Flush(ack_tx)).is_ok() {
+                let _ = ack_rx.recv();
+            }
+        }
     }
 }
 
@@ -414,6 +810,59 @@ mod tests {
         assert!(hex.contains("01 02 03"));
     }
 
+    #[test]
+    fn test_timestamp_styles() {
+        let mut entry = LogEntry::new(Direction::Received, b"OK".to_vec());
+        entry.timestamp = chrono::DateTime::parse_from_rfc3339("2024-05-01T11:45:12.345Z")
+            .unwrap()
+            .with_timezone(&Local);
+        entry.elapsed = Duration::from_millis(1500);
+
+        assert_eq!(entry.timestamp_string(&TimestampConfig::iso8601_utc()), "2024-05-01T11:45:12.345Z");
+        assert_eq!(
+            entry.to_text_with(Some(&TimestampConfig::relative())),
+            "[1.500000] RX OK"
+        );
+        assert!(entry.to_csv_with(&TimestampConfig::relative()).starts_with("\"1.500000\",\"RX\""));
+        let custom = TimestampConfig { utc: true, ..TimestampConfig::custom("%H:%M") };
+        assert!(entry.to_json_with(Some(&custom)).contains("\"time\":\"11:45\""));
+        assert_eq!(entry.to_line(LogFormat::Raw, Some(&custom)), None);
+
+        // A bad user format falls back instead of panicking the writer
+        let broken = TimestampConfig { utc: true, ..TimestampConfig::custom("%Q %H") };
+        assert!(broken.validate().is_err());
+        assert!(custom.validate().is_ok());
+        assert_eq!(entry.timestamp_string(&broken), "2024-05-01 11:45:12.345");
+    }
+
+    #[test]
+    fn test_redactor_inline_secrets() {
+        let mut redactor = LogRedactor::default();
+        let out = redactor.redact(Direction::Sent, b"GET /api?token=abc123&page=2\r\n");
+        assert_eq!(&out[..], b"GET /api?token=***&page=2\r\n");
+
+        // Nothing to hide: no copy
+        assert!(matches!(redactor.redact(Direction::Received, b"OK\r\n"), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn test_redactor_masks_reply_to_password_prompt() {
+        let mut redacted = SessionLogger::new();
+        redacted.set_redactor(Some(LogRedactor::default()));
+        let mut raw = SessionLogger::new();
+
+        for logger in [&mut redacted, &mut raw] {
+            logger.log_rx(b"login: admin\r\nPassword: ");
+            logger.log_tx(b"hunt");
+            logger.log_tx(b"er2\r");
+            logger.log_tx(b"ls\r");
+        }
+
+        let sent: Vec<&[u8]> = redacted.buffer()[1..].iter().map(|e| e.data.as_slice()).collect();
+        assert_eq!(sent, [&b"***"[..], b"***\r", b"ls\r"]);
+        assert_eq!(raw.buffer()[1].data, b"hunt");
+    }
+
     #[test]
     fn test_buffer_limit() {
         let mut logger = SessionLogger::new();
@@ -425,4 +874,29 @@ mod tests {
 
         assert_eq!(logger.buffer().len(), 5);
     }
+
+    #[test]
+    fn test_background_writer_drains_on_flush() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bg-log.txt");
+
+        let mut logger = SessionLogger::new();
+        logger.set_timestamps(false);
+        logger
+            .start_background(path.clone(), LogFormat::Text, 4, OverflowPolicy::Block)
+            .unwrap();
+        for i in 0..50 {
+            logger.log_rx(format!("line {}", i).as_bytes());
+        }
+        logger.flush();
+
+        let written = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(written.lines().count(), 50);
+        assert!(written.lines().last().unwrap().ends_with("line 49"));
+        assert_eq!(logger.dropped_entries(), 0);
+        assert_eq!(logger.stats().1, 50);
+
+        logger.stop();
+        assert!(!logger.is_logging());
+    }
 }
@@ -3,9 +3,11 @@
 //! Each macro can be:
 //! - A simple command string
 //! - A hex sequence
+//! - A key sequence (`{ENTER}`, `{0x02}`...) encoded like snippets
 //! - A file path to send
 //! - A script reference
 
+use crate::core::snippet::{encode_payload, LineEnding, SnippetType};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
@@ -20,6 +22,8 @@ pub enum MacroContent {
     Text(String),
     /// Hex bytes (e.g., "FF 00 A5")
     Hex(Vec<u8>),
+    /// Text with `{KEY}` names and `{0xNN}` escapes (e.g., "{0x02}payload{0x03}")
+    KeySequence(String),
     /// File to send
     File(PathBuf),
     /// Script reference
@@ -74,11 +78,21 @@ impl MacroSlot {
         matches!(self.content, MacroContent::Empty)
     }
 
+    /// Line ending appended after the content
+    pub fn line_ending(&self) -> LineEnding {
+        if self.append_crlf {
+            LineEnding::CrLf
+        } else {
+            LineEnding::None
+        }
+    }
+
     /// Get the bytes to send
     pub fn get_bytes(&self) -> Vec<u8> {
         let mut bytes = match &self.content {
-            MacroContent::Text(s) => s.as_bytes().to_vec(),
+            MacroContent::Text(s) => encode_payload(SnippetType::Command, s, LineEnding::None),
             MacroContent::Hex(b) => b.clone(),
+            MacroContent::KeySequence(s) => encode_payload(SnippetType::KeySequence, s, LineEnding::None),
             MacroContent::File(path) => {
                 std::fs::read(path).unwrap_or_default()
             }
@@ -86,8 +100,8 @@ impl MacroSlot {
             MacroContent::Empty => Vec::new(),
         };
 
-        if self.append_crlf && !bytes.is_empty() {
-            bytes.extend_from_slice(b"\r\n");
+        if !bytes.is_empty() {
+            bytes.extend_from_slice(self.line_ending().as_bytes());
         }
 
         bytes
@@ -294,3 +308,19 @@ pub fn format_hex_bytes(bytes: &[u8]) -> String {
         .join(" ")
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_sequence_macro_bytes() {
+        let mut slot = MacroSlot::new(1);
+        slot.content = MacroContent::KeySequence("{0x02}payload{0x03}".to_string());
+        slot.append_crlf = false;
+        assert_eq!(slot.get_bytes(), b"\x02payload\x03");
+
+        slot.append_crlf = true;
+        slot.content = MacroContent::Hex(vec![0xFF, 0x00]);
+        assert_eq!(slot.get_bytes(), b"\xFF\x00\r\n");
+    }
+}
@@ -15,7 +15,9 @@
 //! - File transfer (XMODEM/YMODEM/ZMODEM/Kermit)
 //! - Macro recording and playback
 //! - Transport capability registry
+//! - Transport settings schema
 //! - Packet abstraction layer
+//! - PCAP capture export
 //! - Protocol DSL (declarative protocol definitions)
 //! - Session replay and recording
 //! - Virtual device simulation
@@ -32,6 +34,14 @@
 //! - Quick macros (M1-M24)
 //! - Batch operations
 //! - Workspace save/restore
+//! - Persistent command history
+//! - JSON store persistence
+//! - Receive-path transforms
+//! - Connection event log
+//! - Connection pre-flight checks
+//! - Prompt detection
+//! - Session transcripts
+//! - Capture comparison
 
 pub mod adaptive;
 pub mod arbitration;
@@ -42,19 +52,26 @@ pub mod capability;
 pub mod chart;
 pub mod codec;
 pub mod collaborative;
+pub mod compare;
+pub mod config_schema;
 pub mod deterministic;
+pub mod event_log;
 pub mod experiment;
 pub mod explain;
 pub mod external_api;
 pub mod file_transfer;
 pub mod fuzzing;
+pub mod history;
 pub mod knowledge;
 pub mod logger;
 pub mod macro_recorder;
 pub mod macros;
 pub mod packet;
+pub mod pcap;
 pub mod plugin;
+pub mod preflight;
 pub mod profile;
+pub mod prompt;
 pub mod protocol;
 pub mod protocol_dsl;
 pub mod replay;
@@ -63,8 +80,11 @@ pub mod session;
 pub mod simulator;
 pub mod snippet;
 pub mod state_machine;
+pub mod storage;
 pub mod terminal;
 pub mod transfer;
+pub mod transcript;
+pub mod transform;
 pub mod transport;
 pub mod trigger;
 pub mod vault;
@@ -265,6 +265,11 @@ impl PacketBuffer {
         self.packets.push_back(packet);
     }
 
+    /// Sequence number the next packet gets
+    pub fn next_seq(&self) -> u64 {
+        self.seq_counter
+    }
+
     /// Get packet by ID
     pub fn get(&self, id: &Uuid) -> Option<&Packet> {
         self.id_index.get(id).and_then(|&idx| self.packets.get(idx))
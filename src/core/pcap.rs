@@ -0,0 +1,129 @@
+//! PCAP capture export
+//!
+//! Writes session or bridge traffic as a classic libpcap file that
+//! Wireshark can open. Packets use the `USER0` link type (DLT 147);
+//! each record is one RX or TX chunk prefixed by a one-byte direction
+//! pseudo-header:
+//!
+//! | Byte | Meaning                         |
+//! |------|---------------------------------|
+//! | 0x00 | RX (device → host)              |
+//! | 0x01 | TX (host → device)              |
+//! | 0x02 | Internal / annotation           |
+//!
+//! In Wireshark, map `User 0 (DLT=147)` to a payload dissector with a
+//! header size of 1 to decode the payload.
+
+use crate::core::packet::{Packet, PacketDirection};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+/// libpcap magic number (microsecond timestamps)
+const PCAP_MAGIC: u32 = 0xa1b2_c3d4;
+/// `LINKTYPE_USER0`
+pub const LINKTYPE_USER0: u32 = 147;
+/// Maximum captured length per record
+const SNAPLEN: u32 = 65535;
+
+/// Direction pseudo-header byte for a packet direction
+pub fn direction_byte(direction: PacketDirection) -> u8 {
+    match direction {
+        PacketDirection::Rx => 0x00,
+        PacketDirection::Tx => 0x01,
+        PacketDirection::Internal => 0x02,
+    }
+}
+
+/// Streaming PCAP writer
+pub struct PcapWriter<W: Write> {
+    writer: W,
+    packets: u64,
+}
+
+impl PcapWriter<BufWriter<File>> {
+    /// Create a capture file
+    pub fn create(path: &Path) -> Result<Self, String> {
+        let file = File::create(path).map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+        Self::new(BufWriter::new(file))
+    }
+}
+
+impl<W: Write> PcapWriter<W> {
+    /// Start a capture, writing the global header
+    pub fn new(mut writer: W) -> Result<Self, String> {
+        let mut header = Vec::with_capacity(24);
+        header.extend_from_slice(&PCAP_MAGIC.to_le_bytes());
+        header.extend_from_slice(&2u16.to_le_bytes()); // version major
+        header.extend_from_slice(&4u16.to_le_bytes()); // version minor
+        header.extend_from_slice(&0i32.to_le_bytes()); // thiszone
+        header.extend_from_slice(&0u32.to_le_bytes()); // sigfigs
+        header.extend_from_slice(&SNAPLEN.to_le_bytes());
+        header.extend_from_slice(&LINKTYPE_USER0.to_le_bytes());
+        writer.write_all(&header).map_err(|e| e.to_string())?;
+
+        Ok(Self { writer, packets: 0 })
+    }
+
+    /// Append one packet, using its timestamp and direction
+    pub fn write_packet(&mut self, packet: &Packet) -> Result<(), String> {
+        let ts = packet.timestamp.timestamp();
+        let ts_usec = packet.timestamp.timestamp_subsec_micros();
+
+        let orig_len = packet.data.len() as u32 + 1;
+        let incl_len = orig_len.min(SNAPLEN);
+
+        let mut record = Vec::with_capacity(16 + incl_len as usize);
+        record.extend_from_slice(&(ts as u32).to_le_bytes());
+        record.extend_from_slice(&ts_usec.to_le_bytes());
+        record.extend_from_slice(&incl_len.to_le_bytes());
+        record.extend_from_slice(&orig_len.to_le_bytes());
+        record.push(direction_byte(packet.direction));
+        record.extend_from_slice(&packet.data[..incl_len as usize - 1]);
+        self.writer.write_all(&record).map_err(|e| e.to_string())?;
+
+        self.packets += 1;
+        Ok(())
+    }
+
+    /// Number of packets written
+    pub fn packet_count(&self) -> u64 {
+        self.packets
+    }
+
+    /// Flush buffered records
+    pub fn flush(&mut self) -> Result<(), String> {
+        self.writer.flush().map_err(|e| e.to_string())
+    }
+
+    /// Finish the capture and return the underlying writer
+    pub fn into_inner(mut self) -> Result<W, String> {
+        self.flush()?;
+        Ok(self.writer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_pcap_layout() {
+        let mut writer = PcapWriter::new(Vec::new()).unwrap();
+        let mut packet = Packet::tx(b"AT\r".to_vec());
+        packet.timestamp = chrono::Local.timestamp_opt(1_700_000_000, 250_000_000).unwrap();
+        writer.write_packet(&packet).unwrap();
+        assert_eq!(writer.packet_count(), 1);
+
+        let out = writer.into_inner().unwrap();
+        assert_eq!(&out[0..4], &[0xd4, 0xc3, 0xb2, 0xa1]);
+        assert_eq!(u32::from_le_bytes(out[20..24].try_into().unwrap()), LINKTYPE_USER0);
+
+        let record = &out[24..];
+        assert_eq!(u32::from_le_bytes(record[0..4].try_into().unwrap()), 1_700_000_000);
+        assert_eq!(u32::from_le_bytes(record[4..8].try_into().unwrap()), 250_000);
+        assert_eq!(u32::from_le_bytes(record[8..12].try_into().unwrap()), 4);
+        assert_eq!(&record[16..], &[0x01, b'A', b'T', b'\r']);
+    }
+}
@@ -5,9 +5,15 @@
 //! - Protocol decoders
 //! - Custom views
 
+use crate::core::packet::PacketDirection;
+use crate::core::session::FilteredReceiver;
+use parking_lot::RwLock;
 use std::collections::HashMap;
+use std::ops::Range;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use thiserror::Error;
+use tokio::sync::broadcast;
 
 /// Plugin error types
 #[derive(Debug, Error)]
@@ -257,6 +263,9 @@ pub struct DecodedData {
 }
 
 /// A decoded field
+///
+/// Dotted names (`header.length`) nest the field under `header` in the
+/// decode tree.
 #[derive(Debug, Clone)]
 pub struct DecodedField {
     pub name: String,
@@ -266,6 +275,130 @@ pub struct DecodedField {
     pub length: usize,
 }
 
+impl DecodedField {
+    /// Byte range of the field within `DecodedData::raw`
+    pub fn range(&self) -> Range<usize> {
+        self.offset..self.offset + self.length
+    }
+}
+
+/// One row of a decode tree
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodeTreeRow {
+    /// Nesting depth (0 = protocol row)
+    pub indent: usize,
+    pub label: String,
+    pub value: String,
+    /// Bytes to highlight in the hex view when the row is selected
+    pub bytes: Option<Range<usize>>,
+}
+
+impl DecodedData {
+    /// Flatten into display rows: the protocol with its summary, then the
+    /// fields in order, with a group row for each dotted prefix
+    pub fn to_tree(&self) -> Vec<DecodeTreeRow> {
+        let mut rows = vec![DecodeTreeRow {
+            indent: 0,
+            label: self.protocol.clone(),
+            value: self.summary.clone(),
+            bytes: Some(0..self.raw.len()),
+        }];
+
+        let mut open_groups: Vec<&str> = Vec::new();
+        for field in &self.fields {
+            let mut path: Vec<&str> = field.name.split('.').collect();
+            let label = path.pop().unwrap_or_default();
+
+            // Close groups this field is not part of, open the new ones
+            let shared = open_groups
+                .iter()
+                .zip(&path)
+                .take_while(|(open, next)| open == next)
+                .count();
+            open_groups.truncate(shared);
+            for group in &path[shared..] {
+                rows.push(DecodeTreeRow {
+                    indent: open_groups.len() + 1,
+                    label: group.to_string(),
+                    value: String::new(),
+                    bytes: None,
+                });
+                open_groups.push(*group);
+            }
+
+            rows.push(DecodeTreeRow {
+                indent: open_groups.len() + 1,
+                label: label.to_string(),
+                value: field.value.clone(),
+                bytes: Some(field.range()),
+            });
+        }
+        rows
+    }
+}
+
+/// Runs incoming frames through registered decoders and broadcasts the
+/// results to decode views
+pub struct DecoderPipeline {
+    decoders: RwLock<Vec<Arc<dyn ProtocolDecoder>>>,
+    tx: broadcast::Sender<DecodedData>,
+}
+
+impl DecoderPipeline {
+    /// Create an empty pipeline
+    pub fn new() -> Self {
+        let (tx, _) = broadcast::channel(256);
+        Self {
+            decoders: RwLock::new(Vec::new()),
+            tx,
+        }
+    }
+
+    /// Register a decoder; earlier decoders take precedence
+    pub fn add_decoder(&self, decoder: Arc<dyn ProtocolDecoder>) {
+        self.decoders.write().push(decoder);
+    }
+
+    /// Subscribe to decoded frames
+    pub fn subscribe(&self) -> broadcast::Receiver<DecodedData> {
+        self.tx.subscribe()
+    }
+
+    /// Decode a frame with the first decoder that accepts it and publish it
+    pub fn process(&self, data: &[u8]) -> Option<DecodedData> {
+        let decoded = self
+            .decoders
+            .read()
+            .iter()
+            .filter(|d| d.can_decode(data))
+            .find_map(|d| d.decode(data))?;
+        let _ = self.tx.send(decoded.clone());
+        Some(decoded)
+    }
+
+    /// Decode received packets from a `Session::subscribe_filtered`
+    /// receiver until the session closes
+    pub fn spawn(self: Arc<Self>, mut packets: FilteredReceiver) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            while let Some(packet) = packets.recv().await {
+                let dropped = packets.take_dropped();
+                if dropped > 0 {
+                    tracing::warn!("Decoder fell behind; {} packets were not decoded", dropped);
+                }
+                if packet.direction == PacketDirection::Rx {
+                    self.process(&packet.data);
+                }
+            }
+        })
+    }
+}
+
+impl Default for DecoderPipeline {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -288,4 +421,63 @@ mod tests {
         let plugin = Plugin::new(info, PluginType::Decoder, PathBuf::from("."));
         assert!(!plugin.is_active());
     }
+
+    struct LengthValueDecoder;
+
+    impl ProtocolDecoder for LengthValueDecoder {
+        fn name(&self) -> &str {
+            "LV"
+        }
+
+        fn decode(&self, data: &[u8]) -> Option<DecodedData> {
+            let field = |name: &str, value: String, offset, length| DecodedField {
+                name: name.to_string(),
+                value,
+                field_type: "u8".to_string(),
+                offset,
+                length,
+            };
+            Some(DecodedData {
+                protocol: "LV".to_string(),
+                fields: vec![
+                    field("header.type", format!("{}", data[0]), 0, 1),
+                    field("header.length", format!("{}", data[1]), 1, 1),
+                    field("payload", format!("{:02X?}", &data[2..]), 2, data.len() - 2),
+                ],
+                raw: data.to_vec(),
+                summary: format!("{} bytes", data[1]),
+            })
+        }
+
+        fn can_decode(&self, data: &[u8]) -> bool {
+            data.len() >= 2 && data.len() == data[1] as usize + 2
+        }
+    }
+
+    #[test]
+    fn test_decoded_tree_nests_dotted_fields() {
+        let pipeline = DecoderPipeline::new();
+        pipeline.add_decoder(Arc::new(LengthValueDecoder));
+        let mut rx = pipeline.subscribe();
+
+        assert!(pipeline.process(&[0x01, 0x05]).is_none());
+        let decoded = pipeline.process(&[0x01, 0x02, 0xAA, 0xBB]).unwrap();
+        assert_eq!(rx.try_recv().unwrap().summary, "2 bytes");
+
+        let tree: Vec<(usize, String, Option<Range<usize>>)> = decoded
+            .to_tree()
+            .into_iter()
+            .map(|row| (row.indent, row.label, row.bytes))
+            .collect();
+        assert_eq!(
+            tree,
+            vec![
+                (0, "LV".to_string(), Some(0..4)),
+                (1, "header".to_string(), None),
+                (2, "type".to_string(), Some(0..1)),
+                (2, "length".to_string(), Some(1..2)),
+                (1, "payload".to_string(), Some(2..4)),
+            ]
+        );
+    }
 }
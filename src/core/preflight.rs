@@ -0,0 +1,208 @@
+//! Connection pre-flight checks
+//!
+//! Quick checks run before connecting (does the serial port exist and can it
+//! be opened, does the host resolve and accept connections) so problems can
+//! be shown next to the settings instead of as a failed connect.
+
+use crate::core::explain::{ExplainEngine, Symptom};
+use crate::core::session::{network_endpoint, transport_type_of};
+use crate::core::transport::{enumerate_ports, SerialConfig, Transport};
+use std::net::SocketAddr;
+use std::path::Path;
+use std::time::Duration;
+
+/// How long each network check may take
+pub const PREFLIGHT_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// A pre-flight check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PreflightCheck {
+    /// The serial device is present
+    PortExists,
+    /// The serial port can be opened (not busy, permissions OK)
+    PortFree,
+    /// The host name resolves
+    HostResolves,
+    /// The host accepts a TCP connection on the port
+    PortReachable,
+}
+
+/// Outcome of a check
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckStatus {
+    Pass,
+    /// Inconclusive; connecting may still work
+    Warn,
+    Fail,
+}
+
+/// Result of one check
+#[derive(Debug, Clone)]
+pub struct CheckResult {
+    pub check: PreflightCheck,
+    pub status: CheckStatus,
+    /// What was found
+    pub detail: String,
+    /// Suggested fix from the explain engine
+    pub hint: Option<String>,
+}
+
+/// Results of all checks that apply to a transport, in the order run
+#[derive(Debug, Clone, Default)]
+pub struct PreflightReport {
+    pub results: Vec<CheckResult>,
+}
+
+impl PreflightReport {
+    /// Result of `check`, if it was run
+    pub fn get(&self, check: PreflightCheck) -> Option<&CheckResult> {
+        self.results.iter().find(|result| result.check == check)
+    }
+
+    /// Check if no check failed
+    pub fn passed(&self) -> bool {
+        self.results.iter().all(|result| result.status != CheckStatus::Fail)
+    }
+}
+
+/// Run the checks that apply to `transport`
+///
+/// Checking that a serial port is free opens and closes it, which pulses
+/// DTR on most drivers. Bluetooth has no checks.
+pub async fn run(transport: &Transport) -> PreflightReport {
+    let engine = ExplainEngine::new();
+    let context = transport_type_of(transport).to_string().to_lowercase();
+    let mut report = Preflight {
+        engine: &engine,
+        transport: &context,
+        report: PreflightReport::default(),
+    };
+
+    if let Transport::Serial(config) = transport {
+        report.serial(config).await;
+    } else if let Some((host, port)) = network_endpoint(transport) {
+        report.network(&host, port).await;
+    }
+    report.report
+}
+
+struct Preflight<'a> {
+    engine: &'a ExplainEngine,
+    transport: &'a str,
+    report: PreflightReport,
+}
+
+impl Preflight<'_> {
+    fn pass(&mut self, check: PreflightCheck, detail: String) {
+        self.push(check, CheckStatus::Pass, detail, None);
+    }
+
+    fn push(&mut self, check: PreflightCheck, status: CheckStatus, detail: String, symptom: Option<(Symptom, &[(&str, &str)])>) {
+        let hint = symptom.and_then(|(symptom, context)| {
+            let mut context = context.to_vec();
+            context.push(("transport", self.transport));
+            self.engine.hint(symptom, &context)
+        });
+        self.report.results.push(CheckResult { check, status, detail, hint });
+    }
+
+    async fn serial(&mut self, config: &SerialConfig) {
+        let name = config.port.clone();
+        let listed = tokio::task::spawn_blocking(enumerate_ports)
+            .await
+            .unwrap_or_default()
+            .iter()
+            .any(|port| port.name == name);
+        if !listed && !Path::new(&name).exists() {
+            let detail = format!("{} not found", name);
+            self.push(PreflightCheck::PortExists, CheckStatus::Fail, detail, Some((Symptom::DeviceNotFound, &[])));
+            return;
+        }
+        self.pass(PreflightCheck::PortExists, format!("{} present", name));
+
+        let baud_rate = config.baud_rate;
+        let opened = tokio::task::spawn_blocking(move || serialport::new(&name, baud_rate).open().map(drop)).await;
+        match opened {
+            Ok(Ok(())) => self.pass(PreflightCheck::PortFree, format!("{} can be opened", config.port)),
+            Ok(Err(e)) => {
+                let symptom = match e.kind() {
+                    serialport::ErrorKind::Io(std::io::ErrorKind::PermissionDenied) => Symptom::PermissionDenied,
+                    serialport::ErrorKind::NoDevice => Symptom::DeviceNotFound,
+                    _ => Symptom::ResourceBusy,
+                };
+                let detail = format!("Cannot open {}: {}", config.port, e);
+                self.push(PreflightCheck::PortFree, CheckStatus::Fail, detail, Some((symptom, &[])));
+            }
+            Err(e) => self.push(PreflightCheck::PortFree, CheckStatus::Warn, e.to_string(), None),
+        }
+    }
+
+    async fn network(&mut self, host: &str, port: u16) {
+        let addrs: Vec<SocketAddr> = match tokio::time::timeout(PREFLIGHT_TIMEOUT, tokio::net::lookup_host((host, port))).await {
+            Ok(Ok(resolved)) => resolved.collect(),
+            Ok(Err(e)) => {
+                let detail = format!("Cannot resolve {}: {}", host, e);
+                self.push(PreflightCheck::HostResolves, CheckStatus::Fail, detail, Some((Symptom::DnsFailure, &[])));
+                return;
+            }
+            Err(_) => {
+                let detail = format!("Resolving {} timed out", host);
+                self.push(PreflightCheck::HostResolves, CheckStatus::Warn, detail, Some((Symptom::DnsFailure, &[])));
+                return;
+            }
+        };
+        let Some(first) = addrs.first() else {
+            let detail = format!("No addresses for {}", host);
+            self.push(PreflightCheck::HostResolves, CheckStatus::Fail, detail, Some((Symptom::DnsFailure, &[])));
+            return;
+        };
+        self.pass(PreflightCheck::HostResolves, format!("{} is {}", host, first.ip()));
+
+        match tokio::time::timeout(PREFLIGHT_TIMEOUT, tokio::net::TcpStream::connect(&addrs[..])).await {
+            Ok(Ok(_)) => self.pass(PreflightCheck::PortReachable, format!("Port {} is open", port)),
+            Ok(Err(e)) => {
+                let detail = format!("Port {}: {}", port, e);
+                let symptom = (Symptom::ConnectionRefused, &[][..]);
+                self.push(PreflightCheck::PortReachable, CheckStatus::Fail, detail, Some(symptom));
+            }
+            Err(_) => {
+                let detail = format!("No answer on port {} within {}s", port, PREFLIGHT_TIMEOUT.as_secs());
+                let symptom = (Symptom::ConnectionTimeout, &[("tcp_open", "false")][..]);
+                self.push(PreflightCheck::PortReachable, CheckStatus::Warn, detail, Some(symptom));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::transport::TcpConfig;
+
+    #[tokio::test]
+    async fn test_tcp_preflight() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let report = run(&Transport::Tcp(TcpConfig::new("127.0.0.1", port))).await;
+        assert!(report.passed());
+        assert_eq!(report.get(PreflightCheck::PortReachable).unwrap().status, CheckStatus::Pass);
+
+        drop(listener);
+        let report = run(&Transport::Tcp(TcpConfig::new("127.0.0.1", port))).await;
+        let reachable = report.get(PreflightCheck::PortReachable).unwrap();
+        assert_eq!(reachable.status, CheckStatus::Fail);
+        assert!(reachable.hint.is_some());
+        assert_eq!(report.get(PreflightCheck::HostResolves).unwrap().status, CheckStatus::Pass);
+    }
+
+    #[tokio::test]
+    async fn test_missing_serial_port() {
+        let report = run(&Transport::Serial(SerialConfig::new("/dev/termicon-missing", 115200))).await;
+        let exists = report.get(PreflightCheck::PortExists).unwrap();
+        assert_eq!(exists.status, CheckStatus::Fail);
+        assert!(exists.hint.is_some());
+        assert!(report.get(PreflightCheck::PortFree).is_none());
+        assert!(!report.passed());
+    }
+}
@@ -2,10 +2,21 @@
 //!
 //! Supports saving and loading connection profiles with all settings
 
+mod templates;
+
+pub use templates::{DeviceTemplate, TemplateSnippet};
+
+use crate::core::session::{Session, SessionConfig, SessionError, SessionState};
+use crate::core::snippet::{Snippet, SnippetManager};
+use crate::core::storage::{self, Migration, StorageError};
+use crate::core::transport::{
+    enumerate_ports, PortInfo, SerialConfig, SshAuth, SshConfig, TcpConfig, TelnetConfig, Transport,
+    TransportError,
+};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use uuid::Uuid;
 
 /// Profile type
@@ -17,6 +28,47 @@ pub enum ProfileType {
     Ssh,
 }
 
+/// Identifies a USB serial adapter independently of its port name
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UsbSelector {
+    pub vid: u16,
+    pub pid: u16,
+    /// Tells apart several adapters of the same model
+    #[serde(default)]
+    pub serial_number: Option<String>,
+}
+
+impl UsbSelector {
+    /// Selector for the adapter behind a port (None for non-USB ports)
+    pub fn from_port(port: &PortInfo) -> Option<Self> {
+        Some(Self {
+            vid: port.vid?,
+            pid: port.pid?,
+            serial_number: port.serial_number.clone(),
+        })
+    }
+
+    /// Whether a port belongs to this adapter
+    pub fn matches(&self, port: &PortInfo) -> bool {
+        port.vid == Some(self.vid)
+            && port.pid == Some(self.pid)
+            && (self.serial_number.is_none() || port.serial_number == self.serial_number)
+    }
+
+    /// Name of this adapter's port in `ports`
+    ///
+    /// If several ports match, the one named `preferred` wins.
+    pub fn find_port(&self, ports: &[PortInfo], preferred: &str) -> Option<String> {
+        let mut matching = ports.iter().filter(|port| self.matches(port));
+        let first = matching.next()?;
+        let port = std::iter::once(first)
+            .chain(matching)
+            .find(|port| port.name == preferred)
+            .unwrap_or(first);
+        Some(port.name.clone())
+    }
+}
+
 /// Serial profile settings
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SerialProfile {
@@ -26,6 +78,32 @@ pub struct SerialProfile {
     pub parity: String,
     pub stop_bits: u8,
     pub flow_control: String,
+    /// Find the port by USB adapter instead of by name
+    #[serde(default)]
+    pub usb: Option<UsbSelector>,
+}
+
+impl SerialProfile {
+    /// Current name of the profile's port
+    ///
+    /// Without a USB selector this is the saved port name. With one, the
+    /// ports are enumerated and the matching adapter's name is returned,
+    /// so the profile survives the adapter moving to another port. None if
+    /// the adapter is not plugged in.
+    pub fn resolve_port(&self) -> Option<String> {
+        match self.usb {
+            Some(_) => self.resolve_port_in(&enumerate_ports()),
+            None => Some(self.port.clone()).filter(|port| !port.is_empty()),
+        }
+    }
+
+    /// Resolve the port against an already enumerated list
+    pub fn resolve_port_in(&self, ports: &[PortInfo]) -> Option<String> {
+        match &self.usb {
+            Some(selector) => selector.find_port(ports, &self.port),
+            None => Some(self.port.clone()).filter(|port| !port.is_empty()),
+        }
+    }
 }
 
 impl Default for SerialProfile {
@@ -37,6 +115,7 @@ impl Default for SerialProfile {
             parity: "None".to_string(),
             stop_bits: 1,
             flow_control: "None".to_string(),
+            usb: None,
         }
     }
 }
@@ -101,6 +180,8 @@ pub struct Profile {
     #[serde(default)]
     pub snippets: Vec<String>, // IDs of associated snippets
     #[serde(default)]
+    pub on_connect_snippet: Option<String>, // Snippet ID played right after connecting
+    #[serde(default)]
     pub auto_connect: bool,
     #[serde(default)]
     pub local_echo: bool,
@@ -122,6 +203,7 @@ impl Profile {
             tcp: None,
             ssh: None,
             snippets: Vec::new(),
+            on_connect_snippet: None,
             auto_connect: false,
             local_echo: true,
             log_session: false,
@@ -141,6 +223,7 @@ impl Profile {
             tcp: Some(TcpProfile::default()),
             ssh: None,
             snippets: Vec::new(),
+            on_connect_snippet: None,
             auto_connect: false,
             local_echo: true,
             log_session: false,
@@ -160,11 +243,118 @@ impl Profile {
             tcp: None,
             ssh: Some(SshProfile::default()),
             snippets: Vec::new(),
+            on_connect_snippet: None,
             auto_connect: false,
             local_echo: false,
             log_session: false,
         }
     }
+
+    /// Connect using this profile, then play its `on_connect_snippet`
+    ///
+    /// A missing or failing on-connect snippet is logged but does not fail
+    /// the connection.
+    pub async fn connect(&self, snippets: &SnippetManager) -> Result<Session, SessionError> {
+        let mut config = SessionConfig::new(&self.name, Transport::try_from(self)?);
+        config.local_echo.enabled = self.local_echo;
+        let session = Session::connect_with_config(config).await?;
+
+        if session.state() == SessionState::Connected {
+            if let Some(ref id) = self.on_connect_snippet {
+                match snippets.get(id) {
+                    Some(snippet) => {
+                        if let Err(e) = snippet.play(&session).await {
+                            tracing::warn!("On-connect snippet '{}' failed: {}", snippet.name, e);
+                        }
+                    }
+                    None => tracing::warn!("Profile '{}' references unknown snippet {}", self.name, id),
+                }
+            }
+        }
+
+        Ok(session)
+    }
+}
+
+impl Profile {
+    /// Check the fields an imported profile must have
+    fn check(&self) -> Result<(), String> {
+        if self.id.trim().is_empty() {
+            return Err(format!("profile '{}' has no id", self.name));
+        }
+        if self.name.trim().is_empty() {
+            return Err(format!("profile {} has no name", self.id));
+        }
+        let has_settings = match self.profile_type {
+            ProfileType::Serial => self.serial.is_some(),
+            ProfileType::Tcp | ProfileType::Telnet => self.tcp.is_some(),
+            ProfileType::Ssh => self.ssh.is_some(),
+        };
+        if !has_settings {
+            return Err(format!("profile '{}' has no {:?} settings", self.name, self.profile_type));
+        }
+        Ok(())
+    }
+}
+
+impl TryFrom<&Profile> for Transport {
+    type Error = TransportError;
+
+    /// Build the transport for a saved profile
+    ///
+    /// Passwords are not stored in profiles: password-auth SSH profiles get
+    /// an empty password that the caller must fill in before connecting.
+    fn try_from(profile: &Profile) -> Result<Self, Self::Error> {
+        let missing = |section: &str| {
+            TransportError::InvalidConfiguration(format!("profile '{}' has no {} settings", profile.name, section))
+        };
+
+        match profile.profile_type {
+            ProfileType::Serial => {
+                let serial = profile.serial.as_ref().ok_or_else(|| missing("serial"))?;
+                let port = match &serial.usb {
+                    Some(usb) => serial.resolve_port().ok_or_else(|| {
+                        TransportError::PortNotFound(format!("USB {:04X}:{:04X}", usb.vid, usb.pid))
+                    })?,
+                    None => serial.port.clone(),
+                };
+                let mut config = SerialConfig::new(&port, serial.baud_rate);
+                config.data_bits = serial.data_bits;
+                config.stop_bits = serial.stop_bits;
+                let invalid = |field: &str, value: &str| {
+                    TransportError::InvalidConfiguration(format!(
+                        "profile '{}' has invalid {} '{}'",
+                        profile.name, field, value
+                    ))
+                };
+                config.parity = serial.parity.parse().map_err(|()| invalid("parity", &serial.parity))?;
+                config.flow_control =
+                    serial.flow_control.parse().map_err(|()| invalid("flow control", &serial.flow_control))?;
+                Ok(Transport::Serial(config))
+            }
+            ProfileType::Tcp => {
+                let tcp = profile.tcp.as_ref().ok_or_else(|| missing("TCP"))?;
+                let mut config = TcpConfig::new(&tcp.host, tcp.port);
+                config.timeout_secs = tcp.timeout_secs;
+                Ok(Transport::Tcp(config))
+            }
+            ProfileType::Telnet => {
+                let tcp = profile.tcp.as_ref().ok_or_else(|| missing("Telnet"))?;
+                Ok(Transport::Telnet(TelnetConfig::new(&tcp.host).port(tcp.port)))
+            }
+            ProfileType::Ssh => {
+                let ssh = profile.ssh.as_ref().ok_or_else(|| missing("SSH"))?;
+                let mut config = SshConfig::new(&ssh.host, &ssh.username).port(ssh.port);
+                config.term_type = ssh.term_type.clone();
+                config.auth = match (ssh.auth_type.as_str(), &ssh.key_path) {
+                    ("key", Some(key)) => SshAuth::PublicKey { private_key: PathBuf::from(key), passphrase: None },
+                    ("agent", _) => SshAuth::Agent,
+                    _ => SshAuth::Password(String::new()),
+                };
+                Ok(Transport::Ssh(config))
+            }
+        }
+    }
 }
 
 /// Profile manager
@@ -183,7 +373,9 @@ impl ProfileManager {
             folders: Vec::new(),
             config_path,
         };
-        manager.load().ok();
+        if let Err(e) = manager.load() {
+            tracing::error!("Failed to load profiles: {}", e);
+        }
         manager
     }
 
@@ -199,16 +391,14 @@ impl ProfileManager {
     }
 
     /// Load profiles from disk
-    pub fn load(&mut self) -> Result<(), String> {
-        if !self.config_path.exists() {
+    ///
+    /// Older formats are upgraded (see [`storage::read_versioned`]). A file
+    /// that does not parse is renamed to `profiles.json.corrupt` and reported
+    /// as [`StorageError::Corrupt`], leaving the manager empty.
+    pub fn load(&mut self) -> Result<(), StorageError> {
+        let Some(data) = storage::read_versioned::<ProfileData>(&self.config_path, PROFILE_MIGRATIONS)? else {
             return Ok(());
-        }
-
-        let content = fs::read_to_string(&self.config_path)
-            .map_err(|e| format!("Failed to read profiles: {}", e))?;
-
-        let data: ProfileData = serde_json::from_str(&content)
-            .map_err(|e| format!("Failed to parse profiles: {}", e))?;
+        };
 
         self.profiles = data.profiles.into_iter()
             .map(|p| (p.id.clone(), p))
@@ -219,19 +409,51 @@ impl ProfileManager {
     }
 
     /// Save profiles to disk
-    pub fn save(&self) -> Result<(), String> {
+    pub fn save(&self) -> Result<(), StorageError> {
         let data = ProfileData {
+            version: PROFILE_FORMAT_VERSION,
             profiles: self.profiles.values().cloned().collect(),
             folders: self.folders.clone(),
         };
 
-        let content = serde_json::to_string_pretty(&data)
-            .map_err(|e| format!("Failed to serialize profiles: {}", e))?;
+        storage::write_json(&self.config_path, &data)
+    }
 
-        fs::write(&self.config_path, content)
-            .map_err(|e| format!("Failed to write profiles: {}", e))?;
+    /// Merge profiles from a shared or hand-edited file, returning how many were added
+    ///
+    /// The whole file is checked before anything changes: it must parse, be
+    /// a supported format version (older ones are upgraded), and every profile needs a name and the
+    /// settings for its type. Existing profiles are never replaced; imported
+    /// profiles whose ID is taken get a new one.
+    pub fn import(&mut self, path: &Path) -> Result<usize, StorageError> {
+        let invalid = |reason: String| StorageError::Invalid {
+            path: path.to_path_buf(),
+            reason,
+        };
+        let value = storage::read_json::<serde_json::Value>(path)?.ok_or_else(|| invalid("file not found".to_string()))?;
+        let value = storage::upgrade(value, PROFILE_MIGRATIONS).map_err(invalid)?;
+        let data: ProfileData = serde_json::from_value(value).map_err(|source| StorageError::Parse {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        for profile in &data.profiles {
+            profile.check().map_err(invalid)?;
+        }
 
-        Ok(())
+        let count = data.profiles.len();
+        for mut profile in data.profiles {
+            if self.profiles.contains_key(&profile.id) {
+                profile.id = Uuid::new_v4().to_string();
+            }
+            self.profiles.insert(profile.id.clone(), profile);
+        }
+        for folder in data.folders {
+            if !self.folders.contains(&folder) {
+                self.folders.push(folder);
+            }
+        }
+        self.save()?;
+        Ok(count)
     }
 
     /// Add a profile
@@ -301,6 +523,48 @@ impl ProfileManager {
         self.profiles.insert(profile.id.clone(), profile);
         let _ = self.save();
     }
+
+    /// Get available device templates
+    ///
+    /// Bundled templates come first, followed by any user templates from
+    /// `templates.json` in the config directory. A user template with the
+    /// same id replaces the bundled one.
+    pub fn templates(&self) -> Vec<DeviceTemplate> {
+        let mut templates = DeviceTemplate::builtin();
+
+        let user_path = self.config_path.with_file_name("templates.json");
+        if user_path.exists() {
+            match DeviceTemplate::load_file(&user_path) {
+                Ok(user) => {
+                    for template in user {
+                        templates.retain(|t| t.id != template.id);
+                        templates.push(template);
+                    }
+                }
+                Err(e) => tracing::warn!("Ignoring user templates: {}", e),
+            }
+        }
+
+        templates
+    }
+
+    /// Create and add a serial profile from a device template
+    ///
+    /// Returns the new profile and the template's default snippets; the
+    /// profile already references the snippet IDs, so the caller only needs
+    /// to register them with the `SnippetManager`.
+    pub fn from_template(&mut self, template: &DeviceTemplate) -> (Profile, Vec<Snippet>) {
+        let snippets = template.create_snippets();
+
+        let mut profile = Profile::new_serial(&template.name);
+        profile.serial = Some(template.serial.clone());
+        profile.local_echo = template.local_echo;
+        profile.notes = template.description.clone();
+        profile.snippets = snippets.iter().map(|s| s.id.clone()).collect();
+
+        self.add(profile.clone());
+        (profile, snippets)
+    }
 }
 
 impl Default for ProfileManager {
@@ -309,16 +573,26 @@ impl Default for ProfileManager {
     }
 }
 
+/// Upgrades of `profiles.json`; v1 added the version field
+const PROFILE_MIGRATIONS: &[Migration] = &[storage::add_version];
+
+/// Current `profiles.json` format version
+const PROFILE_FORMAT_VERSION: u32 = PROFILE_MIGRATIONS.len() as u32;
+
 /// Serialized profile data
 #[derive(Debug, Serialize, Deserialize)]
 struct ProfileData {
+    /// Format version; files from before versioning are version 0
+    version: u32,
     profiles: Vec<Profile>,
+    #[serde(default)]
     folders: Vec<String>,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::core::transport::{SerialFlowControl, SerialParity};
 
     #[test]
     fn test_profile_creation() {
@@ -328,11 +602,161 @@ mod tests {
         assert!(profile.serial.is_some());
     }
 
+    #[test]
+    fn test_profile_to_transport() {
+        let mut profile = Profile::new_serial("Modem");
+        let serial = profile.serial.as_mut().unwrap();
+        serial.port = "/dev/ttyUSB0".to_string();
+        serial.parity = "Even".to_string();
+        serial.flow_control = "RTS/CTS".to_string();
+
+        match Transport::try_from(&profile).unwrap() {
+            Transport::Serial(cfg) => {
+                assert_eq!(cfg.port, "/dev/ttyUSB0");
+                assert_eq!(cfg.parity, SerialParity::Even);
+                assert_eq!(cfg.flow_control, SerialFlowControl::Hardware);
+            }
+            other => panic!("unexpected transport {:?}", other),
+        }
+
+        let mut ssh = Profile::new_ssh("Router");
+        ssh.ssh.as_mut().unwrap().auth_type = "key".to_string();
+        ssh.ssh.as_mut().unwrap().key_path = Some("/home/me/.ssh/id_ed25519".to_string());
+        match Transport::try_from(&ssh).unwrap() {
+            Transport::Ssh(cfg) => assert!(matches!(cfg.auth, SshAuth::PublicKey { .. })),
+            other => panic!("unexpected transport {:?}", other),
+        }
+
+        let mut broken = Profile::new_tcp("Broken");
+        broken.tcp = None;
+        assert!(Transport::try_from(&broken).is_err());
+
+        let mut typo = Profile::new_serial("Typo");
+        typo.serial.as_mut().unwrap().flow_control = "RTS-CTS".to_string();
+        assert!(matches!(Transport::try_from(&typo), Err(TransportError::InvalidConfiguration(_))));
+    }
+
+    #[test]
+    fn test_usb_selector_resolves_moved_adapter() {
+        let usb_port = |name: &str, serial: &str| {
+            let mut port = PortInfo::new(name);
+            port.vid = Some(0x0403);
+            port.pid = Some(0x6001);
+            port.serial_number = Some(serial.to_string());
+            port
+        };
+        let ports = vec![PortInfo::new("/dev/ttyS0"), usb_port("/dev/ttyUSB3", "A1"), usb_port("/dev/ttyUSB4", "B2")];
+
+        let mut serial = SerialProfile {
+            port: "/dev/ttyUSB0".to_string(),
+            ..SerialProfile::default()
+        };
+        assert_eq!(serial.resolve_port_in(&ports).as_deref(), Some("/dev/ttyUSB0"));
+
+        serial.usb = UsbSelector::from_port(&ports[2]);
+        assert_eq!(serial.resolve_port_in(&ports).as_deref(), Some("/dev/ttyUSB4"));
+
+        // Any adapter of the model; the saved name breaks the tie
+        serial.usb.as_mut().unwrap().serial_number = None;
+        serial.port = "/dev/ttyUSB4".to_string();
+        assert_eq!(serial.resolve_port_in(&ports).as_deref(), Some("/dev/ttyUSB4"));
+
+        assert_eq!(serial.resolve_port_in(&ports[..1]), None);
+    }
+
+    #[test]
+    fn test_on_connect_snippet_defaults_to_none() {
+        let mut profile = Profile::new_tcp("Switch");
+        profile.on_connect_snippet = Some("prep".to_string());
+
+        let mut json = serde_json::to_value(&profile).unwrap();
+        let restored: Profile = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(restored.on_connect_snippet.as_deref(), Some("prep"));
+
+        // Profiles saved before the field existed still load
+        json.as_object_mut().unwrap().remove("on_connect_snippet");
+        let legacy: Profile = serde_json::from_value(json).unwrap();
+        assert!(legacy.on_connect_snippet.is_none());
+    }
+
     #[test]
     fn test_profile_manager() {
         let manager = ProfileManager::new();
         assert_eq!(manager.count(), 0);
     }
+
+    #[test]
+    fn test_corrupt_file_is_kept() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("profiles.json");
+        fs::write(&path, "{\"profiles\": [").unwrap();
+        let mut manager = ProfileManager {
+            profiles: HashMap::new(),
+            folders: Vec::new(),
+            config_path: path.clone(),
+        };
+
+        assert!(matches!(manager.load(), Err(StorageError::Corrupt { .. })));
+        assert_eq!(fs::read_to_string(dir.path().join("profiles.json.corrupt")).unwrap(), "{\"profiles\": [");
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_import_validates_before_merging() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut manager = ProfileManager {
+            profiles: HashMap::new(),
+            folders: Vec::new(),
+            config_path: dir.path().join("profiles.json"),
+        };
+        let existing = Profile::new_tcp("Switch");
+        manager.add(existing.clone());
+
+        let shared = dir.path().join("shared.json");
+        let mut clash = Profile::new_serial("Board");
+        clash.id = existing.id.clone();
+        let data = ProfileData {
+            version: PROFILE_FORMAT_VERSION,
+            profiles: vec![clash, Profile::new_ssh("Router")],
+            folders: vec!["Lab".to_string()],
+        };
+        storage::write_json(&shared, &data).unwrap();
+        assert_eq!(manager.import(&shared).unwrap(), 2);
+        assert_eq!(manager.count(), 3);
+        assert_eq!(manager.get(&existing.id).unwrap().name, "Switch");
+        assert_eq!(manager.folders(), ["Lab".to_string()]);
+
+        let mut broken = Profile::new_tcp("Broken");
+        broken.tcp = None;
+        storage::write_json(&shared, &ProfileData { profiles: vec![broken], ..data }).unwrap();
+        assert!(matches!(manager.import(&shared), Err(StorageError::Invalid { .. })));
+
+        // Files from before versioning are version 0 and upgraded
+        storage::write_json(&shared, &serde_json::json!({"profiles": [Profile::new_tcp("Old")]})).unwrap();
+        assert_eq!(manager.import(&shared).unwrap(), 1);
+
+        storage::write_json(&shared, &serde_json::json!({"version": 99, "profiles": []})).unwrap();
+        assert!(matches!(manager.import(&shared), Err(StorageError::Invalid { .. })));
+        assert_eq!(manager.count(), 4);
+    }
+
+    #[test]
+    fn test_profile_from_template() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut manager = ProfileManager {
+            profiles: HashMap::new(),
+            folders: Vec::new(),
+            config_path: dir.path().join("profiles.json"),
+        };
+
+        let esp32 = manager.templates().into_iter().find(|t| t.id == "esp32").unwrap();
+        let (profile, snippets) = manager.from_template(&esp32);
+
+        assert_eq!(profile.serial.as_ref().unwrap().baud_rate, 115200);
+        assert_eq!(profile.snippets.len(), snippets.len());
+        assert!(!snippets.is_empty());
+        assert_eq!(manager.count(), 1);
+    }
 }
 
 
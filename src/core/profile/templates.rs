@@ -0,0 +1,85 @@
+//! Device templates for new connection profiles
+//!
+//! Templates pre-fill serial settings and default snippets for common
+//! devices. The bundled set lives in `templates.json`; users can add their
+//! own by placing a `templates.json` next to `profiles.json`.
+
+use super::SerialProfile;
+use crate::core::snippet::{LineEnding, Snippet};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// Bundled templates (compiled in)
+const BUILTIN_TEMPLATES: &str = include_str!("templates.json");
+
+/// Snippet attached to a device template
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateSnippet {
+    pub name: String,
+    pub content: String,
+    #[serde(default)]
+    pub line_ending: LineEnding,
+}
+
+/// Connection template for a known device
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeviceTemplate {
+    /// Stable identifier, e.g. "esp32"
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub serial: SerialProfile,
+    #[serde(default)]
+    pub local_echo: bool,
+    #[serde(default)]
+    pub snippets: Vec<TemplateSnippet>,
+}
+
+impl DeviceTemplate {
+    /// Get the bundled templates
+    pub fn builtin() -> Vec<DeviceTemplate> {
+        serde_json::from_str(BUILTIN_TEMPLATES).expect("bundled templates.json is valid")
+    }
+
+    /// Load templates from a JSON file
+    pub fn load_file(path: &Path) -> Result<Vec<DeviceTemplate>, String> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read templates: {}", e))?;
+
+        serde_json::from_str(&content)
+            .map_err(|e| format!("Failed to parse templates: {}", e))
+    }
+
+    /// Create the template's default snippets
+    ///
+    /// Snippets are filed in a folder named after the template.
+    pub fn create_snippets(&self) -> Vec<Snippet> {
+        self.snippets
+            .iter()
+            .map(|s| {
+                let mut snippet = Snippet::new_command(&s.name, &s.content);
+                snippet.line_ending = s.line_ending;
+                snippet.folder = Some(self.name.clone());
+                snippet
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_templates() {
+        let templates = DeviceTemplate::builtin();
+        let ids: Vec<&str> = templates.iter().map(|t| t.id.as_str()).collect();
+        assert!(ids.contains(&"esp32"));
+        assert!(ids.contains(&"cisco-console"));
+
+        let cisco = templates.iter().find(|t| t.id == "cisco-console").unwrap();
+        assert_eq!(cisco.serial.baud_rate, 9600);
+    }
+}
@@ -0,0 +1,103 @@
+//! Prompt detection
+//!
+//! Tells when a device is waiting for input by matching the end of the
+//! received data against a prompt pattern, so scripts and paced sends can
+//! wait for the prompt instead of sleeping for a fixed time.
+
+use regex::bytes::Regex;
+
+/// Default prompt pattern; the named group that matched gives the kind
+pub const DEFAULT_PROMPT_PATTERN: &str = r"(?:(?P<login>(?i:login|username):\s?)|(?P<password>(?i:password):\s?)|(?P<root>#\s)|(?P<shell>\$\s)|(?P<generic>>\s))$";
+
+/// Kind of prompt that was seen
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PromptKind {
+    /// `login:` or `username:`
+    Login,
+    /// `Password:`
+    Password,
+    /// Root shell, `# `
+    Root,
+    /// User shell, `$ `
+    Shell,
+    /// `> ` (bootloaders, REPLs, CLIs)
+    Generic,
+    /// Match of a pattern without a kind group
+    Custom,
+}
+
+/// Named groups that select the kind, in order of precedence
+const KIND_GROUPS: &[(&str, PromptKind)] = &[
+    ("login", PromptKind::Login),
+    ("password", PromptKind::Password),
+    ("root", PromptKind::Root),
+    ("shell", PromptKind::Shell),
+    ("generic", PromptKind::Generic),
+];
+
+/// Matches prompts at the end of received data
+///
+/// Only the text after the last line break is tested. A custom pattern
+/// should be anchored with `$`; its named groups `login`, `password`,
+/// `root`, `shell` and `generic` pick the reported kind.
+#[derive(Debug, Clone)]
+pub struct PromptDetector {
+    pattern: Regex,
+}
+
+impl Default for PromptDetector {
+    fn default() -> Self {
+        Self::new(DEFAULT_PROMPT_PATTERN).expect("default prompt pattern is valid")
+    }
+}
+
+impl PromptDetector {
+    /// Detector for a custom prompt pattern
+    pub fn new(pattern: &str) -> Result<Self, String> {
+        let pattern = Regex::new(pattern).map_err(|e| format!("Invalid prompt pattern: {}", e))?;
+        Ok(Self { pattern })
+    }
+
+    /// Prompt pattern
+    pub fn pattern(&self) -> &str {
+        self.pattern.as_str()
+    }
+
+    /// Kind of prompt `recent` ends with, if any
+    pub fn saw_prompt(&self, recent: &[u8]) -> Option<PromptKind> {
+        let last_line = recent.rsplit(|&b| b == b'\n' || b == b'\r').next().unwrap_or(recent);
+        let captures = self.pattern.captures(last_line)?;
+        let kind = KIND_GROUPS
+            .iter()
+            .find(|(name, _)| captures.name(name).is_some())
+            .map_or(PromptKind::Custom, |&(_, kind)| kind);
+        Some(kind)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_prompts() {
+        let detector = PromptDetector::default();
+        assert_eq!(detector.saw_prompt(b"Welcome\r\nrouter login: "), Some(PromptKind::Login));
+        assert_eq!(detector.saw_prompt(b"Password:"), Some(PromptKind::Password));
+        assert_eq!(detector.saw_prompt(b"\x1b[32mpi@rpi\x1b[0m:~ $ "), Some(PromptKind::Shell));
+        assert_eq!(detector.saw_prompt(b"root@box:/# "), Some(PromptKind::Root));
+        assert_eq!(detector.saw_prompt(b"U-Boot> "), Some(PromptKind::Generic));
+
+        // A prompt-like string that is not at the end is not a prompt
+        assert_eq!(detector.saw_prompt(b"$ ls\r\nfile.txt\r\n"), None);
+        assert_eq!(detector.saw_prompt(b"costs $ 5"), None);
+    }
+
+    #[test]
+    fn test_custom_pattern() {
+        let detector = PromptDetector::new(r"=>\s?$").unwrap();
+        assert_eq!(detector.saw_prompt(b"boot\n=> "), Some(PromptKind::Custom));
+        assert_eq!(detector.saw_prompt(b"U-Boot> "), None);
+        assert!(PromptDetector::new("(").is_err());
+    }
+}
@@ -0,0 +1,183 @@
+//! AT command responses
+//!
+//! Collects the lines a modem or module sends back after an AT command up
+//! to the final result code (`OK`, `ERROR`, `+CME ERROR: <n>`). Used by
+//! [`Session::at_command`](crate::core::session::Session::at_command).
+
+use crate::core::session::SessionError;
+use std::time::Duration;
+use thiserror::Error;
+
+/// Mobile equipment error from `+CME ERROR` (3GPP TS 27.007)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CmeError {
+    PhoneFailure,
+    OperationNotAllowed,
+    OperationNotSupported,
+    SimNotInserted,
+    SimPinRequired,
+    SimPukRequired,
+    SimFailure,
+    SimBusy,
+    IncorrectPassword,
+    MemoryFull,
+    NotFound,
+    NoNetworkService,
+    NetworkTimeout,
+    Unknown,
+    /// Numeric code without a variant
+    Other(u16),
+    /// Verbose error text (`AT+CMEE=2`)
+    Text(String),
+}
+
+impl CmeError {
+    /// Error for a numeric code
+    pub fn from_code(code: u16) -> Self {
+        match code {
+            0 => Self::PhoneFailure,
+            3 => Self::OperationNotAllowed,
+            4 => Self::OperationNotSupported,
+            10 => Self::SimNotInserted,
+            11 => Self::SimPinRequired,
+            12 => Self::SimPukRequired,
+            13 => Self::SimFailure,
+            14 => Self::SimBusy,
+            16 => Self::IncorrectPassword,
+            20 => Self::MemoryFull,
+            22 => Self::NotFound,
+            30 => Self::NoNetworkService,
+            31 => Self::NetworkTimeout,
+            100 => Self::Unknown,
+            other => Self::Other(other),
+        }
+    }
+
+    /// Parse the text after `+CME ERROR:`
+    pub fn parse(text: &str) -> Self {
+        let text = text.trim();
+        match text.parse() {
+            Ok(code) => Self::from_code(code),
+            Err(_) => Self::Text(text.to_string()),
+        }
+    }
+}
+
+/// Final result code of an AT command
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AtStatus {
+    Ok,
+    Error,
+    CmeError(CmeError),
+}
+
+impl AtStatus {
+    /// Final result code for a response line, if it is one
+    pub fn parse(line: &str) -> Option<Self> {
+        match line.trim() {
+            "OK" => Some(Self::Ok),
+            "ERROR" => Some(Self::Error),
+            line => line.strip_prefix("+CME ERROR:").map(|rest| Self::CmeError(CmeError::parse(rest))),
+        }
+    }
+}
+
+/// Response to an AT command
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AtResponse {
+    /// Information lines before the final result code
+    pub lines: Vec<String>,
+    /// Final result code
+    pub status: AtStatus,
+}
+
+impl AtResponse {
+    /// Check if the command succeeded
+    pub fn is_ok(&self) -> bool {
+        self.status == AtStatus::Ok
+    }
+}
+
+/// AT command errors
+#[derive(Error, Debug)]
+pub enum AtError {
+    /// No final result code in time; holds the lines received so far
+    #[error("No final result code within {timeout:?}")]
+    Timeout { timeout: Duration, lines: Vec<String> },
+
+    /// The session closed before the response was complete
+    #[error("Session closed while waiting for a response")]
+    Closed,
+
+    /// The command could not be sent
+    #[error(transparent)]
+    Session(#[from] SessionError),
+}
+
+/// Gathers response lines until the final result code
+#[derive(Debug, Clone)]
+pub struct AtCollector {
+    command: String,
+    lines: Vec<String>,
+}
+
+impl AtCollector {
+    /// Collect the response to `command`, whose echo is skipped
+    pub fn new(command: &str) -> Self {
+        Self {
+            command: command.trim().to_string(),
+            lines: Vec::new(),
+        }
+    }
+
+    /// Feed one received line; returns the response once it is complete
+    pub fn feed(&mut self, line: &str) -> Option<AtResponse> {
+        let line = line.trim();
+        if line.is_empty() || line == self.command {
+            return None;
+        }
+        match AtStatus::parse(line) {
+            Some(status) => Some(AtResponse {
+                lines: std::mem::take(&mut self.lines),
+                status,
+            }),
+            None => {
+                self.lines.push(line.to_string());
+                None
+            }
+        }
+    }
+
+    /// Lines received so far
+    pub fn into_lines(self) -> Vec<String> {
+        self.lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collects_until_final_code() {
+        let mut collector = AtCollector::new("AT+CSQ");
+        assert_eq!(collector.feed("AT+CSQ"), None);
+        assert_eq!(collector.feed(""), None);
+        assert_eq!(collector.feed("+CSQ: 21,99"), None);
+        let response = collector.feed("OK").unwrap();
+        assert!(response.is_ok());
+        assert_eq!(response.lines, ["+CSQ: 21,99"]);
+    }
+
+    #[test]
+    fn test_cme_errors() {
+        assert_eq!(AtStatus::parse("+CME ERROR: 10"), Some(AtStatus::CmeError(CmeError::SimNotInserted)));
+        assert_eq!(AtStatus::parse("+CME ERROR: 515"), Some(AtStatus::CmeError(CmeError::Other(515))));
+        assert_eq!(
+            AtStatus::parse("+CME ERROR: SIM PIN required"),
+            Some(AtStatus::CmeError(CmeError::Text("SIM PIN required".to_string())))
+        );
+        assert_eq!(AtStatus::parse("ERROR"), Some(AtStatus::Error));
+        assert_eq!(AtStatus::parse("+CREG: 0,1"), None);
+    }
+}
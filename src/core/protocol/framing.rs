@@ -2,6 +2,8 @@
 //!
 //! Supports: SLIP, COBS, STX/ETX, Length-prefixed, Line-based
 
+use crate::core::protocol_dsl::ByteOrder;
+
 /// Framing protocol types
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum FramingType {
@@ -63,6 +65,124 @@ impl FramingType {
             FramingType::LengthPrefix32Le => "Length-32 LE",
         }
     }
+
+    /// Header layout of the length-prefixed variants
+    pub fn length_prefix_config(&self) -> Option<LengthPrefixConfig> {
+        let (width, byte_order) = match self {
+            FramingType::LengthPrefix8 => (LengthWidth::U8, ByteOrder::Big),
+            FramingType::LengthPrefix16Be => (LengthWidth::U16, ByteOrder::Big),
+            FramingType::LengthPrefix16Le => (LengthWidth::U16, ByteOrder::Little),
+            FramingType::LengthPrefix32Be => (LengthWidth::U32, ByteOrder::Big),
+            FramingType::LengthPrefix32Le => (LengthWidth::U32, ByteOrder::Little),
+            _ => return None,
+        };
+        Some(LengthPrefixConfig {
+            width,
+            byte_order,
+            max_frame_size: width.default_max_frame_size(),
+            ..Default::default()
+        })
+    }
+}
+
+/// Width of a length field
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LengthWidth {
+    U8,
+    U16,
+    U32,
+}
+
+impl LengthWidth {
+    /// Size of the field in bytes
+    pub fn size(&self) -> usize {
+        match self {
+            LengthWidth::U8 => 1,
+            LengthWidth::U16 => 2,
+            LengthWidth::U32 => 4,
+        }
+    }
+
+    /// Frame size cap used unless configured otherwise
+    pub fn default_max_frame_size(&self) -> usize {
+        match self {
+            LengthWidth::U8 | LengthWidth::U16 => DEFAULT_MAX_FRAME_SIZE,
+            LengthWidth::U32 => DEFAULT_MAX_FRAME_SIZE_32,
+        }
+    }
+}
+
+/// Default cap on a single length-prefixed frame
+pub const DEFAULT_MAX_FRAME_SIZE: usize = 64 * 1024;
+
+/// Default cap for 32-bit length fields, which exist to carry larger frames
+pub const DEFAULT_MAX_FRAME_SIZE_32: usize = 16 * 1024 * 1024;
+
+/// Header layout for length-prefixed framing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LengthPrefixConfig {
+    /// Bytes before the length field (sync bytes, message type, ...)
+    pub offset: usize,
+    /// Width of the length field
+    pub width: LengthWidth,
+    /// Byte order of the length field
+    pub byte_order: ByteOrder,
+    /// Length counts the header (offset + length field) as well as the payload
+    pub includes_header: bool,
+    /// Emit the whole frame instead of only the payload
+    pub keep_header: bool,
+    /// Larger frames are treated as corrupt
+    pub max_frame_size: usize,
+}
+
+impl Default for LengthPrefixConfig {
+    fn default() -> Self {
+        Self {
+            offset: 0,
+            width: LengthWidth::U16,
+            byte_order: ByteOrder::Big,
+            includes_header: false,
+            keep_header: false,
+            max_frame_size: DEFAULT_MAX_FRAME_SIZE,
+        }
+    }
+}
+
+impl LengthPrefixConfig {
+    /// Header size: offset plus the length field
+    pub fn header_len(&self) -> usize {
+        self.offset + self.width.size()
+    }
+
+    /// Read the length field from a buffer holding at least the header
+    fn read_length(&self, header: &[u8]) -> usize {
+        let field = &header[self.offset..self.header_len()];
+        let mut bytes = [0u8; 4];
+        match self.byte_order {
+            ByteOrder::Big => {
+                bytes[4 - field.len()..].copy_from_slice(field);
+                u32::from_be_bytes(bytes) as usize
+            }
+            ByteOrder::Little => {
+                bytes[..field.len()].copy_from_slice(field);
+                u32::from_le_bytes(bytes) as usize
+            }
+        }
+    }
+
+    /// Total frame size announced by a header, or None if it is corrupt
+    fn frame_len(&self, header: &[u8]) -> Option<usize> {
+        let length = self.read_length(header);
+        let total = if self.includes_header {
+            if length < self.header_len() {
+                return None;
+            }
+            length
+        } else {
+            length.checked_add(self.header_len())?
+        };
+        (total <= self.max_frame_size).then_some(total)
+    }
 }
 
 // ============ SLIP Constants ============
@@ -456,7 +576,9 @@ fn decode_length_prefix_32(data: &[u8], big_endian: bool) -> Vec<Vec<u8>> {
 /// Streaming frame decoder that handles partial data
 pub struct FrameDecoder {
     framing: FramingType,
+    length_prefix: Option<LengthPrefixConfig>,
     buffer: Vec<u8>,
+    discarded: u64,
 }
 
 impl FrameDecoder {
@@ -464,13 +586,38 @@ impl FrameDecoder {
     pub fn new(framing: FramingType) -> Self {
         Self {
             framing,
+            length_prefix: framing.length_prefix_config(),
+            buffer: Vec::new(),
+            discarded: 0,
+        }
+    }
+
+    /// Create a decoder for a custom length-prefixed header layout
+    pub fn length_prefixed(config: LengthPrefixConfig) -> Self {
+        Self {
+            framing: FramingType::None,
+            length_prefix: Some(config),
             buffer: Vec::new(),
+            discarded: 0,
+        }
+    }
+
+    /// Change the cap on length-prefixed frames; larger ones are treated as corrupt
+    #[must_use]
+    pub fn max_frame_size(mut self, max_frame_size: usize) -> Self {
+        if let Some(ref mut config) = self.length_prefix {
+            config.max_frame_size = max_frame_size;
         }
+        self
     }
 
     /// Add data and return complete frames
     pub fn push(&mut self, data: &[u8]) -> Vec<Vec<u8>> {
         self.buffer.extend_from_slice(data);
+
+        if let Some(config) = self.length_prefix {
+            return self.drain_length_prefixed(&config);
+        }
         
         let frames = decode(&self.buffer, self.framing);
         
@@ -483,6 +630,36 @@ impl FrameDecoder {
         frames
     }
 
+    /// Extract complete length-prefixed frames, keeping any partial tail
+    fn drain_length_prefixed(&mut self, config: &LengthPrefixConfig) -> Vec<Vec<u8>> {
+        let header_len = config.header_len();
+        let mut frames = Vec::new();
+        let mut pos = 0;
+
+        while self.buffer.len() - pos >= header_len {
+            let Some(total) = config.frame_len(&self.buffer[pos..]) else {
+                // Corrupt length: skip a byte and try to resync
+                pos += 1;
+                self.discarded += 1;
+                continue;
+            };
+            if self.buffer.len() - pos < total {
+                break;
+            }
+            let start = if config.keep_header { pos } else { pos + header_len };
+            frames.push(self.buffer[start..pos + total].to_vec());
+            pos += total;
+        }
+
+        self.buffer.drain(..pos);
+        frames
+    }
+
+    /// Bytes dropped while resyncing after a corrupt length
+    pub fn discarded(&self) -> u64 {
+        self.discarded
+    }
+
     /// Clear buffer
     pub fn clear(&mut self) {
         self.buffer.clear();
@@ -534,4 +711,56 @@ mod tests {
         assert_eq!(decoded.len(), 1);
         assert_eq!(decoded[0], original);
     }
+
+    #[test]
+    fn test_length_prefixed_decoder_streams_partial_frames() {
+        let mut decoder = FrameDecoder::new(FramingType::LengthPrefix16Le);
+        assert!(decoder.push(&[0x03, 0x00, b'a']).is_empty());
+        let frames = decoder.push(&[b'b', b'c', 0x01, 0x00]);
+        assert_eq!(frames, vec![b"abc".to_vec()]);
+        assert_eq!(decoder.push(&[b'd']), vec![b"d".to_vec()]);
+    }
+
+    #[test]
+    fn test_length_prefixed_decoder_offset_and_inclusive_length() {
+        // Sync byte, type byte, then a length covering the whole frame
+        let config = LengthPrefixConfig {
+            offset: 2,
+            width: LengthWidth::U8,
+            includes_header: true,
+            keep_header: true,
+            ..Default::default()
+        };
+        let mut decoder = FrameDecoder::length_prefixed(config);
+        let frames = decoder.push(&[0xAA, 0x01, 0x05, 0x10, 0x20, 0xAA, 0x02, 0x03]);
+        assert_eq!(frames, vec![vec![0xAA, 0x01, 0x05, 0x10, 0x20], vec![0xAA, 0x02, 0x03]]);
+    }
+
+    #[test]
+    fn test_length_prefix32_frames_past_64k() {
+        let payload = vec![0x5A; 100 * 1024];
+        let encoded = encode(&payload, FramingType::LengthPrefix32Le);
+
+        let mut decoder = FrameDecoder::new(FramingType::LengthPrefix32Le);
+        assert_eq!(decoder.push(&encoded), vec![payload]);
+
+        let mut capped = FrameDecoder::new(FramingType::LengthPrefix32Le).max_frame_size(1024);
+        assert!(capped.push(&encoded[..16]).is_empty());
+        assert!(capped.discarded() > 0);
+    }
+
+    #[test]
+    fn test_length_prefixed_decoder_rejects_oversized_length() {
+        let config = LengthPrefixConfig {
+            width: LengthWidth::U32,
+            max_frame_size: 16,
+            ..Default::default()
+        };
+        let mut decoder = FrameDecoder::length_prefixed(config);
+        // A bogus 0xFFFFFFFF length must not be waited on or allocated
+        let mut data = vec![0xFF, 0xFF, 0xFF, 0xFF];
+        data.extend_from_slice(&[0x00, 0x00, 0x00, 0x02, b'o', b'k']);
+        assert_eq!(decoder.push(&data), vec![b"ok".to_vec()]);
+        assert_eq!(decoder.discarded(), 4);
+    }
 }
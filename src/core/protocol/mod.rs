@@ -5,7 +5,9 @@
 //! - Checksum algorithms (CRC-16, CRC-32, etc.)
 //! - Framing (SLIP, COBS, STX/ETX, length-prefixed)
 //! - NMEA 0183 (GPS and marine)
+//! - AT command responses
 
+pub mod at;
 pub mod checksum;
 pub mod framing;
 pub mod modbus;
@@ -15,20 +17,25 @@ pub mod nmea;
 // Note: File transfer protocols (XMODEM, YMODEM, ZMODEM) are in core::transfer module
 // Kermit is in core::file_transfer module
 
+pub use at::{AtCollector, AtError, AtResponse, AtStatus, CmeError};
 pub use checksum::{calculate as calc_checksum, ChecksumType};
-pub use framing::{encode as frame_encode, decode as frame_decode, FramingType, FrameDecoder};
+pub use framing::{
+    encode as frame_encode, decode as frame_decode, FramingType, FrameDecoder,
+    LengthPrefixConfig, LengthWidth,
+};
 pub use modbus::{
     ModbusMode, FunctionCode, ExceptionCode, ModbusFrame,
     ModbusRequest, ModbusResponse, ModbusException,
-    build_rtu_request, parse_rtu_frame,
+    build_rtu_request, parse_rtu_frame, parse_rtu_request,
     build_tcp_request, parse_tcp_frame,
 };
 pub use modbus_monitor::{
     ModbusPoller, ModbusDataType, ModbusValue, RegisterDefinition,
     RegisterType, PollGroup, RegisterReading, PollingEvent,
+    RtuFramer, RtuFrame, ModbusMonitor, BusDirection,
 };
 pub use nmea::{
     NmeaParser, NmeaSentence, NmeaSentenceType, NmeaError,
     GgaData, RmcData, GsvData, GsaData, VtgData,
-    Coordinate, SatelliteInfo, GpsFixQuality,
+    Coordinate, SatelliteInfo, GpsFixQuality, NavFix, Stamped,
 };
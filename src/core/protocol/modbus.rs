@@ -234,6 +234,51 @@ pub fn build_rtu_write_multiple_registers(slave_id: u8, start_address: u16, valu
     frame
 }
 
+/// Parse a master-to-slave Modbus RTU request frame
+pub fn parse_rtu_request(data: &[u8]) -> Result<ModbusRequest, &'static str> {
+    if data.len() < 4 {
+        return Err("Frame too short");
+    }
+
+    let frame_len = data.len();
+    let crc_received = u16::from_le_bytes([data[frame_len - 2], data[frame_len - 1]]);
+    if crc_received != checksum::crc16_modbus(&data[..frame_len - 2]) {
+        return Err("CRC mismatch");
+    }
+
+    let slave_id = data[0];
+    let function = FunctionCode::from_u8(data[1]).ok_or("Unknown function code")?;
+    let body = &data[2..frame_len - 2];
+
+    match function {
+        FunctionCode::ReadCoils | FunctionCode::ReadDiscreteInputs |
+        FunctionCode::ReadHoldingRegisters | FunctionCode::ReadInputRegisters |
+        FunctionCode::WriteSingleCoil | FunctionCode::WriteSingleRegister |
+        FunctionCode::WriteMultipleCoils | FunctionCode::WriteMultipleRegisters => {
+            if body.len() < 4 {
+                return Err("Request too short");
+            }
+            // Single writes carry the value where reads carry the quantity
+            let start_address = u16::from_be_bytes([body[0], body[1]]);
+            let quantity = u16::from_be_bytes([body[2], body[3]]);
+            let data = match function {
+                FunctionCode::WriteMultipleCoils | FunctionCode::WriteMultipleRegisters => {
+                    body.get(5..).unwrap_or_default().to_vec()
+                }
+                _ => Vec::new(),
+            };
+            Ok(ModbusRequest { slave_id, function, start_address, quantity, data })
+        }
+        _ => Ok(ModbusRequest {
+            slave_id,
+            function,
+            start_address: 0,
+            quantity: 0,
+            data: body.to_vec(),
+        }),
+    }
+}
+
 /// Parse Modbus RTU frame
 pub fn parse_rtu_frame(data: &[u8]) -> Result<ModbusFrame, &'static str> {
     if data.len() < 4 {
@@ -370,6 +415,10 @@ pub fn parse_tcp_frame(data: &[u8]) -> Result<(MbapHeader, ModbusFrame), &'stati
     if header.protocol_id != 0 {
         return Err("Invalid protocol ID");
     }
+    // The length covers the unit ID and at least the function code
+    if header.length < 2 {
+        return Err("Invalid length");
+    }
     
     let expected_len = 6 + header.length as usize;
     if data.len() < expected_len {
@@ -490,6 +539,45 @@ mod tests {
         assert_eq!(frame[1], 3); // Function code
     }
 
+    #[test]
+    fn test_parse_rtu_request() {
+        let frame = build_rtu_write_multiple_registers(7, 0x10, &[1, 2]);
+        let request = parse_rtu_request(&frame).unwrap();
+        assert_eq!(request.slave_id, 7);
+        assert_eq!(request.function, FunctionCode::WriteMultipleRegisters);
+        assert_eq!(request.start_address, 0x10);
+        assert_eq!(request.quantity, 2);
+        assert_eq!(request.data, vec![0, 1, 0, 2]);
+
+        let mut corrupt = frame.clone();
+        corrupt[3] ^= 0xFF;
+        assert!(parse_rtu_request(&corrupt).is_err());
+    }
+
+    #[test]
+    fn test_tcp_frame_short_length_is_rejected() {
+        // Length 0 and 1 used to slice past the end of the PDU and panic
+        for length in [0u8, 1] {
+            let frame = [0x00, 0x01, 0x00, 0x00, 0x00, length, 0x01, 0x03];
+            assert_eq!(parse_tcp_frame(&frame).err(), Some("Invalid length"));
+        }
+        let frame = [0x00, 0x01, 0x00, 0x00, 0x00, 0x02, 0x01, 0x03];
+        assert!(parse_tcp_frame(&frame).is_ok());
+    }
+
+    #[test]
+    fn test_truncated_frames_are_errors() {
+        let rtu = build_rtu_request(1, FunctionCode::ReadHoldingRegisters, 0, 10);
+        for len in 0..rtu.len() {
+            assert!(parse_rtu_frame(&rtu[..len]).is_err());
+            assert!(parse_rtu_request(&rtu[..len]).is_err());
+        }
+        let tcp = build_tcp_request(1, 1, FunctionCode::ReadHoldingRegisters, 0, 10);
+        for len in 0..tcp.len() {
+            assert!(parse_tcp_frame(&tcp[..len]).is_err());
+        }
+    }
+
     #[test]
     fn test_parse_registers() {
         let data = vec![0x00, 0x64, 0x01, 0x2C]; // 100, 300
@@ -9,6 +9,8 @@ use std::time::{Duration, Instant};
 use parking_lot::RwLock;
 use tokio::sync::mpsc;
 
+use super::modbus::{parse_rtu_frame, parse_rtu_request, FunctionCode, ModbusFrame, ModbusRequest};
+
 /// Data types for register interpretation
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ModbusDataType {
@@ -419,6 +421,17 @@ pub enum PollingEvent {
         old_value: Option<ModbusValue>,
         new_value: ModbusValue,
     },
+    /// Observed request answered by a response or exception
+    Transaction {
+        request: ModbusRequest,
+        response: ModbusFrame,
+        latency: Duration,
+    },
+    /// Observed request that went unanswered
+    Timeout {
+        request: ModbusRequest,
+        waited: Duration,
+    },
     /// Polling error
     Error(String),
     /// Polling started
@@ -716,6 +729,228 @@ pub mod templates {
     }
 }
 
+// ============ RTU Bus Framing ============
+
+/// Bits per RTU character (start + 8 data + parity/stop + stop)
+const RTU_BITS_PER_CHAR: u64 = 11;
+
+/// Fixed t3.5 the spec recommends above 19200 baud
+const RTU_FIXED_GAP: Duration = Duration::from_micros(1750);
+
+/// Frame captured from an RTU bus
+#[derive(Debug, Clone)]
+pub struct RtuFrame {
+    /// Raw bytes between two t3.5 gaps
+    pub raw: Vec<u8>,
+    /// Result of parsing the raw bytes
+    pub parsed: Result<ModbusFrame, &'static str>,
+}
+
+/// Splits a passively sniffed RTU byte stream into frames on the t3.5 idle gap
+#[derive(Debug, Clone)]
+pub struct RtuFramer {
+    gap: Duration,
+    buffer: Vec<u8>,
+    last_byte: Option<Instant>,
+}
+
+impl RtuFramer {
+    /// Create a framer for a bus running at `baud_rate`
+    pub fn new(baud_rate: u32) -> Self {
+        Self::with_gap(Self::inter_frame_gap(baud_rate))
+    }
+
+    /// Create a framer with an explicit inter-frame gap
+    pub fn with_gap(gap: Duration) -> Self {
+        Self {
+            gap,
+            buffer: Vec::new(),
+            last_byte: None,
+        }
+    }
+
+    /// t3.5 for a baud rate: 3.5 character times, fixed at 1.75 ms above 19200 baud
+    pub fn inter_frame_gap(baud_rate: u32) -> Duration {
+        if baud_rate == 0 || baud_rate > 19200 {
+            return RTU_FIXED_GAP;
+        }
+        // 3.5 chars = 7/2 * bits per char / baud
+        Duration::from_micros(7 * RTU_BITS_PER_CHAR * 1_000_000 / (2 * baud_rate as u64))
+    }
+
+    /// Gap used to split frames
+    pub fn gap(&self) -> Duration {
+        self.gap
+    }
+
+    /// Feed bytes that arrived at `now`; returns frames closed by an idle gap
+    pub fn push(&mut self, bytes: &[u8], now: Instant) -> Vec<RtuFrame> {
+        let mut frames = Vec::new();
+        if let Some(frame) = self.poll(now) {
+            frames.push(frame);
+        }
+        if !bytes.is_empty() {
+            self.buffer.extend_from_slice(bytes);
+            self.last_byte = Some(now);
+        }
+        frames
+    }
+
+    /// Emit the pending frame if the bus has been idle for t3.5 by `now`
+    pub fn poll(&mut self, now: Instant) -> Option<RtuFrame> {
+        let last = self.last_byte?;
+        if now.saturating_duration_since(last) >= self.gap {
+            self.flush()
+        } else {
+            None
+        }
+    }
+
+    /// Emit whatever is buffered regardless of timing
+    pub fn flush(&mut self) -> Option<RtuFrame> {
+        self.last_byte = None;
+        if self.buffer.is_empty() {
+            return None;
+        }
+        let raw = std::mem::take(&mut self.buffer);
+        let parsed = parse_rtu_frame(&raw);
+        Some(RtuFrame { raw, parsed })
+    }
+}
+
+// ============ Passive Bus Monitor ============
+
+/// Direction of a frame seen on the bus
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BusDirection {
+    /// Master to slave (request)
+    Request,
+    /// Slave to master (response or exception)
+    Response,
+}
+
+/// Default time a slave has to answer before the request counts as timed out
+pub const DEFAULT_RESPONSE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Passive sniffer pairing observed requests with their responses
+#[derive(Debug)]
+pub struct ModbusMonitor {
+    /// Request awaiting an answer (RTU allows one outstanding request)
+    pending: Option<(ModbusRequest, Instant)>,
+    /// How long a slave has to answer
+    response_timeout: Duration,
+}
+
+impl Default for ModbusMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ModbusMonitor {
+    /// Create new monitor
+    pub fn new() -> Self {
+        Self {
+            pending: None,
+            response_timeout: DEFAULT_RESPONSE_TIMEOUT,
+        }
+    }
+
+    /// Set the response timeout
+    pub fn response_timeout(mut self, timeout: Duration) -> Self {
+        self.response_timeout = timeout;
+        self
+    }
+
+    /// Observe a frame captured now
+    pub fn observe(&mut self, frame: &RtuFrame, direction: BusDirection) -> Vec<PollingEvent> {
+        self.observe_at(frame, direction, Instant::now())
+    }
+
+    /// Observe a frame captured at `now`
+    pub fn observe_at(
+        &mut self,
+        frame: &RtuFrame,
+        direction: BusDirection,
+        now: Instant,
+    ) -> Vec<PollingEvent> {
+        let mut events: Vec<PollingEvent> = self.check_timeout(now).into_iter().collect();
+
+        match direction {
+            BusDirection::Request => match parse_rtu_request(&frame.raw) {
+                Ok(request) => {
+                    // A new request means the previous one was never answered
+                    if let Some((request, sent)) = self.pending.take() {
+                        events.push(PollingEvent::Timeout {
+                            request,
+                            waited: now.saturating_duration_since(sent),
+                        });
+                    }
+                    // Broadcasts are never answered
+                    if request.slave_id != 0 {
+                        self.pending = Some((request, now));
+                    }
+                }
+                Err(e) => events.push(PollingEvent::Error(format!("Bad request: {}", e))),
+            },
+            BusDirection::Response => match &frame.parsed {
+                Ok(response) => match self.pending.take() {
+                    Some((request, sent)) if answers(&request, response) => {
+                        events.push(PollingEvent::Transaction {
+                            request,
+                            response: response.clone(),
+                            latency: now.saturating_duration_since(sent),
+                        });
+                    }
+                    pending => {
+                        self.pending = pending;
+                        events.push(PollingEvent::Error("Unsolicited response".to_string()));
+                    }
+                },
+                Err(e) => events.push(PollingEvent::Error(format!("Bad response: {}", e))),
+            },
+        }
+
+        events
+    }
+
+    /// Report the pending request as timed out once the response timeout has passed
+    pub fn check_timeout(&mut self, now: Instant) -> Option<PollingEvent> {
+        let (_, sent) = self.pending.as_ref()?;
+        let waited = now.saturating_duration_since(*sent);
+        if waited < self.response_timeout {
+            return None;
+        }
+        let (request, _) = self.pending.take()?;
+        Some(PollingEvent::Timeout { request, waited })
+    }
+}
+
+/// Whether a response belongs to a request: same slave and function, and for
+/// write echoes the same address
+fn answers(request: &ModbusRequest, response: &ModbusFrame) -> bool {
+    match response {
+        ModbusFrame::Exception(e) => {
+            e.slave_id == request.slave_id && e.function == request.function as u8
+        }
+        ModbusFrame::Response(r) => {
+            if r.slave_id != request.slave_id || r.function != request.function {
+                return false;
+            }
+            match r.function {
+                FunctionCode::WriteSingleCoil | FunctionCode::WriteSingleRegister |
+                FunctionCode::WriteMultipleCoils | FunctionCode::WriteMultipleRegisters => {
+                    r.data.get(..2) == Some(&request.start_address.to_be_bytes()[..])
+                }
+                _ => true,
+            }
+        }
+        ModbusFrame::Request(r) => {
+            r.slave_id == request.slave_id && r.function == request.function
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -772,5 +1007,97 @@ mod tests {
         let pressure = templates::pressure_f32(10, "Pressure");
         assert_eq!(pressure.count, 2); // F32 uses 2 registers
     }
-}
 
+    #[test]
+    fn test_rtu_inter_frame_gap() {
+        // 9600 baud: 11 bits/char -> ~1.146 ms/char, t3.5 ~4.01 ms
+        assert_eq!(RtuFramer::inter_frame_gap(9600), Duration::from_micros(4010));
+        assert_eq!(RtuFramer::inter_frame_gap(115200), Duration::from_micros(1750));
+    }
+
+    #[test]
+    fn test_rtu_framer_splits_on_gap() {
+        use super::super::modbus::{build_rtu_request, FunctionCode};
+
+        let request = build_rtu_request(1, FunctionCode::ReadHoldingRegisters, 0, 2);
+        let mut framer = RtuFramer::new(9600);
+        let t0 = Instant::now();
+        let char_time = Duration::from_millis(1);
+
+        // Request arrives in two chunks well within t3.5
+        assert!(framer.push(&request[..3], t0).is_empty());
+        assert!(framer.push(&request[3..], t0 + char_time).is_empty());
+
+        // Next burst after an idle gap closes the first frame
+        let frames = framer.push(&[0x01, 0x03], t0 + Duration::from_millis(20));
+        assert_eq!(frames.len(), 1);
+        assert_eq!(frames[0].raw, request);
+        assert!(frames[0].parsed.is_ok());
+
+        // Trailing bytes are emitted once the bus goes idle
+        assert!(framer.poll(t0 + Duration::from_millis(21)).is_none());
+        let tail = framer.poll(t0 + Duration::from_millis(30)).unwrap();
+        assert_eq!(tail.raw, vec![0x01, 0x03]);
+        assert!(tail.parsed.is_err());
+    }
+
+    fn rtu_frame(raw: Vec<u8>) -> RtuFrame {
+        let parsed = parse_rtu_frame(&raw);
+        RtuFrame { raw, parsed }
+    }
+
+    fn with_crc(mut frame: Vec<u8>) -> Vec<u8> {
+        let crc = super::super::checksum::crc16_modbus(&frame);
+        frame.extend_from_slice(&crc.to_le_bytes());
+        frame
+    }
+
+    #[test]
+    fn test_monitor_pairs_request_and_response() {
+        use super::super::modbus::build_rtu_request;
+
+        let mut monitor = ModbusMonitor::new();
+        let t0 = Instant::now();
+        let request = rtu_frame(build_rtu_request(1, FunctionCode::ReadHoldingRegisters, 0, 1));
+        let response = rtu_frame(with_crc(vec![0x01, 0x03, 0x02, 0x00, 0x2A]));
+
+        assert!(monitor.observe_at(&request, BusDirection::Request, t0).is_empty());
+        let events = monitor.observe_at(
+            &response,
+            BusDirection::Response,
+            t0 + Duration::from_millis(12),
+        );
+        match events.as_slice() {
+            [PollingEvent::Transaction { request, response, latency }] => {
+                assert_eq!(request.slave_id, 1);
+                assert!(matches!(response, ModbusFrame::Response(r) if r.data == [0x00, 0x2A]));
+                assert_eq!(*latency, Duration::from_millis(12));
+            }
+            other => panic!("unexpected events: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_monitor_flags_exceptions_and_timeouts() {
+        use super::super::modbus::build_rtu_write_single_register;
+
+        let mut monitor = ModbusMonitor::new().response_timeout(Duration::from_millis(100));
+        let t0 = Instant::now();
+        let write = rtu_frame(build_rtu_write_single_register(2, 0x20, 5));
+
+        monitor.observe_at(&write, BusDirection::Request, t0);
+        let exception = rtu_frame(with_crc(vec![0x02, 0x86, 0x02]));
+        let events = monitor.observe_at(&exception, BusDirection::Response, t0);
+        assert!(matches!(
+            events.as_slice(),
+            [PollingEvent::Transaction { response: ModbusFrame::Exception(_), .. }]
+        ));
+
+        monitor.observe_at(&write, BusDirection::Request, t0);
+        assert!(monitor.check_timeout(t0 + Duration::from_millis(50)).is_none());
+        assert!(matches!(
+            monitor.check_timeout(t0 + Duration::from_millis(150)),
+            Some(PollingEvent::Timeout { .. })
+        ));
+    }
+}
@@ -14,6 +14,7 @@
 //! - DBT: Depth Below Transducer
 
 use std::collections::HashMap;
+use std::time::{Duration, Instant};
 use chrono::{NaiveTime, NaiveDate};
 
 /// NMEA sentence types
@@ -255,6 +256,94 @@ pub enum NmeaError {
     ParseError(String),
 }
 
+/// A value and when it was last received
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stamped<T> {
+    pub value: T,
+    pub updated: Instant,
+}
+
+impl<T> Stamped<T> {
+    /// Time since the value was received
+    pub fn age(&self) -> Duration {
+        self.updated.elapsed()
+    }
+}
+
+/// Navigation state fused from GGA, RMC, VTG and GLL
+///
+/// Each field keeps the last value any sentence reported, with its own
+/// update time: altitude only comes from GGA, so it may be older than a
+/// speed taken from the latest RMC.
+#[derive(Debug, Clone, Default)]
+pub struct NavFix {
+    /// Latitude in signed decimal degrees
+    pub lat: Option<Stamped<f64>>,
+    /// Longitude in signed decimal degrees
+    pub lon: Option<Stamped<f64>>,
+    /// Altitude above mean sea level in meters (GGA)
+    pub altitude: Option<Stamped<f32>>,
+    /// Speed over ground in knots (RMC, VTG)
+    pub speed: Option<Stamped<f32>>,
+    /// Course over ground in degrees true (RMC, VTG)
+    pub course: Option<Stamped<f32>>,
+    /// Fix quality (GGA)
+    pub fix_quality: Option<Stamped<GpsFixQuality>>,
+    /// Satellites used in the fix (GGA)
+    pub sats_used: Option<Stamped<u8>>,
+    /// Horizontal dilution of precision (GGA)
+    pub hdop: Option<Stamped<f32>>,
+    /// UTC time of the most recent fix
+    pub timestamp: Option<Stamped<NaiveTime>>,
+}
+
+impl NavFix {
+    /// Merge the fields `sentence` carries
+    ///
+    /// Void RMC and GLL sentences (status `V`) carry no usable fix and are ignored.
+    fn update(&mut self, sentence: &NmeaSentence, now: Instant) {
+        let stamp = |value| Some(Stamped { value, updated: now });
+        match sentence {
+            NmeaSentence::Gga(gga) => {
+                self.update_position(gga.latitude, gga.longitude, gga.time, now);
+                self.altitude = gga.altitude.map_or(self.altitude, stamp);
+                self.fix_quality = Some(Stamped { value: gga.fix_quality, updated: now });
+                self.sats_used = Some(Stamped { value: gga.satellites_used, updated: now });
+                self.hdop = gga.hdop.map_or(self.hdop, stamp);
+            }
+            NmeaSentence::Rmc(rmc) if rmc.status == 'A' => {
+                self.update_position(rmc.latitude, rmc.longitude, rmc.time, now);
+                self.speed = rmc.speed_knots.map_or(self.speed, stamp);
+                self.course = rmc.course.map_or(self.course, stamp);
+            }
+            NmeaSentence::Gll(gll) if gll.status == 'A' => {
+                self.update_position(gll.latitude, gll.longitude, gll.time, now);
+            }
+            NmeaSentence::Vtg(vtg) => {
+                self.speed = vtg.speed_knots.map_or(self.speed, stamp);
+                self.course = vtg.track_true.map_or(self.course, stamp);
+            }
+            _ => {}
+        }
+    }
+
+    fn update_position(
+        &mut self,
+        lat: Option<Coordinate>,
+        lon: Option<Coordinate>,
+        time: Option<NaiveTime>,
+        now: Instant,
+    ) {
+        if let (Some(lat), Some(lon)) = (lat, lon) {
+            self.lat = Some(Stamped { value: lat.to_decimal(), updated: now });
+            self.lon = Some(Stamped { value: lon.to_decimal(), updated: now });
+        }
+        if let Some(time) = time {
+            self.timestamp = Some(Stamped { value: time, updated: now });
+        }
+    }
+}
+
 /// NMEA 0183 Parser
 #[derive(Debug, Default)]
 pub struct NmeaParser {
@@ -262,6 +351,8 @@ pub struct NmeaParser {
     pub satellites: Vec<SatelliteInfo>,
     /// Last parsed sentences by type
     pub last_data: HashMap<NmeaSentenceType, NmeaSentence>,
+    /// Fix fused from the sentences so far
+    fix: NavFix,
 }
 
 impl NmeaParser {
@@ -343,6 +434,7 @@ impl NmeaParser {
             },
         };
         
+        self.fix.update(&parsed, Instant::now());
         self.last_data.insert(sentence_type, parsed.clone());
         
         Ok(parsed)
@@ -350,7 +442,7 @@ impl NmeaParser {
     
     /// Parse time from HHMMSS.sss format
     fn parse_time(s: &str) -> Option<NaiveTime> {
-        if s.len() < 6 {
+        if s.len() < 6 || !s.is_ascii() {
             return None;
         }
         
@@ -366,7 +458,7 @@ impl NmeaParser {
     
     /// Parse date from DDMMYY format
     fn parse_date(s: &str) -> Option<NaiveDate> {
-        if s.len() < 6 {
+        if s.len() < 6 || !s.is_ascii() {
             return None;
         }
         
@@ -692,6 +784,11 @@ impl NmeaParser {
         0
     }
     
+    /// Fused navigation state, once a position has been received
+    pub fn current_fix(&self) -> Option<NavFix> {
+        self.fix.lat.is_some().then(|| self.fix.clone())
+    }
+
     /// Format position as human-readable string
     pub fn format_position(&self) -> Option<String> {
         let (lat, lon) = self.get_position()?;
@@ -727,6 +824,19 @@ mod tests {
         }
     }
     
+    #[test]
+    fn test_non_ascii_time_and_date() {
+        // Multi-byte characters used to be sliced mid-character and panic
+        let mut parser = NmeaParser::new();
+        match parser.parse("$GPRMC,1\u{e9}3456,A,,,,,,,2\u{e9}0394") {
+            Ok(NmeaSentence::Rmc(rmc)) => {
+                assert!(rmc.time.is_none());
+                assert!(rmc.date.is_none());
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
     #[test]
     fn test_rmc_parse() {
         let mut parser = NmeaParser::new();
@@ -741,6 +851,32 @@ mod tests {
         }
     }
     
+    #[test]
+    fn test_current_fix_merges_sentences() {
+        let mut parser = NmeaParser::new();
+        assert!(parser.current_fix().is_none());
+
+        parser.parse("$GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47").unwrap();
+        let after_gga = parser.current_fix().unwrap();
+        assert!(after_gga.speed.is_none());
+
+        parser.parse("$GPRMC,123519,A,4807.038,N,01131.000,E,022.4,084.4,230394,003.1,W*6A").unwrap();
+        let fix = parser.current_fix().unwrap();
+        assert!((fix.lat.unwrap().value - 48.1173).abs() < 1e-6);
+        assert!((fix.lon.unwrap().value - 11.516_666).abs() < 1e-6);
+        assert_eq!(fix.altitude.unwrap().value, 545.4);
+        assert_eq!(fix.speed.unwrap().value, 22.4);
+        assert_eq!(fix.course.unwrap().value, 84.4);
+        assert_eq!(fix.fix_quality.unwrap().value, GpsFixQuality::GpsFix);
+        assert_eq!(fix.sats_used.unwrap().value, 8);
+        assert_eq!(fix.hdop.unwrap().value, 0.9);
+        assert_eq!(fix.timestamp.unwrap().value, NaiveTime::from_hms_opt(12, 35, 19).unwrap());
+
+        // Altitude still dates from the GGA
+        assert_eq!(fix.altitude.unwrap().updated, after_gga.altitude.unwrap().updated);
+        assert!(fix.speed.unwrap().updated >= fix.altitude.unwrap().updated);
+    }
+
     #[test]
     fn test_checksum() {
         let checksum = NmeaParser::calculate_checksum("GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,");
@@ -3,8 +3,36 @@
 //! Declarative protocol schema for auto-generating parsers, builders, and UI.
 //! Define protocols in YAML/JSON and get automatic decoding/encoding.
 
+use crate::core::protocol::checksum::{self, ChecksumType};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use std::collections::HashMap;
+use thiserror::Error;
+
+/// Error building a frame from a protocol definition
+#[derive(Debug, Error)]
+pub enum DslError {
+    /// No message with this name
+    #[error("Unknown message: {0}")]
+    UnknownMessage(String),
+
+    /// A required field has no value and no constant
+    #[error("Missing value for field {0}")]
+    MissingField(String),
+
+    /// A value does not fit its field
+    #[error("Invalid value for field {field}: {reason}")]
+    InvalidValue {
+        /// Field name
+        field: String,
+        /// What is wrong with it
+        reason: String,
+    },
+
+    /// The definition uses something encoding does not support
+    #[error("Unsupported: {0}")]
+    Unsupported(String),
+}
 
 /// Byte order (endianness)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
@@ -119,6 +147,33 @@ impl Default for ChecksumConfig {
     }
 }
 
+impl ChecksumConfig {
+    /// Algorithm to compute, or `None` if the message has no checksum
+    pub fn checksum_type(&self) -> Result<Option<ChecksumType>, DslError> {
+        Ok(match self {
+            Self::None => None,
+            Self::Xor8 => Some(ChecksumType::Xor),
+            Self::Lrc8 => Some(ChecksumType::Lrc),
+            Self::Crc16Modbus => Some(ChecksumType::Crc16Modbus),
+            Self::Crc16Ccitt => Some(ChecksumType::Crc16Ccitt),
+            Self::Crc32 => Some(ChecksumType::Crc32),
+            Self::Custom { algorithm, .. } => {
+                let key = algorithm.to_lowercase().replace(['-', '_', '/'], "");
+                let found = ChecksumType::all()
+                    .iter()
+                    .find(|t| t.name().to_lowercase().replace(['-', '/'], "") == key)
+                    .copied()
+                    .or(match key.as_str() {
+                        "xor8" => Some(ChecksumType::Xor),
+                        "lrc8" => Some(ChecksumType::Lrc),
+                        _ => None,
+                    });
+                Some(found.ok_or_else(|| DslError::Unsupported(format!("checksum algorithm '{}'", algorithm)))?)
+            }
+        })
+    }
+}
+
 /// Field definition
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FieldDef {
@@ -177,6 +232,19 @@ pub struct MessageDef {
     pub response: Option<String>,
 }
 
+impl MessageDef {
+    /// The field holding the checksum: the one named `checksum`, else the last field
+    pub fn checksum_field(&self) -> Option<&FieldDef> {
+        if matches!(self.checksum, ChecksumConfig::None) {
+            return None;
+        }
+        self.fields
+            .iter()
+            .find(|f| f.name == "checksum")
+            .or_else(|| self.fields.last())
+    }
+}
+
 /// Complete protocol definition
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProtocolDef {
@@ -234,6 +302,170 @@ impl ProtocolDef {
     pub fn get_message_by_id(&self, id: u8) -> Option<&MessageDef> {
         self.messages.iter().find(|m| m.id == Some(id))
     }
+
+    /// Build the frame for `message` from field values
+    ///
+    /// Fields without a value use their `constant`; optional fields may be
+    /// left out. Scaled fields take the scaled value. The checksum field is
+    /// computed over the bytes before it, or over `start_field..=end_field`
+    /// for a custom checksum.
+    pub fn encode(&self, message: &str, fields: &HashMap<String, Value>) -> Result<Vec<u8>, DslError> {
+        let msg = self
+            .get_message(message)
+            .ok_or_else(|| DslError::UnknownMessage(message.to_string()))?;
+        let checksum_type = msg.checksum.checksum_type()?;
+        let checksum_field = msg.checksum_field().map(|f| f.name.as_str());
+
+        let mut out = Vec::new();
+        let mut spans: HashMap<&str, (usize, usize)> = HashMap::new();
+        for field in self.header.iter().flatten().chain(&msg.fields) {
+            let start = out.len();
+            if Some(field.name.as_str()) == checksum_field {
+                // Placeholder, filled in once the covered bytes are known
+                encode_field(&mut out, field, &Value::from(0), self.byte_order)?;
+            } else {
+                match fields.get(&field.name).or(field.constant.as_ref()) {
+                    Some(value) => encode_field(&mut out, field, value, self.byte_order)?,
+                    None if field.optional => {}
+                    None => return Err(DslError::MissingField(field.name.clone())),
+                }
+            }
+            spans.insert(&field.name, (start, out.len()));
+        }
+
+        if let (Some(kind), Some(name)) = (checksum_type, checksum_field) {
+            let (start, end) = spans[name];
+            let range = match &msg.checksum {
+                ChecksumConfig::Custom { start_field, end_field, .. } => {
+                    let span = |field: &String| {
+                        spans
+                            .get(field.as_str())
+                            .copied()
+                            .ok_or_else(|| DslError::MissingField(field.clone()))
+                    };
+                    span(start_field)?.0..span(end_field)?.1
+                }
+                _ => 0..start,
+            };
+            let sum = checksum::calculate(&out[range], kind);
+            if sum.len() != end - start {
+                return Err(DslError::InvalidValue {
+                    field: name.to_string(),
+                    reason: format!("{} needs {} bytes, field has {}", kind.name(), sum.len(), end - start),
+                });
+            }
+            out[start..end].copy_from_slice(&sum);
+        }
+
+        Ok(out)
+    }
+}
+
+/// Append `value` encoded as `field`
+fn encode_field(out: &mut Vec<u8>, field: &FieldDef, value: &Value, default_order: ByteOrder) -> Result<(), DslError> {
+    let order = field.byte_order.unwrap_or(default_order);
+    let invalid = |reason: String| DslError::InvalidValue {
+        field: field.name.clone(),
+        reason,
+    };
+
+    macro_rules! put_int {
+        ($ty:ty) => {{
+            let n = int_value(field, value).ok_or_else(|| invalid(format!("expected a number, got {}", value)))?;
+            let v = <$ty>::try_from(n).map_err(|_| invalid(format!("{} out of range", n)))?;
+            match order {
+                ByteOrder::Little => out.extend_from_slice(&v.to_le_bytes()),
+                ByteOrder::Big => out.extend_from_slice(&v.to_be_bytes()),
+            }
+        }};
+    }
+    macro_rules! put_float {
+        ($ty:ty) => {{
+            let v = value.as_f64().ok_or_else(|| invalid(format!("expected a number, got {}", value)))? as $ty;
+            match order {
+                ByteOrder::Little => out.extend_from_slice(&v.to_le_bytes()),
+                ByteOrder::Big => out.extend_from_slice(&v.to_be_bytes()),
+            }
+        }};
+    }
+
+    match &field.field_type {
+        FieldType::U8 => put_int!(u8),
+        FieldType::I8 => put_int!(i8),
+        FieldType::U16 => put_int!(u16),
+        FieldType::I16 => put_int!(i16),
+        FieldType::U32 => put_int!(u32),
+        FieldType::I32 => put_int!(i32),
+        FieldType::U64 => put_int!(u64),
+        FieldType::I64 => put_int!(i64),
+        FieldType::F32 => put_float!(f32),
+        FieldType::F64 => put_float!(f64),
+        FieldType::Bool => {
+            let v = value.as_bool().or_else(|| value.as_i64().map(|n| n != 0));
+            out.push(v.ok_or_else(|| invalid(format!("expected a boolean, got {}", value)))? as u8);
+        }
+        FieldType::Bytes(len) => {
+            let bytes = bytes_value(value).ok_or_else(|| invalid("expected hex or a byte array".to_string()))?;
+            if bytes.len() != *len {
+                return Err(invalid(format!("expected {} bytes, got {}", len, bytes.len())));
+            }
+            out.extend_from_slice(&bytes);
+        }
+        FieldType::String(len) => {
+            let text = value.as_str().ok_or_else(|| invalid(format!("expected a string, got {}", value)))?;
+            if text.len() > *len {
+                return Err(invalid(format!("longer than {} bytes", len)));
+            }
+            out.extend_from_slice(text.as_bytes());
+            out.resize(out.len() + len - text.len(), 0);
+        }
+        FieldType::CString => {
+            let text = value.as_str().ok_or_else(|| invalid(format!("expected a string, got {}", value)))?;
+            out.extend_from_slice(text.as_bytes());
+            out.push(0);
+        }
+        FieldType::Enum(mapping) => {
+            let v = match value.as_str() {
+                Some(name) => mapping
+                    .iter()
+                    .find(|(_, n)| n.as_str() == name)
+                    .map(|(v, _)| *v)
+                    .ok_or_else(|| invalid(format!("unknown name '{}'", name)))?,
+                None => value
+                    .as_u64()
+                    .and_then(|n| u8::try_from(n).ok())
+                    .ok_or_else(|| invalid(format!("expected a name or u8, got {}", value)))?,
+            };
+            out.push(v);
+        }
+        other => return Err(DslError::Unsupported(format!("encoding {:?} field {}", other, field.name))),
+    }
+    Ok(())
+}
+
+/// Raw integer for a field, undoing a scale transform
+fn int_value(field: &FieldDef, value: &Value) -> Option<i128> {
+    if let Transform::Scale { scale, offset } = &field.transform {
+        let v = value.as_f64()?;
+        return Some(((v - offset) / scale).round() as i128);
+    }
+    value
+        .as_i64()
+        .map(i128::from)
+        .or_else(|| value.as_u64().map(i128::from))
+        .or_else(|| value.as_f64().filter(|v| v.fract() == 0.0).map(|v| v as i128))
+}
+
+/// Bytes from a hex string ("AA 01") or an array of numbers
+fn bytes_value(value: &Value) -> Option<Vec<u8>> {
+    match value {
+        Value::String(s) => hex::decode(s.replace(' ', "")).ok(),
+        Value::Array(items) => items
+            .iter()
+            .map(|item| item.as_u64().and_then(|n| u8::try_from(n).ok()))
+            .collect(),
+        _ => None,
+    }
 }
 
 /// Decoded field value
@@ -582,6 +814,8 @@ messages:
         constant: 0x10
       - name: "checksum"
         type: u8
+    checksum:
+      type: xor8
 "#;
 
 #[cfg(test)]
@@ -598,6 +832,29 @@ mod tests {
         assert_eq!(sensor_data.fields.len(), 5);
     }
 
+    #[test]
+    fn test_encode_computes_checksum() {
+        let protocol = ProtocolDef::from_yaml(EXAMPLE_PROTOCOL_YAML).unwrap();
+
+        let mut fields = HashMap::new();
+        fields.insert("msg_id".to_string(), Value::from(1));
+        fields.insert("temperature_raw".to_string(), Value::from(100.0));
+        fields.insert("humidity_raw".to_string(), Value::from(40.0));
+        let frame = protocol.encode("SensorData", &fields).unwrap();
+        assert_eq!(frame, vec![0xAA, 0x01, 0xE8, 0x03, 0x90, 0x01, 0xD1]);
+
+        let decoded = ProtocolDecoder::new(protocol.clone()).decode_as(&frame, "SensorData").unwrap();
+        assert_eq!(decoded.fields["humidity_raw"].as_f64(), Some(40.0));
+
+        // Constants fill the rest of the frame
+        assert_eq!(protocol.encode("ReadCommand", &HashMap::new()).unwrap(), vec![0xAA, 0x10, 0xBA]);
+
+        fields.remove("msg_id");
+        assert!(matches!(protocol.encode("SensorData", &fields), Err(DslError::MissingField(f)) if f == "msg_id"));
+        fields.insert("msg_id".to_string(), Value::from(300));
+        assert!(matches!(protocol.encode("SensorData", &fields), Err(DslError::InvalidValue { .. })));
+    }
+
     #[test]
     fn test_decode_message() {
         let protocol = ProtocolDef::from_yaml(EXAMPLE_PROTOCOL_YAML).unwrap();
@@ -10,8 +10,12 @@ use std::io::{BufReader, BufWriter, Read, Write};
 use std::path::Path;
 use std::time::{Duration, Instant};
 
+use super::logger::{Direction, LogEntry, LogFormat};
 use super::packet::{Packet, PacketDirection};
 
+/// Timestamp format written by `LogEntry`
+const LOG_TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S%.3f";
+
 /// Replay event type
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ReplayEvent {
@@ -221,6 +225,59 @@ impl SessionRecording {
             metadata: RecordingMetadata::default(),
         }
     }
+
+    /// Reconstruct a recording from a `SessionLogger` file
+    ///
+    /// RX/TX lines become data events and `##`/INFO lines become markers.
+    /// Timing comes from the logged timestamps; logs written without them
+    /// replay with no delays.
+    pub fn from_log(path: &Path, format: LogFormat) -> std::io::Result<Self> {
+        let data = std::fs::read(path)?;
+        let mut recording = Self::from_log_bytes(&data, format)?;
+        recording.connection_info = path.display().to_string();
+        Ok(recording)
+    }
+
+    /// Reconstruct a recording from the contents of a log file
+    pub fn from_log_bytes(data: &[u8], format: LogFormat) -> std::io::Result<Self> {
+        let entries = parse_log(data, format)?;
+
+        let mut recording = Self::new("Log", "");
+        let start = entries.iter().find_map(|(ts, _)| *ts);
+        if let Some(start) = start {
+            recording.start_time = start;
+        }
+
+        let mut previous = start;
+        for (timestamp, entry) in entries {
+            let offset_us = match (timestamp, start) {
+                (Some(ts), Some(start)) => micros_between(start, ts),
+                _ => 0,
+            };
+            let delta_us = match (timestamp, previous) {
+                (Some(ts), Some(prev)) => micros_between(prev, ts),
+                _ => 0,
+            };
+            if timestamp.is_some() {
+                previous = timestamp;
+            }
+
+            let event = match entry.direction {
+                Direction::Received => ReplayEvent::Rx(entry.data),
+                Direction::Sent => ReplayEvent::Tx(entry.data),
+                Direction::Info => ReplayEvent::Marker(String::from_utf8_lossy(&entry.data).into_owned()),
+            };
+            recording.events.push(RecordedEvent {
+                timestamp: timestamp.unwrap_or(recording.start_time),
+                offset_us,
+                delta_us,
+                event,
+            });
+        }
+
+        recording.end_time = previous;
+        Ok(recording)
+    }
     
     /// Get all markers
     pub fn markers(&self) -> Vec<EventMarker> {
@@ -792,6 +849,172 @@ impl SessionPlayer {
     pub fn recording(&self) -> &SessionRecording {
         &self.recording
     }
+
+    /// Create a player for a `SessionLogger` file
+    pub fn from_log(path: &Path, format: LogFormat) -> std::io::Result<Self> {
+        SessionRecording::from_log(path, format).map(Self::new)
+    }
+}
+
+fn micros_between(from: DateTime<Local>, to: DateTime<Local>) -> u64 {
+    (to - from).num_microseconds().unwrap_or(0).max(0) as u64
+}
+
+fn invalid_log(msg: String) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, msg)
+}
+
+/// Parse log file contents back into entries, keeping the timestamp only
+/// when one was logged
+fn parse_log(data: &[u8], format: LogFormat) -> std::io::Result<Vec<(Option<DateTime<Local>>, LogEntry)>> {
+    if format == LogFormat::Raw {
+        return Ok(vec![(None, LogEntry::new(Direction::Received, data.to_vec()))]);
+    }
+
+    let text = String::from_utf8_lossy(data);
+    let text = text.strip_suffix('\n').unwrap_or(&text);
+    let mut entries: Vec<(Option<DateTime<Local>>, LogEntry)> = Vec::new();
+
+    match format {
+        LogFormat::JsonLines => {
+            for line in text.lines().filter(|l| !l.trim().is_empty()) {
+                let entry: LogEntry = serde_json::from_str(line)
+                    .map_err(|e| invalid_log(format!("Invalid JSON log line: {}", e)))?;
+                entries.push((Some(entry.timestamp), entry));
+            }
+        }
+        LogFormat::Csv => {
+            for line in csv_records(text).iter().filter(|l| l.starts_with('"')) {
+                let fields = parse_csv_line(line);
+                let [timestamp, dir, hex_data, ..] = fields.as_slice() else {
+                    return Err(invalid_log(format!("Invalid CSV log line: {}", line)));
+                };
+                let Some(direction) = parse_direction(dir) else {
+                    continue;
+                };
+                let data = hex::decode(hex_data).map_err(|e| invalid_log(format!("Invalid hex in log: {}", e)))?;
+                entries.push((parse_timestamp(timestamp), LogEntry::new(direction, data)));
+            }
+        }
+        LogFormat::Hex | LogFormat::Mixed | LogFormat::Text => {
+            // Text entries may span lines: the logger writes the data as-is,
+            // so lines without a header continue the previous entry
+            for line in text.split('\n') {
+                match parse_header(line) {
+                    Some((timestamp, direction, body)) => {
+                        let data = match format {
+                            LogFormat::Hex => decode_hex_body(body)
+                                .ok_or_else(|| invalid_log(format!("Invalid hex log line: {}", line)))?,
+                            LogFormat::Mixed => decode_hex_body(body).unwrap_or_else(|| body.as_bytes().to_vec()),
+                            _ => body.as_bytes().to_vec(),
+                        };
+                        entries.push((timestamp, LogEntry::new(direction, data)));
+                    }
+                    None => {
+                        if let Some((_, entry)) = entries.last_mut() {
+                            entry.data.push(b'\n');
+                            entry.data.extend_from_slice(line.as_bytes());
+                        }
+                    }
+                }
+            }
+        }
+        LogFormat::Raw => unreachable!(),
+    }
+
+    Ok(entries)
+}
+
+fn parse_timestamp(text: &str) -> Option<DateTime<Local>> {
+    let naive = chrono::NaiveDateTime::parse_from_str(text, LOG_TIMESTAMP_FORMAT).ok()?;
+    naive.and_local_timezone(Local).earliest()
+}
+
+fn parse_direction(text: &str) -> Option<Direction> {
+    match text {
+        "RX" => Some(Direction::Received),
+        "TX" => Some(Direction::Sent),
+        "##" | "INFO" => Some(Direction::Info),
+        _ => None,
+    }
+}
+
+/// Split `[timestamp] DIR body` (or `DIR body`) into its parts
+fn parse_header(line: &str) -> Option<(Option<DateTime<Local>>, Direction, &str)> {
+    let (timestamp, rest) = match line.strip_prefix('[') {
+        Some(rest) => {
+            let (ts, rest) = rest.split_once("] ")?;
+            (Some(parse_timestamp(ts)?), rest)
+        }
+        None => (None, line),
+    };
+
+    let direction = parse_direction(rest.get(..2)?)?;
+    let body = rest.get(2..)?.strip_prefix(' ')?;
+    Some((timestamp, direction, body))
+}
+
+/// Decode a `to_hex` body (`48 65 6C `); `None` if it is not hex
+fn decode_hex_body(body: &str) -> Option<Vec<u8>> {
+    let body = body.trim_end();
+    if body.is_empty() {
+        return Some(Vec::new());
+    }
+    body.split(' ')
+        .map(|byte| {
+            if byte.len() == 2 && byte.bytes().all(|b| b.is_ascii_digit() || (b'A'..=b'F').contains(&b)) {
+                u8::from_str_radix(byte, 16).ok()
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// Split CSV text into records, keeping line breaks that fall inside a
+/// quoted field (the Text column holds the data as logged)
+fn csv_records(text: &str) -> Vec<String> {
+    let mut records = Vec::new();
+    let mut record = String::new();
+
+    for line in text.split('\n') {
+        if !record.is_empty() {
+            record.push('\n');
+        }
+        record.push_str(line);
+        // Escaped quotes come in pairs, so an odd count leaves a field open
+        if record.matches('"').count() % 2 == 0 {
+            records.push(std::mem::take(&mut record).trim_end_matches('\r').to_string());
+        }
+    }
+    if !record.is_empty() {
+        records.push(record);
+    }
+
+    records
+}
+
+/// Split a `to_csv` line into its (unquoted) fields
+fn parse_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field);
+
+    fields
 }
 
 #[cfg(test)]
@@ -814,6 +1037,50 @@ mod tests {
         assert_eq!(recording.rx_bytes(), 5);
     }
 
+    #[test]
+    fn test_recording_from_text_log() {
+        let log = b"[2026-01-05 10:00:00.000] TX AT\r\n\n\
+[2026-01-05 10:00:00.250] RX \x1b[32mOK\x1b[0m\r\nline two\r\n\n\
+[2026-01-05 10:00:01.000] ## reboot\n";
+        let recording = SessionRecording::from_log_bytes(log, LogFormat::Text).unwrap();
+
+        assert_eq!(recording.event_count(), 3);
+        assert!(matches!(&recording.events[0].event, ReplayEvent::Tx(d) if d == b"AT\r\n"));
+        assert!(matches!(
+            &recording.events[1].event,
+            ReplayEvent::Rx(d) if d == b"\x1b[32mOK\x1b[0m\r\nline two\r\n"
+        ));
+        assert!(matches!(&recording.events[2].event, ReplayEvent::Marker(m) if m == "reboot"));
+        assert_eq!(recording.events[1].delta_us, 250_000);
+        assert_eq!(recording.events[2].offset_us, 1_000_000);
+    }
+
+    #[test]
+    fn test_recording_from_hex_and_mixed_log() {
+        let hex = b"RX 1B 5B 31 6D 00 \nTX 41 0D \n";
+        let recording = SessionRecording::from_log_bytes(hex, LogFormat::Hex).unwrap();
+        assert_eq!(recording.rx_bytes(), 5);
+        assert_eq!(recording.tx_bytes(), 2);
+        assert!(recording.events.iter().all(|e| e.delta_us == 0));
+
+        let mixed = b"[2026-01-05 10:00:00.000] RX hello\n[2026-01-05 10:00:00.100] RX 00 FF \n";
+        let recording = SessionRecording::from_log_bytes(mixed, LogFormat::Mixed).unwrap();
+        assert!(matches!(&recording.events[0].event, ReplayEvent::Rx(d) if d == b"hello"));
+        assert!(matches!(&recording.events[1].event, ReplayEvent::Rx(d) if d == &[0x00, 0xFF]));
+    }
+
+    #[test]
+    fn test_recording_from_csv_and_json_log() {
+        let entry = LogEntry::new(Direction::Received, b"say \"hi\"\r\n".to_vec());
+        let csv = format!("Timestamp,Direction,Hex,Text\n{}\n", entry.to_csv());
+        let recording = SessionRecording::from_log_bytes(csv.as_bytes(), LogFormat::Csv).unwrap();
+        assert!(matches!(&recording.events[0].event, ReplayEvent::Rx(d) if d == &entry.data));
+
+        let json = format!("{}\n", entry.to_json());
+        let recording = SessionRecording::from_log_bytes(json.as_bytes(), LogFormat::JsonLines).unwrap();
+        assert!(matches!(&recording.events[0].event, ReplayEvent::Rx(d) if d == &entry.data));
+    }
+
     #[test]
     fn test_playback() {
         let mut recorder = SessionRecorder::new("TCP", "localhost:23");
@@ -5,6 +5,9 @@
 //! - Transformers/filters between transports
 //! - Graph-based routing configuration
 
+use crate::core::transform::{
+    Base64Decode, Base64Encode, DataTransform, HexDecode, HexDump, HexEncode, LineFilter, StripAnsi, TransformChain,
+};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -75,6 +78,43 @@ pub enum TransformType {
     Custom { script: String },
 }
 
+/// Re-encoding applied to data crossing an edge
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum EdgeTransform {
+    /// Bytes to one line of hex pairs per chunk
+    HexEncode,
+    /// Hex text to bytes
+    HexDecode,
+    /// Bytes to hexdump text with an ASCII column
+    HexDump,
+    /// Bytes to one Base64 line per chunk
+    Base64Encode,
+    /// Base64 lines to bytes
+    Base64Decode,
+    /// Remove escape sequences
+    StripAnsi,
+    /// Only pass lines matching a regex
+    LineFilter { pattern: String },
+}
+
+impl EdgeTransform {
+    /// Create the transform
+    pub fn build(&self) -> Result<Box<dyn DataTransform>, String> {
+        Ok(match self {
+            Self::HexEncode => Box::new(HexEncode::new()),
+            Self::HexDecode => Box::new(HexDecode::default()),
+            Self::HexDump => Box::new(HexDump::new()),
+            Self::Base64Encode => Box::new(Base64Encode),
+            Self::Base64Decode => Box::new(Base64Decode::default()),
+            Self::StripAnsi => Box::new(StripAnsi::new()),
+            Self::LineFilter { pattern } => {
+                let re = regex::bytes::Regex::new(pattern).map_err(|e| format!("Invalid line filter: {}", e))?;
+                Box::new(LineFilter::new(re))
+            }
+        })
+    }
+}
+
 /// Edge connecting two nodes
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RoutingEdge {
@@ -90,6 +130,20 @@ pub struct RoutingEdge {
     pub bytes_transferred: u64,
     /// Packets transferred
     pub packets_transferred: u64,
+    /// Transforms applied to data on this edge, in order
+    #[serde(default)]
+    pub transforms: Vec<EdgeTransform>,
+}
+
+impl RoutingEdge {
+    /// Chain applying this edge's transforms
+    pub fn transform_chain(&self) -> Result<TransformChain, String> {
+        let mut chain = TransformChain::new();
+        for transform in &self.transforms {
+            chain.push(transform.build()?);
+        }
+        Ok(chain)
+    }
 }
 
 /// Complete routing graph
@@ -140,6 +194,11 @@ impl RoutingGraph {
 
     /// Add an edge
     pub fn add_edge(&mut self, from: &str, to: &str, label: &str) {
+        self.connect(from, to, label, Vec::new());
+    }
+
+    /// Add an edge that re-encodes data on the way
+    pub fn connect(&mut self, from: &str, to: &str, label: &str, transforms: Vec<EdgeTransform>) {
         self.edges.push(RoutingEdge {
             from: from.to_string(),
             to: to.to_string(),
@@ -147,6 +206,7 @@ impl RoutingGraph {
             active: true,
             bytes_transferred: 0,
             packets_transferred: 0,
+            transforms,
         });
     }
 
@@ -322,6 +382,30 @@ impl GraphManager {
     }
 }
 
-
-
-
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_edge_transforms() {
+        let mut graph = RoutingGraph::new("gateway");
+        graph.connect("serial_in", "tcp_out", "Hex mirror", vec![EdgeTransform::HexEncode]);
+        graph.connect(
+            "serial_in",
+            "log",
+            "Errors",
+            vec![EdgeTransform::StripAnsi, EdgeTransform::LineFilter { pattern: "ERR".to_string() }],
+        );
+
+        let mut errors = graph.edges[1].transform_chain().unwrap();
+        assert_eq!(errors.names(), vec!["strip-ansi", "line-filter"]);
+        assert_eq!(errors.apply(b"ok\n\x1b[31mERR x\x1b[0m\n"), b"ERR x\n");
+
+        let json = serde_json::to_string(&graph).unwrap();
+        let restored: RoutingGraph = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.edges[0].transforms, [EdgeTransform::HexEncode]);
+
+        let bad = EdgeTransform::LineFilter { pattern: "(".to_string() };
+        assert!(bad.build().is_err());
+    }
+}
@@ -3,13 +3,41 @@
 //! A Session represents an active connection that can be controlled,
 //! monitored, and logged.
 
-use super::transport::{create_transport, Transport, TransportError, TransportStats, TransportTrait, ModemLines};
-use crate::core::logger::Logger;
+use super::transport::{
+    create_transport, BufferLevel, LineStep, ModemLines, ResetProfile, SerialParams, TlsInfo, Transport, TransportError,
+    TransportStats, TransportTrait, TransportType,
+};
+use crate::core::chart::ChartManager;
+use crate::core::codec::{BinaryDetector, CodecType, DEFAULT_BINARY_THRESHOLD};
+use crate::core::logger::{LogFormat, Logger, OverflowPolicy, SessionLogger, DEFAULT_WRITER_QUEUE};
+use crate::core::packet::{Packet, PacketBuffer, PacketDirection};
+use crate::core::pcap::PcapWriter;
+use crate::core::preflight::PreflightReport;
+use crate::core::prompt::{PromptDetector, PromptKind};
+use crate::core::protocol::{AtCollector, AtError, AtResponse};
+use crate::core::transform::TransformChain;
+use crate::core::transcript::{self, Bookmark, TranscriptFormat};
+use crate::core::transfer::{
+    TransferCancel, TransferDirection, TransferError, TransferProgress, TransferProtocol, ZmodemConfig,
+    ZmodemEvent, ZmodemFileInfo, ZmodemTransfer,
+};
 use crate::core::trigger::{Trigger, TriggerAction};
+use crate::utils::notifier::Notifier;
 use bytes::Bytes;
 use parking_lot::RwLock;
+use regex::bytes::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashSet, VecDeque};
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use tokio::sync::{broadcast, mpsc};
+use std::time::{Duration, Instant};
+use thiserror::Error;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::{broadcast, mpsc, oneshot, watch};
+use tokio::task::JoinHandle;
 use uuid::Uuid;
 
 /// Session state
@@ -23,8 +51,20 @@ pub enum SessionState {
     Connected,
     /// Connection error occurred
     Error,
-    /// Reconnecting after disconnect
-    Reconnecting,
+    /// Connection dropped; waiting `next_in` before reconnect attempt `attempt` (1-based)
+    Reconnecting {
+        /// Attempt about to be made
+        attempt: u32,
+        /// Backoff before the attempt
+        next_in: Duration,
+    },
+}
+
+impl SessionState {
+    /// Whether the session is trying to restore a dropped connection
+    pub fn is_reconnecting(&self) -> bool {
+        matches!(self, Self::Reconnecting { .. })
+    }
 }
 
 /// Session events
@@ -47,6 +87,933 @@ pub enum SessionEvent {
     },
     /// Connection statistics updated
     StatsUpdated(TransportStats),
+    /// Local echo of sent data, rendered per the session's `EchoPolicy`
+    LocalEcho(Bytes),
+    /// Connection ended
+    Disconnected(DisconnectReason),
+    /// No data received within the idle timeout
+    IdleTimeout(IdleAction),
+    /// Auto-started file transfer began; received data is not displayed until it ends
+    TransferStarted(TransferProtocol),
+    /// Auto-started file transfer progress
+    TransferProgress(TransferProgress),
+    /// Auto-started file transfer ended with the saved file or an error
+    TransferFinished(Result<PathBuf, String>),
+    /// The subscriber fell behind and this many events were dropped
+    ///
+    /// Only produced by [`EventReceiver`]; the session never broadcasts it.
+    Lagged(u64),
+    /// Progress through [`SessionState::Connecting`]
+    ConnectProgress(ConnectPhase),
+    /// Serial line settings changed without reconnecting
+    Reconfigured(SerialParams),
+    /// Text-mode session started receiving binary data; suggest a hex view
+    BinaryDataDetected {
+        /// Share of the chunk that was printable
+        printable_ratio: f32,
+    },
+    /// The transport's receive buffer overflowed and this many bytes were lost
+    ///
+    /// Only sent when nothing could hold off the sender (no flow control).
+    ReceiveOverflow(u64),
+}
+
+/// Sub-phase of a connection attempt
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectPhase {
+    /// Resolving the host name
+    Resolving,
+    /// Opening the port or TCP connection
+    Opening,
+    /// Past TCP, in the protocol handshake (SSH)
+    ///
+    /// Only known once an attempt has failed; it is not sent as progress.
+    Handshaking,
+}
+
+/// What a connection attempt got through before it failed
+///
+/// `None` means the step does not apply to the transport (serial has no
+/// DNS) or could not be determined.
+#[derive(Debug, Clone)]
+pub struct ConnectDiagnostics {
+    /// Transport being connected
+    pub transport: TransportType,
+    /// Phase the attempt was in when it ended
+    pub phase: ConnectPhase,
+    /// Host name resolved to at least one address
+    pub dns_resolved: Option<bool>,
+    /// Target accepted a TCP connection
+    pub tcp_open: Option<bool>,
+    /// Protocol handshake was reached (SSH key exchange or authentication)
+    pub handshake_reached: Option<bool>,
+    /// The attempt ran out of time
+    pub timed_out: bool,
+    /// Time spent before giving up
+    pub elapsed: Duration,
+}
+
+impl ConnectDiagnostics {
+    fn new(transport: TransportType) -> Self {
+        Self {
+            transport,
+            phase: ConnectPhase::Opening,
+            dns_resolved: None,
+            tcp_open: None,
+            handshake_reached: None,
+            timed_out: false,
+            elapsed: Duration::ZERO,
+        }
+    }
+}
+
+/// Failed connection attempt with what was learned about it
+#[derive(Error, Debug)]
+#[error("{error}")]
+pub struct ConnectFailure {
+    /// Underlying transport error
+    pub error: TransportError,
+    /// How far the attempt got
+    pub diagnostics: ConnectDiagnostics,
+}
+
+/// How long a post-failure reachability probe may take
+const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Host and port of network transports
+pub(crate) fn network_endpoint(transport: &Transport) -> Option<(String, u16)> {
+    match transport {
+        Transport::Tcp(cfg) => Some((cfg.host.clone(), cfg.port)),
+        Transport::Telnet(cfg) => Some((cfg.host.clone(), cfg.port)),
+        Transport::Ssh(cfg) => Some((cfg.host.clone(), cfg.port)),
+        Transport::Serial(_) | Transport::Bluetooth(_) => None,
+    }
+}
+
+pub(crate) fn transport_type_of(transport: &Transport) -> TransportType {
+    match transport {
+        Transport::Serial(_) => TransportType::Serial,
+        Transport::Tcp(_) => TransportType::Tcp,
+        Transport::Telnet(_) => TransportType::Telnet,
+        Transport::Ssh(_) => TransportType::Ssh,
+        Transport::Bluetooth(_) => TransportType::Bluetooth,
+    }
+}
+
+/// Whether a TCP connection to `addrs` opens within `limit`
+async fn probe_tcp(addrs: &[std::net::SocketAddr], limit: Duration) -> bool {
+    matches!(
+        tokio::time::timeout(limit, tokio::net::TcpStream::connect(addrs)).await,
+        Ok(Ok(_))
+    )
+}
+
+/// Default capacity of the session event channel
+pub const DEFAULT_EVENT_CAPACITY: usize = 1024;
+
+/// Default size of the pre-trigger history ring
+pub const DEFAULT_RING_CAPACITY: usize = 64 * 1024;
+
+/// Default number of sent and received chunks kept for transcripts
+pub const DEFAULT_PACKET_HISTORY: usize = 10_000;
+
+/// Default size above which `Session::send` asks before sending
+pub const DEFAULT_LARGE_SEND_THRESHOLD: usize = 16 * 1024;
+
+/// Pacing used for confirmed large sends when the session has none set
+pub const LARGE_SEND_PACING: SendPacing = SendPacing {
+    chunk_size: 256,
+    chunk_delay: Duration::from_millis(10),
+    char_delay: Duration::ZERO,
+};
+
+/// Session event receiver that reports lag instead of hiding it
+///
+/// Events are broadcast through a bounded channel; a subscriber that falls
+/// more than the channel capacity behind loses the oldest events. This
+/// receiver turns that loss into a [`SessionEvent::Lagged`] so the consumer
+/// can warn, then resumes with the oldest retained event. There is no
+/// lossless subscription: consumers that must keep up with fast data should
+/// raise [`SessionConfig::event_capacity`]. [`Session::subscribe_filtered`]
+/// is bounded too, but only queues the data its filter passes.
+#[derive(Debug)]
+pub struct EventReceiver {
+    rx: broadcast::Receiver<SessionEvent>,
+}
+
+impl EventReceiver {
+    /// Wait for the next event; `None` once the session is gone
+    pub async fn recv(&mut self) -> Option<SessionEvent> {
+        match self.rx.recv().await {
+            Ok(event) => Some(event),
+            Err(broadcast::error::RecvError::Lagged(skipped)) => Some(SessionEvent::Lagged(skipped)),
+            Err(broadcast::error::RecvError::Closed) => None,
+        }
+    }
+
+    /// Next event if one is ready
+    pub fn try_recv(&mut self) -> Option<SessionEvent> {
+        match self.rx.try_recv() {
+            Ok(event) => Some(event),
+            Err(broadcast::error::TryRecvError::Lagged(skipped)) => Some(SessionEvent::Lagged(skipped)),
+            Err(_) => None,
+        }
+    }
+}
+
+/// What to do when the idle timeout fires
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdleAction {
+    /// Flush the session log
+    FlushLog,
+    /// Disconnect the session
+    Disconnect,
+    /// Flush the log, then disconnect
+    Both,
+}
+
+impl IdleAction {
+    /// Whether the log should be flushed
+    pub fn flushes_log(self) -> bool {
+        matches!(self, Self::FlushLog | Self::Both)
+    }
+
+    /// Whether the session should be disconnected
+    pub fn disconnects(self) -> bool {
+        matches!(self, Self::Disconnect | Self::Both)
+    }
+}
+
+/// Optional PCAP sink shared with the I/O tasks
+type CaptureSink = Arc<parking_lot::Mutex<Option<PcapWriter<BufWriter<File>>>>>;
+
+/// Write a packet to the capture sink, if one is active
+fn capture_packet(sink: &CaptureSink, packet: Packet) {
+    if let Some(writer) = sink.lock().as_mut() {
+        if let Err(e) = writer.write_packet(&packet) {
+            tracing::warn!("Session capture write failed: {}", e);
+        }
+    }
+}
+
+/// Sent and received chunks kept for transcripts (None = off)
+type HistorySink = Arc<parking_lot::Mutex<Option<PacketBuffer>>>;
+
+/// Add a packet to the transcript history, if it is kept
+fn record_history(sink: &HistorySink, packet: Packet) {
+    if let Some(history) = sink.lock().as_mut() {
+        history.push(packet);
+    }
+}
+
+/// Fixed-size history of the most recently received bytes
+#[derive(Debug)]
+struct ByteRing {
+    data: VecDeque<u8>,
+    capacity: usize,
+}
+
+impl ByteRing {
+    fn new(capacity: usize) -> Self {
+        Self {
+            data: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Append bytes, dropping the oldest beyond capacity
+    fn push(&mut self, bytes: &[u8]) {
+        let bytes = &bytes[bytes.len().saturating_sub(self.capacity)..];
+        let overflow = (self.data.len() + bytes.len()).saturating_sub(self.capacity);
+        self.data.drain(..overflow);
+        self.data.extend(bytes);
+    }
+
+    fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        let overflow = self.data.len().saturating_sub(capacity);
+        self.data.drain(..overflow);
+    }
+
+    fn snapshot(&self) -> Bytes {
+        let (front, back) = self.data.as_slices();
+        let mut out = Vec::with_capacity(self.data.len());
+        out.extend_from_slice(front);
+        out.extend_from_slice(back);
+        Bytes::from(out)
+    }
+}
+
+/// Pre-trigger history shared with the receive task
+type RingSink = Arc<parking_lot::Mutex<ByteRing>>;
+
+/// Receive-path transforms shared with the receive task
+type TransformSink = Arc<parking_lot::Mutex<TransformChain>>;
+
+/// Session logger, created on demand by `StartLogging` triggers
+type LoggerSlot = Arc<RwLock<Option<Logger>>>;
+
+/// Chart fed with received data, if one is attached
+pub type SharedChart = Arc<parking_lot::Mutex<ChartManager>>;
+
+/// Expand `{time}` in a trigger's output path
+fn expand_time(path: &str) -> String {
+    path.replace("{time}", &chrono::Local::now().format("%Y%m%d-%H%M%S%.3f").to_string())
+}
+
+/// Apply a trigger's logging and chart recording actions
+fn apply_recording_actions(
+    logger: &LoggerSlot,
+    chart: &RwLock<Option<SharedChart>>,
+    trigger: &Trigger,
+    format: LogFormat,
+) {
+    for action in &trigger.actions {
+        match action {
+            TriggerAction::StartLogging(path) => {
+                let path = PathBuf::from(expand_time(path));
+                let logger = logger
+                    .write()
+                    .get_or_insert_with(|| Arc::new(parking_lot::Mutex::new(SessionLogger::new())))
+                    .clone();
+                let mut logger = logger.lock();
+                if logger.is_logging() && logger.path() == Some(&path) {
+                    continue;
+                }
+                // Entries are written by the logger's thread, not the receive task
+                if let Err(e) = logger.start_background(path, format, DEFAULT_WRITER_QUEUE, OverflowPolicy::Drop) {
+                    tracing::warn!("Trigger '{}' could not start logging: {}", trigger.name, e);
+                }
+            }
+            TriggerAction::StopLogging => {
+                if let Some(logger) = logger.read().as_ref() {
+                    logger.lock().stop();
+                }
+            }
+            TriggerAction::StartChartRecording | TriggerAction::StopChartRecording => {
+                if let Some(chart) = chart.read().as_ref() {
+                    chart.lock().set_recording(matches!(action, TriggerAction::StartChartRecording));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Whether the session logger is writing to a file
+fn is_logging(logger: &LoggerSlot) -> bool {
+    logger.read().as_ref().is_some_and(|logger| logger.lock().is_logging())
+}
+
+/// Write the history ring for a trigger's `SaveHistory` actions
+fn save_trigger_history(ring: &RingSink, trigger: &Trigger) {
+    let mut snapshot = None;
+    for action in &trigger.actions {
+        if let TriggerAction::SaveHistory(path) = action {
+            let data = snapshot.get_or_insert_with(|| ring.lock().snapshot());
+            let path = expand_time(path);
+            if let Err(e) = std::fs::write(&path, data) {
+                tracing::warn!("Trigger '{}' could not save history to {}: {}", trigger.name, path, e);
+            }
+        }
+    }
+}
+
+/// Post a trigger's `Notify` actions, titled with the trigger name
+fn notify_trigger(notifier: &Notifier, trigger: &Trigger) {
+    for action in &trigger.actions {
+        if let TriggerAction::Notify(message) = action {
+            notifier.notify(&trigger.name, message);
+        }
+    }
+}
+
+/// How often the idle watchdog checks for inactivity
+const IDLE_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// RX inactivity tracking
+#[derive(Debug)]
+struct IdleTimer {
+    timeout: Option<(Duration, IdleAction)>,
+    last_rx: Instant,
+    fired: bool,
+}
+
+impl IdleTimer {
+    fn new() -> Self {
+        Self {
+            timeout: None,
+            last_rx: Instant::now(),
+            fired: false,
+        }
+    }
+
+    /// Record RX activity, re-arming the timer
+    fn touch(&mut self, now: Instant) {
+        self.last_rx = now;
+        self.fired = false;
+    }
+
+    /// Return the action to take if the timeout has elapsed; fires once per idle period
+    fn poll(&mut self, now: Instant) -> Option<IdleAction> {
+        let (timeout, action) = self.timeout?;
+        if self.fired || now.duration_since(self.last_rx) < timeout {
+            return None;
+        }
+        self.fired = true;
+        Some(action)
+    }
+}
+
+/// Why a session ended
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DisconnectReason {
+    /// `disconnect()` was called
+    UserRequested,
+    /// The remote end closed the connection
+    RemoteClosed,
+    /// The connection timed out
+    Timeout,
+    /// Transport I/O error
+    Io(String),
+    /// The remote rejected our credentials
+    AuthFailed,
+    /// The server's host key is unknown or has changed
+    HostKeyRejected,
+}
+
+impl DisconnectReason {
+    /// Whether reconnecting could help
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, Self::RemoteClosed | Self::Timeout | Self::Io(_))
+    }
+}
+
+impl std::fmt::Display for DisconnectReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UserRequested => write!(f, "Disconnected by user"),
+            Self::RemoteClosed => write!(f, "Connection closed by remote"),
+            Self::Timeout => write!(f, "Connection timed out"),
+            Self::Io(e) => write!(f, "I/O error: {}", e),
+            Self::AuthFailed => write!(f, "Authentication failed"),
+            Self::HostKeyRejected => write!(f, "Host key verification failed"),
+        }
+    }
+}
+
+impl From<&TransportError> for DisconnectReason {
+    fn from(err: &TransportError) -> Self {
+        match err {
+            TransportError::Disconnected | TransportError::NotConnected => Self::RemoteClosed,
+            TransportError::Timeout(_) => Self::Timeout,
+            TransportError::AuthFailed(_) => Self::AuthFailed,
+            TransportError::Ssh(_) => Self::HostKeyRejected,
+            TransportError::IoError(e) if e.kind() == std::io::ErrorKind::TimedOut => Self::Timeout,
+            other => Self::Io(other.to_string()),
+        }
+    }
+}
+
+/// Session error types
+#[derive(Error, Debug)]
+pub enum SessionError {
+    /// Data contains a guarded byte; confirm and resend with `send_forced`
+    #[error("Guarded byte 0x{0:02X} needs confirmation")]
+    Guarded(u8),
+
+    /// The send needs confirmation before it goes out
+    #[error(transparent)]
+    Warning(#[from] SendWarning),
+
+    /// Transport error
+    #[error(transparent)]
+    Transport(#[from] TransportError),
+}
+
+/// Reason `Session::send` held back data for confirmation
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SendWarning {
+    /// Payload of this many bytes is over the large-send threshold; offer a
+    /// file transfer, or resend with `send_large` to pace it automatically
+    #[error("Sending {0} bytes at once; use a file transfer or paced sending")]
+    LargePayload(usize),
+}
+
+/// First byte of `data` that is in `guard`
+fn find_guarded(guard: &HashSet<u8>, data: &[u8]) -> Option<u8> {
+    if guard.is_empty() {
+        return None;
+    }
+    data.iter().copied().find(|b| guard.contains(b))
+}
+
+/// Update the session state and announce it
+fn set_state(state: &RwLock<SessionState>, event_tx: &broadcast::Sender<SessionEvent>, new: SessionState) {
+    *state.write() = new;
+    let _ = event_tx.send(SessionEvent::StateChanged(new));
+}
+
+/// Re-establish a dropped connection
+///
+/// Walks `Reconnecting` -> `Connecting` for each attempt and ends in
+/// `Connected` on success. Gives up with the last error once the attempts
+/// are exhausted, the error is not retryable, or `stop` is set.
+///
+/// `stop` is set before a user disconnect is queued, and the disconnect
+/// needs the transport lock, so checking it under the lock means a
+/// disconnect is never overridden by a late reconnect.
+async fn reconnect(
+    transport: &tokio::sync::Mutex<Box<dyn TransportTrait>>,
+    state: &RwLock<SessionState>,
+    event_tx: &broadcast::Sender<SessionEvent>,
+    policy: ReconnectPolicy,
+    stop: &mut watch::Receiver<bool>,
+    mut last_error: TransportError,
+) -> Result<(), TransportError> {
+    let mut attempt = 0;
+
+    loop {
+        let exhausted = policy.max_attempts > 0 && attempt >= policy.max_attempts;
+        if exhausted || !DisconnectReason::from(&last_error).is_retryable() {
+            return Err(last_error);
+        }
+        attempt += 1;
+
+        let next_in = policy.delay_for(attempt);
+        set_state(state, event_tx, SessionState::Reconnecting { attempt, next_in });
+        tokio::select! {
+            () = tokio::time::sleep(next_in) => {}
+            _ = stop.wait_for(|stop| *stop) => return Err(last_error),
+        }
+
+        let mut transport = transport.lock().await;
+        if *stop.borrow() {
+            return Err(last_error);
+        }
+        set_state(state, event_tx, SessionState::Connecting);
+        let _ = transport.disconnect().await;
+        match transport.connect().await {
+            Ok(()) => {
+                set_state(state, event_tx, SessionState::Connected);
+                return Ok(());
+            }
+            Err(e) => {
+                tracing::warn!("Reconnect attempt {} failed: {}", attempt, e);
+                last_error = e;
+            }
+        }
+    }
+}
+
+/// How long dropping a session waits for its tasks to end
+const DROP_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Poll interval while `shutdown_blocking` waits
+const SHUTDOWN_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Give up on an auto-started transfer after this long without data
+const TRANSFER_IDLE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// ZMODEM abort sequence sent when a transfer is cancelled
+const ZMODEM_ABORT: [u8; 5] = [0x18; 5];
+
+/// Run a ZMODEM receive triggered by the auto-start sequence
+///
+/// `initial` is the chunk that held the auto-start; its ZRQINIT is answered
+/// like any later frame. Holds the transport for the whole transfer so
+/// normal display and sends pause until it ends. Each file is saved into
+/// `dir` under the sender's file name (without any path components); the
+/// path of the last one is returned.
+async fn zmodem_auto_receive(
+    transport: &mut dyn TransportTrait,
+    initial: &[u8],
+    dir: &Path,
+    event_tx: &broadcast::Sender<SessionEvent>,
+) -> Result<PathBuf, TransferError> {
+    let mut zmodem = ZmodemTransfer::new(TransferDirection::Receive, ZmodemConfig::default());
+    zmodem.process_data(initial);
+
+    let mut reply = Vec::new();
+    let mut current: Option<(tokio::fs::File, PathBuf, ZmodemFileInfo)> = None;
+    let mut saved = None;
+    let mut last_rx = Instant::now();
+    loop {
+        while let Some(frame) = zmodem.next_frame()? {
+            match zmodem.receive_frame(frame, &mut reply)? {
+                ZmodemEvent::FileStart(info) => {
+                    let name = Path::new(&info.name)
+                        .file_name()
+                        .map(|n| n.to_os_string())
+                        .unwrap_or_else(|| "zmodem-download.bin".into());
+                    let path = dir.join(name);
+                    tokio::fs::create_dir_all(dir).await?;
+                    current = Some((tokio::fs::File::create(&path).await?, path, info));
+                    let _ = event_tx.send(SessionEvent::TransferProgress(zmodem.progress().clone()));
+                }
+                ZmodemEvent::Data(data) => {
+                    if let Some((file, ..)) = current.as_mut() {
+                        file.write_all(&data).await?;
+                    }
+                    let _ = event_tx.send(SessionEvent::TransferProgress(zmodem.progress().clone()));
+                }
+                ZmodemEvent::FileEnd => {
+                    if let Some((mut file, path, info)) = current.take() {
+                        file.flush().await?;
+                        let file = file.into_std().await;
+                        let applied = tokio::task::spawn_blocking(move || info.apply(&file)).await;
+                        if let Ok(Err(e)) = applied {
+                            tracing::warn!("Could not restore metadata of {}: {}", path.display(), e);
+                        }
+                        saved = Some(path);
+                    }
+                }
+                ZmodemEvent::Finished => {
+                    transport.send(&reply).await?;
+                    return saved.ok_or_else(|| TransferError::Protocol("Sender finished without a file".to_string()));
+                }
+                ZmodemEvent::None => {}
+            }
+            if !reply.is_empty() {
+                transport.send(&reply).await?;
+                reply.clear();
+            }
+        }
+
+        let bytes = transport.receive().await?;
+        if bytes.is_empty() {
+            if last_rx.elapsed() >= TRANSFER_IDLE_TIMEOUT {
+                return Err(TransferError::Timeout("waiting for ZMODEM data"));
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            continue;
+        }
+        last_rx = Instant::now();
+        zmodem.process_data(&bytes);
+    }
+}
+
+/// Which data a filtered subscription forwards
+///
+/// All configured criteria must pass. In line mode the data is split on LF
+/// (a trailing CR is dropped) and each complete line is tested and emitted
+/// on its own, with lines over [`MAX_FILTER_LINE`] split; otherwise each
+/// received or sent chunk is.
+#[derive(Clone, Default)]
+pub struct DataFilter {
+    direction: Option<PacketDirection>,
+    pattern: Option<Regex>,
+    predicate: Option<Arc<dyn Fn(&Packet) -> bool + Send + Sync>>,
+    lines: bool,
+}
+
+impl DataFilter {
+    /// Filter that forwards everything
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only forward data in one direction
+    #[must_use]
+    pub fn direction(mut self, direction: PacketDirection) -> Self {
+        self.direction = Some(direction);
+        self
+    }
+
+    /// Only forward data matching a regex
+    #[must_use]
+    pub fn pattern(mut self, pattern: Regex) -> Self {
+        self.pattern = Some(pattern);
+        self
+    }
+
+    /// Only forward data accepted by a closure
+    #[must_use]
+    pub fn predicate(mut self, predicate: impl Fn(&Packet) -> bool + Send + Sync + 'static) -> Self {
+        self.predicate = Some(Arc::new(predicate));
+        self
+    }
+
+    /// Split data into lines before filtering
+    #[must_use]
+    pub fn lines(mut self) -> Self {
+        self.lines = true;
+        self
+    }
+
+    fn accepts(&self, packet: &Packet) -> bool {
+        self.direction.map_or(true, |d| d == packet.direction)
+            && self.pattern.as_ref().map_or(true, |re| re.is_match(&packet.data))
+            && self.predicate.as_ref().map_or(true, |f| f(packet))
+    }
+}
+
+/// Received bytes kept for prompt detection
+const PROMPT_TAIL_LEN: usize = 256;
+
+/// Capacity of a filtered subscription; packets are dropped while it is full
+const FILTERED_CHANNEL_CAPACITY: usize = 256;
+
+/// Longest line a line-mode filter buffers; longer lines are split
+pub const MAX_FILTER_LINE: usize = 64 * 1024;
+
+/// Receiver of [`Session::subscribe_filtered`]
+///
+/// Packets that do not fit while the consumer is behind are dropped and
+/// counted; see [`FilteredReceiver::take_dropped`].
+#[derive(Debug)]
+pub struct FilteredReceiver {
+    rx: mpsc::Receiver<Packet>,
+    dropped: Arc<AtomicU64>,
+}
+
+impl FilteredReceiver {
+    /// Wait for the next packet; `None` once the session is gone
+    pub async fn recv(&mut self) -> Option<Packet> {
+        self.rx.recv().await
+    }
+
+    /// Next packet if one is ready
+    pub fn try_recv(&mut self) -> Option<Packet> {
+        self.rx.try_recv().ok()
+    }
+
+    /// Packets dropped since the last call because the receiver was full
+    pub fn take_dropped(&self) -> u64 {
+        self.dropped.swap(0, Ordering::Relaxed)
+    }
+}
+
+/// A `subscribe_filtered` receiver and its line buffers
+struct FilteredSubscriber {
+    filter: DataFilter,
+    rx_line: Vec<u8>,
+    tx_line: Vec<u8>,
+    sender: mpsc::Sender<Packet>,
+    dropped: Arc<AtomicU64>,
+}
+
+impl FilteredSubscriber {
+    /// Filter and forward one chunk; returns false once the receiver is gone
+    fn feed(&mut self, direction: PacketDirection, data: &[u8]) -> bool {
+        if self.sender.is_closed() {
+            return false;
+        }
+        if self.filter.direction.is_some_and(|d| d != direction) {
+            return true;
+        }
+
+        if !self.filter.lines {
+            self.forward(Packet::new(direction, data.to_vec()));
+            return true;
+        }
+
+        let partial = match direction {
+            PacketDirection::Tx => &mut self.tx_line,
+            _ => &mut self.rx_line,
+        };
+        partial.extend_from_slice(data);
+        let complete: Vec<u8> = match partial.iter().rposition(|&b| b == b'\n') {
+            Some(last_lf) => partial.drain(..=last_lf).collect(),
+            None => Vec::new(),
+        };
+        // A line with no LF in sight is split rather than buffered forever
+        let overlong: Vec<u8> = if partial.len() > MAX_FILTER_LINE {
+            partial.drain(..partial.len() / MAX_FILTER_LINE * MAX_FILTER_LINE).collect()
+        } else {
+            Vec::new()
+        };
+
+        if let Some(body) = complete.strip_suffix(b"\n") {
+            for line in body.split(|&b| b == b'\n') {
+                let line = line.strip_suffix(b"\r").unwrap_or(line);
+                self.forward(Packet::new(direction, line.to_vec()));
+            }
+        }
+        for piece in overlong.chunks(MAX_FILTER_LINE) {
+            self.forward(Packet::new(direction, piece.to_vec()));
+        }
+        true
+    }
+
+    fn forward(&self, packet: Packet) {
+        if self.filter.accepts(&packet) && self.sender.try_send(packet).is_err() {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+type FilteredSubscribers = Arc<parking_lot::Mutex<Vec<FilteredSubscriber>>>;
+
+/// Hand data to every filtered subscriber, dropping closed ones
+fn dispatch_filtered(subscribers: &FilteredSubscribers, direction: PacketDirection, data: &[u8]) {
+    let mut subscribers = subscribers.lock();
+    if !subscribers.is_empty() {
+        subscribers.retain_mut(|sub| sub.feed(direction, data));
+    }
+}
+
+/// How control characters appear in the local echo
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ControlCharEcho {
+    /// Echo the raw byte (the view decides what to do with it)
+    Raw,
+    /// Caret notation, e.g. `^C` for 0x03 and `^?` for DEL
+    #[default]
+    Caret,
+    /// Hex notation, e.g. `<03>`
+    Hex,
+}
+
+/// Local echo policy for a session
+///
+/// Only affects what is shown locally; the transport always receives the
+/// raw bytes. CR, LF and TAB are echoed as-is since they carry layout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct EchoPolicy {
+    /// Emit `SessionEvent::LocalEcho` for sent data
+    pub enabled: bool,
+    /// Presentation of other control characters
+    pub control_chars: ControlCharEcho,
+}
+
+impl EchoPolicy {
+    /// Create an enabled policy with the given control-char presentation
+    pub fn new(control_chars: ControlCharEcho) -> Self {
+        Self {
+            enabled: true,
+            control_chars,
+        }
+    }
+
+    /// Render sent bytes for the local view
+    pub fn render(&self, data: &[u8]) -> Vec<u8> {
+        let mut out = Vec::with_capacity(data.len());
+        for &b in data {
+            let is_control = (b < 0x20 && !matches!(b, b'\r' | b'\n' | b'\t')) || b == 0x7f;
+            if !is_control {
+                out.push(b);
+                continue;
+            }
+
+            match self.control_chars {
+                ControlCharEcho::Raw => out.push(b),
+                ControlCharEcho::Caret => {
+                    out.push(b'^');
+                    out.push(if b == 0x7f { b'?' } else { b + 0x40 });
+                }
+                ControlCharEcho::Hex => {
+                    out.extend_from_slice(format!("<{:02X}>", b).as_bytes());
+                }
+            }
+        }
+        out
+    }
+}
+
+/// Pacing of outgoing data for devices without flow control
+///
+/// Writes are split into `chunk_size` pieces with `chunk_delay` after each,
+/// or sent one byte at a time with `char_delay` after each byte when that
+/// is set. Pacing happens in the session's send task, so later sends queue
+/// behind a paced one and received data keeps flowing in between.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SendPacing {
+    /// Bytes per chunk (0 = no chunking)
+    pub chunk_size: usize,
+    /// Pause after each chunk
+    pub chunk_delay: Duration,
+    /// Pause after every byte; takes precedence over chunking
+    pub char_delay: Duration,
+}
+
+impl SendPacing {
+    /// Send `chunk_size` bytes, then pause for `delay`
+    pub fn chunked(chunk_size: usize, delay: Duration) -> Self {
+        Self {
+            chunk_size,
+            chunk_delay: delay,
+            char_delay: Duration::ZERO,
+        }
+    }
+
+    /// Pause `delay` after every byte
+    pub fn per_char(delay: Duration) -> Self {
+        Self {
+            char_delay: delay,
+            ..Self::default()
+        }
+    }
+
+    /// Whether sends are paced at all
+    pub fn is_enabled(&self) -> bool {
+        !self.char_delay.is_zero() || (self.chunk_size > 0 && !self.chunk_delay.is_zero())
+    }
+
+    /// Chunk size and pause to use for a write
+    fn step(&self) -> Option<(usize, Duration)> {
+        if !self.char_delay.is_zero() {
+            Some((1, self.char_delay))
+        } else if self.is_enabled() {
+            Some((self.chunk_size, self.chunk_delay))
+        } else {
+            None
+        }
+    }
+}
+
+/// Write `data` in paced chunks, releasing the transport between them
+///
+/// Stops early once `stop` turns true. Returns how many bytes were written.
+async fn send_paced(
+    transport: &tokio::sync::Mutex<Box<dyn TransportTrait>>,
+    data: &[u8],
+    pacing: SendPacing,
+    stop: &mut watch::Receiver<bool>,
+) -> Result<usize, TransportError> {
+    let Some((size, delay)) = pacing.step() else {
+        transport.lock().await.send(data).await?;
+        return Ok(data.len());
+    };
+
+    let mut sent = 0;
+    for chunk in data.chunks(size) {
+        if *stop.borrow() {
+            break;
+        }
+        if sent > 0 {
+            tokio::select! {
+                () = tokio::time::sleep(delay) => {}
+                _ = stop.wait_for(|stopping| *stopping) => break,
+            }
+        }
+        transport.lock().await.send(chunk).await?;
+        sent += chunk.len();
+    }
+    Ok(sent)
+}
+
+/// Line ending appended by `Session::send_line`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum LineEnding {
+    /// Carriage Return only
+    Cr,
+    /// Line Feed only
+    Lf,
+    /// Both CR and LF
+    #[default]
+    CrLf,
+}
+
+impl LineEnding {
+    /// Get the byte sequence for this line ending
+    pub fn bytes(&self) -> &'static [u8] {
+        match self {
+            Self::Cr => b"\r",
+            Self::Lf => b"\n",
+            Self::CrLf => b"\r\n",
+        }
+    }
 }
 
 /// Session configuration
@@ -60,12 +1027,38 @@ pub struct SessionConfig {
     pub logging_enabled: bool,
     /// Log file path (if logging enabled)
     pub log_path: Option<String>,
+    /// Format of the session log and of logs started by triggers
+    pub log_format: LogFormat,
     /// Auto-reconnect on disconnect
     pub auto_reconnect: bool,
-    /// Reconnect delay in seconds
+    /// Reconnect delay in seconds, doubled after each failed attempt
     pub reconnect_delay_secs: u64,
+    /// Upper bound on the reconnect delay in seconds
+    pub max_reconnect_delay_secs: u64,
     /// Maximum reconnect attempts (0 = infinite)
     pub max_reconnect_attempts: u32,
+    /// Local echo policy
+    pub local_echo: EchoPolicy,
+    /// Display codec
+    pub codec: CodecType,
+    /// Line ending appended to sent lines
+    pub line_ending: LineEnding,
+    /// Bytes that `Session::send` refuses without confirmation (empty = off)
+    pub guard_bytes: HashSet<u8>,
+    /// Download directory for ZMODEM auto-receive (None = off)
+    pub auto_receive_zmodem: Option<PathBuf>,
+    /// Events buffered per subscriber before the oldest are dropped
+    pub event_capacity: usize,
+    /// Pacing of outgoing data
+    pub send_pacing: SendPacing,
+    /// Received bytes kept as pre-trigger history (0 = off)
+    pub ring_capacity: usize,
+    /// Sends larger than this need confirmation (0 = off)
+    pub large_send_threshold: usize,
+    /// Sent and received chunks kept for transcripts (0 = off)
+    pub packet_history: usize,
+    /// Printable share below which text-mode data is reported as binary (0 = off)
+    pub binary_threshold: f32,
 }
 
 impl SessionConfig {
@@ -76,9 +1069,22 @@ impl SessionConfig {
             transport,
             logging_enabled: false,
             log_path: None,
+            log_format: LogFormat::default(),
             auto_reconnect: false,
             reconnect_delay_secs: 5,
+            max_reconnect_delay_secs: 60,
             max_reconnect_attempts: 0,
+            local_echo: EchoPolicy::default(),
+            codec: CodecType::default(),
+            line_ending: LineEnding::default(),
+            guard_bytes: HashSet::new(),
+            auto_receive_zmodem: None,
+            event_capacity: DEFAULT_EVENT_CAPACITY,
+            send_pacing: SendPacing::default(),
+            ring_capacity: DEFAULT_RING_CAPACITY,
+            large_send_threshold: DEFAULT_LARGE_SEND_THRESHOLD,
+            packet_history: DEFAULT_PACKET_HISTORY,
+            binary_threshold: DEFAULT_BINARY_THRESHOLD,
         }
     }
 }
@@ -98,20 +1104,86 @@ pub struct Session {
     /// Command sender
     cmd_tx: mpsc::Sender<SessionCommand>,
     /// Logger instance
-    logger: Option<Logger>,
+    logger: LoggerSlot,
+    /// Chart fed with received data
+    chart: Arc<RwLock<Option<SharedChart>>>,
     /// Triggers
     triggers: Arc<RwLock<Vec<Trigger>>>,
     /// Receive buffer (for trigger matching)
     receive_buffer: Arc<RwLock<Vec<u8>>>,
+    /// Local echo policy
+    echo: RwLock<EchoPolicy>,
+    /// Idle timeout state
+    idle: Arc<parking_lot::Mutex<IdleTimer>>,
+    /// PCAP capture sink
+    capture: CaptureSink,
+    /// Display codec
+    codec: CodecType,
+    /// Line ending appended to sent lines
+    line_ending: LineEnding,
+    /// Bytes that need confirmation before sending
+    guard_bytes: RwLock<HashSet<u8>>,
+    /// Pacing of outgoing data, shared with the send task
+    pacing: Arc<RwLock<SendPacing>>,
+    /// Sends larger than this need confirmation (0 = off)
+    large_send_threshold: RwLock<usize>,
+    /// Auto-reconnect settings (None = off)
+    reconnect: Option<ReconnectPolicy>,
+    /// Download directory for ZMODEM auto-receive (None = off)
+    auto_receive_zmodem: Option<PathBuf>,
+    /// Sink for trigger `Notify` actions
+    notifier: Arc<RwLock<Option<Arc<Notifier>>>>,
+    /// `subscribe_filtered` receivers
+    filtered: FilteredSubscribers,
+    /// Set once shutdown has begun; cancels auto-started transfers
+    shutdown: watch::Sender<bool>,
+    /// Set when told to disconnect; cuts paced sends and reconnects short
+    stop_sending: watch::Sender<bool>,
+    /// Receive and command tasks, awaited on shutdown
+    tasks: parking_lot::Mutex<Vec<JoinHandle<()>>>,
+    /// Transfers cancelled on shutdown
+    transfers: parking_lot::Mutex<Vec<TransferCancel>>,
+    /// Most recently received bytes, kept for pre-trigger dumps
+    ring: RingSink,
+    /// Transforms applied to received data after the history ring
+    transforms: TransformSink,
+    /// Sent and received chunks for transcripts
+    history: HistorySink,
+    /// Markers in the history, ordered by position
+    bookmarks: RwLock<Vec<Bookmark>>,
+    /// Transport receive buffer level after the last read
+    rx_level: Arc<RwLock<Option<BufferLevel>>>,
+}
+
+/// Auto-reconnect settings taken from `SessionConfig`
+#[derive(Debug, Clone, Copy)]
+struct ReconnectPolicy {
+    /// Backoff before the first attempt
+    delay: Duration,
+    /// Cap on the doubling backoff
+    max_delay: Duration,
+    /// 0 = unlimited
+    max_attempts: u32,
+}
+
+impl ReconnectPolicy {
+    /// Backoff before `attempt` (1-based): doubles from `delay` up to `max_delay`
+    fn delay_for(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.delay.saturating_mul(factor).min(self.max_delay.max(self.delay))
+    }
 }
 
 /// Internal commands for session control
 enum SessionCommand {
-    Send(Bytes),
-    Disconnect,
+    /// Data and a pacing override (None = session pacing)
+    Send(Bytes, Option<SendPacing>),
+    Disconnect(DisconnectReason),
     SetDtr(bool),
     SetRts(bool),
     SendBreak,
+    LineSequence(Vec<LineStep>),
+    Reconfigure(SerialParams, oneshot::Sender<Result<(), TransportError>>),
 }
 
 impl Session {
@@ -121,32 +1193,153 @@ impl Session {
         Self::connect_with_config(config).await
     }
 
+    /// Check that `transport` looks connectable without connecting
+    ///
+    /// See [`crate::core::preflight`] for the checks run per transport.
+    pub async fn preflight(transport: &Transport) -> PreflightReport {
+        crate::core::preflight::run(transport).await
+    }
+
     /// Connect with full configuration
     pub async fn connect_with_config(config: SessionConfig) -> Result<Self, TransportError> {
+        // Create and connect transport
+        let mut transport = create_transport(config.transport.clone()).await?;
+        transport.connect().await?;
+
+        Ok(Self::start(config, transport))
+    }
+
+    /// Connect, giving up after `timeout` with a diagnosis of how far the attempt got
+    pub async fn connect_with_timeout(transport: Transport, timeout: Duration) -> Result<Self, ConnectFailure> {
+        let config = SessionConfig::new("Session", transport);
+        Self::connect_with_timeout_config(config, timeout, None).await
+    }
+
+    /// Connect with full configuration and a deadline
+    ///
+    /// Each [`ConnectPhase`] is sent to `progress` as a
+    /// [`SessionEvent::ConnectProgress`] as it starts (the session's own
+    /// event channel does not exist until the connection is up). Network
+    /// transports resolve the host first. The connect itself runs on a
+    /// blocking thread, since ssh2 and serial opens block, so the deadline
+    /// always fires; an attempt still running then is abandoned. On failure
+    /// the port is re-probed briefly to fill in
+    /// [`ConnectDiagnostics::tcp_open`], which tells a stalled SSH key
+    /// exchange apart from a filtered port.
+    pub async fn connect_with_timeout_config(
+        config: SessionConfig,
+        timeout: Duration,
+        progress: Option<mpsc::UnboundedSender<SessionEvent>>,
+    ) -> Result<Self, ConnectFailure> {
+        let started = Instant::now();
+        let deadline = tokio::time::Instant::now() + timeout;
+        let mut diagnostics = ConnectDiagnostics::new(transport_type_of(&config.transport));
+        let enter = |diagnostics: &mut ConnectDiagnostics, phase: ConnectPhase| {
+            diagnostics.phase = phase;
+            if let Some(tx) = &progress {
+                let _ = tx.send(SessionEvent::ConnectProgress(phase));
+            }
+        };
+        let fail = |mut diagnostics: ConnectDiagnostics, error: TransportError| {
+            diagnostics.timed_out = matches!(error, TransportError::Timeout(_));
+            diagnostics.elapsed = started.elapsed();
+            ConnectFailure { error, diagnostics }
+        };
+        let timed_out = || TransportError::Timeout(timeout.as_secs());
+
+        let mut addrs = Vec::new();
+        if let Some((host, port)) = network_endpoint(&config.transport) {
+            enter(&mut diagnostics, ConnectPhase::Resolving);
+            match tokio::time::timeout_at(deadline, tokio::net::lookup_host((host.as_str(), port))).await {
+                Ok(Ok(resolved)) => addrs.extend(resolved),
+                Ok(Err(e)) => {
+                    diagnostics.dns_resolved = Some(false);
+                    let error = TransportError::ConnectionFailed(format!("Cannot resolve {}: {}", host, e));
+                    return Err(fail(diagnostics, error));
+                }
+                Err(_) => return Err(fail(diagnostics, timed_out())),
+            }
+            diagnostics.dns_resolved = Some(!addrs.is_empty());
+            if addrs.is_empty() {
+                let error = TransportError::ConnectionFailed(format!("No addresses for {}", host));
+                return Err(fail(diagnostics, error));
+            }
+        }
+
+        enter(&mut diagnostics, ConnectPhase::Opening);
+        let transport_config = config.transport.clone();
+        let runtime = tokio::runtime::Handle::current();
+        let connect = tokio::task::spawn_blocking(move || {
+            runtime.block_on(async {
+                let mut transport = create_transport(transport_config).await?;
+                transport.connect().await?;
+                Ok::<_, TransportError>(transport)
+            })
+        });
+        let error = match tokio::time::timeout_at(deadline, connect).await {
+            Ok(Ok(Ok(transport))) => return Ok(Self::start(config, transport)),
+            Ok(Ok(Err(e))) => e,
+            Ok(Err(e)) => TransportError::ConnectionFailed(format!("Connect task failed: {}", e)),
+            Err(_) => timed_out(),
+        };
+
+        match &error {
+            TransportError::AuthFailed(_) | TransportError::Ssh(_) => {
+                diagnostics.phase = ConnectPhase::Handshaking;
+                diagnostics.tcp_open = Some(true);
+                diagnostics.handshake_reached = Some(true);
+            }
+            _ if !addrs.is_empty() => {
+                let open = probe_tcp(&addrs, PROBE_TIMEOUT).await;
+                diagnostics.tcp_open = Some(open);
+                if !open {
+                    diagnostics.handshake_reached = Some(false);
+                } else if diagnostics.transport == TransportType::Ssh {
+                    diagnostics.phase = ConnectPhase::Handshaking;
+                }
+            }
+            _ => {}
+        }
+        Err(fail(diagnostics, error))
+    }
+
+    /// Wire up a connected transport and spawn the session tasks
+    fn start(config: SessionConfig, transport: Box<dyn TransportTrait>) -> Self {
         let id = Uuid::new_v4();
         let state = Arc::new(RwLock::new(SessionState::Connecting));
-        let (event_tx, _) = broadcast::channel(1024);
+        let (event_tx, _) = broadcast::channel(config.event_capacity.max(1));
         let (cmd_tx, cmd_rx) = mpsc::channel(256);
 
-        // Create and connect transport
-        let mut transport = create_transport(config.transport).await?;
-        transport.connect().await?;
-
         *state.write() = SessionState::Connected;
         let _ = event_tx.send(SessionEvent::StateChanged(SessionState::Connected));
 
         // Create logger if enabled
-        let logger: Option<Logger> = if config.logging_enabled {
-            config.log_path.map(|_path| {
-                Arc::new(parking_lot::Mutex::new(crate::core::logger::SessionLogger::new()))
-            })
-        } else {
-            None
-        };
+        let logger: LoggerSlot = Arc::new(RwLock::new(match (config.logging_enabled, &config.log_path) {
+            (true, Some(path)) => {
+                let mut logger = SessionLogger::new();
+                if let Err(e) = logger.start(PathBuf::from(path), config.log_format) {
+                    tracing::warn!("Could not open session log {}: {}", path, e);
+                }
+                Some(Arc::new(parking_lot::Mutex::new(logger)))
+            }
+            _ => None,
+        }));
+        let chart: Arc<RwLock<Option<SharedChart>>> = Arc::new(RwLock::new(None));
+        let (shutdown, _) = watch::channel(false);
+        let (stop_sending, _) = watch::channel(false);
 
         let transport = Arc::new(tokio::sync::Mutex::new(transport));
         let triggers = Arc::new(RwLock::new(Vec::new()));
         let receive_buffer = Arc::new(RwLock::new(Vec::with_capacity(8192)));
+        let idle = Arc::new(parking_lot::Mutex::new(IdleTimer::new()));
+        let capture: CaptureSink = Arc::new(parking_lot::Mutex::new(None));
+        let notifier: Arc<RwLock<Option<Arc<Notifier>>>> = Arc::new(RwLock::new(None));
+        let filtered: FilteredSubscribers = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let ring: RingSink = Arc::new(parking_lot::Mutex::new(ByteRing::new(config.ring_capacity)));
+        let transforms: TransformSink = Arc::new(parking_lot::Mutex::new(TransformChain::new()));
+        let history: HistorySink = Arc::new(parking_lot::Mutex::new(
+            (config.packet_history > 0).then(|| PacketBuffer::new(config.packet_history)),
+        ));
 
         let session = Self {
             id,
@@ -155,19 +1348,103 @@ impl Session {
             transport: transport.clone(),
             event_tx: event_tx.clone(),
             cmd_tx,
-            logger,
+            logger: logger.clone(),
+            chart: chart.clone(),
             triggers: triggers.clone(),
             receive_buffer: receive_buffer.clone(),
+            echo: RwLock::new(config.local_echo),
+            idle: idle.clone(),
+            capture: capture.clone(),
+            codec: config.codec,
+            line_ending: config.line_ending,
+            guard_bytes: RwLock::new(config.guard_bytes),
+            pacing: Arc::new(RwLock::new(config.send_pacing)),
+            large_send_threshold: RwLock::new(config.large_send_threshold),
+            reconnect: config.auto_reconnect.then_some(ReconnectPolicy {
+                delay: Duration::from_secs(config.reconnect_delay_secs),
+                max_delay: Duration::from_secs(config.max_reconnect_delay_secs),
+                max_attempts: config.max_reconnect_attempts,
+            }),
+            auto_receive_zmodem: config.auto_receive_zmodem,
+            notifier: notifier.clone(),
+            filtered: filtered.clone(),
+            shutdown,
+            stop_sending,
+            tasks: parking_lot::Mutex::new(Vec::new()),
+            transfers: parking_lot::Mutex::new(Vec::new()),
+            ring: ring.clone(),
+            transforms: transforms.clone(),
+            history: history.clone(),
+            bookmarks: RwLock::new(Vec::new()),
+            rx_level: Arc::new(RwLock::new(None)),
         };
 
+        // Spawn idle watchdog
+        let idle_state = state.clone();
+        let idle_event_tx = event_tx.clone();
+        let idle_cmd_tx = session.cmd_tx.clone();
+        let idle_logger = session.logger.clone();
+        let idle_timer = idle.clone();
+        let mut idle_shutdown = session.shutdown.subscribe();
+
+        tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    () = tokio::time::sleep(IDLE_POLL_INTERVAL) => {}
+                    _ = idle_shutdown.wait_for(|stopping| *stopping) => break,
+                }
+                match *idle_state.read() {
+                    SessionState::Connected => {}
+                    SessionState::Connecting | SessionState::Reconnecting { .. } => continue,
+                    _ => break,
+                }
+
+                let Some(action) = idle_timer.lock().poll(Instant::now()) else {
+                    continue;
+                };
+
+                if action.flushes_log() {
+                    if let Some(logger) = idle_logger.read().as_ref() {
+                        logger.lock().flush();
+                    }
+                }
+                let _ = idle_event_tx.send(SessionEvent::IdleTimeout(action));
+                if action.disconnects() {
+                    let _ = idle_cmd_tx
+                        .send(SessionCommand::Disconnect(DisconnectReason::Timeout))
+                        .await;
+                    break;
+                }
+            }
+        });
+
         // Spawn receive loop
         let rx_state = state.clone();
         let rx_transport = transport.clone();
         let rx_event_tx = event_tx.clone();
         let rx_triggers = triggers;
         let rx_buffer = receive_buffer;
+        let rx_idle = idle;
+        let rx_capture = capture.clone();
+        let rx_reconnect = session.reconnect;
+        let mut rx_stop = session.stop_sending.subscribe();
+        let rx_notifier = notifier;
+        let rx_auto_zmodem = session.auto_receive_zmodem.clone();
+        let rx_filtered = filtered.clone();
+        let mut rx_shutdown = session.shutdown.subscribe();
+        let rx_ring = ring;
+        let rx_logger = logger;
+        let rx_chart = chart;
+        let rx_transforms = transforms;
+        let rx_history = history.clone();
+        let rx_level = session.rx_level.clone();
+        let rx_log_format = config.log_format;
+        let mut rx_binary = match session.codec {
+            CodecType::Text | CodecType::TextSafe => BinaryDetector::new(config.binary_threshold),
+            _ => BinaryDetector::new(0.0),
+        };
 
-        tokio::spawn(async move {
+        let rx_task = tokio::spawn(async move {
             loop {
                 if *rx_state.read() != SessionState::Connected {
                     break;
@@ -175,11 +1452,64 @@ impl Session {
 
                 let data = {
                     let mut transport = rx_transport.lock().await;
-                    transport.receive().await
+                    let data = transport.receive().await;
+                    *rx_level.write() = transport.receive_buffer();
+                    let dropped = transport.take_dropped();
+                    if dropped > 0 {
+                        tracing::warn!("Receive buffer overflowed, {} bytes lost", dropped);
+                        let _ = rx_event_tx.send(SessionEvent::ReceiveOverflow(dropped));
+                    }
+                    data
                 };
 
                 match data {
                     Ok(bytes) if !bytes.is_empty() => {
+                        rx_idle.lock().touch(Instant::now());
+                        capture_packet(&rx_capture, Packet::rx(bytes.to_vec()));
+                        record_history(&rx_history, Packet::rx(bytes.to_vec()));
+
+                        if let Some(ref dir) = rx_auto_zmodem {
+                            if ZmodemTransfer::is_auto_start(&bytes) {
+                                let _ = rx_event_tx.send(SessionEvent::TransferStarted(TransferProtocol::Zmodem));
+                                let result = {
+                                    let mut transport = rx_transport.lock().await;
+                                    let outcome = tokio::select! {
+                                        result = zmodem_auto_receive(&mut **transport, &bytes, dir, &rx_event_tx) => Some(result),
+                                        _ = rx_shutdown.wait_for(|stopping| *stopping) => None,
+                                    };
+                                    match outcome {
+                                        Some(result) => result,
+                                        None => {
+                                            let _ = transport.send(&ZMODEM_ABORT).await;
+                                            Err(TransferError::Cancelled("shutdown"))
+                                        }
+                                    }
+                                };
+                                if let Err(ref e) = result {
+                                    tracing::warn!("ZMODEM auto-receive failed: {}", e);
+                                }
+                                let _ = rx_event_tx.send(SessionEvent::TransferFinished(result.map_err(|e| e.to_string())));
+                                rx_idle.lock().touch(Instant::now());
+                                continue;
+                            }
+                        }
+
+                        rx_ring.lock().push(&bytes);
+                        let bytes = {
+                            let mut chain = rx_transforms.lock();
+                            if chain.is_empty() {
+                                bytes
+                            } else {
+                                Bytes::from(chain.apply(&bytes))
+                            }
+                        };
+                        if bytes.is_empty() {
+                            continue;
+                        }
+                        if let Some(logger) = rx_logger.read().as_ref() {
+                            logger.lock().log_rx(&bytes);
+                        }
+
                         // Add to receive buffer for trigger matching
                         {
                             let mut buffer = rx_buffer.write();
@@ -192,33 +1522,90 @@ impl Session {
                             }
                         }
 
-                        // Check triggers
-                        let triggers = rx_triggers.read().clone();
+                        // Check triggers; data up to the furthest match is consumed so
+                        // each occurrence fires once
                         let buffer = rx_buffer.read().clone();
-                        for trigger in &triggers {
-                            if let Some(matched) = trigger.check(&buffer) {
-                                let _ = rx_event_tx.send(SessionEvent::TriggerMatched {
-                                    trigger_id: trigger.id,
-                                    pattern: matched,
-                                });
+                        let now = Instant::now();
+                        let mut matches = Vec::new();
+                        let mut consumed = 0;
+                        for trigger in rx_triggers.write().iter_mut() {
+                            if let Some((matched, end)) = trigger.find(&buffer) {
+                                consumed = consumed.max(end);
+                                // An occurrence inside the cooldown is consumed without firing
+                                if !trigger.is_cooling_down(now) {
+                                    trigger.record_firing(now);
+                                    matches.push((trigger.clone(), matched));
+                                }
                             }
                         }
+                        if consumed > 0 {
+                            let mut buffer = rx_buffer.write();
+                            let consumed = consumed.min(buffer.len());
+                            buffer.drain(..consumed);
+                        }
+                        if !matches.is_empty() {
+                            let was_logging = is_logging(&rx_logger);
+                            // History snapshots and log files are disk work; keep it off the executor
+                            let fired: Vec<Trigger> = matches.iter().map(|(trigger, _)| trigger.clone()).collect();
+                            let (ring, logger, chart) = (rx_ring.clone(), rx_logger.clone(), rx_chart.clone());
+                            let log_format = rx_log_format;
+                            let _ = tokio::task::spawn_blocking(move || {
+                                for trigger in &fired {
+                                    save_trigger_history(&ring, trigger);
+                                    apply_recording_actions(&logger, &chart, trigger, log_format);
+                                }
+                            })
+                            .await;
+                            // A recording started by this chunk includes it
+                            if !was_logging && is_logging(&rx_logger) {
+                                if let Some(logger) = rx_logger.read().as_ref() {
+                                    logger.lock().log_rx(&bytes);
+                                }
+                            }
+                        }
+                        for (trigger, matched) in matches {
+                            if let Some(notifier) = rx_notifier.read().as_ref() {
+                                notify_trigger(notifier, &trigger);
+                            }
+                            let _ = rx_event_tx.send(SessionEvent::TriggerMatched {
+                                trigger_id: trigger.id,
+                                pattern: matched,
+                            });
+                        }
+                        if let Some(chart) = rx_chart.read().as_ref() {
+                            chart.lock().process_bytes(&bytes);
+                        }
 
+                        dispatch_filtered(&rx_filtered, PacketDirection::Rx, &bytes);
+                        if let Some(printable_ratio) = rx_binary.check(&bytes) {
+                            let _ = rx_event_tx.send(SessionEvent::BinaryDataDetected { printable_ratio });
+                        }
                         let _ = rx_event_tx.send(SessionEvent::DataReceived(bytes));
                     }
                     Ok(_) => {
                         // No data, continue
                         tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
                     }
-                    Err(TransportError::Disconnected) => {
-                        *rx_state.write() = SessionState::Disconnected;
-                        let _ = rx_event_tx.send(SessionEvent::StateChanged(SessionState::Disconnected));
-                        break;
-                    }
                     Err(e) => {
-                        *rx_state.write() = SessionState::Error;
-                        let _ = rx_event_tx.send(SessionEvent::Error(e.to_string()));
-                        break;
+                        if let Some(policy) = rx_reconnect {
+                            if let Err(e) = reconnect(&rx_transport, &rx_state, &rx_event_tx, policy, &mut rx_stop, e).await {
+                                // A user disconnect during the reconnect already reported itself
+                                if *rx_state.read() != SessionState::Disconnected {
+                                    set_state(&rx_state, &rx_event_tx, SessionState::Disconnected);
+                                    let _ = rx_event_tx.send(SessionEvent::Disconnected(DisconnectReason::from(&e)));
+                                }
+                                break;
+                            }
+                        } else if let TransportError::Disconnected = e {
+                            set_state(&rx_state, &rx_event_tx, SessionState::Disconnected);
+                            let _ = rx_event_tx.send(SessionEvent::Disconnected(DisconnectReason::RemoteClosed));
+                            break;
+                        } else {
+                            *rx_state.write() = SessionState::Error;
+                            let _ = rx_event_tx.send(SessionEvent::Error(e.to_string()));
+                            let _ = rx_event_tx.send(SessionEvent::Disconnected(DisconnectReason::from(&e)));
+                            break;
+                        }
                     }
                 }
             }
@@ -228,15 +1615,25 @@ impl Session {
         let cmd_state = state;
         let cmd_transport = transport;
         let cmd_event_tx = event_tx;
+        let cmd_capture = capture;
+        let cmd_filtered = filtered;
+        let cmd_pacing = session.pacing.clone();
+        let cmd_history = history;
+        let mut cmd_stop = session.stop_sending.subscribe();
 
-        tokio::spawn(async move {
+        let cmd_task = tokio::spawn(async move {
             let mut cmd_rx = cmd_rx;
             while let Some(cmd) = cmd_rx.recv().await {
                 match cmd {
-                    SessionCommand::Send(data) => {
-                        let mut transport = cmd_transport.lock().await;
-                        match transport.send(&data).await {
-                            Ok(_) => {
+                    SessionCommand::Send(data, pacing) => {
+                        let pacing = pacing.unwrap_or_else(|| *cmd_pacing.read());
+                        match send_paced(&cmd_transport, &data, pacing, &mut cmd_stop).await {
+                            Ok(0) if !data.is_empty() => {}
+                            Ok(sent) => {
+                                let data = data.slice(..sent);
+                                capture_packet(&cmd_capture, Packet::tx(data.to_vec()));
+                                record_history(&cmd_history, Packet::tx(data.to_vec()));
+                                dispatch_filtered(&cmd_filtered, PacketDirection::Tx, &data);
                                 let _ = cmd_event_tx.send(SessionEvent::DataSent(data));
                             }
                             Err(e) => {
@@ -244,11 +1641,12 @@ impl Session {
                             }
                         }
                     }
-                    SessionCommand::Disconnect => {
+                    SessionCommand::Disconnect(reason) => {
                         let mut transport = cmd_transport.lock().await;
                         let _ = transport.disconnect().await;
                         *cmd_state.write() = SessionState::Disconnected;
                         let _ = cmd_event_tx.send(SessionEvent::StateChanged(SessionState::Disconnected));
+                        let _ = cmd_event_tx.send(SessionEvent::Disconnected(reason));
                         break;
                     }
                     SessionCommand::SetDtr(state) => {
@@ -263,66 +1661,501 @@ impl Session {
                         let mut transport = cmd_transport.lock().await;
                         let _ = transport.send_break().await;
                     }
+                    SessionCommand::LineSequence(steps) => {
+                        // Hold the transport for the whole sequence so the timing is exact
+                        let mut transport = cmd_transport.lock().await;
+                        for step in steps {
+                            let result = match step {
+                                LineStep::Dtr(state) => transport.set_dtr(state).await,
+                                LineStep::Rts(state) => transport.set_rts(state).await,
+                                LineStep::Delay(delay) => {
+                                    tokio::time::sleep(delay).await;
+                                    Ok(())
+                                }
+                            };
+                            if let Err(e) = result {
+                                let _ = cmd_event_tx.send(SessionEvent::Error(e.to_string()));
+                                break;
+                            }
+                        }
+                    }
+                    SessionCommand::Reconfigure(params, reply) => {
+                        let result = cmd_transport.lock().await.reconfigure(params).await;
+                        if result.is_ok() {
+                            let _ = cmd_event_tx.send(SessionEvent::Reconfigured(params));
+                        }
+                        let _ = reply.send(result);
+                    }
                 }
             }
         });
+        *session.tasks.lock() = vec![rx_task, cmd_task];
+
+        session
+    }
+
+    /// Get session ID
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    /// Get session name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get current state
+    pub fn state(&self) -> SessionState {
+        *self.state.read()
+    }
+
+    /// Check if connected
+    pub fn is_connected(&self) -> bool {
+        *self.state.read() == SessionState::Connected
+    }
+
+    /// Post trigger `Notify` actions through `notifier` (None = off)
+    pub fn set_notifier(&self, notifier: Option<Arc<Notifier>>) {
+        *self.notifier.write() = notifier;
+    }
+
+    /// Attach a chart fed with received data (None = detach)
+    ///
+    /// Trigger `StartChartRecording`/`StopChartRecording` actions control it.
+    pub fn set_chart(&self, chart: Option<SharedChart>) {
+        *self.chart.write() = chart;
+    }
+
+    /// Replace the transforms applied to received data
+    ///
+    /// They run before triggers, logging and `DataReceived`; the history
+    /// ring and packet capture still see the raw bytes.
+    pub fn set_transforms(&self, chain: TransformChain) {
+        *self.transforms.lock() = chain;
+    }
+
+    /// The session logger, if logging was configured or started by a trigger
+    pub fn logger(&self) -> Option<Logger> {
+        self.logger.read().clone()
+    }
+
+    /// ZMODEM auto-receive download directory, or `None` if off
+    pub fn auto_receive_zmodem(&self) -> Option<&Path> {
+        self.auto_receive_zmodem.as_deref()
+    }
+
+    /// Maximum auto-reconnect attempts (0 = unlimited), or `None` if auto-reconnect is off
+    pub fn max_reconnect_attempts(&self) -> Option<u32> {
+        self.reconnect.map(|policy| policy.max_attempts)
+    }
+
+    /// Send data
+    ///
+    /// Fails with [`SessionError::Guarded`] if the data contains a guarded
+    /// byte; use [`Session::send_forced`] once the user has confirmed. Data
+    /// over the large-send threshold fails with [`SendWarning::LargePayload`];
+    /// resend it with [`Session::send_large`] or as a file transfer.
+    pub async fn send(&self, data: &[u8]) -> Result<(), SessionError> {
+        if let Some(byte) = find_guarded(&self.guard_bytes.read(), data) {
+            return Err(SessionError::Guarded(byte));
+        }
+        let threshold = *self.large_send_threshold.read();
+        if threshold > 0 && data.len() > threshold {
+            return Err(SendWarning::LargePayload(data.len()).into());
+        }
+        self.send_forced(data).await
+    }
+
+    /// Send data, bypassing the guard list and large-send check
+    pub async fn send_forced(&self, data: &[u8]) -> Result<(), SessionError> {
+        self.enqueue(data, None).await
+    }
+
+    /// Send a confirmed large payload with pacing
+    ///
+    /// Uses the session's pacing, or [`LARGE_SEND_PACING`] if none is set.
+    /// Bypasses the guard list like [`Session::send_forced`].
+    pub async fn send_large(&self, data: &[u8]) -> Result<(), SessionError> {
+        let pacing = self.send_pacing();
+        let pacing = if pacing.is_enabled() { pacing } else { LARGE_SEND_PACING };
+        self.enqueue(data, Some(pacing)).await
+    }
+
+    async fn enqueue(&self, data: &[u8], pacing: Option<SendPacing>) -> Result<(), SessionError> {
+        if !self.is_connected() {
+            return Err(TransportError::Disconnected.into());
+        }
+
+        self.cmd_tx
+            .send(SessionCommand::Send(Bytes::copy_from_slice(data), pacing))
+            .await
+            .map_err(|e| TransportError::SendError(e.to_string()))?;
+
+        // Log if enabled
+        if let Some(logger) = self.logger.read().as_ref() {
+            logger.lock().log_tx(data);
+        }
+
+        let echo = *self.echo.read();
+        if echo.enabled {
+            let _ = self.event_tx.send(SessionEvent::LocalEcho(Bytes::from(echo.render(data))));
+        }
+
+        Ok(())
+    }
+
+    /// Send a line terminated with the session's line ending
+    pub async fn send_line(&self, line: &str) -> Result<(), SessionError> {
+        let mut data = line.as_bytes().to_vec();
+        data.extend_from_slice(self.line_ending.bytes());
+        self.send(&data).await
+    }
+
+    /// Send an AT command and wait for its final result code
+    ///
+    /// The command is sent with CR LF; received lines are collected until
+    /// `OK`, `ERROR` or `+CME ERROR`. An echo of the command is skipped.
+    pub async fn at_command(&self, cmd: &str, timeout: Duration) -> Result<AtResponse, AtError> {
+        // Subscribe first so a fast reply is not missed
+        let mut lines = self.subscribe_filtered(DataFilter::new().direction(PacketDirection::Rx).lines());
+        let mut collector = AtCollector::new(cmd);
+        self.send(format!("{}\r\n", cmd).as_bytes()).await?;
+
+        let collect = async {
+            while let Some(packet) = lines.recv().await {
+                if let Some(response) = collector.feed(&String::from_utf8_lossy(&packet.data)) {
+                    return Ok(response);
+                }
+            }
+            Err(AtError::Closed)
+        };
+        let result = tokio::time::timeout(timeout, collect).await;
+        match result {
+            Ok(result) => result,
+            Err(_) => Err(AtError::Timeout {
+                timeout,
+                lines: collector.into_lines(),
+            }),
+        }
+    }
+
+    /// Wait until received data ends with a prompt
+    ///
+    /// Only data received after the call counts. Returns `None` on timeout
+    /// or if the session closes first.
+    pub async fn wait_for_prompt(&self, detector: &PromptDetector, timeout: Duration) -> Option<PromptKind> {
+        let mut received = self.subscribe_filtered(DataFilter::new().direction(PacketDirection::Rx));
+        let mut tail = Vec::new();
+        let wait = async {
+            while let Some(packet) = received.recv().await {
+                tail.extend_from_slice(&packet.data);
+                if tail.len() > PROMPT_TAIL_LEN {
+                    tail.drain(..tail.len() - PROMPT_TAIL_LEN);
+                }
+                if let Some(kind) = detector.saw_prompt(&tail) {
+                    return Some(kind);
+                }
+            }
+            None
+        };
+        tokio::time::timeout(timeout, wait).await.ok().flatten()
+    }
+
+    /// Get the bytes that need confirmation before sending
+    pub fn guard_bytes(&self) -> HashSet<u8> {
+        self.guard_bytes.read().clone()
+    }
+
+    /// Set the bytes that need confirmation before sending (empty disables the guard)
+    pub fn set_guard_bytes(&self, bytes: HashSet<u8>) {
+        *self.guard_bytes.write() = bytes;
+    }
+
+    /// Split sends into `bytes_per_chunk` pieces with `inter_chunk_delay` between them
+    ///
+    /// A zero chunk size or delay turns chunking off.
+    pub fn set_send_pacing(&self, bytes_per_chunk: usize, inter_chunk_delay: Duration) {
+        let mut pacing = self.pacing.write();
+        pacing.chunk_size = bytes_per_chunk;
+        pacing.chunk_delay = inter_chunk_delay;
+    }
+
+    /// Pause after every sent byte (zero turns it off); overrides chunking
+    pub fn set_char_delay(&self, delay: Duration) {
+        self.pacing.write().char_delay = delay;
+    }
+
+    /// Get the send pacing
+    pub fn send_pacing(&self) -> SendPacing {
+        *self.pacing.read()
+    }
+
+    /// Get the size above which sends need confirmation (0 = off)
+    pub fn large_send_threshold(&self) -> usize {
+        *self.large_send_threshold.read()
+    }
+
+    /// Set the size above which sends need confirmation (0 = off)
+    pub fn set_large_send_threshold(&self, bytes: usize) {
+        *self.large_send_threshold.write() = bytes;
+    }
+
+    /// The most recently received bytes, oldest first
+    ///
+    /// Holds up to `ring_capacity` bytes, including the data that made a
+    /// trigger fire, like a scope's pre-trigger buffer.
+    pub fn snapshot_ring(&self) -> Bytes {
+        self.ring.lock().snapshot()
+    }
+
+    /// Resize the pre-trigger history (0 = off), keeping the newest bytes
+    pub fn set_ring_capacity(&self, capacity: usize) {
+        self.ring.lock().set_capacity(capacity);
+    }
+
+    /// Readable transcript of the kept history, sent lines marked with `> `
+    ///
+    /// Covers the last `packet_history` chunks; empty if history is off.
+    /// Bookmarks are included as `-- label --` lines.
+    pub fn export_transcript(&self, format: TranscriptFormat) -> String {
+        let history = self.history.lock();
+        let bookmarks = self.bookmarks.read();
+        let bookmarks = if history.is_some() { &bookmarks[..] } else { &[] };
+        let packets = history.iter().flat_map(PacketBuffer::all);
+        transcript::render_annotated(&self.name, packets, bookmarks, format)
+    }
 
-        Ok(session)
+    /// Mark the current point of the history with `label`
+    ///
+    /// The marker goes before the next chunk sent or received and is kept
+    /// for the life of the session.
+    pub fn add_bookmark(&self, label: &str) -> Bookmark {
+        let history = self.history.lock();
+        let bookmark = Bookmark::new(label, history.as_ref().map_or(0, PacketBuffer::next_seq));
+        self.bookmarks.write().push(bookmark.clone());
+        bookmark
     }
 
-    /// Get session ID
-    pub fn id(&self) -> Uuid {
-        self.id
+    /// Bookmarks in the order they were added
+    pub fn bookmarks(&self) -> Vec<Bookmark> {
+        self.bookmarks.read().clone()
     }
 
-    /// Get session name
-    pub fn name(&self) -> &str {
-        &self.name
+    /// Transport receive buffer level, for transports that read ahead
+    ///
+    /// A level that stays high means the session is falling behind the
+    /// incoming data; once it is full, a serial port with flow control holds
+    /// off the sender, otherwise bytes are dropped and reported with
+    /// [`SessionEvent::ReceiveOverflow`].
+    pub fn receive_buffer_level(&self) -> Option<BufferLevel> {
+        *self.rx_level.read()
     }
 
-    /// Get current state
-    pub fn state(&self) -> SessionState {
-        *self.state.read()
+    /// Get the display codec
+    pub fn codec(&self) -> CodecType {
+        self.codec
     }
 
-    /// Check if connected
-    pub fn is_connected(&self) -> bool {
-        *self.state.read() == SessionState::Connected
+    /// Get the line ending appended by `send_line`
+    pub fn line_ending(&self) -> LineEnding {
+        self.line_ending
     }
 
-    /// Send data
-    pub async fn send(&self, data: &[u8]) -> Result<(), TransportError> {
-        if !self.is_connected() {
-            return Err(TransportError::Disconnected);
+    /// Get the local echo policy
+    pub fn echo_policy(&self) -> EchoPolicy {
+        *self.echo.read()
+    }
+
+    /// Set the local echo policy
+    pub fn set_echo_policy(&self, policy: EchoPolicy) {
+        *self.echo.write() = policy;
+    }
+
+    /// Flush the log and/or disconnect after `timeout` without received data
+    pub fn set_idle_timeout(&self, timeout: Duration, action: IdleAction) {
+        let mut idle = self.idle.lock();
+        idle.timeout = Some((timeout, action));
+        idle.touch(Instant::now());
+    }
+
+    /// Disable the idle timeout
+    pub fn clear_idle_timeout(&self) {
+        self.idle.lock().timeout = None;
+    }
+
+    /// Start writing RX/TX traffic to a PCAP file, replacing any running capture
+    pub fn start_capture(&self, path: &Path) -> Result<(), String> {
+        let writer = PcapWriter::create(path)?;
+        if let Some(mut old) = self.capture.lock().replace(writer) {
+            old.flush()?;
         }
+        Ok(())
+    }
 
-        self.cmd_tx
-            .send(SessionCommand::Send(Bytes::copy_from_slice(data)))
-            .await
-            .map_err(|e| TransportError::SendError(e.to_string()))?;
+    /// Stop the PCAP capture, flushing it to disk
+    pub fn stop_capture(&self) -> Result<(), String> {
+        match self.capture.lock().take() {
+            Some(mut writer) => writer.flush(),
+            None => Ok(()),
+        }
+    }
 
-        // Log if enabled
-        if let Some(ref logger) = self.logger {
-            logger.lock().log_tx(data);
+    /// Cancel `transfer` when the session shuts down
+    ///
+    /// For transfers run with a [`TransferManager`](crate::core::transfer::TransferManager)
+    /// over this session's connection; see its `cancel_handle`.
+    pub fn track_transfer(&self, transfer: TransferCancel) {
+        if *self.shutdown.borrow() {
+            transfer.cancel();
+        } else {
+            self.transfers.lock().push(transfer);
         }
+    }
 
-        Ok(())
+    /// Start shutting down; false if shutdown had already begun
+    fn begin_shutdown(&self) -> bool {
+        if self.shutdown.send_replace(true) {
+            return false;
+        }
+        self.stop_sending.send_replace(true);
+        for transfer in self.transfers.lock().drain(..) {
+            transfer.cancel();
+        }
+        true
+    }
+
+    /// Shut the session down cleanly
+    ///
+    /// Cancels auto-started and tracked transfers and paced sends, lets
+    /// other already queued sends go out, disconnects the transport (closing SSH channels and port
+    /// forwards) and waits for the session's tasks to end, then flushes and
+    /// closes the log and capture files. Tasks still running after `timeout`
+    /// are aborted, releasing the transport, and the session is marked
+    /// disconnected. Calling it again is a no-op.
+    pub async fn shutdown(&self, timeout: Duration) -> Result<(), TransportError> {
+        if !self.begin_shutdown() {
+            return Ok(());
+        }
+
+        let mut tasks = std::mem::take(&mut *self.tasks.lock());
+        let graceful = async {
+            // A closed channel means the command loop already ended
+            let _ = self
+                .cmd_tx
+                .send(SessionCommand::Disconnect(DisconnectReason::UserRequested))
+                .await;
+            for task in &mut tasks {
+                let _ = task.await;
+            }
+        };
+        let result = match tokio::time::timeout(timeout, graceful).await {
+            Ok(()) => Ok(()),
+            Err(_) => {
+                self.force_stop(&tasks, timeout);
+                Err(TransportError::Timeout(timeout.as_secs()))
+            }
+        };
+
+        self.close_files();
+        result
+    }
+
+    /// Shut down from synchronous code such as `Drop`
+    ///
+    /// Like [`Session::shutdown`], blocking the calling thread until the
+    /// session's tasks end or `timeout` passes. On a current-thread runtime
+    /// the tasks cannot run while this thread blocks, so it does not wait
+    /// there; the transport closes once the command task gets to run.
+    pub fn shutdown_blocking(&self, timeout: Duration) {
+        if !self.begin_shutdown() {
+            return;
+        }
+        let _ = self.cmd_tx.try_send(SessionCommand::Disconnect(DisconnectReason::UserRequested));
+
+        let tasks = std::mem::take(&mut *self.tasks.lock());
+        let wait = || {
+            let deadline = Instant::now() + timeout;
+            while !tasks.iter().all(JoinHandle::is_finished) {
+                if Instant::now() >= deadline {
+                    return false;
+                }
+                std::thread::sleep(SHUTDOWN_POLL_INTERVAL);
+            }
+            true
+        };
+        let finished = match tokio::runtime::Handle::try_current().map(|handle| handle.runtime_flavor()) {
+            Ok(tokio::runtime::RuntimeFlavor::CurrentThread) => true,
+            Ok(_) => tokio::task::block_in_place(wait),
+            Err(_) => wait(),
+        };
+        if !finished {
+            self.force_stop(&tasks, timeout);
+        }
+        self.close_files();
+    }
+
+    /// Abort tasks that outlived the shutdown timeout
+    fn force_stop(&self, tasks: &[JoinHandle<()>], timeout: Duration) {
+        tracing::warn!("Session '{}' did not close within {:?}; aborting its tasks", self.name, timeout);
+        for task in tasks {
+            task.abort();
+        }
+        set_state(&self.state, &self.event_tx, SessionState::Disconnected);
+    }
+
+    /// Flush and close the log and capture files
+    fn close_files(&self) {
+        if let Some(logger) = self.logger.read().as_ref() {
+            logger.lock().stop();
+        }
+        if let Err(e) = self.stop_capture() {
+            tracing::warn!("Session capture flush failed: {}", e);
+        }
     }
 
     /// Disconnect the session
+    ///
+    /// A paced send in progress is cut short rather than finished first.
     pub async fn disconnect(&self) -> Result<(), TransportError> {
+        self.stop_sending.send_replace(true);
         self.cmd_tx
-            .send(SessionCommand::Disconnect)
+            .send(SessionCommand::Disconnect(DisconnectReason::UserRequested))
             .await
             .map_err(|e| TransportError::SendError(e.to_string()))?;
         Ok(())
     }
 
     /// Subscribe to session events
+    ///
+    /// The raw receiver returns `RecvError::Lagged` when events were dropped;
+    /// see [`Session::subscribe_events`] for a receiver that reports it as an event.
     pub fn subscribe(&self) -> broadcast::Receiver<SessionEvent> {
         self.event_tx.subscribe()
     }
 
+    /// Subscribe to session events, with lag reported as [`SessionEvent::Lagged`]
+    pub fn subscribe_events(&self) -> EventReceiver {
+        EventReceiver { rx: self.event_tx.subscribe() }
+    }
+
+    /// Subscribe to received/sent data passing `filter`
+    ///
+    /// Filtering happens in the session's I/O tasks, so rejected data never
+    /// crosses to the consumer. Packets are dropped and counted if the
+    /// receiver lags.
+    pub fn subscribe_filtered(&self, filter: DataFilter) -> FilteredReceiver {
+        let (sender, rx) = mpsc::channel(FILTERED_CHANNEL_CAPACITY);
+        let dropped = Arc::new(AtomicU64::new(0));
+        self.filtered.lock().push(FilteredSubscriber {
+            filter,
+            rx_line: Vec::new(),
+            tx_line: Vec::new(),
+            sender,
+            dropped: dropped.clone(),
+        });
+        FilteredReceiver { rx, dropped }
+    }
+
     /// Set DTR line state
     pub async fn set_dtr(&self, state: bool) -> Result<(), TransportError> {
         self.cmd_tx
@@ -341,6 +2174,33 @@ impl Session {
         Ok(())
     }
 
+    /// Toggle DTR/RTS to reset the device, e.g. into its bootloader
+    pub async fn reset_sequence(&self, profile: ResetProfile) -> Result<(), TransportError> {
+        self.line_sequence(profile.steps()).await
+    }
+
+    /// Run a custom DTR/RTS sequence
+    pub async fn line_sequence(&self, steps: Vec<LineStep>) -> Result<(), TransportError> {
+        self.cmd_tx
+            .send(SessionCommand::LineSequence(steps))
+            .await
+            .map_err(|e| TransportError::SendError(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Change serial baud rate, framing or flow control, keeping the connection
+    ///
+    /// Data queued by earlier `send` calls goes out at the old settings first,
+    /// so a "switch to 115200" command can be followed directly by this call.
+    pub async fn reconfigure(&self, params: SerialParams) -> Result<(), TransportError> {
+        let (reply, result) = oneshot::channel();
+        self.cmd_tx
+            .send(SessionCommand::Reconfigure(params, reply))
+            .await
+            .map_err(|e| TransportError::SendError(e.to_string()))?;
+        result.await.map_err(|_| TransportError::Disconnected)?
+    }
+
     /// Send break signal
     pub async fn send_break(&self) -> Result<(), TransportError> {
         self.cmd_tx
@@ -368,6 +2228,12 @@ impl Session {
         transport.connection_info()
     }
 
+    /// Negotiated TLS protocol, cipher and server certificates, if the connection uses TLS
+    pub async fn tls_info(&self) -> Option<TlsInfo> {
+        let transport = self.transport.lock().await;
+        transport.tls_info()
+    }
+
     /// Add a trigger
     pub fn add_trigger(&self, trigger: Trigger) {
         self.triggers.write().push(trigger);
@@ -389,4 +2255,742 @@ impl Session {
     }
 }
 
+impl Drop for Session {
+    fn drop(&mut self) {
+        self.shutdown_blocking(DROP_SHUTDOWN_TIMEOUT);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_notify_trigger_posts_notify_actions() {
+        use crate::core::trigger::TriggerCondition;
+
+        let seen = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let sink = seen.clone();
+        let notifier = Notifier::with_callback(move |title, body| {
+            sink.lock().push((title.to_string(), body.to_string()));
+        });
+        let trigger = Trigger::new("Build", TriggerCondition::Text("BUILD OK".to_string()))
+            .with_action(TriggerAction::LogMessage("ignored".to_string()))
+            .with_action(TriggerAction::Notify("Build finished".to_string()));
+
+        notify_trigger(&notifier, &trigger);
+        assert_eq!(*seen.lock(), vec![("Build".to_string(), "Build finished".to_string())]);
+    }
+
+    #[test]
+    fn test_echo_caret_notation() {
+        let policy = EchoPolicy::new(ControlCharEcho::Caret);
+        assert_eq!(policy.render(b"ls\x03\x7f\r\n"), b"ls^C^?\r\n");
+    }
+
+    #[test]
+    fn test_echo_hex_notation() {
+        let policy = EchoPolicy::new(ControlCharEcho::Hex);
+        assert_eq!(policy.render(b"\x1b[A"), b"<1B>[A");
+
+        let raw = EchoPolicy::new(ControlCharEcho::Raw);
+        assert_eq!(raw.render(b"\x03"), b"\x03");
+    }
+
+    #[test]
+    fn test_guard_bytes() {
+        let guard: HashSet<u8> = [0x03, 0x1a].into_iter().collect();
+        assert_eq!(find_guarded(&guard, b"ls -la\r\n"), None);
+        assert_eq!(find_guarded(&guard, b"top\x03"), Some(0x03));
+        assert_eq!(find_guarded(&HashSet::new(), b"\x03"), None);
+    }
+
+    /// Fails the first `failures` connects with a retryable error
+    struct FlakyTransport {
+        failures: u32,
+        tx: broadcast::Sender<Bytes>,
+        sent: Arc<parking_lot::Mutex<Vec<Vec<u8>>>>,
+    }
+
+    #[async_trait::async_trait]
+    impl TransportTrait for FlakyTransport {
+        async fn connect(&mut self) -> Result<(), TransportError> {
+            if self.failures == 0 {
+                return Ok(());
+            }
+            self.failures -= 1;
+            Err(TransportError::ConnectionFailed("refused".into()))
+        }
+        async fn disconnect(&mut self) -> Result<(), TransportError> {
+            Ok(())
+        }
+        fn is_connected(&self) -> bool {
+            true
+        }
+        async fn send(&mut self, data: &[u8]) -> Result<usize, TransportError> {
+            self.sent.lock().push(data.to_vec());
+            Ok(data.len())
+        }
+        async fn receive(&mut self) -> Result<Bytes, TransportError> {
+            Ok(Bytes::new())
+        }
+        fn transport_type(&self) -> crate::core::transport::TransportType {
+            crate::core::transport::TransportType::Tcp
+        }
+        fn connection_info(&self) -> String {
+            "flaky".to_string()
+        }
+        fn stats(&self) -> TransportStats {
+            TransportStats::default()
+        }
+        fn subscribe(&self) -> broadcast::Receiver<Bytes> {
+            self.tx.subscribe()
+        }
+    }
+
+    fn flaky(failures: u32) -> tokio::sync::Mutex<Box<dyn TransportTrait>> {
+        tokio::sync::Mutex::new(Box::new(FlakyTransport {
+            failures,
+            tx: broadcast::channel(1).0,
+            sent: Arc::default(),
+        }))
+    }
+
+    #[tokio::test]
+    async fn test_send_pacing_chunks() {
+        let sent: Arc<parking_lot::Mutex<Vec<Vec<u8>>>> = Arc::default();
+        let transport: tokio::sync::Mutex<Box<dyn TransportTrait>> = tokio::sync::Mutex::new(Box::new(FlakyTransport {
+            failures: 0,
+            tx: broadcast::channel(1).0,
+            sent: sent.clone(),
+        }));
+        let sizes = |sent: &parking_lot::Mutex<Vec<Vec<u8>>>| sent.lock().drain(..).map(|c| c.len()).collect::<Vec<_>>();
+
+        let (stop, mut stop_rx) = watch::channel(false);
+
+        send_paced(&transport, b"AT+CGMI\r", SendPacing::default(), &mut stop_rx).await.unwrap();
+        assert_eq!(sizes(&sent), [8]);
+
+        let chunked = SendPacing::chunked(3, Duration::from_millis(1));
+        send_paced(&transport, b"AT+CGMI\r", chunked, &mut stop_rx).await.unwrap();
+        assert_eq!(sizes(&sent), [3, 3, 2]);
+
+        let slow = SendPacing { char_delay: Duration::from_millis(1), ..chunked };
+        send_paced(&transport, b"ATZ\r", slow, &mut stop_rx).await.unwrap();
+        assert_eq!(sizes(&sent), [1, 1, 1, 1]);
+
+        // Stopping cuts a long paced send short, mid-pause
+        let crawl = SendPacing::per_char(Duration::from_secs(60));
+        let stopper = async {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            stop.send_replace(true);
+        };
+        let (written, ()) = tokio::join!(send_paced(&transport, b"ATZ\r", crawl, &mut stop_rx), stopper);
+        assert_eq!(written.unwrap(), 1);
+        assert_eq!(sizes(&sent), [1]);
+
+        assert!(!SendPacing::chunked(0, Duration::from_millis(5)).is_enabled());
+    }
+
+    fn states(rx: &mut broadcast::Receiver<SessionEvent>) -> Vec<SessionState> {
+        std::iter::from_fn(|| rx.try_recv().ok())
+            .filter_map(|event| match event {
+                SessionEvent::StateChanged(state) => Some(state),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_connect_timeout_diagnoses_closed_port() {
+        // Grab a free port, then close it so the connect is refused
+        let port = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+        let transport = Transport::Tcp(crate::core::transport::TcpConfig::new("127.0.0.1", port));
+        let (progress_tx, mut progress_rx) = mpsc::unbounded_channel();
+
+        let config = SessionConfig::new("Probe", transport);
+        let Err(failure) = Session::connect_with_timeout_config(config, Duration::from_secs(5), Some(progress_tx)).await
+        else {
+            panic!("connected to a closed port");
+        };
+        let diagnostics = failure.diagnostics;
+        assert_eq!(diagnostics.dns_resolved, Some(true));
+        assert_eq!(diagnostics.tcp_open, Some(false));
+        assert_eq!(diagnostics.phase, ConnectPhase::Opening);
+        assert!(!diagnostics.timed_out);
+
+        let mut phases = Vec::new();
+        while let Ok(SessionEvent::ConnectProgress(phase)) = progress_rx.try_recv() {
+            phases.push(phase);
+        }
+        assert_eq!(phases, [ConnectPhase::Resolving, ConnectPhase::Opening]);
+    }
+
+    #[tokio::test]
+    async fn test_connect_timeout_preempts_blocking_ssh_handshake() {
+        // Accepts TCP but never sends an SSH banner, so ssh2 blocks in the handshake
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let (release_tx, release_rx) = std::sync::mpsc::channel::<()>();
+        let server = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let _ = release_rx.recv();
+            drop(stream);
+        });
+
+        let config = crate::core::transport::SshConfig::new("127.0.0.1", "user").port(port);
+        let started = Instant::now();
+        let Err(failure) = Session::connect_with_timeout(Transport::Ssh(config), Duration::from_millis(300)).await else {
+            panic!("connected without an SSH server");
+        };
+        assert!(started.elapsed() < Duration::from_secs(5));
+        let diagnostics = &failure.diagnostics;
+        assert!(diagnostics.timed_out);
+        assert_eq!(diagnostics.tcp_open, Some(true));
+        assert_eq!(diagnostics.phase, ConnectPhase::Handshaking);
+
+        let explained = crate::core::explain::ExplainEngine::new().explain_connect(diagnostics, &failure.error);
+        assert!(explained.root_causes[0].description.contains("key exchange"));
+
+        // Let the abandoned handshake fail so its thread exits
+        release_tx.send(()).unwrap();
+        server.join().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_reconnect_transitions() {
+        let state = RwLock::new(SessionState::Connected);
+        let (event_tx, mut rx) = broadcast::channel(16);
+        let policy = ReconnectPolicy {
+            delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(3),
+            max_attempts: 3,
+        };
+        let (stop, mut stop_rx) = watch::channel(false);
+
+        reconnect(&flaky(1), &state, &event_tx, policy, &mut stop_rx, TransportError::Disconnected).await.unwrap();
+        assert_eq!(
+            states(&mut rx),
+            [
+                SessionState::Reconnecting { attempt: 1, next_in: Duration::from_millis(1) },
+                SessionState::Connecting,
+                SessionState::Reconnecting { attempt: 2, next_in: Duration::from_millis(2) },
+                SessionState::Connecting,
+                SessionState::Connected,
+            ]
+        );
+        assert_eq!(policy.delay_for(3), Duration::from_millis(3));
+        assert_eq!(policy.delay_for(40), Duration::from_millis(3));
+
+        // Retries exhausted
+        let err = reconnect(&flaky(5), &state, &event_tx, policy, &mut stop_rx, TransportError::Disconnected).await;
+        assert!(matches!(err, Err(TransportError::ConnectionFailed(_))));
+        assert_eq!(states(&mut rx).len(), 6);
+
+        // Credentials errors are not retried
+        let auth = TransportError::AuthFailed("root".into());
+        let err = reconnect(&flaky(0), &state, &event_tx, policy, &mut stop_rx, auth).await;
+        assert!(matches!(err, Err(TransportError::AuthFailed(_))));
+        assert!(states(&mut rx).is_empty());
+
+        // A disconnect during the backoff stops before connecting
+        let slow = ReconnectPolicy { delay: Duration::from_secs(60), ..policy };
+        let transport = flaky(0);
+        let stopper = async {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+            stop.send_replace(true);
+        };
+        let (err, ()) = tokio::join!(
+            reconnect(&transport, &state, &event_tx, slow, &mut stop_rx, TransportError::Disconnected),
+            stopper
+        );
+        assert!(matches!(err, Err(TransportError::Disconnected)));
+        assert_eq!(states(&mut rx), [SessionState::Reconnecting { attempt: 1, next_in: Duration::from_secs(60) }]);
+    }
+
+    #[test]
+    fn test_disconnect_reason_from_transport_error() {
+        assert_eq!(DisconnectReason::from(&TransportError::Disconnected), DisconnectReason::RemoteClosed);
+        assert_eq!(DisconnectReason::from(&TransportError::Timeout(5)), DisconnectReason::Timeout);
+
+        let auth = DisconnectReason::from(&TransportError::AuthFailed("root".into()));
+        assert_eq!(auth, DisconnectReason::AuthFailed);
+        assert!(!auth.is_retryable());
+
+        let host_key = DisconnectReason::from(&TransportError::Ssh(crate::core::transport::SshError::HostKeyMismatch {
+            host: "example.com".into(),
+            fingerprint: "SHA256:abc".into(),
+        }));
+        assert_eq!(host_key, DisconnectReason::HostKeyRejected);
+        assert!(!host_key.is_retryable());
+
+        let io = DisconnectReason::from(&TransportError::ReceiveError("broken pipe".into()));
+        assert!(matches!(io, DisconnectReason::Io(_)));
+        assert!(io.is_retryable());
+        assert!(!DisconnectReason::UserRequested.is_retryable());
+    }
+
+    #[test]
+    fn test_idle_timer_fires_once_per_idle_period() {
+        let start = Instant::now();
+        let mut timer = IdleTimer::new();
+        timer.touch(start);
+        assert_eq!(timer.poll(start + Duration::from_secs(60)), None);
+
+        timer.timeout = Some((Duration::from_secs(30), IdleAction::Both));
+        assert_eq!(timer.poll(start + Duration::from_secs(10)), None);
+        assert_eq!(timer.poll(start + Duration::from_secs(30)), Some(IdleAction::Both));
+        assert_eq!(timer.poll(start + Duration::from_secs(40)), None);
+
+        // RX activity re-arms the timer
+        timer.touch(start + Duration::from_secs(45));
+        assert_eq!(timer.poll(start + Duration::from_secs(70)), None);
+        assert_eq!(timer.poll(start + Duration::from_secs(75)), Some(IdleAction::Both));
+    }
+
+    /// Replays RX chunks and records what is sent
+    struct ScriptedRx {
+        chunks: std::collections::VecDeque<Bytes>,
+        sent: Arc<parking_lot::Mutex<Vec<Vec<u8>>>>,
+        tx: broadcast::Sender<Bytes>,
+    }
+
+    #[async_trait::async_trait]
+    impl TransportTrait for ScriptedRx {
+        async fn connect(&mut self) -> Result<(), TransportError> {
+            Ok(())
+        }
+        async fn disconnect(&mut self) -> Result<(), TransportError> {
+            Ok(())
+        }
+        fn is_connected(&self) -> bool {
+            true
+        }
+        async fn send(&mut self, data: &[u8]) -> Result<usize, TransportError> {
+            self.sent.lock().push(data.to_vec());
+            Ok(data.len())
+        }
+        async fn receive(&mut self) -> Result<Bytes, TransportError> {
+            Ok(self.chunks.pop_front().unwrap_or_default())
+        }
+        fn transport_type(&self) -> crate::core::transport::TransportType {
+            crate::core::transport::TransportType::Tcp
+        }
+        fn connection_info(&self) -> String {
+            "scripted".to_string()
+        }
+        fn stats(&self) -> TransportStats {
+            TransportStats::default()
+        }
+        fn subscribe(&self) -> broadcast::Receiver<Bytes> {
+            self.tx.subscribe()
+        }
+    }
+
+    /// Start a session over a `ScriptedRx` replaying `chunks`
+    ///
+    /// `tweak` adjusts the config first. Also returns the sent-data log.
+    fn scripted_session(
+        name: &str,
+        chunks: &[&'static [u8]],
+        tweak: impl FnOnce(&mut SessionConfig),
+    ) -> (Session, Arc<parking_lot::Mutex<Vec<Vec<u8>>>>) {
+        let sent = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let transport = ScriptedRx {
+            chunks: chunks.iter().copied().map(Bytes::from_static).collect(),
+            sent: sent.clone(),
+            tx: broadcast::channel(1).0,
+        };
+        let mut config = SessionConfig::new(name, Transport::Tcp(crate::core::transport::TcpConfig::new("127.0.0.1", 1)));
+        tweak(&mut config);
+        (Session::start(config, Box::new(transport)), sent)
+    }
+
+    /// Wait up to two seconds for the first event `pick` accepts
+    async fn wait_for_event<T>(
+        events: &mut broadcast::Receiver<SessionEvent>,
+        mut pick: impl FnMut(SessionEvent) -> Option<T>,
+    ) -> T {
+        tokio::time::timeout(Duration::from_secs(2), async {
+            loop {
+                if let Some(found) = events.recv().await.ok().and_then(&mut pick) {
+                    break found;
+                }
+            }
+        })
+        .await
+        .expect("timed out waiting for session event")
+    }
+
+    /// Transport whose disconnect never completes
+    struct StuckOnDisconnect(broadcast::Sender<Bytes>);
+
+    #[async_trait::async_trait]
+    impl TransportTrait for StuckOnDisconnect {
+        async fn connect(&mut self) -> Result<(), TransportError> {
+            Ok(())
+        }
+        async fn disconnect(&mut self) -> Result<(), TransportError> {
+            std::future::pending().await
+        }
+        fn is_connected(&self) -> bool {
+            true
+        }
+        async fn send(&mut self, data: &[u8]) -> Result<usize, TransportError> {
+            Ok(data.len())
+        }
+        async fn receive(&mut self) -> Result<Bytes, TransportError> {
+            Ok(Bytes::new())
+        }
+        fn transport_type(&self) -> crate::core::transport::TransportType {
+            crate::core::transport::TransportType::Tcp
+        }
+        fn connection_info(&self) -> String {
+            "stuck".to_string()
+        }
+        fn stats(&self) -> TransportStats {
+            TransportStats::default()
+        }
+        fn subscribe(&self) -> broadcast::Receiver<Bytes> {
+            self.0.subscribe()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_aborts_tasks_after_timeout() {
+        let config = SessionConfig::new("Stuck", Transport::Tcp(crate::core::transport::TcpConfig::new("127.0.0.1", 1)));
+        let session = Session::start(config, Box::new(StuckOnDisconnect(broadcast::channel(1).0)));
+        let transfer = TransferCancel::default();
+        session.track_transfer(transfer.clone());
+
+        let err = session.shutdown(Duration::from_millis(200)).await.unwrap_err();
+        assert!(matches!(err, TransportError::Timeout(_)));
+        assert!(transfer.is_cancelled());
+        assert_eq!(session.state(), SessionState::Disconnected);
+        // The aborted command task no longer holds the transport
+        let _transport = tokio::time::timeout(Duration::from_secs(1), session.transport.lock()).await.unwrap();
+    }
 
+    #[tokio::test]
+    async fn test_zmodem_auto_receive_round_trips_payload() {
+        // Every byte value, so escaping of ZDLE, XON/XOFF and DEL is exercised
+        let payload: Vec<u8> = (0..3000u32).map(|i| (i * 7 % 256) as u8).collect();
+        let info = ZmodemFileInfo { name: "../fw.bin".into(), size: payload.len() as u64, mtime: None, mode: None };
+
+        let sender = ZmodemTransfer::new(TransferDirection::Send, ZmodemConfig::default());
+        let mut stream = b"rz\r".to_vec();
+        ZmodemTransfer::new(TransferDirection::Send, ZmodemConfig::default())
+            .send_zrqinit(&mut stream)
+            .unwrap();
+        let initial = stream.clone();
+        let mut rest = sender.build_zfile(&info);
+        rest.extend_from_slice(&sender.build_zdata(0, &payload));
+        rest.extend_from_slice(&sender.build_zeof(payload.len() as u64));
+        ZmodemTransfer::new(TransferDirection::Send, ZmodemConfig::default())
+            .send_zfin(&mut rest)
+            .unwrap();
+
+        // Odd chunk sizes split headers, escapes and CRCs across reads
+        let sent = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let mut transport = ScriptedRx {
+            chunks: rest.chunks(7).map(Bytes::copy_from_slice).collect(),
+            sent: sent.clone(),
+            tx: broadcast::channel(1).0,
+        };
+        let (event_tx, mut rx) = broadcast::channel(1024);
+        let dir = tempfile::tempdir().unwrap();
+
+        let path = zmodem_auto_receive(&mut transport, &initial, dir.path(), &event_tx).await.unwrap();
+        assert_eq!(path, dir.path().join("fw.bin"));
+        assert_eq!(std::fs::read(&path).unwrap(), payload);
+
+        // ZRINIT, ZRPOS(0) after ZFILE, ZRINIT after ZEOF, ZFIN
+        let sent = sent.lock();
+        let types: Vec<&[u8]> = sent.iter().map(|s| &s[4..6]).collect();
+        assert_eq!(types, [b"01", b"09", b"01", b"08"]);
+        assert!(sent[1].starts_with(b"**\x18B0900000000"));
+
+        let progress: Vec<u64> = std::iter::from_fn(|| rx.try_recv().ok())
+            .filter_map(|event| match event {
+                SessionEvent::TransferProgress(p) => Some(p.bytes_transferred),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(progress.first(), Some(&0));
+        assert_eq!(progress.last(), Some(&(payload.len() as u64)));
+        assert!(progress.windows(2).all(|w| w[0] <= w[1]));
+    }
+
+    #[test]
+    fn test_byte_ring_keeps_newest() {
+        let mut ring = ByteRing::new(8);
+        ring.push(b"abcd");
+        ring.push(b"efghij");
+        assert_eq!(ring.snapshot(), Bytes::from_static(b"cdefghij"));
+        ring.push(b"0123456789ABC");
+        assert_eq!(ring.snapshot(), Bytes::from_static(b"56789ABC"));
+        ring.set_capacity(3);
+        assert_eq!(ring.snapshot(), Bytes::from_static(b"ABC"));
+        ring.set_capacity(0);
+        ring.push(b"xyz");
+        assert!(ring.snapshot().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_trigger_saves_pre_trigger_history() {
+        let dir = tempfile::tempdir().unwrap();
+        let dump = dir.path().join("fault.bin");
+
+        let (session, _) = scripted_session("Ring", &[b"boot", b"tick", b"ERR!"], |config| config.ring_capacity = 8);
+        session.add_trigger(
+            Trigger::new("fault", crate::core::trigger::TriggerCondition::Text("ERR!".to_string()))
+                .with_action(TriggerAction::SaveHistory(dump.display().to_string())),
+        );
+
+        tokio::time::timeout(Duration::from_secs(2), async {
+            while std::fs::read(&dump).map_or(true, |data| data != b"tickERR!") {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(session.snapshot_ring(), Bytes::from_static(b"tickERR!"));
+    }
+
+    #[tokio::test]
+    async fn test_bookmarks_annotate_transcript() {
+        let (session, _) = scripted_session("Marks", &[], |_| {});
+
+        let start = session.add_bookmark("started test 3");
+        session.send(b"run 3\n").await.unwrap();
+        tokio::time::timeout(Duration::from_secs(2), async {
+            while !session.export_transcript(TranscriptFormat::text()).contains("> run 3") {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
+        let done = session.add_bookmark("done");
+
+        assert_eq!(session.bookmarks(), [start.clone(), done.clone()]);
+        assert_eq!(
+            session.export_transcript(TranscriptFormat::text()),
+            "-- started test 3 --\n> run 3\n-- done --"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_large_send_needs_confirmation() {
+        let (session, sent) = scripted_session("Paste", &[], |config| config.large_send_threshold = 600);
+
+        let blob = vec![b'x'; 700];
+        assert!(matches!(
+            session.send(&blob).await,
+            Err(SessionError::Warning(SendWarning::LargePayload(700)))
+        ));
+        session.send(&blob[..600]).await.unwrap();
+        session.send_large(&blob).await.unwrap();
+
+        tokio::time::timeout(Duration::from_secs(2), async {
+            while sent.lock().iter().map(Vec::len).sum::<usize>() < 1300 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
+        let sizes: Vec<usize> = sent.lock().iter().map(Vec::len).collect();
+        assert_eq!(sizes, [600, 256, 256, 188]);
+    }
+
+    #[tokio::test]
+    async fn test_transforms_apply_to_received_data() {
+        let (session, _) = scripted_session("Strip", &[b"\x1b[32mOK\x1b[0m\n"], |_| {});
+        session.set_transforms(TransformChain::new().with(crate::core::transform::StripAnsi::new()));
+        let mut events = session.subscribe();
+
+        let data = wait_for_event(&mut events, |event| match event {
+            SessionEvent::DataReceived(data) => Some(data),
+            _ => None,
+        })
+        .await;
+        assert_eq!(&data[..], b"OK\n");
+        assert_eq!(&session.snapshot_ring()[..], b"\x1b[32mOK\x1b[0m\n");
+    }
+
+    #[tokio::test]
+    async fn test_trigger_match_keeps_data_after_it() {
+        let (session, _) = scripted_session("Match", &[b"OK\nlogin: ad", b"min\n"], |_| {});
+        let ok = Trigger::new("ok", crate::core::trigger::TriggerCondition::Text("OK".to_string()));
+        let login = Trigger::new("login", crate::core::trigger::TriggerCondition::Text("login: admin".to_string()));
+        let ids = [ok.id, login.id];
+        session.add_trigger(ok);
+        session.add_trigger(login);
+        let mut events = session.subscribe();
+
+        let mut fired = Vec::new();
+        for _ in 0..2 {
+            let id = wait_for_event(&mut events, |event| match event {
+                SessionEvent::TriggerMatched { trigger_id, .. } => Some(trigger_id),
+                _ => None,
+            })
+            .await;
+            fired.push(id);
+        }
+        assert_eq!(fired, ids);
+    }
+
+    #[tokio::test]
+    async fn test_one_shot_and_cooldown_triggers_fire_once() {
+        let (session, _) = scripted_session("Once", &[b"READY\n", b"READY\n", b"READY\n", b"DONE\n"], |_| {});
+        let once = Trigger::new("once", crate::core::trigger::TriggerCondition::Text("READY".to_string())).one_shot(true);
+        let slow = Trigger::new("slow", crate::core::trigger::TriggerCondition::Text("READY".to_string()))
+            .cooldown(Duration::from_secs(60));
+        let done = Trigger::new("done", crate::core::trigger::TriggerCondition::Text("DONE".to_string()));
+        let ids = [once.id, slow.id];
+        let done_id = done.id;
+        session.add_trigger(once);
+        session.add_trigger(slow);
+        session.add_trigger(done);
+        let mut events = session.subscribe();
+
+        let mut fired = Vec::new();
+        wait_for_event(&mut events, |event| match event {
+            SessionEvent::TriggerMatched { trigger_id, .. } if trigger_id == done_id => Some(()),
+            SessionEvent::TriggerMatched { trigger_id, .. } => {
+                fired.push(trigger_id);
+                None
+            }
+            _ => None,
+        })
+        .await;
+        assert_eq!(fired, ids);
+
+        let triggers = session.triggers();
+        assert!(triggers[0].fired);
+        assert!(triggers[1].last_fired.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_triggers_start_and_stop_recording() {
+        let dir = tempfile::tempdir().unwrap();
+        let log_path = dir.path().join("boot.log");
+
+        let chunks: &[&[u8]] = &[b"noise\n", b"BOOT\n", b"data\n", b"SHUTDOWN\n", b"after\n"];
+        let (session, _) = scripted_session("Rec", chunks, |_| {});
+        let chart: SharedChart = Arc::new(parking_lot::Mutex::new(ChartManager::new()));
+        chart.lock().set_recording(false);
+        session.set_chart(Some(chart.clone()));
+        session.add_trigger(
+            Trigger::new("boot", crate::core::trigger::TriggerCondition::Text("BOOT".to_string()))
+                .with_action(TriggerAction::StartLogging(log_path.display().to_string()))
+                .with_action(TriggerAction::StartChartRecording),
+        );
+        session.add_trigger(
+            Trigger::new("shutdown", crate::core::trigger::TriggerCondition::Text("SHUTDOWN".to_string()))
+                .with_action(TriggerAction::StopLogging),
+        );
+
+        tokio::time::timeout(Duration::from_secs(2), async {
+            while session.logger().map_or(true, |logger| logger.lock().is_logging())
+                || !std::fs::read_to_string(&log_path).unwrap_or_default().contains("SHUTDOWN")
+            {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
+        // Let the last chunk through, then check it was not logged
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let log = std::fs::read_to_string(&log_path).unwrap();
+        assert!(log.contains("BOOT"));
+        assert!(log.contains("data"));
+        assert!(!log.contains("noise"));
+        assert!(!log.contains("after"));
+        assert!(chart.lock().is_recording());
+    }
+
+    #[tokio::test]
+    async fn test_reconfigure_reports_unsupported_transport() {
+        let (session, _) = scripted_session("Baud", &[], |_| {});
+
+        let params = crate::core::transport::SerialConfig::default().params().with_baud(921_600);
+        let result = session.reconfigure(params).await;
+        assert!(matches!(result, Err(TransportError::ConfigError(_))));
+        assert!(session.is_connected());
+    }
+
+    #[test]
+    fn test_filtered_subscriber_splits_and_matches_lines() {
+        let subscribers: FilteredSubscribers = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let (sender, mut receiver) = mpsc::channel(8);
+        subscribers.lock().push(FilteredSubscriber {
+            filter: DataFilter::new()
+                .direction(PacketDirection::Rx)
+                .pattern(Regex::new(r"^\$GP").unwrap())
+                .lines(),
+            rx_line: Vec::new(),
+            tx_line: Vec::new(),
+            sender,
+            dropped: Arc::new(AtomicU64::new(0)),
+        });
+
+        dispatch_filtered(&subscribers, PacketDirection::Rx, b"$GPGGA,1\r\nnoise\r\n$GPR");
+        dispatch_filtered(&subscribers, PacketDirection::Tx, b"$GPTX\n");
+        dispatch_filtered(&subscribers, PacketDirection::Rx, b"MC,2\r\n");
+
+        let lines: Vec<Vec<u8>> = std::iter::from_fn(|| receiver.try_recv().ok())
+            .map(|packet| packet.data)
+            .collect();
+        assert_eq!(lines, vec![b"$GPGGA,1".to_vec(), b"$GPRMC,2".to_vec()]);
+
+        // Dropped receivers are unsubscribed
+        drop(receiver);
+        dispatch_filtered(&subscribers, PacketDirection::Rx, b"x");
+        assert!(subscribers.lock().is_empty());
+    }
+
+    #[test]
+    fn test_filtered_lines_are_capped_and_drops_counted() {
+        let subscribers: FilteredSubscribers = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let (sender, rx) = mpsc::channel(2);
+        let dropped = Arc::new(AtomicU64::new(0));
+        subscribers.lock().push(FilteredSubscriber {
+            filter: DataFilter::new().lines(),
+            rx_line: Vec::new(),
+            tx_line: Vec::new(),
+            sender,
+            dropped: dropped.clone(),
+        });
+        let mut receiver = FilteredReceiver { rx, dropped };
+
+        // No LF: the line is split at the cap instead of growing
+        dispatch_filtered(&subscribers, PacketDirection::Rx, &vec![b'x'; MAX_FILTER_LINE + 10]);
+        assert_eq!(subscribers.lock()[0].rx_line.len(), 10);
+        assert_eq!(receiver.try_recv().unwrap().data.len(), MAX_FILTER_LINE);
+
+        dispatch_filtered(&subscribers, PacketDirection::Rx, b"\na\nb\nc\n");
+        assert_eq!(receiver.take_dropped(), 2);
+        assert_eq!(receiver.take_dropped(), 0);
+        assert_eq!(receiver.try_recv().unwrap().data, vec![b'x'; 10]);
+        assert_eq!(receiver.try_recv().unwrap().data, b"a");
+        assert!(receiver.try_recv().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_event_receiver_reports_lag() {
+        let (event_tx, rx) = broadcast::channel(2);
+        let mut events = EventReceiver { rx };
+        for _ in 0..5 {
+            event_tx.send(SessionEvent::DataReceived(Bytes::from_static(b"x"))).unwrap();
+        }
+
+        assert!(matches!(events.recv().await, Some(SessionEvent::Lagged(3))));
+        assert!(matches!(events.try_recv(), Some(SessionEvent::DataReceived(_))));
+        assert!(matches!(events.try_recv(), Some(SessionEvent::DataReceived(_))));
+        assert!(events.try_recv().is_none());
+
+        drop(event_tx);
+        assert!(events.recv().await.is_none());
+    }
+}
@@ -2,10 +2,14 @@
 //!
 //! Supports quick command execution, macros, and command sequences
 
+use crate::core::session::{Session, SessionError};
+use crate::core::storage::{self, Migration, StorageError};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
+use std::time::Duration;
 use uuid::Uuid;
 
 /// Snippet type
@@ -52,6 +56,55 @@ impl Default for LineEnding {
     }
 }
 
+/// Variables substituted into snippet content as `${name}`
+#[derive(Debug, Clone, Default)]
+pub struct VarContext {
+    vars: HashMap<String, String>,
+}
+
+impl VarContext {
+    /// Create an empty context
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set a variable
+    pub fn set(&mut self, name: &str, value: &str) {
+        self.vars.insert(name.to_string(), value.to_string());
+    }
+
+    /// Get a variable
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.vars.get(name).map(String::as_str)
+    }
+
+    /// Replace `${name}` references; unknown names are left as written
+    pub fn expand(&self, text: &str) -> String {
+        let mut result = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(start) = rest.find("${") {
+            result.push_str(&rest[..start]);
+            let after = &rest[start + 2..];
+            match after.find('}') {
+                Some(end) => {
+                    let name = &after[..end];
+                    match self.get(name) {
+                        Some(value) => result.push_str(value),
+                        None => result.push_str(&rest[start..start + end + 3]),
+                    }
+                    rest = &after[end + 1..];
+                }
+                None => {
+                    result.push_str(&rest[start..]);
+                    rest = "";
+                }
+            }
+        }
+        result.push_str(rest);
+        result
+    }
+}
+
 /// A snippet
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Snippet {
@@ -67,6 +120,12 @@ pub struct Snippet {
     pub delay_ms: u64, // Delay between lines for Script type
     #[serde(default)]
     pub color: Option<String>,
+    /// Times the snippet was sent
+    #[serde(default)]
+    pub usage_count: u32,
+    /// When the snippet was last sent
+    #[serde(default)]
+    pub last_used: Option<DateTime<Utc>>,
 }
 
 impl Snippet {
@@ -83,6 +142,8 @@ impl Snippet {
             line_ending: LineEnding::CrLf,
             delay_ms: 0,
             color: None,
+            usage_count: 0,
+            last_used: None,
         }
     }
 
@@ -99,6 +160,8 @@ impl Snippet {
             line_ending: LineEnding::CrLf,
             delay_ms: 100,
             color: None,
+            usage_count: 0,
+            last_used: None,
         }
     }
 
@@ -115,27 +178,46 @@ impl Snippet {
             line_ending: LineEnding::None,
             delay_ms: 0,
             color: None,
+            usage_count: 0,
+            last_used: None,
         }
     }
 
+    /// Count a use of the snippet
+    pub fn record_use(&mut self) {
+        self.usage_count += 1;
+        self.last_used = Some(Utc::now());
+    }
+
     /// Get content as bytes
     pub fn as_bytes(&self) -> Vec<u8> {
-        match self.snippet_type {
-            SnippetType::Binary => {
-                // Parse hex string
-                hex::decode(self.content.replace(" ", "").replace("\n", ""))
-                    .unwrap_or_default()
-            }
-            SnippetType::KeySequence => {
-                // Parse key sequences like {ENTER}, {TAB}, etc.
-                self.parse_key_sequence()
-            }
-            _ => {
-                let mut bytes = self.content.as_bytes().to_vec();
-                bytes.extend_from_slice(self.line_ending.as_bytes());
-                bytes
-            }
-        }
+        self.content_bytes(&self.content)
+    }
+
+    /// Bytes for `content` interpreted as this snippet's type
+    fn content_bytes(&self, content: &str) -> Vec<u8> {
+        encode_payload(self.snippet_type, content, self.line_ending)
+    }
+
+    /// Resolve variables and return the exact bytes that would be sent,
+    /// plus an annotated form with control bytes shown as `<CR>`, `<0x1B>`...
+    ///
+    /// Script lines are concatenated with their line endings.
+    pub fn preview(&self, ctx: &VarContext) -> (Vec<u8>, String) {
+        let content = ctx.expand(&self.content);
+        let bytes = match self.snippet_type {
+            SnippetType::Script => content
+                .lines()
+                .flat_map(|line| {
+                    let mut bytes = line.as_bytes().to_vec();
+                    bytes.extend_from_slice(self.line_ending.as_bytes());
+                    bytes
+                })
+                .collect(),
+            _ => self.content_bytes(&content),
+        };
+        let annotated = annotate_bytes(&bytes);
+        (bytes, annotated)
     }
 
     /// Get lines for script type
@@ -143,51 +225,129 @@ impl Snippet {
         self.content.lines().map(|s| s.to_string()).collect()
     }
 
+    /// Payloads to send, in order
+    ///
+    /// Scripts send one payload per line (each with the line ending); all
+    /// other types send a single payload.
+    pub fn payloads(&self) -> Vec<Vec<u8>> {
+        match self.snippet_type {
+            SnippetType::Script => self
+                .content
+                .lines()
+                .map(|line| {
+                    let mut bytes = line.as_bytes().to_vec();
+                    bytes.extend_from_slice(self.line_ending.as_bytes());
+                    bytes
+                })
+                .collect(),
+            _ => vec![self.as_bytes()],
+        }
+    }
+
+    /// Play the snippet on a session, waiting `delay_ms` between script lines
+    pub async fn play(&self, session: &Session) -> Result<(), SessionError> {
+        for (i, payload) in self.payloads().into_iter().enumerate() {
+            if i > 0 && self.delay_ms > 0 {
+                tokio::time::sleep(Duration::from_millis(self.delay_ms)).await;
+            }
+            session.send(&payload).await?;
+        }
+        Ok(())
+    }
+
     /// Parse key sequence
     fn parse_key_sequence(&self) -> Vec<u8> {
-        let mut result = Vec::new();
-        let mut chars = self.content.chars().peekable();
-
-        while let Some(c) = chars.next() {
-            if c == '{' {
-                // Parse key name
-                let mut key_name = String::new();
-                while let Some(&nc) = chars.peek() {
-                    if nc == '}' {
-                        chars.next();
-                        break;
-                    }
-                    key_name.push(chars.next().unwrap());
+        parse_key_sequence(&self.content)
+    }
+}
+
+/// Bytes for `content` interpreted as `snippet_type`
+///
+/// Binary and key-sequence content ignore `line_ending`. Shared with the
+/// quick macro slots.
+pub fn encode_payload(snippet_type: SnippetType, content: &str, line_ending: LineEnding) -> Vec<u8> {
+    match snippet_type {
+        SnippetType::Binary => {
+            // Parse hex string
+            hex::decode(content.replace(" ", "").replace("\n", ""))
+                .unwrap_or_default()
+        }
+        SnippetType::KeySequence => {
+            // Parse key sequences like {ENTER}, {TAB}, etc.
+            parse_key_sequence(content)
+        }
+        _ => {
+            let mut bytes = content.as_bytes().to_vec();
+            bytes.extend_from_slice(line_ending.as_bytes());
+            bytes
+        }
+    }
+}
+
+/// Parse `{KEY}` names and `{0xNN}` escapes into bytes
+pub fn parse_key_sequence(content: &str) -> Vec<u8> {
+    let mut result = Vec::new();
+    let mut chars = content.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c == '{' {
+            // Parse key name
+            let mut key_name = String::new();
+            while let Some(&nc) = chars.peek() {
+                if nc == '}' {
+                    chars.next();
+                    break;
                 }
+                key_name.push(chars.next().unwrap());
+            }
 
-                match key_name.to_uppercase().as_str() {
-                    "ENTER" | "CR" => result.push(b'\r'),
-                    "LF" => result.push(b'\n'),
-                    "TAB" => result.push(b'\t'),
-                    "ESC" | "ESCAPE" => result.push(0x1B),
-                    "BS" | "BACKSPACE" => result.push(0x08),
-                    "DEL" | "DELETE" => result.push(0x7F),
-                    "NULL" | "NUL" => result.push(0x00),
-                    "SPACE" => result.push(b' '),
-                    "CTRL-C" | "ETX" => result.push(0x03),
-                    "CTRL-D" | "EOT" => result.push(0x04),
-                    "CTRL-Z" | "SUB" => result.push(0x1A),
-                    _ => {
-                        // Try hex format: {0x1B}
-                        if key_name.starts_with("0x") || key_name.starts_with("0X") {
-                            if let Ok(b) = u8::from_str_radix(&key_name[2..], 16) {
-                                result.push(b);
-                            }
+            match key_name.to_uppercase().as_str() {
+                "ENTER" | "CR" => result.push(b'\r'),
+                "LF" => result.push(b'\n'),
+                "TAB" => result.push(b'\t'),
+                "ESC" | "ESCAPE" => result.push(0x1B),
+                "BS" | "BACKSPACE" => result.push(0x08),
+                "DEL" | "DELETE" => result.push(0x7F),
+                "NULL" | "NUL" => result.push(0x00),
+                "SPACE" => result.push(b' '),
+                "CTRL-C" | "ETX" => result.push(0x03),
+                "CTRL-D" | "EOT" => result.push(0x04),
+                "CTRL-Z" | "SUB" => result.push(0x1A),
+                _ => {
+                    // Try hex format: {0x1B}
+                    if key_name.starts_with("0x") || key_name.starts_with("0X") {
+                        if let Ok(b) = u8::from_str_radix(&key_name[2..], 16) {
+                            result.push(b);
                         }
                     }
                 }
-            } else {
-                result.push(c as u8);
             }
+        } else {
+            let mut buf = [0u8; 4];
+            result.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
         }
+    }
 
-        result
+    result
+}
+
+/// Printable form of a payload, with control and non-ASCII bytes in `<..>`
+fn annotate_bytes(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len());
+    for &b in bytes {
+        match b {
+            b'\r' => out.push_str("<CR>"),
+            b'\n' => out.push_str("<LF>"),
+            b'\t' => out.push_str("<TAB>"),
+            0x1B => out.push_str("<ESC>"),
+            0x00 => out.push_str("<NUL>"),
+            0x08 => out.push_str("<BS>"),
+            0x7F => out.push_str("<DEL>"),
+            0x20..=0x7E => out.push(b as char),
+            _ => out.push_str(&format!("<0x{:02X}>", b)),
+        }
     }
+    out
 }
 
 /// Snippet manager
@@ -248,16 +408,10 @@ impl SnippetManager {
     }
 
     /// Load snippets from disk
-    pub fn load(&mut self) -> Result<(), String> {
-        if !self.config_path.exists() {
+    pub fn load(&mut self) -> Result<(), StorageError> {
+        let Some(data) = storage::read_versioned::<SnippetData>(&self.config_path, SNIPPET_MIGRATIONS)? else {
             return Ok(());
-        }
-
-        let content = fs::read_to_string(&self.config_path)
-            .map_err(|e| format!("Failed to read snippets: {}", e))?;
-
-        let data: SnippetData = serde_json::from_str(&content)
-            .map_err(|e| format!("Failed to parse snippets: {}", e))?;
+        };
 
         self.snippets = data.snippets.into_iter()
             .map(|s| (s.id.clone(), s))
@@ -268,19 +422,14 @@ impl SnippetManager {
     }
 
     /// Save snippets to disk
-    pub fn save(&self) -> Result<(), String> {
+    pub fn save(&self) -> Result<(), StorageError> {
         let data = SnippetData {
+            version: SNIPPET_MIGRATIONS.len() as u32,
             snippets: self.snippets.values().cloned().collect(),
             folders: self.folders.clone(),
         };
 
-        let content = serde_json::to_string_pretty(&data)
-            .map_err(|e| format!("Failed to serialize snippets: {}", e))?;
-
-        fs::write(&self.config_path, content)
-            .map_err(|e| format!("Failed to write snippets: {}", e))?;
-
-        Ok(())
+        storage::write_json(&self.config_path, &data)
     }
 
     /// Add a snippet
@@ -350,6 +499,35 @@ impl SnippetManager {
         let _ = self.save();
     }
 
+    /// Count a use of snippet `id` and save; returns false if there is none
+    pub fn record_use(&mut self, id: &str) -> bool {
+        let Some(snippet) = self.snippets.get_mut(id) else {
+            return false;
+        };
+        snippet.record_use();
+        let _ = self.save();
+        true
+    }
+
+    /// All snippets, most used first; ties go to the most recently used
+    pub fn sorted_by_usage(&self) -> Vec<&Snippet> {
+        let mut snippets = self.all();
+        snippets.sort_by(|a, b| {
+            b.usage_count
+                .cmp(&a.usage_count)
+                .then(b.last_used.cmp(&a.last_used))
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        snippets
+    }
+
+    /// All snippets, most recently used first; unused ones last, by name
+    pub fn sorted_by_recent(&self) -> Vec<&Snippet> {
+        let mut snippets = self.all();
+        snippets.sort_by(|a, b| b.last_used.cmp(&a.last_used).then_with(|| a.name.cmp(&b.name)));
+        snippets
+    }
+
     /// Search snippets by name
     pub fn search(&self, query: &str) -> Vec<&Snippet> {
         let query_lower = query.to_lowercase();
@@ -366,9 +544,13 @@ impl Default for SnippetManager {
     }
 }
 
+/// Upgrades of `snippets.json`; v1 added the version field
+const SNIPPET_MIGRATIONS: &[Migration] = &[storage::add_version];
+
 /// Serialized snippet data
 #[derive(Debug, Serialize, Deserialize)]
 struct SnippetData {
+    version: u32,
     snippets: Vec<Snippet>,
     folders: Vec<String>,
 }
@@ -384,6 +566,19 @@ mod tests {
         assert_eq!(snippet.content, "echo hello");
     }
 
+    #[test]
+    fn test_script_payloads() {
+        let mut script = Snippet::new_script("Prep", "terminal length 0\nterminal width 200");
+        script.line_ending = LineEnding::Cr;
+        assert_eq!(
+            script.payloads(),
+            vec![b"terminal length 0\r".to_vec(), b"terminal width 200\r".to_vec()]
+        );
+
+        let command = Snippet::new_command("Show", "show version");
+        assert_eq!(command.payloads(), vec![b"show version\r\n".to_vec()]);
+    }
+
     #[test]
     fn test_snippet_as_bytes() {
         let snippet = Snippet::new_command("Test", "hello");
@@ -407,6 +602,57 @@ mod tests {
         let bytes = snippet.parse_key_sequence();
         assert_eq!(bytes, b"hello\r");
     }
+
+    #[test]
+    fn test_usage_tracking() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut manager = SnippetManager {
+            snippets: HashMap::new(),
+            folders: Vec::new(),
+            config_path: dir.path().join("snippets.json"),
+        };
+        let help = Snippet::new_command("Help", "help");
+        let reboot = Snippet::new_command("Reboot", "reboot");
+        let version = Snippet::new_command("Version", "ver");
+        let (help_id, reboot_id, version_id) = (help.id.clone(), reboot.id.clone(), version.id.clone());
+        manager.add(help);
+        manager.add(reboot);
+        manager.add(version);
+
+        assert!(manager.record_use(&version_id));
+        assert!(manager.record_use(&version_id));
+        assert!(manager.record_use(&reboot_id));
+        assert!(!manager.record_use("missing"));
+
+        let names = |snippets: Vec<&Snippet>| snippets.iter().map(|s| s.name.clone()).collect::<Vec<_>>();
+        assert_eq!(names(manager.sorted_by_usage()), ["Version", "Reboot", "Help"]);
+        assert_eq!(names(manager.sorted_by_recent()), ["Reboot", "Version", "Help"]);
+
+        manager.load().unwrap();
+        assert_eq!(manager.get(&version_id).unwrap().usage_count, 2);
+        assert!(manager.get(&help_id).unwrap().last_used.is_none());
+    }
+
+    #[test]
+    fn test_preview_resolves_variables() {
+        let mut ctx = VarContext::new();
+        ctx.set("name", "world");
+
+        let mut keys = Snippet::new_command("Greet", "hello ${name}{ESC}{0x03}");
+        keys.snippet_type = SnippetType::KeySequence;
+        let (bytes, annotated) = keys.preview(&ctx);
+        assert_eq!(bytes, b"hello world\x1b\x03");
+        assert_eq!(annotated, "hello world<ESC><0x03>");
+
+        let command = Snippet::new_command("Echo", "echo ${missing}");
+        assert_eq!(command.preview(&ctx).1, "echo ${missing}<CR><LF>");
+
+        let mut script = Snippet::new_script("Login", "user ${name}\nshow");
+        script.line_ending = LineEnding::Cr;
+        let (bytes, annotated) = script.preview(&ctx);
+        assert_eq!(bytes, b"user world\rshow\r");
+        assert_eq!(annotated, "user world<CR>show<CR>");
+    }
 }
 
 
@@ -0,0 +1,238 @@
+//! JSON stores in the config directory
+//!
+//! Shared load/save helpers and error type for profile, snippet and trigger
+//! storage. Versioned stores carry a `"version"` field and are upgraded on
+//! load by a list of migrations; files from before versioning are version 0.
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// Error loading or saving a store
+#[derive(Debug, Error)]
+pub enum StorageError {
+    /// Reading or writing the file failed
+    #[error("Failed to access {path}: {source}")]
+    Io {
+        /// Store file
+        path: PathBuf,
+        /// Underlying error
+        #[source]
+        source: std::io::Error,
+    },
+
+    /// The file is not valid for this store
+    #[error("Failed to parse {path}: {source}")]
+    Parse {
+        /// Store file
+        path: PathBuf,
+        /// Underlying error
+        #[source]
+        source: serde_json::Error,
+    },
+
+    /// The data could not be serialized
+    #[error("Failed to serialize: {0}")]
+    Serialize(#[source] serde_json::Error),
+
+    /// The file did not parse and was moved aside
+    #[error("{path} is corrupt and was kept as {backup}: {source}")]
+    Corrupt {
+        /// Store file
+        path: PathBuf,
+        /// Where the original content now lives
+        backup: PathBuf,
+        /// Underlying error
+        #[source]
+        source: serde_json::Error,
+    },
+
+    /// The file parsed but its content is not acceptable
+    #[error("Invalid {path}: {reason}")]
+    Invalid {
+        /// Store file
+        path: PathBuf,
+        /// What is wrong
+        reason: String,
+    },
+}
+
+/// Read a JSON store; a missing file is `Ok(None)`
+pub fn read_json<T: DeserializeOwned>(path: &Path) -> Result<Option<T>, StorageError> {
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let content = fs::read_to_string(path).map_err(|source| StorageError::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    serde_json::from_str(&content)
+        .map(Some)
+        .map_err(|source| StorageError::Parse {
+            path: path.to_path_buf(),
+            source,
+        })
+}
+
+/// Read a JSON store, moving a file that does not parse to `<name>.corrupt`
+///
+/// The caller can then start empty without the next save destroying the
+/// original content; the failure is returned as [`StorageError::Corrupt`].
+pub fn read_json_or_quarantine<T: DeserializeOwned>(path: &Path) -> Result<Option<T>, StorageError> {
+    match read_json(path) {
+        Err(StorageError::Parse { path, source }) => Err(quarantine(path, source)),
+        result => result,
+    }
+}
+
+/// `path` with `suffix` appended to the file name
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+/// Move an unreadable store aside and describe what happened
+fn quarantine(path: PathBuf, source: serde_json::Error) -> StorageError {
+    let backup = with_suffix(&path, ".corrupt");
+    match fs::rename(&path, &backup) {
+        Ok(()) => StorageError::Corrupt { path, backup, source },
+        Err(source) => StorageError::Io { path, source },
+    }
+}
+
+/// Upgrade of a store's raw JSON by one version
+pub type Migration = fn(serde_json::Value) -> Result<serde_json::Value, String>;
+
+/// Migration for stores whose first versioned format only added the `"version"` field
+pub fn add_version(value: serde_json::Value) -> Result<serde_json::Value, String> {
+    Ok(value)
+}
+
+/// Read a versioned JSON store, upgrading older files
+///
+/// `migrations[n]` upgrades version `n` to `n + 1`, so the current version
+/// is `migrations.len()`. Before upgrading, the original file is copied to
+/// `<name>.v<N>.bak`. Files from a newer version are rejected untouched;
+/// files that do not parse are quarantined as by [`read_json_or_quarantine`].
+pub fn read_versioned<T: DeserializeOwned>(path: &Path, migrations: &[Migration]) -> Result<Option<T>, StorageError> {
+    let invalid = |reason: String| StorageError::Invalid {
+        path: path.to_path_buf(),
+        reason,
+    };
+    let Some(mut value) = read_json_or_quarantine::<serde_json::Value>(path)? else {
+        return Ok(None);
+    };
+
+    let version = format_version(&value, migrations).map_err(invalid)?;
+    if version < migrations.len() {
+        let backup = with_suffix(path, &format!(".v{}.bak", version));
+        fs::copy(path, &backup).map_err(|source| StorageError::Io { path: backup, source })?;
+        value = upgrade(value, migrations).map_err(invalid)?;
+    }
+
+    serde_json::from_value(value)
+        .map(Some)
+        .map_err(|source| quarantine(path.to_path_buf(), source))
+}
+
+/// Format version of a store's raw JSON; no `"version"` field is version 0
+fn format_version(value: &serde_json::Value, migrations: &[Migration]) -> Result<usize, String> {
+    let version = match value.get("version") {
+        Some(version) => version
+            .as_u64()
+            .and_then(|v| usize::try_from(v).ok())
+            .ok_or_else(|| format!("bad version {}", version))?,
+        None => 0,
+    };
+    if version > migrations.len() {
+        return Err(format!(
+            "format version {} is newer than supported version {}",
+            version,
+            migrations.len()
+        ));
+    }
+    Ok(version)
+}
+
+/// Upgrade a store's raw JSON to the current version
+///
+/// For content that does not live in the store's own file, such as an
+/// imported copy; [`read_versioned`] does this for the store itself.
+pub fn upgrade(mut value: serde_json::Value, migrations: &[Migration]) -> Result<serde_json::Value, String> {
+    let version = format_version(&value, migrations)?;
+    for migration in &migrations[version..] {
+        value = migration(value)?;
+    }
+    if let Some(object) = value.as_object_mut() {
+        object.insert("version".to_string(), migrations.len().into());
+    }
+    Ok(value)
+}
+
+/// Write a JSON store, pretty-printed
+pub fn write_json<T: Serialize>(path: &Path, data: &T) -> Result<(), StorageError> {
+    let content = serde_json::to_string_pretty(data).map_err(StorageError::Serialize)?;
+    fs::write(path, content).map_err(|source| StorageError::Io {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_and_corrupt_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("store.json");
+        assert!(read_json::<Vec<String>>(&path).unwrap().is_none());
+
+        write_json(&path, &vec!["a".to_string()]).unwrap();
+        assert_eq!(read_json::<Vec<String>>(&path).unwrap(), Some(vec!["a".to_string()]));
+
+        fs::write(&path, "{not json").unwrap();
+        assert!(matches!(read_json::<Vec<String>>(&path), Err(StorageError::Parse { .. })));
+
+        match read_json_or_quarantine::<Vec<String>>(&path) {
+            Err(StorageError::Corrupt { backup, .. }) => {
+                assert_eq!(backup, dir.path().join("store.json.corrupt"));
+                assert_eq!(fs::read_to_string(backup).unwrap(), "{not json");
+            }
+            other => panic!("expected a corrupt store, got {:?}", other.map(|_| ())),
+        }
+        assert!(read_json::<Vec<String>>(&path).unwrap().is_none());
+    }
+
+    #[derive(Debug, serde::Deserialize)]
+    struct Names {
+        version: u32,
+        names: Vec<String>,
+    }
+
+    fn wrap_names(value: serde_json::Value) -> Result<serde_json::Value, String> {
+        Ok(serde_json::json!({ "names": value }))
+    }
+
+    #[test]
+    fn test_versioned_store_migrates_and_backs_up() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("names.json");
+        let migrations: &[Migration] = &[wrap_names];
+
+        // Pre-versioning files were a bare list
+        fs::write(&path, r#"["a", "b"]"#).unwrap();
+        let names: Names = read_versioned(&path, migrations).unwrap().unwrap();
+        assert_eq!(names.version, 1);
+        assert_eq!(names.names, ["a", "b"]);
+        assert_eq!(fs::read_to_string(dir.path().join("names.json.v0.bak")).unwrap(), r#"["a", "b"]"#);
+
+        fs::write(&path, r#"{"version": 2, "names": []}"#).unwrap();
+        assert!(matches!(read_versioned::<Names>(&path, migrations), Err(StorageError::Invalid { .. })));
+        assert!(path.exists());
+    }
+}
@@ -0,0 +1,237 @@
+//! Keyboard input encoding
+//!
+//! Translates key presses into the bytes an xterm sends, honouring
+//! DECCKM (application cursor keys) and DECKPAM (application keypad).
+
+/// A key press to send to the remote
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    Up,
+    Down,
+    Right,
+    Left,
+    Home,
+    End,
+    Insert,
+    Delete,
+    PageUp,
+    PageDown,
+    /// Function key F1-F12
+    F(u8),
+    Enter,
+    Tab,
+    Backspace,
+    Escape,
+    /// Printable character
+    Char(char),
+    /// Numeric keypad key
+    Keypad(KeypadKey),
+}
+
+/// Numeric keypad keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeypadKey {
+    /// Digit 0-9
+    Digit(u8),
+    Decimal,
+    Plus,
+    Minus,
+    Multiply,
+    Divide,
+    Enter,
+}
+
+/// Modifier keys held during a key press
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct KeyModifiers {
+    pub shift: bool,
+    pub alt: bool,
+    pub ctrl: bool,
+}
+
+impl KeyModifiers {
+    /// xterm modifier parameter (1 = none)
+    fn xterm_param(&self) -> u8 {
+        1 + self.shift as u8 + 2 * self.alt as u8 + 4 * self.ctrl as u8
+    }
+
+    fn any(&self) -> bool {
+        self.shift || self.alt || self.ctrl
+    }
+}
+
+impl From<egui::Modifiers> for KeyModifiers {
+    fn from(modifiers: egui::Modifiers) -> Self {
+        Self {
+            shift: modifiers.shift,
+            alt: modifiers.alt,
+            ctrl: modifiers.ctrl,
+        }
+    }
+}
+
+impl Key {
+    /// Map an egui key; egui does not distinguish keypad keys
+    pub fn from_egui(key: egui::Key) -> Option<Self> {
+        use egui::Key as K;
+
+        let key = match key {
+            K::ArrowUp => Key::Up,
+            K::ArrowDown => Key::Down,
+            K::ArrowRight => Key::Right,
+            K::ArrowLeft => Key::Left,
+            K::Home => Key::Home,
+            K::End => Key::End,
+            K::Insert => Key::Insert,
+            K::Delete => Key::Delete,
+            K::PageUp => Key::PageUp,
+            K::PageDown => Key::PageDown,
+            K::Enter => Key::Enter,
+            K::Tab => Key::Tab,
+            K::Backspace => Key::Backspace,
+            K::Escape => Key::Escape,
+            K::Space => Key::Char(' '),
+            K::F1 => Key::F(1),
+            K::F2 => Key::F(2),
+            K::F3 => Key::F(3),
+            K::F4 => Key::F(4),
+            K::F5 => Key::F(5),
+            K::F6 => Key::F(6),
+            K::F7 => Key::F(7),
+            K::F8 => Key::F(8),
+            K::F9 => Key::F(9),
+            K::F10 => Key::F(10),
+            K::F11 => Key::F(11),
+            K::F12 => Key::F(12),
+            K::OpenBracket => Key::Char('['),
+            K::CloseBracket => Key::Char(']'),
+            K::Backslash => Key::Char('\\'),
+            K::Slash => Key::Char('/'),
+            K::Minus => Key::Char('-'),
+            K::Questionmark => Key::Char('?'),
+            other => {
+                // Letters and digits: the name is the character itself
+                let mut chars = other.name().chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) if c.is_ascii_alphanumeric() => Key::Char(c.to_ascii_lowercase()),
+                    _ => return None,
+                }
+            }
+        };
+        Some(key)
+    }
+}
+
+/// Encode a key press as xterm would; `None` if the key has no encoding
+pub fn encode_key(
+    key: Key,
+    modifiers: KeyModifiers,
+    app_cursor_keys: bool,
+    app_keypad: bool,
+) -> Option<Vec<u8>> {
+    let bytes = match key {
+        Key::Up => cursor_key(b'A', modifiers, app_cursor_keys),
+        Key::Down => cursor_key(b'B', modifiers, app_cursor_keys),
+        Key::Right => cursor_key(b'C', modifiers, app_cursor_keys),
+        Key::Left => cursor_key(b'D', modifiers, app_cursor_keys),
+        Key::Home => cursor_key(b'H', modifiers, app_cursor_keys),
+        Key::End => cursor_key(b'F', modifiers, app_cursor_keys),
+        Key::Insert => tilde_key(2, modifiers),
+        Key::Delete => tilde_key(3, modifiers),
+        Key::PageUp => tilde_key(5, modifiers),
+        Key::PageDown => tilde_key(6, modifiers),
+        Key::F(n @ 1..=4) => {
+            let final_byte = b'P' + (n - 1);
+            if modifiers.any() {
+                format!("\x1b[1;{}{}", modifiers.xterm_param(), final_byte as char).into_bytes()
+            } else {
+                vec![0x1B, b'O', final_byte]
+            }
+        }
+        Key::F(n @ 5..=12) => {
+            // F5-F12 skip 16 and 22
+            const CODES: [u8; 8] = [15, 17, 18, 19, 20, 21, 23, 24];
+            tilde_key(CODES[(n - 5) as usize], modifiers)
+        }
+        Key::F(_) => return None,
+        Key::Enter => alt_prefixed(b"\r", modifiers),
+        Key::Tab if modifiers.shift => b"\x1b[Z".to_vec(),
+        Key::Tab => alt_prefixed(b"\t", modifiers),
+        Key::Backspace if modifiers.ctrl => alt_prefixed(&[0x08], modifiers),
+        Key::Backspace => alt_prefixed(&[0x7F], modifiers),
+        Key::Escape => vec![0x1B],
+        Key::Char(c) => {
+            if modifiers.ctrl {
+                alt_prefixed(&[control_byte(c)?], modifiers)
+            } else {
+                let mut buf = [0u8; 4];
+                alt_prefixed(c.encode_utf8(&mut buf).as_bytes(), modifiers)
+            }
+        }
+        Key::Keypad(key) => keypad_key(key, app_keypad),
+    };
+    Some(bytes)
+}
+
+/// Arrows, Home and End: SS3 in application mode, CSI otherwise
+fn cursor_key(final_byte: u8, modifiers: KeyModifiers, app_cursor_keys: bool) -> Vec<u8> {
+    if modifiers.any() {
+        format!("\x1b[1;{}{}", modifiers.xterm_param(), final_byte as char).into_bytes()
+    } else if app_cursor_keys {
+        vec![0x1B, b'O', final_byte]
+    } else {
+        vec![0x1B, b'[', final_byte]
+    }
+}
+
+/// `CSI n ~` keys, with an optional modifier parameter
+fn tilde_key(code: u8, modifiers: KeyModifiers) -> Vec<u8> {
+    if modifiers.any() {
+        format!("\x1b[{};{}~", code, modifiers.xterm_param()).into_bytes()
+    } else {
+        format!("\x1b[{}~", code).into_bytes()
+    }
+}
+
+/// Alt sends ESC before the key
+fn alt_prefixed(bytes: &[u8], modifiers: KeyModifiers) -> Vec<u8> {
+    let mut out = Vec::with_capacity(bytes.len() + 1);
+    if modifiers.alt {
+        out.push(0x1B);
+    }
+    out.extend_from_slice(bytes);
+    out
+}
+
+/// Ctrl+key control code
+fn control_byte(c: char) -> Option<u8> {
+    match c {
+        'a'..='z' | 'A'..='Z' => Some(c.to_ascii_lowercase() as u8 - b'a' + 1),
+        ' ' | '@' | '2' => Some(0x00),
+        '[' | '3' => Some(0x1B),
+        '\\' | '4' => Some(0x1C),
+        ']' | '5' => Some(0x1D),
+        '^' | '6' => Some(0x1E),
+        '_' | '-' | '7' => Some(0x1F),
+        '?' | '8' => Some(0x7F),
+        _ => None,
+    }
+}
+
+/// Keypad keys: SS3 sequences in application mode, plain characters otherwise
+fn keypad_key(key: KeypadKey, app_keypad: bool) -> Vec<u8> {
+    let (app, normal) = match key {
+        KeypadKey::Digit(d) => (b'p' + d.min(9), b'0' + d.min(9)),
+        KeypadKey::Decimal => (b'n', b'.'),
+        KeypadKey::Plus => (b'k', b'+'),
+        KeypadKey::Minus => (b'm', b'-'),
+        KeypadKey::Multiply => (b'j', b'*'),
+        KeypadKey::Divide => (b'o', b'/'),
+        KeypadKey::Enter => (b'M', b'\r'),
+    };
+    if app_keypad {
+        vec![0x1B, b'O', app]
+    } else {
+        vec![normal]
+    }
+}
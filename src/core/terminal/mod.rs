@@ -13,13 +13,21 @@ mod parser;
 mod screen;
 mod cell;
 mod color;
+mod mouse;
+mod keyboard;
 pub mod sixel;
+mod view;
+mod selection;
 
 pub use parser::{AnsiParser, AnsiEvent};
 pub use screen::{Screen, ScreenMode};
 pub use cell::{Cell, CellStyle};
 pub use color::{Color, NamedColor};
+pub use mouse::{MouseButton, MouseEncoding, MouseEvent, MouseEventKind};
+pub use keyboard::{Key, KeyModifiers, KeypadKey};
 pub use sixel::{SixelEncoder, SixelImage, SixelParser, SixelColor};
+pub use view::{LineStyler, StyledLine, StyledSpan, TerminalView};
+pub use selection::{Selection, SelectionMode, SelectionPoint};
 
 /// Terminal size
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -43,6 +51,32 @@ impl TerminalSize {
     }
 }
 
+/// Display-side newline normalization for devices with non-standard line endings
+///
+/// Applied to what is drawn only; received bytes are not changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub enum NewlineTranslation {
+    /// Use CR and LF as received
+    #[default]
+    None,
+    /// Bare CR starts a new line (a following LF is absorbed)
+    CrToCrLf,
+    /// LF also returns the cursor to the first column
+    LfToCrLf,
+}
+
+/// Screen cell an image is anchored to
+///
+/// `row` goes negative as the image scrolls off the top of the screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CellPos {
+    pub row: i32,
+    pub col: u16,
+}
+
+/// Default cell size in pixels used to lay out Sixel images
+pub const DEFAULT_CELL_PIXELS: (u16, u16) = (10, 20);
+
 /// Terminal emulator state
 pub struct Terminal {
     /// Screen buffer
@@ -63,10 +97,38 @@ pub struct Terminal {
     bracketed_paste: bool,
     /// Mouse reporting mode
     mouse_mode: MouseMode,
+    /// Mouse report encoding
+    mouse_encoding: MouseEncoding,
     /// Title
     title: String,
+    /// BEL received since last `take_bell`
+    bell: bool,
+    /// Replies to host queries, drained by `take_responses`
+    responses: Vec<u8>,
+    /// Decoder for Sixel DCS payloads
+    sixel_parser: SixelParser,
+    /// Sixel images on screen, by the cell of their top-left corner
+    images: Vec<(CellPos, SixelImage)>,
+    /// Sixel scrolling (DECSDM reset): images are drawn at the cursor and
+    /// scroll the screen; otherwise at the top-left corner, clipped
+    sixel_scrolling: bool,
+    /// Cell width and height in pixels
+    cell_pixels: (u16, u16),
+    /// Newline normalization for display
+    newline_translation: NewlineTranslation,
+    /// Previous event was a CR translated to CR LF
+    after_cr: bool,
+    /// Printer controller mode (MC): all data goes to `printer_buffer`
+    printer_controller: bool,
+    /// Data printed in printer controller mode, drained by `take_printer_output`
+    printer_buffer: Vec<u8>,
+    /// End of the last chunk that may be the start of `CSI 4 i`
+    printer_pending: Vec<u8>,
 }
 
+/// Ends printer controller mode
+const PRINTER_OFF: &[u8] = b"\x1b[4i";
+
 /// Mouse reporting mode
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum MouseMode {
@@ -101,19 +163,78 @@ impl Terminal {
             app_keypad: false,
             bracketed_paste: false,
             mouse_mode: MouseMode::None,
+            mouse_encoding: MouseEncoding::X10,
             title: String::new(),
+            bell: false,
+            responses: Vec::new(),
+            sixel_parser: SixelParser::new(),
+            images: Vec::new(),
+            sixel_scrolling: true,
+            cell_pixels: DEFAULT_CELL_PIXELS,
+            newline_translation: NewlineTranslation::None,
+            after_cr: false,
+            printer_controller: false,
+            printer_buffer: Vec::new(),
+            printer_pending: Vec::new(),
         }
     }
 
     /// Process input bytes
     pub fn process(&mut self, data: &[u8]) {
-        for event in self.parser.parse(data) {
-            self.handle_event(event);
+        let mut rest = data;
+        while !rest.is_empty() {
+            if self.printer_controller {
+                rest = self.print_raw(rest);
+                continue;
+            }
+            // `CSI 5 i` ends with `i`: parse up to each one so the bytes after
+            // it reach the printer unparsed
+            let end = memchr::memchr(b'i', rest).map_or(rest.len(), |i| i + 1);
+            for event in self.parser.parse(&rest[..end]) {
+                let scrolled_before = self.current_screen().lines_scrolled_off();
+                self.handle_event(event);
+                let scrolled = self.current_screen().lines_scrolled_off().saturating_sub(scrolled_before);
+                if scrolled > 0 && !self.images.is_empty() {
+                    self.scroll_images(scrolled);
+                }
+            }
+            rest = &rest[end..];
+        }
+    }
+
+    /// Divert data to the printer up to `CSI 4 i`; returns what follows it
+    ///
+    /// Nothing is interpreted in printer controller mode, escape sequences
+    /// included. A partial `CSI 4 i` at the end is held back for the next chunk.
+    fn print_raw<'a>(&mut self, data: &'a [u8]) -> &'a [u8] {
+        let mut pending = std::mem::take(&mut self.printer_pending);
+        pending.extend_from_slice(data);
+        // Bytes of `data` before the joined buffer's start
+        let held = pending.len() - data.len();
+
+        if let Some(pos) = memchr::memmem::find(&pending, PRINTER_OFF) {
+            self.printer_buffer.extend_from_slice(&pending[..pos]);
+            self.printer_controller = false;
+            return &data[pos + PRINTER_OFF.len() - held..];
         }
+        let keep = (1..PRINTER_OFF.len())
+            .rev()
+            .find(|&n| pending.ends_with(&PRINTER_OFF[..n]))
+            .unwrap_or(0);
+        self.printer_buffer.extend_from_slice(&pending[..pending.len() - keep]);
+        self.printer_pending = pending[pending.len() - keep..].to_vec();
+        &[]
     }
 
     /// Handle a parsed ANSI event
     fn handle_event(&mut self, event: AnsiEvent) {
+        if let AnsiEvent::Execute(byte) = event {
+            if self.translate_newline(byte) {
+                return;
+            }
+        }
+        self.after_cr = false;
+
         let screen = if self.use_alt_screen {
             self.alt_screen.as_mut().unwrap_or(&mut self.screen)
         } else {
@@ -136,18 +257,144 @@ impl Terminal {
             AnsiEvent::OscDispatch { params } => {
                 self.handle_osc(params);
             }
+            AnsiEvent::DcsDispatch { params, intermediates, action, data } => {
+                self.handle_dcs(&params, &intermediates, action, &data);
+            }
         }
     }
 
+    /// Apply `newline_translation` to a CR or LF; true if it was handled
+    fn translate_newline(&mut self, byte: u8) -> bool {
+        let after_cr = std::mem::take(&mut self.after_cr);
+        let translation = self.newline_translation;
+        let screen = self.current_screen_mut();
+        match (translation, byte) {
+            (NewlineTranslation::CrToCrLf, 0x0D) => {
+                screen.carriage_return();
+                screen.linefeed();
+                self.after_cr = true;
+                true
+            }
+            // The LF of a CR LF pair was already applied
+            (NewlineTranslation::CrToCrLf, 0x0A) => after_cr,
+            (NewlineTranslation::LfToCrLf, 0x0A) => {
+                screen.carriage_return();
+                screen.linefeed();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Handle DCS (Device Control String)
+    fn handle_dcs(&mut self, params: &[u16], intermediates: &[u8], action: u8, data: &[u8]) {
+        match (intermediates, action) {
+            // DECRQSS - Request Status String
+            (b"$", b'q') => self.handle_decrqss(data),
+            // Sixel graphics
+            ([], b'q') => {
+                if let Some(image) = self.sixel_parser.parse(data) {
+                    self.place_image(image);
+                }
+            }
+            _ => {
+                tracing::debug!("Unknown DCS: {:?} {:?} {}", params, intermediates, action as char);
+            }
+        }
+    }
+
+    /// Answer DECRQSS with `DCS 1 $ r <setting> ST`, or `DCS 0 $ r ST` if unsupported
+    fn handle_decrqss(&mut self, setting: &[u8]) {
+        let screen = self.current_screen();
+        let report = match setting {
+            b"m" => Some(format!("{}m", sgr_params(&screen.current_style()))),
+            b"r" => {
+                let (top, bottom) = screen.scroll_region();
+                Some(format!("{};{}r", top + 1, bottom + 1))
+            }
+            _ => None,
+        };
+        let reply = match report {
+            Some(report) => format!("\x1bP1$r{}\x1b\\", report),
+            None => "\x1bP0$r\x1b\\".to_string(),
+        };
+        self.responses.extend_from_slice(reply.as_bytes());
+    }
+
+    /// Take replies to host queries, to be sent back to the remote
+    pub fn take_responses(&mut self) -> Vec<u8> {
+        std::mem::take(&mut self.responses)
+    }
+
+    /// Check if printer controller mode is on
+    pub fn is_printer_controller(&self) -> bool {
+        self.printer_controller
+    }
+
+    /// Take data printed in printer controller mode (`CSI 5 i` .. `CSI 4 i`)
+    pub fn take_printer_output(&mut self) -> Vec<u8> {
+        std::mem::take(&mut self.printer_buffer)
+    }
+
+    /// Anchor a decoded image per the Sixel scrolling mode
+    fn place_image(&mut self, mut image: SixelImage) {
+        let cell_height = self.cell_pixels.1.max(1) as usize;
+        let image_rows = image.height.div_ceil(cell_height).max(1);
+
+        if !self.sixel_scrolling {
+            // Top-left corner, clipped to the screen; the cursor stays put
+            let max_height = self.size.rows as usize * cell_height;
+            if image.height > max_height {
+                image.height = max_height;
+                image.pixels.truncate(image.width * max_height);
+            }
+            self.images.push((CellPos { row: 0, col: 0 }, image));
+            return;
+        }
+
+        // At the cursor; the cursor ends on the line below the image,
+        // scrolling the screen (and the image with it) as needed
+        let screen = self.current_screen_mut();
+        let (row, col) = screen.cursor_pos();
+        for _ in 0..image_rows {
+            screen.linefeed();
+        }
+        screen.carriage_return();
+        self.images.push((CellPos { row: row as i32, col }, image));
+    }
+
+    /// Move images up with scrolled content, dropping those fully off screen
+    fn scroll_images(&mut self, lines: u64) {
+        let cell_height = self.cell_pixels.1.max(1) as usize;
+        let lines = lines.min(i32::MAX as u64) as i32;
+        self.images.retain_mut(|(pos, image)| {
+            pos.row = pos.row.saturating_sub(lines);
+            let image_rows = image.height.div_ceil(cell_height) as i32;
+            pos.row + image_rows > 0
+        });
+    }
+
+    /// Sixel images on screen with their anchor cells
+    pub fn images(&self) -> &[(CellPos, SixelImage)] {
+        &self.images
+    }
+
+    /// Set the cell size in pixels used to lay out images
+    pub fn set_cell_pixels(&mut self, width: u16, height: u16) {
+        self.cell_pixels = (width.max(1), height.max(1));
+    }
+
     /// Handle control character (C0)
     fn handle_control(&mut self, byte: u8) {
+        if byte == 0x07 {
+            // BEL - Bell
+            self.bell = true;
+            return;
+        }
+
         let screen = self.current_screen_mut();
         
         match byte {
-            0x07 => {
-                // BEL - Bell
-                // TODO: Trigger bell notification
-            }
             0x08 => {
                 // BS - Backspace
                 screen.move_cursor_left(1);
@@ -236,6 +483,14 @@ impl Terminal {
             b'X' => self.current_screen_mut().erase_chars(param(0, 1)),
             b'@' => self.current_screen_mut().insert_chars(param(0, 1)),
             b'd' => self.current_screen_mut().set_cursor_row(param(0, 1).saturating_sub(1)),
+            b'g' => {
+                // TBC - Tab Clear
+                match param(0, 0) {
+                    0 => self.current_screen_mut().clear_tab_stop(),
+                    3 => self.current_screen_mut().clear_all_tab_stops(),
+                    _ => {}
+                }
+            }
             b'm' => {
                 // SGR - Select Graphic Rendition
                 self.handle_sgr(&params);
@@ -264,6 +519,18 @@ impl Terminal {
                 // DA - Device Attributes
                 // TODO: Send response
             }
+            b'i' if intermediates.is_empty() => {
+                // MC - Media Copy: printer controller on (5) / off (4)
+                match param(0, 0) {
+                    5 => self.printer_controller = true,
+                    4 => self.printer_controller = false,
+                    n => tracing::debug!("Unsupported media copy: {}", n),
+                }
+            }
+            b'p' if intermediates == [b'!'] => {
+                // DECSTR - Soft Terminal Reset
+                self.soft_reset();
+            }
             _ => {
                 // Unknown CSI sequence
                 tracing::debug!("Unknown CSI: {:?} {:?} {}", params, intermediates, action as char);
@@ -358,6 +625,7 @@ impl Terminal {
                 // DEC private modes
                 match param {
                     1 => self.app_cursor_keys = set,
+                    6 => self.current_screen_mut().set_origin_mode(set),
                     7 => self.current_screen_mut().set_auto_wrap(set),
                     12 => {
                         // Start/stop cursor blink
@@ -366,20 +634,24 @@ impl Terminal {
                     47 | 1047 => {
                         // Alternate screen buffer
                         if set && self.alt_screen.is_none() {
-                            self.alt_screen = Some(Screen::new(self.size.cols, self.size.rows));
+                            self.alt_screen = Some(self.new_alt_screen());
                         }
                         self.use_alt_screen = set;
                     }
+                    80 => self.sixel_scrolling = !set,
                     1000 => self.mouse_mode = if set { MouseMode::Normal } else { MouseMode::None },
                     1002 => self.mouse_mode = if set { MouseMode::ButtonEvent } else { MouseMode::None },
                     1003 => self.mouse_mode = if set { MouseMode::AnyEvent } else { MouseMode::None },
+                    1006 => self.mouse_encoding = if set { MouseEncoding::Sgr } else { MouseEncoding::X10 },
                     1049 => {
-                        // Alternate screen with save/restore cursor
+                        // Alternate screen with save/restore cursor. The alternate
+                        // screen is cleared on entry and keeps its own DECSC slot,
+                        // so ESC 7/8 inside it cannot clobber the main screen's state
                         if set {
-                            self.screen.save_cursor();
-                            if self.alt_screen.is_none() {
-                                self.alt_screen = Some(Screen::new(self.size.cols, self.size.rows));
+                            if !self.use_alt_screen {
+                                self.screen.save_cursor();
                             }
+                            self.alt_screen = Some(self.new_alt_screen());
                             self.use_alt_screen = true;
                         } else {
                             self.use_alt_screen = false;
@@ -400,6 +672,13 @@ impl Terminal {
         }
     }
 
+    /// Alternate screens have no scrollback
+    fn new_alt_screen(&self) -> Screen {
+        let mut screen = Screen::new(self.size.cols, self.size.rows);
+        screen.set_scrollback_limit(0);
+        screen
+    }
+
     /// Handle DSR (Device Status Report)
     fn handle_dsr(&self, params: &[u16]) {
         // TODO: Send responses through callback
@@ -427,10 +706,16 @@ impl Terminal {
                 self.current_screen_mut().linefeed();
             }
             b'M' => self.current_screen_mut().reverse_linefeed(),
+            b'H' if intermediates.is_empty() => {
+                // HTS - Horizontal Tab Set
+                self.current_screen_mut().set_tab_stop();
+            }
             b'c' => {
                 // RIS - Reset to Initial State
                 self.reset();
             }
+            b'=' => self.app_keypad = true,  // DECKPAM
+            b'>' => self.app_keypad = false, // DECKPNM
             _ => {
                 if !intermediates.is_empty() {
                     // Character set designation
@@ -466,6 +751,15 @@ impl Terminal {
         }
     }
 
+    /// Get reference to current screen
+    fn current_screen(&self) -> &Screen {
+        if self.use_alt_screen {
+            self.alt_screen.as_ref().unwrap_or(&self.screen)
+        } else {
+            &self.screen
+        }
+    }
+
     /// Get mutable reference to current screen
     fn current_screen_mut(&mut self) -> &mut Screen {
         if self.use_alt_screen {
@@ -477,14 +771,32 @@ impl Terminal {
 
     /// Reset terminal to initial state
     pub fn reset(&mut self) {
-        self.screen = Screen::new(self.size.cols, self.size.rows);
+        let mut screen = Screen::new(self.size.cols, self.size.rows);
+        // Memory limits are host configuration, not terminal state
+        screen.set_scrollback_limit(self.screen.scrollback_limit());
+        screen.set_scrollback_byte_limit(self.screen.scrollback_byte_limit());
+        self.screen = screen;
         self.alt_screen = None;
         self.use_alt_screen = false;
         self.app_cursor_keys = false;
         self.app_keypad = false;
         self.bracketed_paste = false;
         self.mouse_mode = MouseMode::None;
+        self.mouse_encoding = MouseEncoding::X10;
         self.title.clear();
+        self.images.clear();
+        self.sixel_scrolling = true;
+        self.printer_controller = false;
+        self.printer_pending.clear();
+    }
+
+    /// Soft reset (DECSTR): keypad and cursor key modes, SGR, scroll
+    /// region and character set are reset; screen content and scrollback
+    /// are preserved
+    pub fn soft_reset(&mut self) {
+        self.app_cursor_keys = false;
+        self.app_keypad = false;
+        self.current_screen_mut().soft_reset();
     }
 
     /// Resize the terminal
@@ -525,6 +837,11 @@ impl Terminal {
         self.mouse_mode
     }
 
+    /// Get mouse report encoding
+    pub fn mouse_encoding(&self) -> MouseEncoding {
+        self.mouse_encoding
+    }
+
     /// Check if bracketed paste is enabled
     pub fn bracketed_paste(&self) -> bool {
         self.bracketed_paste
@@ -535,30 +852,67 @@ impl Terminal {
         self.app_cursor_keys
     }
 
+    /// Check and clear the pending bell
+    pub fn take_bell(&mut self) -> bool {
+        std::mem::take(&mut self.bell)
+    }
+
+    /// Check if application keypad mode
+    pub fn app_keypad(&self) -> bool {
+        self.app_keypad
+    }
+
+    /// Get the display newline translation
+    pub fn newline_translation(&self) -> NewlineTranslation {
+        self.newline_translation
+    }
+
+    /// Set the display newline translation
+    pub fn set_newline_translation(&mut self, translation: NewlineTranslation) {
+        self.newline_translation = translation;
+        self.after_cr = false;
+    }
+
+    /// Encode a key press for the current cursor/keypad modes
+    /// Returns bytes to send to remote
+    pub fn encode_key(&self, key: Key, modifiers: KeyModifiers) -> Option<Vec<u8>> {
+        keyboard::encode_key(key, modifiers, self.app_cursor_keys, self.app_keypad)
+    }
+
     /// Generate mouse button press event
     /// Returns bytes to send to remote
     pub fn mouse_press(&self, button: u8, col: u16, row: u16, modifiers: MouseModifiers) -> Option<Vec<u8>> {
-        self.encode_mouse_event(button, col, row, modifiers, false)
+        self.encode_mouse_event(button, col, row, modifiers, false, false)
     }
 
-    /// Generate mouse button release event
-    pub fn mouse_release(&self, col: u16, row: u16, modifiers: MouseModifiers) -> Option<Vec<u8>> {
-        self.encode_mouse_event(3, col, row, modifiers, false) // Button 3 = release
+    /// Generate mouse button release event for `button`
+    ///
+    /// X10 reports every release as button 3; SGR names the released button.
+    pub fn mouse_release(&self, button: u8, col: u16, row: u16, modifiers: MouseModifiers) -> Option<Vec<u8>> {
+        self.encode_mouse_event(button, col, row, modifiers, false, true)
     }
 
     /// Generate mouse motion event
     pub fn mouse_motion(&self, button: u8, col: u16, row: u16, modifiers: MouseModifiers) -> Option<Vec<u8>> {
-        self.encode_mouse_event(button, col, row, modifiers, true)
+        self.encode_mouse_event(button, col, row, modifiers, true, false)
     }
 
     /// Generate mouse wheel event
     pub fn mouse_wheel(&self, up: bool, col: u16, row: u16, modifiers: MouseModifiers) -> Option<Vec<u8>> {
         let button = if up { 64 } else { 65 }; // Wheel up = 64, Wheel down = 65
-        self.encode_mouse_event(button, col, row, modifiers, false)
+        self.encode_mouse_event(button, col, row, modifiers, false, false)
     }
 
     /// Encode mouse event based on current mode
-    fn encode_mouse_event(&self, button: u8, col: u16, row: u16, modifiers: MouseModifiers, motion: bool) -> Option<Vec<u8>> {
+    fn encode_mouse_event(
+        &self,
+        button: u8,
+        col: u16,
+        row: u16,
+        modifiers: MouseModifiers,
+        motion: bool,
+        release: bool,
+    ) -> Option<Vec<u8>> {
         if self.mouse_mode == MouseMode::None {
             return None;
         }
@@ -568,13 +922,19 @@ impl Terminal {
             return None;
         }
 
-        // Build button code
-        let mut cb = button;
+        // Build button code; X10 cannot say which button was released
+        let mut cb = if release && self.mouse_encoding != MouseEncoding::Sgr { 3 } else { button };
         if modifiers.shift { cb |= 4; }
         if modifiers.alt { cb |= 8; }
         if modifiers.ctrl { cb |= 16; }
         if motion { cb |= 32; }
 
+        if self.mouse_encoding == MouseEncoding::Sgr {
+            // SGR encoding: ESC [ < Cb ; Cx ; Cy M (press) or m (release), 1-based
+            let last = if release { 'm' } else { 'M' };
+            return Some(format!("\x1b[<{};{};{}{}", cb, col + 1, row + 1, last).into_bytes());
+        }
+
         // X10 encoding: ESC [ M Cb Cx Cy
         // Values are 1-based and offset by 32
         let cx = (col.min(222) + 33) as u8;
@@ -587,10 +947,20 @@ impl Terminal {
     pub fn set_mouse_mode(&mut self, mode: MouseMode) {
         self.mouse_mode = mode;
     }
+
+    /// Set mouse report encoding
+    pub fn set_mouse_encoding(&mut self, encoding: MouseEncoding) {
+        self.mouse_encoding = encoding;
+    }
+
+    /// Decode an X10 or SGR mouse report, e.g. from a replayed session
+    pub fn decode_mouse(data: &[u8]) -> Option<MouseEvent> {
+        mouse::decode(data)
+    }
 }
 
 /// Mouse modifiers
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
 pub struct MouseModifiers {
     pub shift: bool,
     pub alt: bool,
@@ -603,3 +973,242 @@ impl Default for Terminal {
     }
 }
 
+/// SGR parameters reproducing a style, starting from a reset
+fn sgr_params(style: &CellStyle) -> String {
+    let mut params = vec!["0".to_string()];
+    for (on, code) in [
+        (style.bold, "1"),
+        (style.dim, "2"),
+        (style.italic, "3"),
+        (style.underline, "4"),
+        (style.blink, "5"),
+        (style.inverse, "7"),
+        (style.hidden, "8"),
+        (style.strikethrough, "9"),
+    ] {
+        if on {
+            params.push(code.to_string());
+        }
+    }
+    for (color, base) in [(style.fg, 30), (style.bg, 40)] {
+        match color {
+            Color::Default => {}
+            Color::Named(named) => {
+                let idx = named as u16;
+                params.push(if idx < 8 { base + idx } else { base + 60 + idx - 8 }.to_string());
+            }
+            Color::Indexed(idx) => params.push(format!("{};5;{}", base + 8, idx)),
+            Color::Rgb(r, g, b) => params.push(format!("{};2;{};{};{}", base + 8, r, g, b)),
+        }
+    }
+    params.join(";")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_newline_translation() {
+        let mut term = Terminal::with_size(TerminalSize { cols: 20, rows: 5 });
+        term.set_newline_translation(NewlineTranslation::CrToCrLf);
+        term.process(b"one\rtwo\r");
+        term.process(b"\nthree");
+        assert_eq!(term.screen().line_text(0), "one");
+        assert_eq!(term.screen().line_text(1), "two");
+        assert_eq!(term.screen().line_text(2), "three");
+
+        let mut term = Terminal::with_size(TerminalSize { cols: 20, rows: 5 });
+        term.set_newline_translation(NewlineTranslation::LfToCrLf);
+        term.process(b"one\ntwo");
+        assert_eq!(term.screen().line_text(1), "two");
+        assert_eq!(term.screen().cursor_pos(), (1, 3));
+    }
+
+    #[test]
+    fn test_decstr_keeps_screen() {
+        let mut term = Terminal::with_size(TerminalSize { cols: 20, rows: 5 });
+        term.process(b"\x1b[?1h\x1b=\x1b[2;4r\x1b[?7l\x1b[1;31mkept\x1b[!p");
+
+        assert_eq!(term.screen().line_text(0), "kept");
+        assert!(!term.app_cursor_keys());
+        assert!(!term.app_keypad());
+        assert_eq!(term.screen().scroll_region(), (0, 4));
+        assert_eq!(term.screen().current_style(), CellStyle::default());
+        assert_eq!(term.screen().cursor_pos(), (0, 4));
+
+        term.process(b"x");
+        assert!(!term.screen().cell(0, 4).unwrap().style.bold);
+    }
+
+    #[test]
+    fn test_decsc_decrc_preserves_sgr() {
+        let mut term = Terminal::new();
+        term.process(b"\x1b[3;5H\x1b[1;4;32m\x1b7\x1b[0m\x1b[10;10Hplain\x1b8X");
+
+        assert_eq!(term.screen().cursor_pos(), (2, 5));
+        let style = term.screen().cell(2, 4).unwrap().style;
+        assert!(style.bold);
+        assert!(style.underline);
+        assert_eq!(style.fg, Color::Named(NamedColor::Green));
+    }
+
+    #[test]
+    fn test_decom_cup_relative_to_margins() {
+        let mut term = Terminal::new();
+        term.process(b"\x1b[5;10r\x1b[?6h\x1b[2;3HX");
+
+        assert!(term.screen().origin_mode());
+        assert_eq!(term.screen().line_text(5), "  X");
+
+        term.process(b"\x1b[?6l\x1b[2;3HY");
+        assert_eq!(term.screen().line_text(1), "  Y");
+    }
+
+    #[test]
+    fn test_alt_screen_1049_restores_main_cursor_state() {
+        let mut term = Terminal::new();
+        term.process(b"\x1b[5;7H\x1b[7m\x1b[?1049h");
+        assert!(term.is_alt_screen());
+
+        // Saving inside the alternate screen must not affect the main screen
+        term.process(b"\x1b[0m\x1b[2;2H\x1b7text\x1b[?1049l");
+        assert!(!term.is_alt_screen());
+        assert_eq!(term.screen().cursor_pos(), (4, 6));
+
+        term.process(b"Y");
+        assert!(term.screen().cell(4, 6).unwrap().style.inverse);
+    }
+
+    #[test]
+    fn test_encode_key_follows_cursor_and_keypad_modes() {
+        let mut term = Terminal::new();
+        let none = KeyModifiers::default();
+        assert_eq!(term.encode_key(Key::Up, none), Some(b"\x1b[A".to_vec()));
+
+        // vim enables DECCKM and DECKPAM
+        term.process(b"\x1b[?1h\x1b=");
+        assert_eq!(term.encode_key(Key::Up, none), Some(b"\x1bOA".to_vec()));
+        assert_eq!(
+            term.encode_key(Key::Keypad(KeypadKey::Digit(5)), none),
+            Some(b"\x1bOu".to_vec())
+        );
+
+        // Modified keys always use the CSI form
+        let ctrl_shift = KeyModifiers { shift: true, ctrl: true, ..Default::default() };
+        assert_eq!(term.encode_key(Key::Up, ctrl_shift), Some(b"\x1b[1;6A".to_vec()));
+        assert_eq!(term.encode_key(Key::F(5), ctrl_shift), Some(b"\x1b[15;6~".to_vec()));
+
+        term.process(b"\x1b[?1l\x1b>");
+        assert_eq!(term.encode_key(Key::Left, none), Some(b"\x1b[D".to_vec()));
+        assert_eq!(term.encode_key(Key::Keypad(KeypadKey::Digit(5)), none), Some(b"5".to_vec()));
+    }
+
+    #[test]
+    fn test_encode_control_and_function_keys() {
+        let term = Terminal::new();
+        let ctrl = KeyModifiers { ctrl: true, ..Default::default() };
+        let alt = KeyModifiers { alt: true, ..Default::default() };
+        let none = KeyModifiers::default();
+
+        assert_eq!(term.encode_key(Key::Char('c'), ctrl), Some(vec![0x03]));
+        assert_eq!(term.encode_key(Key::Char('x'), alt), Some(b"\x1bx".to_vec()));
+        assert_eq!(term.encode_key(Key::F(1), none), Some(b"\x1bOP".to_vec()));
+        assert_eq!(term.encode_key(Key::F(12), none), Some(b"\x1b[24~".to_vec()));
+        assert_eq!(term.encode_key(Key::Delete, none), Some(b"\x1b[3~".to_vec()));
+        assert_eq!(term.encode_key(Key::F(13), none), None);
+        assert_eq!(Key::from_egui(egui::Key::A), Some(Key::Char('a')));
+        assert_eq!(Key::from_egui(egui::Key::Num7), Some(Key::Char('7')));
+    }
+
+    #[test]
+    fn test_bel_sets_pending_bell() {
+        let mut term = Terminal::new();
+        assert!(!term.take_bell());
+        term.process(b"make: done\x07");
+        assert!(term.take_bell());
+        assert!(!term.take_bell());
+        assert_eq!(term.screen().line_text(0), "make: done");
+    }
+
+    #[test]
+    fn test_decrqss_reports_sgr_and_margins() {
+        let mut term = Terminal::with_size(TerminalSize::new(80, 24));
+        term.process(b"\x1b[1;31;48;5;200m\x1b[5;20r");
+        term.process(b"\x1bP$qm\x1b\\\x1bP$qr\x1b\\\x1bP$qz\x1b\\");
+        assert_eq!(
+            term.take_responses(),
+            b"\x1bP1$r0;1;31;48;5;200m\x1b\\\x1bP1$r5;20r\x1b\\\x1bP0$r\x1b\\".to_vec()
+        );
+        assert!(term.take_responses().is_empty());
+    }
+
+    #[test]
+    fn test_sixel_dcs_is_decoded() {
+        let mut term = Terminal::new();
+        term.process(b"\x1bPq#1~~~\x1b\\");
+        assert_eq!(term.images().len(), 1);
+        // The payload is not printed
+        assert_eq!(term.screen().line_text(0).trim(), "");
+    }
+
+    /// Sixel stream 3 pixels wide and `rows * 6` pixels tall
+    fn sixel_block(rows: usize) -> Vec<u8> {
+        let mut seq = b"\x1bPq#1".to_vec();
+        seq.extend(vec!["~~~"; rows].join("-").into_bytes());
+        seq.extend_from_slice(b"\x1b\\");
+        seq
+    }
+
+    #[test]
+    fn test_sixel_image_advances_cursor_and_scrolls() {
+        let mut term = Terminal::with_size(TerminalSize::new(20, 5));
+        term.set_cell_pixels(10, 20);
+        term.process(b"\x1b[4;3H");
+
+        // 60px = 3 cell rows from row 3: the screen scrolls by 2
+        term.process(&sixel_block(10));
+        assert_eq!(term.images()[0].0, CellPos { row: 1, col: 2 });
+        assert_eq!(term.screen().cursor_pos(), (4, 0));
+
+        // Scrolling the image fully off screen drops it
+        term.process(b"\n\n\n\n");
+        assert!(term.images().is_empty());
+    }
+
+    #[test]
+    fn test_sixel_without_scrolling_clips_at_origin() {
+        let mut term = Terminal::with_size(TerminalSize::new(20, 5));
+        term.set_cell_pixels(10, 20);
+        term.process(b"\x1b[?80h\x1b[3;6H");
+
+        // 120px is taller than the 100px screen
+        term.process(&sixel_block(20));
+        let (pos, image) = &term.images()[0];
+        assert_eq!(*pos, CellPos { row: 0, col: 0 });
+        assert_eq!(image.height, 100);
+        assert_eq!(image.pixels.len(), image.width * 100);
+        assert_eq!(term.screen().cursor_pos(), (2, 5));
+    }
+
+    #[test]
+    fn test_printer_controller_diverts_text() {
+        let mut term = Terminal::new();
+        term.process(b"a\x1b[5iREPORT\x1b[1m\x1b[2J\r\n\x1b[");
+        assert!(term.is_printer_controller());
+        term.process(b"4ib");
+        assert!(!term.is_printer_controller());
+
+        // Escape sequences are printed, not applied to the screen
+        assert_eq!(term.take_printer_output(), b"REPORT\x1b[1m\x1b[2J\r\n");
+        assert!(term.take_printer_output().is_empty());
+        assert_eq!(term.screen().cursor_pos(), (0, 2));
+        assert_eq!(term.screen().line_text(0).trim_end(), "ab");
+        assert!(!term.screen().cell(0, 1).unwrap().style.bold);
+
+        // A lone ESC at the end of a chunk that is not `CSI 4 i` is printed
+        term.process(b"\x1b[5iX\x1b");
+        term.process(b"Y\x1b[4i");
+        assert_eq!(term.take_printer_output(), b"X\x1bY");
+    }
+}
@@ -0,0 +1,187 @@
+//! Mouse report decoding
+//!
+//! Parses the reports produced by `Terminal::mouse_press` and friends,
+//! in both the legacy X10 (`ESC [ M Cb Cx Cy`) and SGR
+//! (`ESC [ < Cb ; Cx ; Cy M/m`) formats.
+
+use super::MouseModifiers;
+
+/// Mouse report encoding
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MouseEncoding {
+    /// `ESC [ M Cb Cx Cy`, coordinates limited to 223
+    #[default]
+    X10,
+    /// `ESC [ < Cb ; Cx ; Cy M/m` (mode 1006), unlimited coordinates
+    Sgr,
+}
+
+/// Mouse button in a report
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MouseButton {
+    /// Left button
+    Left,
+    /// Middle button
+    Middle,
+    /// Right button
+    Right,
+    /// Wheel scrolled up
+    WheelUp,
+    /// Wheel scrolled down
+    WheelDown,
+    /// No button (X10 release, or motion with no button held)
+    None,
+}
+
+/// What happened
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MouseEventKind {
+    /// Button pressed (or wheel step)
+    Press,
+    /// Button released
+    Release,
+    /// Pointer moved
+    Motion,
+}
+
+/// A decoded mouse report
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MouseEvent {
+    /// Button involved
+    pub button: MouseButton,
+    /// Press, release or motion
+    pub kind: MouseEventKind,
+    /// Column (0-based)
+    pub col: u16,
+    /// Row (0-based)
+    pub row: u16,
+    /// Modifier keys held
+    pub modifiers: MouseModifiers,
+}
+
+/// Split a button code into (button, modifiers, motion)
+fn split_button_code(cb: u16) -> (MouseButton, MouseModifiers, bool) {
+    let modifiers = MouseModifiers {
+        shift: cb & 4 != 0,
+        alt: cb & 8 != 0,
+        ctrl: cb & 16 != 0,
+    };
+    let button = match (cb & 64 != 0, cb & 3) {
+        (false, 0) => MouseButton::Left,
+        (false, 1) => MouseButton::Middle,
+        (false, 2) => MouseButton::Right,
+        (true, 0) => MouseButton::WheelUp,
+        (true, 1) => MouseButton::WheelDown,
+        _ => MouseButton::None,
+    };
+    (button, modifiers, cb & 32 != 0)
+}
+
+/// Decode a single X10 or SGR mouse report
+pub fn decode(data: &[u8]) -> Option<MouseEvent> {
+    let body = data.strip_prefix(b"\x1b[")?;
+
+    if let Some(&[cb, cx, cy]) = body.strip_prefix(b"M") {
+        let (button, modifiers, motion) = split_button_code(u16::from(cb.checked_sub(32)?));
+        let kind = if motion {
+            MouseEventKind::Motion
+        } else if button == MouseButton::None {
+            MouseEventKind::Release
+        } else {
+            MouseEventKind::Press
+        };
+        return Some(MouseEvent {
+            button,
+            kind,
+            col: u16::from(cx.checked_sub(33)?),
+            row: u16::from(cy.checked_sub(33)?),
+            modifiers,
+        });
+    }
+
+    let body = body.strip_prefix(b"<")?;
+    let (&last, params) = body.split_last()?;
+    let released = match last {
+        b'M' => false,
+        b'm' => true,
+        _ => return None,
+    };
+
+    let params = std::str::from_utf8(params).ok()?;
+    let mut fields = params.split(';').map(|f| f.parse::<u16>().ok());
+    let (cb, cx, cy) = (fields.next()??, fields.next()??, fields.next()??);
+    if fields.next().is_some() {
+        return None;
+    }
+
+    let (button, modifiers, motion) = split_button_code(cb);
+    let kind = if released {
+        MouseEventKind::Release
+    } else if motion {
+        MouseEventKind::Motion
+    } else {
+        MouseEventKind::Press
+    };
+    Some(MouseEvent {
+        button,
+        kind,
+        col: cx.checked_sub(1)?,
+        row: cy.checked_sub(1)?,
+        modifiers,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::terminal::{MouseMode, Terminal};
+
+    fn ctrl() -> MouseModifiers {
+        MouseModifiers { ctrl: true, ..Default::default() }
+    }
+
+    #[test]
+    fn test_x10_round_trip() {
+        let mut term = Terminal::new();
+        term.set_mouse_mode(MouseMode::ButtonEvent);
+
+        let press = term.mouse_press(2, 10, 4, ctrl()).unwrap();
+        assert_eq!(
+            Terminal::decode_mouse(&press),
+            Some(MouseEvent { button: MouseButton::Right, kind: MouseEventKind::Press, col: 10, row: 4, modifiers: ctrl() })
+        );
+
+        let release = term.mouse_release(2, 10, 4, MouseModifiers::default()).unwrap();
+        let event = Terminal::decode_mouse(&release).unwrap();
+        assert_eq!((event.button, event.kind), (MouseButton::None, MouseEventKind::Release));
+    }
+
+    #[test]
+    fn test_sgr_round_trip() {
+        let mut term = Terminal::new();
+        term.process(b"\x1b[?1002h\x1b[?1006h");
+        assert_eq!(term.mouse_encoding(), MouseEncoding::Sgr);
+
+        // SGR has no 223-column limit
+        let motion = term.mouse_motion(0, 300, 5, MouseModifiers::default()).unwrap();
+        assert_eq!(motion, b"\x1b[<32;301;6M");
+        let event = Terminal::decode_mouse(&motion).unwrap();
+        assert_eq!((event.button, event.kind, event.col, event.row), (MouseButton::Left, MouseEventKind::Motion, 300, 5));
+
+        let wheel = term.mouse_wheel(false, 0, 0, ctrl()).unwrap();
+        let event = Terminal::decode_mouse(&wheel).unwrap();
+        assert_eq!((event.button, event.modifiers), (MouseButton::WheelDown, ctrl()));
+
+        let release = term.mouse_release(2, 0, 0, MouseModifiers::default()).unwrap();
+        assert_eq!(release, b"\x1b[<2;1;1m");
+        let release = Terminal::decode_mouse(&release).unwrap();
+        assert_eq!((release.button, release.kind), (MouseButton::Right, MouseEventKind::Release));
+    }
+
+    #[test]
+    fn test_decode_rejects_garbage() {
+        assert_eq!(Terminal::decode_mouse(b"\x1b[A"), None);
+        assert_eq!(Terminal::decode_mouse(b"\x1b[<0;1M"), None);
+        assert_eq!(Terminal::decode_mouse(b"\x1b[M  "), None);
+    }
+}
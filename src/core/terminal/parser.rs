@@ -12,8 +12,25 @@ enum State {
     CsiParam,
     CsiIntermediate,
     OscString,
+    /// ESC inside an OSC string, possibly the start of ST
+    OscEscape,
+    /// DCS parameters and intermediates, up to the final byte
+    DcsHeader,
+    /// DCS data string
+    DcsData,
+    /// ESC inside a DCS string, possibly the start of ST
+    DcsEscape,
 }
 
+/// Longest OSC string buffered; longer strings are dropped unparsed
+pub const MAX_OSC_LEN: usize = 8192;
+
+/// Longest DCS data string buffered (room for a sizeable Sixel image)
+pub const MAX_DCS_LEN: usize = 1 << 20;
+
+/// 8-bit String Terminator
+const ST_8BIT: u8 = 0x9C;
+
 /// Parsed ANSI event
 #[derive(Debug, Clone)]
 pub enum AnsiEvent {
@@ -36,6 +53,13 @@ pub enum AnsiEvent {
     OscDispatch {
         params: Vec<Vec<u8>>,
     },
+    /// DCS sequence with its data string
+    DcsDispatch {
+        params: Vec<u16>,
+        intermediates: Vec<u8>,
+        action: u8,
+        data: Vec<u8>,
+    },
 }
 
 /// ANSI escape sequence parser
@@ -46,6 +70,24 @@ pub struct AnsiParser {
     current_param: u16,
     osc_data: Vec<Vec<u8>>,
     osc_current: Vec<u8>,
+    /// Bytes buffered for the current OSC string
+    osc_len: usize,
+    /// The OSC string exceeded `MAX_OSC_LEN` and will be discarded
+    osc_overflow: bool,
+    /// DCS final byte
+    dcs_action: u8,
+    /// DCS data string
+    dcs_data: Vec<u8>,
+    /// The DCS header has a parameter in progress
+    dcs_param_started: bool,
+    /// The DCS data exceeded `MAX_DCS_LEN` and will be discarded
+    dcs_overflow: bool,
+    /// Event produced by the byte that ended an OSC/DCS string with a plain ESC
+    pending: Option<AnsiEvent>,
+    /// Pending UTF-8 sequence bytes
+    utf8_buf: Vec<u8>,
+    /// Total length of the pending UTF-8 sequence
+    utf8_len: usize,
 }
 
 impl AnsiParser {
@@ -58,6 +100,15 @@ impl AnsiParser {
             current_param: 0,
             osc_data: Vec::new(),
             osc_current: Vec::new(),
+            osc_len: 0,
+            osc_overflow: false,
+            dcs_action: 0,
+            dcs_data: Vec::new(),
+            dcs_param_started: false,
+            dcs_overflow: false,
+            pending: None,
+            utf8_buf: Vec::with_capacity(4),
+            utf8_len: 0,
         }
     }
 
@@ -69,6 +120,12 @@ impl AnsiParser {
         self.current_param = 0;
         self.osc_data.clear();
         self.osc_current.clear();
+        self.osc_len = 0;
+        self.osc_overflow = false;
+        self.dcs_action = 0;
+        self.dcs_data.clear();
+        self.dcs_param_started = false;
+        self.dcs_overflow = false;
     }
 
     /// Parse bytes and return events
@@ -79,6 +136,7 @@ impl AnsiParser {
             if let Some(event) = self.advance(byte) {
                 events.push(event);
             }
+            events.extend(self.pending.take());
         }
         
         events
@@ -94,10 +152,34 @@ impl AnsiParser {
             State::CsiParam => self.csi_param(byte),
             State::CsiIntermediate => self.csi_intermediate(byte),
             State::OscString => self.osc_string(byte),
+            State::OscEscape => self.osc_escape(byte),
+            State::DcsHeader => self.dcs_header(byte),
+            State::DcsData => self.dcs_data(byte),
+            State::DcsEscape => self.dcs_escape(byte),
         }
     }
 
     fn ground(&mut self, byte: u8) -> Option<AnsiEvent> {
+        // Continue a pending multi-byte UTF-8 sequence
+        if self.utf8_len > 0 {
+            if (0x80..=0xBF).contains(&byte) {
+                self.utf8_buf.push(byte);
+                if self.utf8_buf.len() < self.utf8_len {
+                    return None;
+                }
+                let c = std::str::from_utf8(&self.utf8_buf)
+                    .ok()
+                    .and_then(|s| s.chars().next())
+                    .unwrap_or(char::REPLACEMENT_CHARACTER);
+                self.utf8_buf.clear();
+                self.utf8_len = 0;
+                return Some(AnsiEvent::Print(c));
+            }
+            // Truncated sequence; the current byte is processed normally
+            self.utf8_buf.clear();
+            self.utf8_len = 0;
+        }
+
         match byte {
             // C0 control characters
             0x00..=0x1A | 0x1C..=0x1F => Some(AnsiEvent::Execute(byte)),
@@ -112,12 +194,18 @@ impl AnsiParser {
             }
             // DEL - ignore
             0x7F => None,
-            // UTF-8 continuation or start
-            0x80..=0xFF => {
-                // Simple UTF-8 handling - treat as printable
-                // TODO: Proper UTF-8 decoding
-                Some(AnsiEvent::Print(byte as char))
+            // UTF-8 lead bytes
+            0xC2..=0xF4 => {
+                self.utf8_len = match byte {
+                    0xC2..=0xDF => 2,
+                    0xE0..=0xEF => 3,
+                    _ => 4,
+                };
+                self.utf8_buf.push(byte);
+                None
             }
+            // Stray continuation or invalid lead byte
+            0x80..=0xC1 | 0xF5..=0xFF => Some(AnsiEvent::Print(char::REPLACEMENT_CHARACTER)),
         }
     }
 
@@ -139,6 +227,11 @@ impl AnsiParser {
                 self.state = State::OscString;
                 None
             }
+            // DCS
+            b'P' => {
+                self.state = State::DcsHeader;
+                None
+            }
             // SS2, SS3
             b'N' | b'O' => {
                 self.reset();
@@ -347,47 +440,172 @@ impl AnsiParser {
         }
     }
 
+    /// OSC strings end with BEL, ST (`ESC \\`) or 8-bit ST (0x9C)
     fn osc_string(&mut self, byte: u8) -> Option<AnsiEvent> {
         match byte {
-            // BEL terminates OSC
-            0x07 => {
-                if !self.osc_current.is_empty() {
-                    self.osc_data.push(std::mem::take(&mut self.osc_current));
-                }
-                let event = AnsiEvent::OscDispatch {
-                    params: std::mem::take(&mut self.osc_data),
-                };
-                self.reset();
-                Some(event)
-            }
-            // ESC might be ST
+            0x07 => self.osc_end(),
             0x1B => {
-                // Need to check for \ next
-                // For now, treat as terminator
-                if !self.osc_current.is_empty() {
-                    self.osc_data.push(std::mem::take(&mut self.osc_current));
-                }
-                let event = AnsiEvent::OscDispatch {
-                    params: std::mem::take(&mut self.osc_data),
-                };
-                self.reset();
-                self.state = State::Escape;
-                Some(event)
+                self.state = State::OscEscape;
+                None
             }
+            // 0x9C is also a UTF-8 continuation byte; only a terminator
+            // outside a multi-byte character
+            ST_8BIT if !in_utf8_sequence(&self.osc_current) => self.osc_end(),
             // ; - parameter separator
             b';' => {
-                self.osc_data.push(std::mem::take(&mut self.osc_current));
+                if self.osc_buffer_byte() {
+                    self.osc_data.push(std::mem::take(&mut self.osc_current));
+                }
                 None
             }
             // Other printable
             0x20..=0x7E | 0x80..=0xFF => {
-                self.osc_current.push(byte);
+                if self.osc_buffer_byte() {
+                    self.osc_current.push(byte);
+                }
+                None
+            }
+            // Ignore other C0
+            _ => None,
+        }
+    }
+
+    fn osc_escape(&mut self, byte: u8) -> Option<AnsiEvent> {
+        let event = self.osc_end();
+        if byte != b'\\' {
+            // A bare ESC ends the string and starts a new sequence
+            self.state = State::Escape;
+            self.pending = self.escape(byte);
+        }
+        event
+    }
+
+    /// Count a byte against `MAX_OSC_LEN`; false once the string is too long
+    fn osc_buffer_byte(&mut self) -> bool {
+        self.osc_len += 1;
+        if self.osc_len > MAX_OSC_LEN {
+            self.osc_overflow = true;
+            self.osc_data.clear();
+            self.osc_current.clear();
+        }
+        !self.osc_overflow
+    }
+
+    /// Finish the OSC string; oversized strings are dropped
+    fn osc_end(&mut self) -> Option<AnsiEvent> {
+        if self.osc_overflow {
+            self.reset();
+            return None;
+        }
+        if !self.osc_current.is_empty() {
+            self.osc_data.push(std::mem::take(&mut self.osc_current));
+        }
+        let event = AnsiEvent::OscDispatch {
+            params: std::mem::take(&mut self.osc_data),
+        };
+        self.reset();
+        Some(event)
+    }
+
+    fn dcs_header(&mut self, byte: u8) -> Option<AnsiEvent> {
+        match byte {
+            0x30..=0x39 => {
+                self.current_param = self.current_param
+                    .saturating_mul(10)
+                    .saturating_add((byte - b'0') as u16);
+                self.dcs_param_started = true;
+                None
+            }
+            b';' => {
+                self.params.push(self.current_param);
+                self.current_param = 0;
+                self.dcs_param_started = true;
+                None
+            }
+            // Intermediates and private markers
+            0x20..=0x2F | 0x3C..=0x3F => {
+                self.intermediates.push(byte);
+                None
+            }
+            // Final byte; the data string follows
+            0x40..=0x7E => {
+                if self.dcs_param_started {
+                    self.params.push(self.current_param);
+                }
+                self.dcs_action = byte;
+                self.state = State::DcsData;
+                None
+            }
+            0x1B => {
+                self.reset();
+                self.state = State::Escape;
                 None
             }
-            // Ignore C0 except BEL
+            // C0 is ignored inside DCS
             _ => None,
         }
     }
+
+    /// DCS strings end with ST (`ESC \\`) or 8-bit ST
+    fn dcs_data(&mut self, byte: u8) -> Option<AnsiEvent> {
+        match byte {
+            0x1B => {
+                self.state = State::DcsEscape;
+                None
+            }
+            ST_8BIT => self.dcs_end(),
+            _ => {
+                if self.dcs_data.len() < MAX_DCS_LEN {
+                    self.dcs_data.push(byte);
+                } else if !self.dcs_overflow {
+                    self.dcs_overflow = true;
+                    self.dcs_data = Vec::new();
+                }
+                None
+            }
+        }
+    }
+
+    fn dcs_escape(&mut self, byte: u8) -> Option<AnsiEvent> {
+        let event = self.dcs_end();
+        if byte != b'\\' {
+            // A bare ESC ends the string and starts a new sequence
+            self.state = State::Escape;
+            self.pending = self.escape(byte);
+        }
+        event
+    }
+
+    /// Finish the DCS string; oversized strings are dropped
+    fn dcs_end(&mut self) -> Option<AnsiEvent> {
+        if self.dcs_overflow {
+            self.reset();
+            return None;
+        }
+        let event = AnsiEvent::DcsDispatch {
+            params: std::mem::take(&mut self.params),
+            intermediates: std::mem::take(&mut self.intermediates),
+            action: self.dcs_action,
+            data: std::mem::take(&mut self.dcs_data),
+        };
+        self.reset();
+        Some(event)
+    }
+}
+
+/// Whether `buf` ends inside an incomplete UTF-8 character
+fn in_utf8_sequence(buf: &[u8]) -> bool {
+    let continuation = buf.iter().rev().take_while(|&&b| (0x80..=0xBF).contains(&b)).count();
+    if continuation >= 3 {
+        return false;
+    }
+    let expected = match buf.len().checked_sub(continuation + 1).map(|i| buf[i]) {
+        Some(0xC2..=0xDF) => 2,
+        Some(0xE0..=0xEF) => 3,
+        Some(0xF0..=0xF4) => 4,
+        _ => return false,
+    };
+    continuation + 1 < expected
 }
 
 impl Default for AnsiParser {
@@ -415,6 +633,24 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_utf8() {
+        let mut parser = AnsiParser::new();
+        let mut events = parser.parse("Fá".as_bytes());
+        // Split sequences are completed across calls
+        events.extend(parser.parse(&"€".as_bytes()[..1]));
+        events.extend(parser.parse(&"€".as_bytes()[1..]));
+
+        let text: String = events
+            .iter()
+            .filter_map(|e| match e {
+                AnsiEvent::Print(c) => Some(*c),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(text, "Fá€");
+    }
+
     #[test]
     fn test_parse_csi_cursor_up() {
         let mut parser = AnsiParser::new();
@@ -442,11 +678,99 @@ mod tests {
             _ => panic!("Expected CSI dispatch"),
         }
     }
-}
 
+    fn osc_titles(events: &[AnsiEvent]) -> Vec<String> {
+        events
+            .iter()
+            .filter_map(|e| match e {
+                AnsiEvent::OscDispatch { params } => Some(
+                    params.iter().map(|p| String::from_utf8_lossy(p)).collect::<Vec<_>>().join(";"),
+                ),
+                _ => None,
+            })
+            .collect()
+    }
 
+    #[test]
+    fn test_osc_terminators() {
+        for seq in [&b"\x1b]0;title\x07"[..], b"\x1b]0;title\x1b\\", b"\x1b]0;title\x9c"] {
+            let mut parser = AnsiParser::new();
+            let mut events = parser.parse(seq);
+            events.extend(parser.parse(b"x"));
+            assert_eq!(osc_titles(&events), ["0;title"], "{:02X?}", seq);
+            // Nothing else is emitted for the terminator
+            assert_eq!(events.len(), 2);
+            assert!(matches!(events[1], AnsiEvent::Print('x')));
+        }
+    }
 
+    #[test]
+    fn test_osc_split_across_calls() {
+        let mut parser = AnsiParser::new();
+        let mut events = parser.parse(b"\x1b]2;my ti");
+        assert!(events.is_empty());
+        events.extend(parser.parse(b"tle\x1b"));
+        events.extend(parser.parse(b"\\"));
+        assert_eq!(osc_titles(&events), ["2;my title"]);
+        assert_eq!(events.len(), 1);
+    }
 
+    #[test]
+    fn test_osc_keeps_utf8_continuation_0x9c() {
+        // U+271C is E2 9C 9C
+        let mut parser = AnsiParser::new();
+        let mut seq = b"\x1b]0;".to_vec();
+        seq.extend_from_slice("\u{271C}".as_bytes());
+        seq.push(0x07);
+        assert_eq!(osc_titles(&parser.parse(&seq)), ["0;\u{271C}"]);
+    }
 
+    #[test]
+    fn test_osc_bare_esc_starts_new_sequence() {
+        let mut parser = AnsiParser::new();
+        let events = parser.parse(b"\x1b]0;t\x1b7");
+        assert_eq!(osc_titles(&events), ["0;t"]);
+        assert!(matches!(events[1], AnsiEvent::EscDispatch { action: b'7', .. }));
+    }
 
+    #[test]
+    fn test_osc_length_guard() {
+        let mut parser = AnsiParser::new();
+        let mut seq = b"\x1b]0;".to_vec();
+        seq.extend(std::iter::repeat(b'a').take(MAX_OSC_LEN + 10));
+        seq.extend_from_slice(b"\x07ok");
+        let events = parser.parse(&seq);
+        assert!(osc_titles(&events).is_empty());
+        assert_eq!(events.len(), 2);
+    }
 
+    #[test]
+    fn test_dcs_dispatch() {
+        let mut parser = AnsiParser::new();
+        let mut events = parser.parse(b"\x1bP0;1;0q#1~~");
+        events.extend(parser.parse(b"-~\x1b\\A"));
+        assert_eq!(events.len(), 2);
+        match &events[0] {
+            AnsiEvent::DcsDispatch { params, intermediates, action, data } => {
+                assert_eq!(params, &[0, 1, 0]);
+                assert!(intermediates.is_empty());
+                assert_eq!(*action, b'q');
+                assert_eq!(data, b"#1~~-~");
+            }
+            _ => panic!("Expected DCS dispatch"),
+        }
+        assert!(matches!(events[1], AnsiEvent::Print('A')));
+
+        // DECRQSS: DCS $ q m ST
+        let events = parser.parse(b"\x1bP$qm\x9c");
+        match &events[0] {
+            AnsiEvent::DcsDispatch { params, intermediates, action, data } => {
+                assert!(params.is_empty());
+                assert_eq!(intermediates, b"$");
+                assert_eq!(*action, b'q');
+                assert_eq!(data, b"m");
+            }
+            _ => panic!("Expected DCS dispatch"),
+        }
+    }
+}
@@ -1,7 +1,10 @@
 //! Terminal screen buffer
 
+use std::collections::VecDeque;
+
 use super::cell::{Cell, CellStyle};
 use super::color::Color;
+use super::selection::{Selection, SelectionMode, SelectionPoint};
 
 /// Screen mode
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -12,12 +15,18 @@ pub enum ScreenMode {
     Alternate,
 }
 
-/// Saved cursor state
+/// Cursor state saved by DECSC (ESC 7, CSI s, and entering mode 1049)
 #[derive(Debug, Clone, Default)]
 struct SavedCursor {
     row: u16,
     col: u16,
     style: CellStyle,
+    /// Active character set (GL selection)
+    charset: u8,
+    /// Origin mode (DECOM)
+    origin_mode: bool,
+    /// Cursor sat past the last column, with the wrap still pending
+    pending_wrap: bool,
 }
 
 /// Terminal screen buffer
@@ -30,8 +39,10 @@ pub struct Screen {
     cells: Vec<Cell>,
     /// Current cursor row (0-indexed)
     cursor_row: u16,
-    /// Current cursor column (0-indexed)
+    /// Current cursor column (0-indexed, always on screen)
     cursor_col: u16,
+    /// A glyph was written in the last column; the next one wraps first
+    pending_wrap: bool,
     /// Current cell style
     current_style: CellStyle,
     /// Cursor visible
@@ -42,6 +53,8 @@ pub struct Screen {
     insert_mode: bool,
     /// Newline mode (LF implies CR)
     newline_mode: bool,
+    /// Origin mode (DECOM): cursor rows are relative to the scroll region
+    origin_mode: bool,
     /// Scroll region top (0-indexed, inclusive)
     scroll_top: u16,
     /// Scroll region bottom (0-indexed, inclusive)
@@ -50,8 +63,37 @@ pub struct Screen {
     saved_cursor: SavedCursor,
     /// Current character set (0 = G0, 1 = G1)
     current_charset: u8,
-    /// Tab stops
-    tab_stops: Vec<u16>,
+    /// Tab stops, one flag per column
+    tab_stops: Vec<bool>,
+    /// Lines scrolled off the top, oldest first (trailing blanks trimmed)
+    scrollback: VecDeque<Vec<Cell>>,
+    /// Maximum scrollback lines
+    scrollback_limit: usize,
+    /// Maximum scrollback cell storage in bytes (None = unlimited)
+    scrollback_byte_limit: Option<usize>,
+    /// Cell storage currently held by the scrollback
+    scrollback_bytes: usize,
+    /// Scrollback lines dropped to stay within the limits
+    lines_evicted: u64,
+    /// Lines scrolled off the top of the screen
+    lines_scrolled_off: u64,
+    /// Copy-mode selection
+    selection: Option<Selection>,
+}
+
+/// Default tab stop spacing
+pub(super) const TAB_WIDTH: u16 = 8;
+
+/// Default scrollback line limit
+pub const DEFAULT_SCROLLBACK_LINES: usize = 10_000;
+
+/// Bytes of storage a scrollback line of `len` cells accounts for
+fn line_bytes(len: usize) -> usize {
+    len * std::mem::size_of::<Cell>()
+}
+
+fn default_tab_stops(cols: u16) -> Vec<bool> {
+    (0..cols).map(|c| c % TAB_WIDTH == 0).collect()
 }
 
 impl Screen {
@@ -60,7 +102,7 @@ impl Screen {
         let size = (cols as usize) * (rows as usize);
         
         // Default tab stops every 8 columns
-        let tab_stops: Vec<u16> = (0..cols).filter(|c| c % 8 == 0).collect();
+        let tab_stops = default_tab_stops(cols);
         
         Self {
             cols,
@@ -68,16 +110,25 @@ impl Screen {
             cells: vec![Cell::default(); size],
             cursor_row: 0,
             cursor_col: 0,
+            pending_wrap: false,
             current_style: CellStyle::default(),
             cursor_visible: true,
             auto_wrap: true,
             insert_mode: false,
             newline_mode: false,
+            origin_mode: false,
             scroll_top: 0,
             scroll_bottom: rows - 1,
             saved_cursor: SavedCursor::default(),
             current_charset: 0,
             tab_stops,
+            scrollback: VecDeque::new(),
+            scrollback_limit: DEFAULT_SCROLLBACK_LINES,
+            scrollback_byte_limit: None,
+            scrollback_bytes: 0,
+            lines_evicted: 0,
+            lines_scrolled_off: 0,
+            selection: None,
         }
     }
 
@@ -104,6 +155,7 @@ impl Screen {
         // Adjust cursor
         self.cursor_row = self.cursor_row.min(rows - 1);
         self.cursor_col = self.cursor_col.min(cols - 1);
+        self.pending_wrap = false;
         
         // Adjust scroll region
         self.scroll_bottom = rows - 1;
@@ -111,8 +163,10 @@ impl Screen {
             self.scroll_top = 0;
         }
 
-        // Update tab stops
-        self.tab_stops = (0..cols).filter(|c| c % 8 == 0).collect();
+        // Keep stops within the new width; new columns get default stops
+        let old_cols = self.tab_stops.len() as u16;
+        self.tab_stops.truncate(cols as usize);
+        self.tab_stops.extend((old_cols..cols).map(|c| c % TAB_WIDTH == 0));
     }
 
     /// Get cell at position
@@ -125,6 +179,13 @@ impl Screen {
         }
     }
 
+    /// Cells of a screen row
+    pub(super) fn row_cells(&self, row: u16) -> Option<&[Cell]> {
+        let cols = self.cols as usize;
+        let start = (row as usize) * cols;
+        (row < self.rows).then(|| &self.cells[start..start + cols])
+    }
+
     /// Get mutable cell at position
     fn cell_mut(&mut self, row: u16, col: u16) -> Option<&mut Cell> {
         if row < self.rows && col < self.cols {
@@ -136,15 +197,15 @@ impl Screen {
     }
 
     /// Put a character at cursor position
+    ///
+    /// Wrapping is deferred: a glyph written in the last column leaves the
+    /// cursor there and arms pending-wrap (with DECAWM on), and the wrap
+    /// happens only when the next glyph arrives. With DECAWM off further
+    /// glyphs overwrite the last column.
     pub fn put_char(&mut self, c: char) {
-        // Handle wrapping
-        if self.cursor_col >= self.cols {
-            if self.auto_wrap {
-                self.carriage_return();
-                self.linefeed();
-            } else {
-                self.cursor_col = self.cols - 1;
-            }
+        if self.pending_wrap {
+            self.carriage_return();
+            self.linefeed();
         }
 
         // Insert mode: shift characters right
@@ -161,44 +222,74 @@ impl Screen {
             cell.style = style;
         }
 
-        // Advance cursor
-        self.cursor_col += 1;
+        // Advance cursor, or arm the wrap at the right margin
+        if self.cursor_col + 1 < self.cols {
+            self.cursor_col += 1;
+        } else {
+            self.pending_wrap = self.auto_wrap;
+        }
+    }
+
+    /// Whether the next glyph wraps to a new line first
+    pub fn pending_wrap(&self) -> bool {
+        self.pending_wrap
     }
 
     /// Carriage return
     pub fn carriage_return(&mut self) {
         self.cursor_col = 0;
+        self.pending_wrap = false;
     }
 
     /// Line feed
     pub fn linefeed(&mut self) {
+        self.pending_wrap = false;
         if self.newline_mode {
             self.carriage_return();
         }
 
-        if self.cursor_row >= self.scroll_bottom {
+        // Only the bottom margin scrolls; below the region the cursor
+        // just moves down until the last row
+        if self.cursor_row == self.scroll_bottom {
             self.scroll_up(1);
-        } else {
+        } else if self.cursor_row + 1 < self.rows {
             self.cursor_row += 1;
         }
     }
 
     /// Reverse line feed
     pub fn reverse_linefeed(&mut self) {
-        if self.cursor_row <= self.scroll_top {
+        self.pending_wrap = false;
+        // Mirror of `linefeed`: scroll the region down at its top margin
+        if self.cursor_row == self.scroll_top {
             self.scroll_down(1);
-        } else {
+        } else if self.cursor_row > 0 {
             self.cursor_row -= 1;
         }
     }
 
     /// Move to next tab stop
     pub fn move_to_next_tab(&mut self) {
-        let next = self.tab_stops.iter()
-            .find(|&&t| t > self.cursor_col)
-            .copied()
+        let next = (self.cursor_col + 1..self.cols)
+            .find(|&c| self.tab_stops[c as usize])
             .unwrap_or(self.cols - 1);
-        self.cursor_col = next.min(self.cols - 1);
+        self.cursor_col = next;
+        self.pending_wrap = false;
+    }
+
+    /// Set a tab stop at the cursor column (HTS)
+    pub fn set_tab_stop(&mut self) {
+        self.tab_stops[self.cursor_col as usize] = true;
+    }
+
+    /// Clear the tab stop at the cursor column (TBC 0)
+    pub fn clear_tab_stop(&mut self) {
+        self.tab_stops[self.cursor_col as usize] = false;
+    }
+
+    /// Clear all tab stops (TBC 3)
+    pub fn clear_all_tab_stops(&mut self) {
+        self.tab_stops.fill(false);
     }
 
     /// Scroll up n lines (content moves up, blank lines at bottom)
@@ -212,8 +303,21 @@ impl Screen {
         let bottom = self.scroll_bottom as usize;
         let cols = self.cols as usize;
 
+        if top == 0 {
+            self.lines_scrolled_off += n as u64;
+        }
+
+        // Lines leaving the top of the screen go to the scrollback
+        if top == 0 && self.scrollback_limit > 0 {
+            for row in 0..n as usize {
+                let line = &self.cells[row * cols..(row + 1) * cols];
+                let len = line.iter().rposition(|c| !c.is_empty()).map_or(0, |i| i + 1);
+                self.push_scrollback(line[..len].to_vec());
+            }
+        }
+
         // Move lines up
-        for row in top..=bottom - (n as usize) {
+        for row in top..(bottom + 1 - n as usize) {
             let src_start = (row + n as usize) * cols;
             let dst_start = row * cols;
             for col in 0..cols {
@@ -222,7 +326,7 @@ impl Screen {
         }
 
         // Clear bottom lines
-        for row in (bottom - n as usize + 1)..=bottom {
+        for row in (bottom + 1 - n as usize)..=bottom {
             let start = row * cols;
             for col in 0..cols {
                 self.cells[start + col] = Cell::default();
@@ -262,31 +366,44 @@ impl Screen {
     /// Cursor movement
     pub fn move_cursor_up(&mut self, n: u16) {
         self.cursor_row = self.cursor_row.saturating_sub(n);
+        self.pending_wrap = false;
     }
 
     pub fn move_cursor_down(&mut self, n: u16) {
         self.cursor_row = (self.cursor_row + n).min(self.rows - 1);
+        self.pending_wrap = false;
     }
 
     pub fn move_cursor_left(&mut self, n: u16) {
         self.cursor_col = self.cursor_col.saturating_sub(n);
+        self.pending_wrap = false;
     }
 
     pub fn move_cursor_right(&mut self, n: u16) {
         self.cursor_col = (self.cursor_col + n).min(self.cols - 1);
+        self.pending_wrap = false;
     }
 
+    /// Move the cursor; in origin mode `row` is relative to the scroll region
     pub fn set_cursor_pos(&mut self, row: u16, col: u16) {
-        self.cursor_row = row.min(self.rows - 1);
+        self.set_cursor_row(row);
         self.cursor_col = col.min(self.cols - 1);
     }
 
+    /// Move the cursor to `row`; in origin mode it is relative to, and clamped
+    /// within, the scroll region
     pub fn set_cursor_row(&mut self, row: u16) {
-        self.cursor_row = row.min(self.rows - 1);
+        self.pending_wrap = false;
+        self.cursor_row = if self.origin_mode {
+            self.scroll_top.saturating_add(row).min(self.scroll_bottom)
+        } else {
+            row.min(self.rows - 1)
+        };
     }
 
     pub fn set_cursor_col(&mut self, col: u16) {
         self.cursor_col = col.min(self.cols - 1);
+        self.pending_wrap = false;
     }
 
     /// Erase operations
@@ -315,7 +432,8 @@ impl Screen {
     }
 
     pub fn erase_scrollback(&mut self) {
-        // No scrollback in basic implementation
+        self.scrollback.clear();
+        self.scrollback_bytes = 0;
     }
 
     pub fn erase_line_right(&mut self) {
@@ -406,6 +524,16 @@ impl Screen {
         }
     }
 
+    /// Style applied to newly written cells (SGR state)
+    pub fn current_style(&self) -> CellStyle {
+        self.current_style
+    }
+
+    /// Scroll region as (top, bottom), 0-indexed and inclusive
+    pub fn scroll_region(&self) -> (u16, u16) {
+        (self.scroll_top, self.scroll_bottom)
+    }
+
     /// Style operations
     pub fn reset_style(&mut self) {
         self.current_style = CellStyle::default();
@@ -457,23 +585,135 @@ impl Screen {
         let bottom = bottom.min(self.rows - 1).max(top);
         self.scroll_top = top;
         self.scroll_bottom = bottom;
-        self.cursor_row = 0;
-        self.cursor_col = 0;
+        self.set_cursor_pos(0, 0);
     }
 
-    /// Cursor save/restore
+    /// Save the full cursor state (DECSC): position, SGR attributes,
+    /// character set and pending wrap
     pub fn save_cursor(&mut self) {
         self.saved_cursor = SavedCursor {
             row: self.cursor_row,
             col: self.cursor_col,
             style: self.current_style,
+            charset: self.current_charset,
+            origin_mode: self.origin_mode,
+            pending_wrap: self.pending_wrap,
         };
     }
 
+    /// Restore the state saved by [`Screen::save_cursor`] (DECRC)
+    ///
+    /// Without a prior save this homes the cursor and resets attributes.
     pub fn restore_cursor(&mut self) {
-        self.cursor_row = self.saved_cursor.row.min(self.rows - 1);
-        self.cursor_col = self.saved_cursor.col.min(self.cols - 1);
-        self.current_style = self.saved_cursor.style;
+        let saved = &self.saved_cursor;
+        self.cursor_row = saved.row.min(self.rows - 1);
+        self.cursor_col = saved.col.min(self.cols - 1);
+        self.pending_wrap = saved.pending_wrap && saved.col == self.cols - 1;
+        self.current_style = saved.style;
+        self.current_charset = saved.charset;
+        self.origin_mode = saved.origin_mode;
+    }
+
+    /// Soft reset (DECSTR): modes, attributes, scroll region, character
+    /// set and saved cursor go back to defaults; cells, scrollback and the
+    /// cursor position are kept
+    pub fn soft_reset(&mut self) {
+        self.pending_wrap = false;
+        self.current_style = CellStyle::default();
+        self.cursor_visible = true;
+        self.auto_wrap = true;
+        self.insert_mode = false;
+        self.origin_mode = false;
+        self.scroll_top = 0;
+        self.scroll_bottom = self.rows - 1;
+        self.current_charset = 0;
+        self.saved_cursor = SavedCursor::default();
+    }
+
+    /// Scrollback
+    fn push_scrollback(&mut self, line: Vec<Cell>) {
+        self.scrollback_bytes += line_bytes(line.len());
+        self.scrollback.push_back(line);
+        self.enforce_scrollback_limits();
+    }
+
+    /// Drop the oldest lines until both the line and byte limits hold
+    fn enforce_scrollback_limits(&mut self) {
+        while self.scrollback.len() > self.scrollback_limit
+            || self.scrollback_byte_limit.is_some_and(|limit| self.scrollback_bytes > limit)
+        {
+            let Some(line) = self.scrollback.pop_front() else {
+                break;
+            };
+            self.scrollback_bytes -= line_bytes(line.len());
+            self.lines_evicted += 1;
+        }
+    }
+
+    /// Set the maximum number of scrollback lines (0 disables scrollback)
+    pub fn set_scrollback_limit(&mut self, lines: usize) {
+        self.scrollback_limit = lines;
+        self.enforce_scrollback_limits();
+    }
+
+    /// Cap the memory used by scrollback cells; oldest lines are evicted first
+    pub fn set_scrollback_byte_limit(&mut self, bytes: Option<usize>) {
+        self.scrollback_byte_limit = bytes;
+        self.enforce_scrollback_limits();
+    }
+
+    pub fn scrollback_limit(&self) -> usize {
+        self.scrollback_limit
+    }
+
+    pub fn scrollback_byte_limit(&self) -> Option<usize> {
+        self.scrollback_byte_limit
+    }
+
+    /// Number of lines in the scrollback
+    pub fn scrollback_len(&self) -> usize {
+        self.scrollback.len()
+    }
+
+    /// Scrollback line by index (0 = oldest); trailing blank cells are not stored
+    pub fn scrollback_line(&self, index: usize) -> Option<&[Cell]> {
+        self.scrollback.get(index).map(Vec::as_slice)
+    }
+
+    /// Bytes of cell storage held by the scrollback
+    pub fn scrollback_bytes(&self) -> usize {
+        self.scrollback_bytes
+    }
+
+    /// Total scrollback lines evicted by the line or byte limit
+    pub fn lines_evicted(&self) -> u64 {
+        self.lines_evicted
+    }
+
+    /// Total lines scrolled off the top of the screen
+    pub fn lines_scrolled_off(&self) -> u64 {
+        self.lines_scrolled_off
+    }
+
+    /// Current selection
+    pub fn selection(&self) -> Option<&Selection> {
+        self.selection.as_ref()
+    }
+
+    /// Start a selection at `point`
+    pub fn set_selection(&mut self, point: SelectionPoint, mode: SelectionMode) {
+        self.selection = Some(Selection::new(point, mode));
+    }
+
+    /// Move the free end of the selection to `point`
+    pub fn extend_selection(&mut self, point: SelectionPoint) {
+        if let Some(selection) = &mut self.selection {
+            selection.head = point;
+        }
+    }
+
+    pub fn clear_selection(&mut self) {
+        self.selection = None;
     }
 
     /// Mode setters
@@ -483,6 +723,7 @@ impl Screen {
 
     pub fn set_auto_wrap(&mut self, v: bool) {
         self.auto_wrap = v;
+        self.pending_wrap &= v;
     }
 
     pub fn set_insert_mode(&mut self, v: bool) {
@@ -493,6 +734,12 @@ impl Screen {
         self.newline_mode = v;
     }
 
+    /// Set origin mode (DECOM); the cursor moves to the new home position
+    pub fn set_origin_mode(&mut self, v: bool) {
+        self.origin_mode = v;
+        self.set_cursor_pos(0, 0);
+    }
+
     pub fn set_charset(&mut self, charset: u8) {
         self.current_charset = charset;
     }
@@ -514,6 +761,14 @@ impl Screen {
         self.cursor_visible
     }
 
+    pub fn charset(&self) -> u8 {
+        self.current_charset
+    }
+
+    pub fn origin_mode(&self) -> bool {
+        self.origin_mode
+    }
+
     /// Get line as string
     pub fn line_text(&self, row: u16) -> String {
         if row >= self.rows {
@@ -540,3 +795,243 @@ impl Screen {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 10x6 screen with each row labelled by its index
+    fn labelled_screen() -> Screen {
+        let mut screen = Screen::new(10, 6);
+        for row in 0..6 {
+            screen.set_cursor_pos(row, 0);
+            screen.put_char(char::from(b'0' + row as u8));
+        }
+        screen
+    }
+
+    fn rows(screen: &Screen) -> Vec<String> {
+        (0..screen.rows()).map(|r| screen.line_text(r)).collect()
+    }
+
+    #[test]
+    fn test_save_restore_cursor_preserves_sgr_and_charset() {
+        let mut screen = Screen::new(10, 6);
+        screen.set_cursor_pos(2, 3);
+        screen.set_bold(true);
+        screen.set_fg_color(Color::Indexed(1));
+        screen.set_charset(1);
+        screen.save_cursor();
+
+        screen.reset_style();
+        screen.set_charset(0);
+        screen.set_cursor_pos(5, 9);
+        screen.restore_cursor();
+
+        assert_eq!(screen.cursor_pos(), (2, 3));
+        assert_eq!(screen.charset(), 1);
+        screen.put_char('X');
+        let style = screen.cell(2, 3).unwrap().style;
+        assert!(style.bold);
+        assert_eq!(style.fg, Color::Indexed(1));
+    }
+
+    #[test]
+    fn test_pending_wrap_at_right_margin() {
+        let mut screen = Screen::new(4, 3);
+        for c in "abcd".chars() {
+            screen.put_char(c);
+        }
+        // The cursor stays on the last column until the next glyph
+        assert_eq!(screen.cursor_pos(), (0, 3));
+        assert!(screen.pending_wrap());
+
+        screen.put_char('e');
+        assert_eq!(rows(&screen), ["abcd", "e", ""]);
+        assert_eq!(screen.cursor_pos(), (1, 1));
+        assert!(!screen.pending_wrap());
+    }
+
+    #[test]
+    fn test_cursor_motion_cancels_pending_wrap() {
+        let mut screen = Screen::new(4, 3);
+        for c in "abcd".chars() {
+            screen.put_char(c);
+        }
+        // Backspace from the armed state moves off the last column
+        screen.move_cursor_left(1);
+        screen.put_char('X');
+        assert_eq!(rows(&screen), ["abXd", "", ""]);
+
+        screen.put_char('Y');
+        screen.carriage_return();
+        screen.put_char('Z');
+        assert_eq!(rows(&screen), ["ZbXY", "", ""]);
+    }
+
+    #[test]
+    fn test_no_auto_wrap_overwrites_last_column() {
+        let mut screen = Screen::new(4, 3);
+        screen.set_auto_wrap(false);
+        for c in "abcdef".chars() {
+            screen.put_char(c);
+        }
+        assert_eq!(rows(&screen), ["abcf", "", ""]);
+        assert_eq!(screen.cursor_pos(), (0, 3));
+        assert!(!screen.pending_wrap());
+    }
+
+    #[test]
+    fn test_save_restore_cursor_keeps_pending_wrap() {
+        let mut screen = Screen::new(4, 3);
+        for c in "abcd".chars() {
+            screen.put_char(c);
+        }
+        screen.save_cursor();
+        screen.set_cursor_pos(2, 0);
+        screen.restore_cursor();
+
+        // The wrap is still pending, so the next character starts row 1
+        screen.put_char('e');
+        assert_eq!(rows(&screen), ["abcd", "e", ""]);
+    }
+
+    #[test]
+    fn test_origin_mode_addresses_within_scroll_region() {
+        let mut screen = Screen::new(10, 6);
+        screen.set_scroll_region(2, 4);
+        assert_eq!(screen.cursor_pos(), (0, 0));
+
+        screen.set_origin_mode(true);
+        assert_eq!(screen.cursor_pos(), (2, 0));
+
+        screen.set_cursor_pos(1, 3);
+        assert_eq!(screen.cursor_pos(), (3, 3));
+
+        // Rows past the bottom margin clamp to it
+        screen.set_cursor_row(9);
+        assert_eq!(screen.cursor_pos(), (4, 3));
+
+        screen.set_origin_mode(false);
+        assert_eq!(screen.cursor_pos(), (0, 0));
+        screen.set_cursor_pos(5, 0);
+        assert_eq!(screen.cursor_pos(), (5, 0));
+    }
+
+    fn scrollback_text(screen: &Screen) -> Vec<String> {
+        (0..screen.scrollback_len())
+            .map(|i| screen.scrollback_line(i).unwrap().iter().map(|c| c.c).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_scrollback_byte_limit_evicts_oldest() {
+        let mut screen = labelled_screen();
+        let cell = std::mem::size_of::<Cell>();
+        screen.set_scrollback_byte_limit(Some(3 * cell));
+
+        // Each row holds one non-blank cell
+        screen.set_cursor_pos(5, 0);
+        for _ in 0..5 {
+            screen.linefeed();
+        }
+
+        assert_eq!(scrollback_text(&screen), ["2", "3", "4"]);
+        assert_eq!(screen.scrollback_bytes(), 3 * cell);
+        assert_eq!(screen.lines_evicted(), 2);
+
+        screen.set_scrollback_byte_limit(Some(cell));
+        assert_eq!(scrollback_text(&screen), ["4"]);
+        assert_eq!(screen.lines_evicted(), 4);
+
+        screen.erase_scrollback();
+        assert_eq!(screen.scrollback_bytes(), 0);
+        assert_eq!(screen.lines_evicted(), 4);
+    }
+
+    #[test]
+    fn test_scroll_region_below_top_skips_scrollback() {
+        let mut screen = labelled_screen();
+        screen.set_scroll_region(1, 3);
+        screen.set_cursor_pos(3, 0);
+        screen.linefeed();
+        assert_eq!(screen.scrollback_len(), 0);
+    }
+
+    #[test]
+    fn test_linefeed_scrolls_region_at_bottom_margin() {
+        let mut screen = labelled_screen();
+        screen.set_scroll_region(1, 3);
+        screen.set_cursor_pos(3, 0);
+        screen.linefeed();
+
+        assert_eq!(screen.cursor_pos(), (3, 0));
+        assert_eq!(rows(&screen), ["0", "2", "3", "", "4", "5"]);
+    }
+
+    #[test]
+    fn test_reverse_linefeed_scrolls_region_at_top_margin() {
+        let mut screen = labelled_screen();
+        screen.set_scroll_region(1, 3);
+        screen.set_cursor_pos(1, 0);
+        screen.reverse_linefeed();
+
+        assert_eq!(screen.cursor_pos(), (1, 0));
+        assert_eq!(rows(&screen), ["0", "", "1", "2", "4", "5"]);
+    }
+
+    #[test]
+    fn test_linefeed_outside_region_does_not_scroll() {
+        let mut screen = labelled_screen();
+        screen.set_scroll_region(1, 3);
+
+        // Below the region: move down, stop at the last row
+        screen.set_cursor_pos(4, 0);
+        screen.linefeed();
+        screen.linefeed();
+        assert_eq!(screen.cursor_pos(), (5, 0));
+
+        // Above the region: move up, stop at the first row
+        screen.set_cursor_pos(0, 0);
+        screen.reverse_linefeed();
+        assert_eq!(screen.cursor_pos(), (0, 0));
+
+        assert_eq!(rows(&screen), ["0", "1", "2", "3", "4", "5"]);
+    }
+
+    #[test]
+    fn test_custom_tab_stops() {
+        let mut screen = Screen::new(20, 2);
+        screen.clear_all_tab_stops();
+        for col in [4, 8, 12] {
+            screen.set_cursor_col(col);
+            screen.set_tab_stop();
+        }
+
+        screen.set_cursor_col(0);
+        screen.move_to_next_tab();
+        assert_eq!(screen.cursor_pos(), (0, 4));
+
+        screen.set_cursor_col(8);
+        screen.clear_tab_stop();
+        screen.set_cursor_col(4);
+        screen.move_to_next_tab();
+        assert_eq!(screen.cursor_pos(), (0, 12));
+
+        // No more stops: go to the last column
+        screen.move_to_next_tab();
+        assert_eq!(screen.cursor_pos(), (0, 19));
+
+        // Growing keeps custom stops and adds defaults past the old width
+        screen.resize(30, 2);
+        screen.set_cursor_col(12);
+        screen.move_to_next_tab();
+        assert_eq!(screen.cursor_pos(), (0, 24));
+    }
+
+    #[test]
+    fn test_scroll_whole_region() {
+        let mut screen = labelled_screen();
+        screen.scroll_up(6);
+        assert!(rows(&screen).iter().all(String::is_empty));
+    }
+}
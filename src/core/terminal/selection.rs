@@ -0,0 +1,186 @@
+//! Copy-mode selection
+//!
+//! Selections are addressed by absolute line number, counted from the first
+//! line that ever scrolled off the screen, so they stay on the same text as
+//! output scrolls and scrollback is evicted.
+
+use super::cell::Cell;
+use super::screen::Screen;
+
+/// A cell in the combined scrollback and screen buffer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SelectionPoint {
+    /// Absolute line number
+    pub line: u64,
+    /// Column (0-indexed)
+    pub col: u16,
+}
+
+impl SelectionPoint {
+    /// Create a point
+    pub fn new(line: u64, col: u16) -> Self {
+        Self { line, col }
+    }
+}
+
+/// How the cells between the two ends are selected
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SelectionMode {
+    /// Running text from one end to the other
+    #[default]
+    Normal,
+    /// Whole lines
+    Line,
+    /// Rectangle with the two ends as corners
+    Block,
+}
+
+/// Selected region
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Selection {
+    /// Where the selection started
+    pub anchor: SelectionPoint,
+    /// End that follows the pointer
+    pub head: SelectionPoint,
+    /// Selection mode
+    pub mode: SelectionMode,
+}
+
+impl Selection {
+    /// Empty selection at `point`
+    pub fn new(point: SelectionPoint, mode: SelectionMode) -> Self {
+        Self {
+            anchor: point,
+            head: point,
+            mode,
+        }
+    }
+
+    /// Ends in reading order
+    pub fn ordered(&self) -> (SelectionPoint, SelectionPoint) {
+        (self.anchor.min(self.head), self.anchor.max(self.head))
+    }
+
+    /// Columns selected on `line` (inclusive), or `None` if it is outside
+    pub fn columns(&self, line: u64) -> Option<(u16, u16)> {
+        let (start, end) = self.ordered();
+        if line < start.line || line > end.line {
+            return None;
+        }
+        match self.mode {
+            SelectionMode::Line => Some((0, u16::MAX)),
+            SelectionMode::Block => {
+                let (a, b) = (self.anchor.col, self.head.col);
+                Some((a.min(b), a.max(b)))
+            }
+            SelectionMode::Normal => {
+                let first = if line == start.line { start.col } else { 0 };
+                let last = if line == end.line { end.col } else { u16::MAX };
+                Some((first, last))
+            }
+        }
+    }
+
+    /// Check if the cell at `point` is selected
+    pub fn contains(&self, point: SelectionPoint) -> bool {
+        self.columns(point.line)
+            .is_some_and(|(first, last)| (first..=last).contains(&point.col))
+    }
+}
+
+impl Screen {
+    /// Absolute line number of the oldest line still held
+    pub fn first_line(&self) -> u64 {
+        self.lines_scrolled_off() - self.scrollback_len() as u64
+    }
+
+    /// Selection point for a screen cell
+    pub fn screen_point(&self, row: u16, col: u16) -> SelectionPoint {
+        SelectionPoint::new(self.lines_scrolled_off() + row as u64, col)
+    }
+
+    /// Selection point for a scrollback cell (0 = oldest line)
+    pub fn scrollback_point(&self, index: usize, col: u16) -> SelectionPoint {
+        SelectionPoint::new(self.first_line() + index as u64, col)
+    }
+
+    /// Cells of an absolute line, if it is still held
+    fn line_cells(&self, line: u64) -> Option<&[Cell]> {
+        let index = usize::try_from(line.checked_sub(self.first_line())?).ok()?;
+        match index.checked_sub(self.scrollback_len()) {
+            None => self.scrollback_line(index),
+            Some(row) => u16::try_from(row).ok().and_then(|row| self.row_cells(row)),
+        }
+    }
+
+    /// Text of the selection, one line per buffer line with trailing blanks trimmed
+    ///
+    /// Lines evicted from the scrollback since the selection was made are skipped.
+    pub fn selected_text(&self) -> Option<String> {
+        let selection = self.selection()?;
+        let (start, end) = selection.ordered();
+        let lines: Vec<String> = (start.line.max(self.first_line())..=end.line)
+            .map_while(|line| {
+                let cells = self.line_cells(line)?;
+                let (first, last) = selection.columns(line)?;
+                let first = (first as usize).min(cells.len());
+                let last = (last as usize).saturating_add(1).min(cells.len());
+                let text: String = cells[first..last].iter().map(|cell| cell.c).collect();
+                Some(text.trim_end().to_string())
+            })
+            .collect();
+        Some(lines.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 6x3 screen with "line N" rows scrolled through it
+    fn scrolled_screen(lines: u8) -> Screen {
+        let mut screen = Screen::new(6, 3);
+        for n in 0..lines {
+            if n > 0 {
+                screen.carriage_return();
+                screen.linefeed();
+            }
+            for c in format!("ln {}", n).chars() {
+                screen.put_char(c);
+            }
+        }
+        screen
+    }
+
+    #[test]
+    fn test_normal_selection_spans_scrollback() {
+        let mut screen = scrolled_screen(5);
+        assert_eq!(screen.scrollback_len(), 2);
+
+        screen.set_selection(screen.scrollback_point(1, 3), SelectionMode::Normal);
+        screen.extend_selection(screen.screen_point(1, 1));
+        assert_eq!(screen.selected_text().as_deref(), Some("1\nln 2\nln"));
+
+        // More output scrolls the selected text but the selection follows it
+        screen.carriage_return();
+        screen.linefeed();
+        assert_eq!(screen.selected_text().as_deref(), Some("1\nln 2\nln"));
+    }
+
+    #[test]
+    fn test_line_and_block_selection() {
+        let mut screen = scrolled_screen(3);
+        screen.set_selection(screen.screen_point(2, 4), SelectionMode::Line);
+        screen.extend_selection(screen.screen_point(1, 1));
+        assert_eq!(screen.selected_text().as_deref(), Some("ln 1\nln 2"));
+
+        screen.set_selection(screen.screen_point(0, 3), SelectionMode::Block);
+        screen.extend_selection(screen.screen_point(2, 1));
+        assert_eq!(screen.selected_text().as_deref(), Some("n 0\nn 1\nn 2"));
+        assert!(screen.selection().unwrap().contains(screen.screen_point(1, 2)));
+        assert!(!screen.selection().unwrap().contains(screen.screen_point(1, 0)));
+
+        screen.clear_selection();
+        assert_eq!(screen.selected_text(), None);
+    }
+}
@@ -0,0 +1,201 @@
+//! Toolkit-independent render model
+//!
+//! Converts the cell grid into styled spans so any frontend (egui, a TUI,
+//! a test harness) can draw the terminal without re-parsing escape codes.
+
+use super::cell::{Cell, CellStyle};
+use super::screen::{Screen, TAB_WIDTH};
+use super::{Terminal, TerminalSize};
+
+/// A run of text sharing one style
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StyledSpan {
+    /// Text content
+    pub text: String,
+    /// Style of every character in the span
+    pub style: CellStyle,
+}
+
+/// A single rendered line
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StyledLine {
+    /// Spans in display order
+    pub spans: Vec<StyledSpan>,
+}
+
+impl StyledLine {
+    /// Build a line from a row of cells
+    ///
+    /// Adjacent cells with the same style are merged; trailing blank cells
+    /// with the default style are dropped.
+    pub fn from_cells(cells: &[Cell]) -> Self {
+        let len = cells
+            .iter()
+            .rposition(|c| !c.is_empty())
+            .map_or(0, |i| i + 1);
+
+        let mut spans: Vec<StyledSpan> = Vec::new();
+        for cell in &cells[..len] {
+            match spans.last_mut() {
+                Some(span) if span.style == cell.style => span.text.push(cell.c),
+                _ => spans.push(StyledSpan {
+                    text: cell.c.to_string(),
+                    style: cell.style,
+                }),
+            }
+        }
+
+        Self { spans }
+    }
+
+    /// Render a single line of ANSI text with the core emulator
+    ///
+    /// See [`LineStyler`] for styling a stream of lines.
+    pub fn from_ansi(text: &str) -> Self {
+        LineStyler::new().style(text)
+    }
+
+    /// Plain text of the line
+    pub fn text(&self) -> String {
+        self.spans.iter().map(|s| s.text.as_str()).collect()
+    }
+
+    /// Check if the line has no visible content
+    pub fn is_empty(&self) -> bool {
+        self.spans.is_empty()
+    }
+}
+
+/// Styles a stream of text lines with one emulator
+///
+/// For line-oriented views that still want SGR styling. Attributes set on
+/// one line stay in effect on the next, as on a real terminal. Cursor
+/// movement is confined to the line, CR/LF are ignored and tabs expand to
+/// the default tab stops.
+pub struct LineStyler {
+    terminal: Terminal,
+}
+
+impl LineStyler {
+    /// Create a styler with default attributes
+    pub fn new() -> Self {
+        Self {
+            terminal: Terminal::with_size(TerminalSize::new(1, 1)),
+        }
+    }
+
+    /// Style the next line
+    pub fn style(&mut self, text: &str) -> StyledLine {
+        // Wide enough that every tab reaches its stop
+        let width: usize = text
+            .chars()
+            .map(|c| if c == '\t' { usize::from(TAB_WIDTH) } else { 1 })
+            .sum();
+        let cols = u16::try_from(width.max(1)).unwrap_or(u16::MAX);
+        if cols != self.terminal.size().cols {
+            self.terminal.resize(TerminalSize::new(cols, 1));
+        }
+
+        let filtered: Vec<u8> = text
+            .bytes()
+            .filter(|&b| b != b'\r' && b != b'\n')
+            .collect();
+        self.terminal.process(&filtered);
+        let line = self.terminal.screen().styled_line(0);
+
+        // Blank the row for the next line; the SGR state is kept
+        self.terminal.process(b"\r\x1b[2K");
+        line
+    }
+}
+
+impl Default for LineStyler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Snapshot of everything a frontend needs to draw the terminal
+#[derive(Debug, Clone, Default)]
+pub struct TerminalView {
+    /// Visible lines, top to bottom
+    pub lines: Vec<StyledLine>,
+    /// Cursor position (row, col)
+    pub cursor: (u16, u16),
+    /// Whether the cursor should be drawn
+    pub cursor_visible: bool,
+    /// Window title set via OSC
+    pub title: String,
+}
+
+impl Screen {
+    /// Get a row as styled spans
+    pub fn styled_line(&self, row: u16) -> StyledLine {
+        let cells: Vec<Cell> = (0..self.cols())
+            .filter_map(|col| self.cell(row, col).copied())
+            .collect();
+        StyledLine::from_cells(&cells)
+    }
+}
+
+impl Terminal {
+    /// Render the visible screen as styled lines
+    pub fn render_lines(&self) -> Vec<StyledLine> {
+        let screen = self.screen();
+        (0..screen.rows()).map(|row| screen.styled_line(row)).collect()
+    }
+
+    /// Take a render snapshot of the terminal
+    pub fn view(&self) -> TerminalView {
+        let screen = self.screen();
+        TerminalView {
+            lines: self.render_lines(),
+            cursor: screen.cursor_pos(),
+            cursor_visible: screen.cursor_visible(),
+            title: self.title().to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::terminal::{Color, NamedColor};
+
+    #[test]
+    fn test_render_lines_merges_styles() {
+        let mut terminal = Terminal::with_size(TerminalSize::new(20, 2));
+        terminal.process(b"ab\x1b[31mcd\x1b[0m");
+
+        let lines = terminal.render_lines();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].spans.len(), 2);
+        assert_eq!(lines[0].spans[0].text, "ab");
+        assert_eq!(lines[0].spans[1].text, "cd");
+        assert_eq!(lines[0].spans[1].style.fg, Color::Named(NamedColor::Red));
+        assert!(lines[1].is_empty());
+    }
+
+    #[test]
+    fn test_from_ansi() {
+        let line = StyledLine::from_ansi("\x1b[01;32muser\x1b[0m@\x1b[01;34mhost\x1b[0m\r\n");
+        assert_eq!(line.text(), "user@host");
+        assert_eq!(line.spans.len(), 3);
+        assert!(line.spans[0].style.bold);
+    }
+
+    #[test]
+    fn test_line_styler_keeps_tabs_and_state() {
+        let mut styler = LineStyler::new();
+        assert_eq!(styler.style("a\tb\tc").text(), format!("a{}b{}c", " ".repeat(7), " ".repeat(7)));
+
+        // Color set on one line carries over to the next until reset
+        let first = styler.style("\x1b[31mred");
+        let second = styler.style("still red\x1b[0m plain");
+        assert_eq!(first.spans[0].style.fg, Color::Named(NamedColor::Red));
+        assert_eq!(second.spans[0].text, "still red");
+        assert_eq!(second.spans[0].style.fg, Color::Named(NamedColor::Red));
+        assert_eq!(second.spans[1].style.fg, Color::Default);
+        assert_eq!(styler.style("short").text(), "short");
+    }
+}
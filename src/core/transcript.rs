@@ -0,0 +1,285 @@
+//! Session transcripts
+//!
+//! Renders recorded packets as readable text for bug reports and lab
+//! notebooks: sent lines are prefixed with `> `, received lines are shown
+//! as is, with escape sequences removed. Session bookmarks show up as
+//! `-- label --` lines.
+
+use crate::core::packet::{Packet, PacketDirection};
+use crate::core::transform::{DataTransform, StripAnsi};
+use chrono::{DateTime, Local};
+use std::borrow::Cow;
+use uuid::Uuid;
+
+/// Output markup of a transcript
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TranscriptStyle {
+    /// Plain text
+    #[default]
+    Text,
+    /// Markdown heading and fenced code block
+    Markdown,
+}
+
+/// How a transcript is rendered
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TranscriptFormat {
+    /// Output markup
+    pub style: TranscriptStyle,
+    /// Prefix each line with the time it started
+    pub timestamps: bool,
+}
+
+impl TranscriptFormat {
+    /// Plain text without timestamps
+    pub fn text() -> Self {
+        Self::default()
+    }
+
+    /// Markdown without timestamps
+    pub fn markdown() -> Self {
+        Self {
+            style: TranscriptStyle::Markdown,
+            timestamps: false,
+        }
+    }
+
+    /// Prefix lines with timestamps
+    #[must_use]
+    pub fn with_timestamps(mut self) -> Self {
+        self.timestamps = true;
+        self
+    }
+}
+
+/// Named marker at a point in a session's history
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bookmark {
+    /// Unique ID
+    pub id: Uuid,
+    /// Label, e.g. "started test 3"
+    pub label: String,
+    /// Sequence number of the first packet after the marker ([`Packet::seq`]),
+    /// by which other views of the history place it
+    pub seq: u64,
+    /// When the marker was added
+    pub timestamp: DateTime<Local>,
+}
+
+impl Bookmark {
+    /// Marker placed before the packet with sequence number `seq`
+    pub fn new(label: &str, seq: u64) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            label: label.to_string(),
+            seq,
+            timestamp: Local::now(),
+        }
+    }
+}
+
+/// Render `packets` as a transcript titled `title`
+///
+/// Lines are split on LF with CR dropped. A line that is still open when
+/// the direction changes is ended there.
+pub fn render<'a>(title: &str, packets: impl IntoIterator<Item = &'a Packet>, format: TranscriptFormat) -> String {
+    render_annotated(title, packets, &[], format)
+}
+
+/// Render `packets` with `bookmarks` shown as `-- label --` lines
+///
+/// Bookmarks must be ordered by sequence number. Each one goes before the
+/// first packet at or after it; one that falls inside a line goes after
+/// that line. Bookmarks that point before the oldest packet are left out.
+pub fn render_annotated<'a>(
+    title: &str,
+    packets: impl IntoIterator<Item = &'a Packet>,
+    bookmarks: &[Bookmark],
+    format: TranscriptFormat,
+) -> String {
+    let body = build(packets, bookmarks, format, true).lines.join("\n");
+    match format.style {
+        TranscriptStyle::Text => body,
+        TranscriptStyle::Markdown => format!("### {}\n\n```text\n{}\n```\n", title, body),
+    }
+}
+
+/// Transcript lines of `packets`, with escape sequences removed if `strip_ansi`
+pub(crate) fn lines<'a>(packets: impl IntoIterator<Item = &'a Packet>, strip_ansi: bool) -> Vec<String> {
+    build(packets, &[], TranscriptFormat::text(), strip_ansi).lines
+}
+
+fn build<'a, 'b>(
+    packets: impl IntoIterator<Item = &'a Packet>,
+    bookmarks: &'b [Bookmark],
+    format: TranscriptFormat,
+    strip_ansi: bool,
+) -> TranscriptBuilder<'b> {
+    let mut builder = TranscriptBuilder {
+        format,
+        rx_strip: strip_ansi.then(StripAnsi::new),
+        tx_strip: strip_ansi.then(StripAnsi::new),
+        current: None,
+        lines: Vec::new(),
+        bookmarks,
+        next_bookmark: 0,
+        started: false,
+        deferred: Vec::new(),
+    };
+    for packet in packets {
+        builder.push(packet);
+    }
+    builder.finish_line();
+    builder.place_bookmarks(None);
+    builder
+}
+
+/// Line being assembled: direction, start time and text
+type OpenLine = (PacketDirection, DateTime<Local>, String);
+
+struct TranscriptBuilder<'b> {
+    format: TranscriptFormat,
+    rx_strip: Option<StripAnsi>,
+    tx_strip: Option<StripAnsi>,
+    current: Option<OpenLine>,
+    lines: Vec<String>,
+    bookmarks: &'b [Bookmark],
+    next_bookmark: usize,
+    /// A packet was seen, so earlier bookmarks are no longer dropped
+    started: bool,
+    /// Bookmarks that fell inside the open line, shown once it ends
+    deferred: Vec<&'b Bookmark>,
+}
+
+impl TranscriptBuilder<'_> {
+    fn push(&mut self, packet: &Packet) {
+        self.place_bookmarks(Some(packet.seq));
+        self.started = true;
+
+        let direction = match packet.direction {
+            PacketDirection::Tx => PacketDirection::Tx,
+            _ => PacketDirection::Rx,
+        };
+        if self.current.as_ref().is_some_and(|(open, _, _)| *open != direction) {
+            self.finish_line();
+        }
+
+        let strip = match direction {
+            PacketDirection::Tx => self.tx_strip.as_mut(),
+            _ => self.rx_strip.as_mut(),
+        };
+        let data = match strip {
+            Some(strip) => Cow::Owned(strip.transform(&packet.data)),
+            None => Cow::Borrowed(&packet.data[..]),
+        };
+        let text = String::from_utf8_lossy(&data);
+        let mut parts = text.split('\n').peekable();
+        while let Some(part) = parts.next() {
+            let last = parts.peek().is_none();
+            if last && part.is_empty() {
+                break;
+            }
+            let (_, _, line) = self
+                .current
+                .get_or_insert_with(|| (direction, packet.timestamp, String::new()));
+            line.extend(part.chars().filter(|&c| c != '\r'));
+            if !last {
+                self.finish_line();
+            }
+        }
+    }
+
+    /// Add the bookmarks up to packet `before` (all if None)
+    fn place_bookmarks(&mut self, before: Option<u64>) {
+        while let Some(bookmark) = self.bookmarks.get(self.next_bookmark) {
+            if before.is_some_and(|seq| bookmark.seq > seq) {
+                break;
+            }
+            self.next_bookmark += 1;
+            if !self.started && before.is_some_and(|seq| bookmark.seq < seq) {
+                continue;
+            }
+            if self.current.is_some() {
+                self.deferred.push(bookmark);
+            } else {
+                self.push_bookmark(bookmark);
+            }
+        }
+    }
+
+    fn push_bookmark(&mut self, bookmark: &Bookmark) {
+        let mut line = self.line_start(bookmark.timestamp);
+        line.push_str(&format!("-- {} --", bookmark.label));
+        self.lines.push(line);
+    }
+
+    fn line_start(&self, timestamp: DateTime<Local>) -> String {
+        if self.format.timestamps {
+            format!("[{}] ", timestamp.format("%H:%M:%S%.3f"))
+        } else {
+            String::new()
+        }
+    }
+
+    fn finish_line(&mut self) {
+        let Some((direction, timestamp, text)) = self.current.take() else {
+            return;
+        };
+        let mut line = self.line_start(timestamp);
+        if direction == PacketDirection::Tx {
+            line.push_str("> ");
+        }
+        line.push_str(&text);
+        self.lines.push(line);
+        for bookmark in std::mem::take(&mut self.deferred) {
+            self.push_bookmark(bookmark);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interleaves_directions() {
+        let packets = [
+            Packet::tx(b"AT+GMR\r\n".to_vec()),
+            Packet::rx(b"AT+GMR\r\n\x1b[1mv1".to_vec()),
+            Packet::rx(b".2\x1b[0m\r\nOK\r\n".to_vec()),
+            Packet::tx(b"ver".to_vec()),
+            Packet::rx(b"sion?".to_vec()),
+        ];
+        assert_eq!(
+            render("dev", &packets, TranscriptFormat::text()),
+            "> AT+GMR\nAT+GMR\nv1.2\nOK\n> ver\nsion?"
+        );
+
+        let markdown = render("dev", &packets[..1], TranscriptFormat::markdown().with_timestamps());
+        assert!(markdown.starts_with("### dev\n\n```text\n["));
+        assert!(markdown.ends_with("] > AT+GMR\n```\n"));
+    }
+
+    #[test]
+    fn test_bookmarks_become_annotation_lines() {
+        let mut history = crate::core::packet::PacketBuffer::new(3);
+        for packet in [
+            Packet::rx(b"old\n".to_vec()),
+            Packet::tx(b"go\n".to_vec()),
+            Packet::rx(b"runn".to_vec()),
+            Packet::rx(b"ing\n".to_vec()),
+        ] {
+            history.push(packet);
+        }
+        let bookmarks = [
+            Bookmark::new("dropped", 0),
+            Bookmark::new("test 3", 3),
+            Bookmark::new("end", 4),
+        ];
+
+        assert_eq!(
+            render_annotated("dev", history.all(), &bookmarks, TranscriptFormat::text()),
+            "> go\nrunning\n-- test 3 --\n-- end --"
+        );
+    }
+}
@@ -5,8 +5,41 @@
 //! - YMODEM (batch)
 //! - ZMODEM (with auto-start)
 
+use crate::core::transport::TransportError;
 use std::io::{Read, Write};
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+
+/// File transfer error
+#[derive(Debug, Error)]
+pub enum TransferError {
+    /// Port or file I/O failed
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// Transport failed during the transfer
+    #[error(transparent)]
+    Transport(#[from] TransportError),
+
+    /// Cancelled by the named party (sender, receiver, local, shutdown)
+    #[error("Transfer cancelled by {0}")]
+    Cancelled(&'static str),
+
+    /// Blocks kept failing their checksum/CRC
+    #[error("Too many corrupted blocks ({0} retries)")]
+    Checksum(u32),
+
+    /// The peer stopped responding
+    #[error("Timed out {0}")]
+    Timeout(&'static str),
+
+    /// Unexpected or malformed protocol data
+    #[error("Protocol error: {0}")]
+    Protocol(String),
+}
 
 /// Transfer protocol type
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -88,6 +121,15 @@ const CAN: u8 = 0x18;  // Cancel
 const SUB: u8 = 0x1A;  // Padding character (Ctrl-Z)
 const CRC: u8 = 0x43;  // 'C' for CRC mode
 
+/// Number of 'C' requests sent before falling back to checksum mode
+const XMODEM_CRC_ATTEMPTS: u32 = 3;
+/// Number of NAK requests sent in checksum mode before giving up
+const XMODEM_NAK_ATTEMPTS: u32 = 10;
+/// Maximum retries per block
+const XMODEM_MAX_RETRIES: u32 = 10;
+/// Timeout for the bytes of a block once its header was seen
+const XMODEM_BLOCK_TIMEOUT: Duration = Duration::from_secs(10);
+
 /// XMODEM transfer handler
 pub struct XmodemTransfer {
     protocol: TransferProtocol,
@@ -95,6 +137,7 @@ pub struct XmodemTransfer {
     progress: TransferProgress,
     use_crc: bool,
     block_size: usize,
+    start_timeout: Duration,
 }
 
 impl XmodemTransfer {
@@ -111,14 +154,109 @@ impl XmodemTransfer {
             progress: TransferProgress::default(),
             use_crc: true,
             block_size,
+            start_timeout: Duration::from_secs(3),
         }
     }
 
+    /// Set how long to wait for the peer after each start request
+    ///
+    /// On receive this is the wait after each `C`/`NAK`; on send the sender
+    /// waits up to `XMODEM_NAK_ATTEMPTS` times this long for the receiver.
+    pub fn set_start_timeout(&mut self, timeout: Duration) {
+        self.start_timeout = timeout;
+    }
+
     /// Get current progress
     pub fn progress(&self) -> &TransferProgress {
         &self.progress
     }
 
+    /// Whether CRC-16 (true) or the 8-bit checksum (false) is in use
+    ///
+    /// Settled by the start handshake of the last transfer.
+    pub fn uses_crc(&self) -> bool {
+        self.use_crc
+    }
+
+    /// Read a single byte, polling until `timeout` elapses
+    fn read_byte<P: Read>(port: &mut P, timeout: Duration) -> Result<Option<u8>, TransferError> {
+        let deadline = Instant::now() + timeout;
+        let mut byte = [0u8; 1];
+
+        loop {
+            match port.read(&mut byte) {
+                Ok(1) => return Ok(Some(byte[0])),
+                Ok(_) => {}
+                Err(ref e) if matches!(
+                    e.kind(),
+                    std::io::ErrorKind::TimedOut
+                        | std::io::ErrorKind::WouldBlock
+                        | std::io::ErrorKind::Interrupted
+                ) => {}
+                Err(e) => return Err(e.into()),
+            }
+
+            if Instant::now() >= deadline {
+                return Ok(None);
+            }
+            std::thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    /// Negotiate CRC vs checksum mode as the receiver
+    ///
+    /// Sends `C` up to `XMODEM_CRC_ATTEMPTS` times; if the sender does not
+    /// start a block, falls back to `NAK` (checksum mode). Returns the first
+    /// header byte received from the sender.
+    fn negotiate_receive<P: Read + Write>(&mut self, port: &mut P) -> Result<u8, TransferError> {
+        let attempts = std::iter::repeat(CRC)
+            .take(XMODEM_CRC_ATTEMPTS as usize)
+            .chain(std::iter::repeat(NAK).take(XMODEM_NAK_ATTEMPTS as usize));
+
+        for request in attempts {
+            port.write_all(&[request])?;
+
+            let deadline = Instant::now() + self.start_timeout;
+            while let Some(byte) = Self::read_byte(port, deadline.saturating_duration_since(Instant::now()))? {
+                match byte {
+                    SOH | STX | EOT => {
+                        self.use_crc = request == CRC;
+                        return Ok(byte);
+                    }
+                    CAN => return Err(TransferError::Cancelled("sender")),
+                    // Line noise before the first block
+                    _ => {}
+                }
+            }
+        }
+
+        Err(TransferError::Timeout("waiting for sender"))
+    }
+
+    /// Wait for the receiver's start request as the sender
+    ///
+    /// `C` selects CRC-16, `NAK` selects the 8-bit checksum.
+    fn negotiate_send<P: Read>(&mut self, port: &mut P) -> Result<(), TransferError> {
+        let deadline = Instant::now() + self.start_timeout * XMODEM_NAK_ATTEMPTS;
+
+        while let Some(byte) = Self::read_byte(port, deadline.saturating_duration_since(Instant::now()))? {
+            match byte {
+                CRC => {
+                    self.use_crc = true;
+                    return Ok(());
+                }
+                NAK => {
+                    self.use_crc = false;
+                    return Ok(());
+                }
+                CAN => return Err(TransferError::Cancelled("receiver")),
+                _ => {}
+            }
+        }
+
+        Err(TransferError::Timeout("waiting for receiver to request transfer"))
+    }
+
     /// Calculate CRC-16 CCITT
     fn crc16(data: &[u8]) -> u16 {
         let mut crc: u16 = 0;
@@ -141,26 +279,22 @@ impl XmodemTransfer {
     }
 
     /// Send file via XMODEM
-    pub fn send_file<R: Read, W: Write>(
+    pub fn send_file<R: Read, P: Read + Write>(
         &mut self,
         file: &mut R,
-        port: &mut W,
+        port: &mut P,
         file_name: &str,
         file_size: u64,
-    ) -> Result<(), String> {
+    ) -> Result<(), TransferError> {
         self.progress.file_name = file_name.to_string();
         self.progress.file_size = file_size;
         self.progress.state = TransferState::WaitingForStart;
         
         // Wait for receiver to send NAK or 'C' for CRC mode
-        let mut start_byte = [0u8; 1];
-        let mut retries = 0;
-        
-        loop {
-            // In a real implementation, we would read from the port
-            // For now, assume CRC mode
-            self.use_crc = true;
-            break;
+        if let Err(e) = self.negotiate_send(port) {
+            self.progress.state = TransferState::Error;
+            self.progress.error_message = Some(e.to_string());
+            return Err(e);
         }
         
         self.progress.state = TransferState::InProgress;
@@ -170,8 +304,7 @@ impl XmodemTransfer {
         
         loop {
             // Read a block from file
-            let bytes_read = file.read(&mut buffer)
-                .map_err(|e| format!("Read error: {}", e))?;
+            let bytes_read = file.read(&mut buffer)?;
             
             if bytes_read == 0 {
                 break;
@@ -196,8 +329,7 @@ impl XmodemTransfer {
             }
             
             // Send packet
-            port.write_all(&packet)
-                .map_err(|e| format!("Write error: {}", e))?;
+            port.write_all(&packet)?;
             
             // Update progress
             self.progress.bytes_transferred += bytes_read as u64;
@@ -208,35 +340,139 @@ impl XmodemTransfer {
         }
         
         // Send EOT
-        port.write_all(&[EOT])
-            .map_err(|e| format!("Write EOT error: {}", e))?;
+        port.write_all(&[EOT])?;
         
         self.progress.state = TransferState::Complete;
         Ok(())
     }
 
     /// Receive file via XMODEM
-    pub fn receive_file<R: Read, W: Write>(
+    ///
+    /// Requests CRC mode first and falls back to checksum mode if the sender
+    /// does not answer. Returns the number of bytes written (padding stripped).
+    pub fn receive_file<P: Read + Write, W: Write>(
         &mut self,
-        port: &mut R,
+        port: &mut P,
         file: &mut W,
         file_name: &str,
-    ) -> Result<u64, String> {
+    ) -> Result<u64, TransferError> {
         self.progress.file_name = file_name.to_string();
         self.progress.state = TransferState::WaitingForStart;
-        
-        // Implementation would go here
-        // For now, return placeholder
-        
-        self.progress.state = TransferState::Complete;
-        Ok(0)
+
+        let result = self.receive_blocks(port, file);
+        match &result {
+            Ok(_) => self.progress.state = TransferState::Complete,
+            Err(e) => {
+                self.progress.state = TransferState::Error;
+                self.progress.error_message = Some(e.to_string());
+            }
+        }
+        result
+    }
+
+    /// Receive loop for `receive_file`
+    fn receive_blocks<P: Read + Write, W: Write>(
+        &mut self,
+        port: &mut P,
+        file: &mut W,
+    ) -> Result<u64, TransferError> {
+        let mut next = Some(self.negotiate_receive(port)?);
+        self.progress.state = TransferState::InProgress;
+
+        let mut expected_block: u8 = 1;
+        let mut retries = 0;
+        // Last block is held back so its SUB padding can be stripped at EOT
+        let mut pending: Option<Vec<u8>> = None;
+        let mut written = 0u64;
+
+        loop {
+            let header = match next.take() {
+                Some(b) => b,
+                None => match Self::read_byte(port, XMODEM_BLOCK_TIMEOUT)? {
+                    Some(b) => b,
+                    None => {
+                        retries += 1;
+                        self.progress.retry_count += 1;
+                        if retries > XMODEM_MAX_RETRIES {
+                            return Err(TransferError::Timeout("waiting for block"));
+                        }
+                        port.write_all(&[NAK])?;
+                        continue;
+                    }
+                },
+            };
+
+            match header {
+                EOT => {
+                    port.write_all(&[ACK])?;
+                    break;
+                }
+                CAN => return Err(TransferError::Cancelled("sender")),
+                SOH | STX => {
+                    let block_size = if header == STX { 1024 } else { 128 };
+                    let trailer = if self.use_crc { 2 } else { 1 };
+                    let mut packet = Vec::with_capacity(2 + block_size + trailer);
+
+                    while packet.len() < 2 + block_size + trailer {
+                        match Self::read_byte(port, XMODEM_BLOCK_TIMEOUT)? {
+                            Some(b) => packet.push(b),
+                            None => break,
+                        }
+                    }
+
+                    let complete = packet.len() == 2 + block_size + trailer;
+                    let data = &packet[2.min(packet.len())..(2 + block_size).min(packet.len())];
+                    let valid = complete && packet[0] == !packet[1] && if self.use_crc {
+                        let received = (u16::from(packet[2 + block_size]) << 8)
+                            | u16::from(packet[3 + block_size]);
+                        Self::crc16(data) == received
+                    } else {
+                        Self::checksum(data) == packet[2 + block_size]
+                    };
+
+                    if valid {
+                        if packet[0] == expected_block {
+                            if let Some(prev) = pending.replace(data.to_vec()) {
+                                file.write_all(&prev)?;
+                                written += prev.len() as u64;
+                            }
+                            self.progress.block_number = u32::from(expected_block);
+                            self.progress.bytes_transferred += block_size as u64;
+                            expected_block = expected_block.wrapping_add(1);
+                        }
+                        // Duplicates of the previous block are ACKed and dropped
+                        retries = 0;
+                        port.write_all(&[ACK])?;
+                    } else {
+                        retries += 1;
+                        self.progress.retry_count += 1;
+                        if retries > XMODEM_MAX_RETRIES {
+                            let _ = port.write_all(&[CAN, CAN, CAN]);
+                            return Err(TransferError::Checksum(retries));
+                        }
+                        port.write_all(&[NAK])?;
+                    }
+                }
+                // Garbage between blocks
+                _ => {}
+            }
+        }
+
+        if let Some(mut last) = pending {
+            while last.last() == Some(&SUB) {
+                last.pop();
+            }
+            file.write_all(&last)?;
+            written += last.len() as u64;
+        }
+
+        Ok(written)
     }
 
     /// Cancel transfer
-    pub fn cancel<W: Write>(&mut self, port: &mut W) -> Result<(), String> {
+    pub fn cancel<W: Write>(&mut self, port: &mut W) -> Result<(), TransferError> {
         // Send CAN bytes
-        port.write_all(&[CAN, CAN, CAN])
-            .map_err(|e| format!("Cancel error: {}", e))?;
+        port.write_all(&[CAN, CAN, CAN])?;
         self.progress.state = TransferState::Cancelled;
         Ok(())
     }
@@ -271,13 +507,207 @@ const ZCAN: u8 = 16;       // Cancel (5 CAN chars)
 const ZFREECNT: u8 = 17;   // Request for free bytes
 const ZCOMMAND: u8 = 18;   // Command from sender
 
+// ZMODEM data subpacket terminators
+const ZCRCE: u8 = 0x68;    // 'h' CRC next, frame ends, header follows
+const ZCRCG: u8 = 0x69;    // 'i' CRC next, frame continues nonstop
+const ZCRCQ: u8 = 0x6a;    // 'j' CRC next, frame continues, ZACK expected
+const ZCRCW: u8 = 0x6b;    // 'k' CRC next, ZACK expected, end of frame
+
+// ZDLE escapes for DEL and DEL with the high bit set
+const ZRUB0: u8 = 0x6c;    // 'l' 0x7f
+const ZRUB1: u8 = 0x6d;    // 'm' 0xff
+
+/// Give up after this many consecutive garbled frames
+const ZMODEM_MAX_RETRIES: u32 = 10;
+/// Give up when the peer stays silent this long
+const ZMODEM_TIMEOUT: Duration = Duration::from_secs(10);
+/// Longest data subpacket accepted before the stream is treated as garbage
+const ZMODEM_MAX_SUBPACKET: usize = 8192;
+
+/// ZMODEM tuning options
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ZmodemConfig {
+    /// Maximum data bytes per subpacket (1024 is the classic maximum)
+    pub subpacket_size: usize,
+    /// Bytes sent before waiting for a ZACK (0 = full streaming)
+    pub window_size: usize,
+    /// Escape every control character instead of only ZDLE/XON/XOFF/DLE
+    ///
+    /// Needed over Telnet or XON/XOFF links that eat control bytes; raw
+    /// serial links can use minimal escaping for better throughput.
+    pub escape_control_chars: bool,
+    /// Use 32-bit CRC frames (ZBIN32) instead of CRC-16 (ZBIN)
+    pub use_crc32: bool,
+}
+
+impl Default for ZmodemConfig {
+    fn default() -> Self {
+        Self {
+            subpacket_size: 1024,
+            window_size: 0,
+            escape_control_chars: false,
+            use_crc32: true,
+        }
+    }
+}
+
+/// File metadata carried by a ZFILE frame
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ZmodemFileInfo {
+    /// File name as sent (may contain a path)
+    pub name: String,
+    /// File size in bytes
+    pub size: u64,
+    /// Modification time
+    pub mtime: Option<SystemTime>,
+    /// Unix file mode
+    pub mode: Option<u32>,
+}
+
+impl ZmodemFileInfo {
+    /// Parse the ZFILE data subpacket: `name NUL "size mtime mode ..." NUL`
+    ///
+    /// Size is decimal, mtime (seconds since epoch) and mode are octal.
+    /// Missing or zero fields are treated as unknown.
+    pub fn parse(data: &[u8]) -> Option<Self> {
+        let name_end = data.iter().position(|&b| b == 0)?;
+        let name = String::from_utf8_lossy(&data[..name_end]).into_owned();
+        if name.is_empty() {
+            return None;
+        }
+
+        let rest = &data[name_end + 1..];
+        let rest_end = rest.iter().position(|&b| b == 0).unwrap_or(rest.len());
+        let header = String::from_utf8_lossy(&rest[..rest_end]);
+        let mut fields = header.split_whitespace();
+
+        let size = fields.next().and_then(|f| f.parse().ok()).unwrap_or(0);
+        let mtime = fields
+            .next()
+            .and_then(|f| u64::from_str_radix(f, 8).ok())
+            .filter(|&secs| secs != 0)
+            .map(|secs| UNIX_EPOCH + Duration::from_secs(secs));
+        let mode = fields
+            .next()
+            .and_then(|f| u32::from_str_radix(f, 8).ok())
+            .filter(|&mode| mode != 0);
+
+        Some(Self { name, size, mtime, mode })
+    }
+
+    /// Encode as a ZFILE data subpacket payload
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mtime = self
+            .mtime
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map_or(0, |d| d.as_secs());
+        let mut out = self.name.as_bytes().to_vec();
+        out.push(0);
+        out.extend_from_slice(
+            format!("{} {:o} {:o}", self.size, mtime, self.mode.unwrap_or(0)).as_bytes(),
+        );
+        out.push(0);
+        out
+    }
+
+    /// Apply the modification time and permissions to a written file
+    ///
+    /// Permissions are only applied on Unix; other platforms keep the
+    /// default mode. Only the read bits and the owner's write and execute
+    /// bits are taken from the sender, so a peer cannot create setuid,
+    /// setgid, sticky, group/world-writable or world-executable files.
+    pub fn apply(&self, file: &std::fs::File) -> std::io::Result<()> {
+        if let Some(mtime) = self.mtime {
+            file.set_modified(mtime)?;
+        }
+
+        #[cfg(unix)]
+        if let Some(mode) = self.mode {
+            use std::os::unix::fs::PermissionsExt;
+            file.set_permissions(std::fs::Permissions::from_mode(mode & 0o744))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A decoded ZMODEM header or data subpacket
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ZmodemFrame {
+    /// Frame header with its type and four flag (or file position) bytes
+    Header { frame_type: u8, flags: [u8; 4] },
+    /// Data subpacket following a ZSINIT, ZFILE, ZDATA or ZCOMMAND header
+    Data { data: Vec<u8>, frame_end: u8 },
+    /// A header or subpacket failed its CRC
+    Garbled,
+}
+
+/// What the receiver made of a frame
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ZmodemEvent {
+    /// Nothing to do beyond sending the reply
+    None,
+    /// The sender offered a file; its data follows
+    FileStart(ZmodemFileInfo),
+    /// File data to append
+    Data(Vec<u8>),
+    /// The current file is complete
+    FileEnd,
+    /// The sender ended the session
+    Finished,
+}
+
+/// One ZDLE-decoded unit of a binary header or subpacket
+enum ZdleUnit {
+    Byte(u8),
+    FrameEnd(u8),
+}
+
+/// Read the unit at `pos`, or `None` when more bytes are needed
+fn read_zdle_unit(buf: &[u8], pos: &mut usize) -> Option<ZdleUnit> {
+    loop {
+        let b = *buf.get(*pos)?;
+        if b == ZDLE {
+            let next = *buf.get(*pos + 1)?;
+            *pos += 2;
+            return Some(match next {
+                ZCRCE | ZCRCG | ZCRCQ | ZCRCW => ZdleUnit::FrameEnd(next),
+                ZRUB0 => ZdleUnit::Byte(0x7f),
+                ZRUB1 => ZdleUnit::Byte(0xff),
+                other => ZdleUnit::Byte(other ^ 0x40),
+            });
+        }
+        *pos += 1;
+        // Data XON/XOFF are always escaped, so raw ones are flow control
+        if !matches!(b & 0x7f, 0x11 | 0x13) {
+            return Some(ZdleUnit::Byte(b));
+        }
+    }
+}
+
+/// ZDATA/ZRPOS/ZACK/ZEOF carry a file position in their flag bytes
+fn position_flags(position: u64) -> [u8; 4] {
+    (position as u32).to_le_bytes()
+}
+
+fn flags_position(flags: [u8; 4]) -> u64 {
+    u32::from_le_bytes(flags) as u64
+}
+
 /// ZMODEM transfer handler
 pub struct ZmodemTransfer {
     direction: TransferDirection,
+    config: ZmodemConfig,
     progress: TransferProgress,
     state: ZmodemState,
     rx_buffer: Vec<u8>,
     file_offset: u64,
+    /// Set while data subpackets are expected; selects CRC-32 over CRC-16
+    subpacket_crc32: Option<bool>,
+    /// Type of the header the expected subpackets belong to
+    data_header: u8,
+    /// Drop ZDATA subpackets until the sender resumes at `file_offset`
+    discard_data: bool,
 }
 
 /// ZMODEM protocol state
@@ -295,13 +725,17 @@ pub enum ZmodemState {
 
 impl ZmodemTransfer {
     /// Create new ZMODEM transfer
-    pub fn new(direction: TransferDirection) -> Self {
+    pub fn new(direction: TransferDirection, config: ZmodemConfig) -> Self {
         Self {
             direction,
+            config,
             progress: TransferProgress::default(),
             state: ZmodemState::Idle,
             rx_buffer: Vec::with_capacity(8192),
             file_offset: 0,
+            subpacket_crc32: None,
+            data_header: ZRQINIT,
+            discard_data: false,
         }
     }
 
@@ -315,6 +749,11 @@ impl ZmodemTransfer {
         self.state
     }
 
+    /// Get the tuning configuration
+    pub fn config(&self) -> &ZmodemConfig {
+        &self.config
+    }
+
     /// Check if data contains ZMODEM auto-start sequence
     pub fn is_auto_start(data: &[u8]) -> bool {
         // ZMODEM auto-start: "rz\r" followed by ZRQINIT frame
@@ -332,6 +771,17 @@ impl ZmodemTransfer {
         false
     }
 
+    /// Check if data contains a ZFIN hex header (sender finished the batch)
+    pub fn is_finish(data: &[u8]) -> bool {
+        let zfin = [ZPAD, ZPAD, ZDLE, ZHEX, b'0', b'8'];
+        data.windows(zfin.len()).any(|w| w == zfin)
+    }
+
+    /// Check if data contains the 5-CAN abort sequence
+    pub fn is_cancel(data: &[u8]) -> bool {
+        data.windows(5).any(|w| w.iter().all(|&b| b == CAN))
+    }
+
     /// Build a hex header
     fn build_hex_header(frame_type: u8, flags: [u8; 4]) -> Vec<u8> {
         let mut header = vec![ZPAD, ZPAD, ZDLE, ZHEX];
@@ -380,112 +830,627 @@ impl ZmodemTransfer {
         crc
     }
 
+    /// Append a byte to `out`, ZDLE-escaping it if required
+    fn push_escaped(&self, out: &mut Vec<u8>, b: u8) {
+        let needs_escape = match b & 0x7f {
+            // ZDLE, DLE, XON, XOFF (and their high-bit twins) always
+            0x18 | 0x10 | 0x11 | 0x13 => true,
+            c if self.config.escape_control_chars => c < 0x20 || c == 0x7f,
+            _ => false,
+        };
+
+        if needs_escape {
+            out.push(ZDLE);
+            out.push(b ^ 0x40);
+        } else {
+            out.push(b);
+        }
+    }
+
+    /// Build a binary header (ZBIN or ZBIN32 depending on config)
+    fn build_bin_header(&self, frame_type: u8, flags: [u8; 4]) -> Vec<u8> {
+        let mut raw = vec![frame_type];
+        raw.extend_from_slice(&flags);
+
+        let mut header = vec![ZPAD, ZDLE];
+        if self.config.use_crc32 {
+            header.push(ZBIN32);
+            raw.extend_from_slice(&crate::core::protocol::checksum::crc32(&raw).to_le_bytes());
+        } else {
+            header.push(ZBIN);
+            raw.extend_from_slice(&Self::crc16(&raw).to_be_bytes());
+        }
+
+        for b in raw {
+            self.push_escaped(&mut header, b);
+        }
+        header
+    }
+
+    /// Build one data subpacket terminated by `frame_end`
+    fn build_subpacket(&self, data: &[u8], frame_end: u8) -> Vec<u8> {
+        let mut packet = Vec::with_capacity(data.len() + data.len() / 8 + 8);
+        for &b in data {
+            self.push_escaped(&mut packet, b);
+        }
+        packet.push(ZDLE);
+        packet.push(frame_end);
+
+        // CRC covers the data and the frame end byte
+        let mut crc_data = data.to_vec();
+        crc_data.push(frame_end);
+        let crc: Vec<u8> = if self.config.use_crc32 {
+            crate::core::protocol::checksum::crc32(&crc_data).to_le_bytes().to_vec()
+        } else {
+            Self::crc16(&crc_data).to_be_bytes().to_vec()
+        };
+        for b in crc {
+            self.push_escaped(&mut packet, b);
+        }
+        packet
+    }
+
+    /// Build a ZDATA frame for `data` starting at file `offset`
+    ///
+    /// The data is split into `subpacket_size` subpackets. With a non-zero
+    /// `window_size`, a ZCRCW subpacket is emitted at every window boundary
+    /// so the sender waits for a ZACK; otherwise the last subpacket (ZCRCE)
+    /// ends the frame.
+    pub fn build_zdata(&self, offset: u64, data: &[u8]) -> Vec<u8> {
+        let mut frame = self.build_bin_header(ZDATA, position_flags(offset));
+
+        let subpacket_size = self.config.subpacket_size.max(1);
+        let chunks: Vec<&[u8]> = data.chunks(subpacket_size).collect();
+        let mut sent = 0usize;
+
+        for (i, chunk) in chunks.iter().enumerate() {
+            let before = sent;
+            sent += chunk.len();
+            let window_boundary = self.config.window_size > 0
+                && before / self.config.window_size != sent / self.config.window_size;
+
+            let frame_end = if window_boundary {
+                ZCRCW
+            } else if i + 1 == chunks.len() {
+                ZCRCE
+            } else {
+                ZCRCG
+            };
+            frame.extend_from_slice(&self.build_subpacket(chunk, frame_end));
+        }
+
+        frame
+    }
+
+    /// Build a ZFILE frame offering `info`
+    pub fn build_zfile(&self, info: &ZmodemFileInfo) -> Vec<u8> {
+        let mut frame = self.build_bin_header(ZFILE, [0; 4]);
+        frame.extend_from_slice(&self.build_subpacket(&info.to_bytes(), ZCRCW));
+        frame
+    }
+
+    /// Build a ZEOF header for a file of `size` bytes
+    pub fn build_zeof(&self, size: u64) -> Vec<u8> {
+        self.build_bin_header(ZEOF, position_flags(size))
+    }
+
     /// Send ZRQINIT (request receive init)
-    pub fn send_zrqinit<W: Write>(&mut self, port: &mut W) -> Result<(), String> {
+    pub fn send_zrqinit<W: Write>(&mut self, port: &mut W) -> Result<(), TransferError> {
         let header = Self::build_hex_header(ZRQINIT, [0, 0, 0, 0]);
-        port.write_all(&header).map_err(|e| e.to_string())?;
+        port.write_all(&header)?;
         self.state = ZmodemState::WaitingZRINIT;
         Ok(())
     }
 
     /// Send ZRINIT (receive init)
-    pub fn send_zrinit<W: Write>(&mut self, port: &mut W) -> Result<(), String> {
+    pub fn send_zrinit<W: Write>(&mut self, port: &mut W) -> Result<(), TransferError> {
         // Flags: CANFDX | CANOVIO | CANFC32
         let flags = [0x23, 0, 0, 0];
         let header = Self::build_hex_header(ZRINIT, flags);
-        port.write_all(&header).map_err(|e| e.to_string())?;
+        port.write_all(&header)?;
         self.state = ZmodemState::WaitingZFILE;
         Ok(())
     }
 
     /// Send ZFIN (finish)
-    pub fn send_zfin<W: Write>(&mut self, port: &mut W) -> Result<(), String> {
+    pub fn send_zfin<W: Write>(&mut self, port: &mut W) -> Result<(), TransferError> {
         let header = Self::build_hex_header(ZFIN, [0, 0, 0, 0]);
-        port.write_all(&header).map_err(|e| e.to_string())?;
+        port.write_all(&header)?;
         self.state = ZmodemState::Complete;
         Ok(())
     }
 
-    /// Process received data
-    pub fn process_data(&mut self, data: &[u8]) -> Option<Vec<u8>> {
+    /// Buffer received bytes for [`next_frame`](Self::next_frame)
+    pub fn process_data(&mut self, data: &[u8]) {
         self.rx_buffer.extend_from_slice(data);
-        
-        // Try to parse frames from buffer
-        // This is a simplified implementation
-        None
+    }
+
+    /// Decode the next complete frame from the buffered bytes
+    ///
+    /// Returns `Ok(None)` until a whole header or subpacket has arrived.
+    /// Line noise between headers is skipped; a 5-CAN sequence from the
+    /// peer aborts the transfer.
+    pub fn next_frame(&mut self) -> Result<Option<ZmodemFrame>, TransferError> {
+        if Self::is_cancel(&self.rx_buffer) {
+            return Err(TransferError::Cancelled(match self.direction {
+                TransferDirection::Receive => "sender",
+                TransferDirection::Send => "receiver",
+            }));
+        }
+
+        match self.subpacket_crc32 {
+            Some(crc32) => Ok(self.parse_subpacket(crc32)),
+            None => Ok(self.parse_header()),
+        }
+    }
+
+    fn parse_header(&mut self) -> Option<ZmodemFrame> {
+        loop {
+            let Some(start) = self.rx_buffer.iter().position(|&b| b == ZPAD) else {
+                // Keep a trailing run of CANs, it may be the start of an abort
+                let keep = self.rx_buffer.iter().rev().take_while(|&&b| b == CAN).count();
+                self.rx_buffer.drain(..self.rx_buffer.len() - keep);
+                return None;
+            };
+            self.rx_buffer.drain(..start);
+
+            let buf = &self.rx_buffer;
+            let mut pos = buf.iter().take_while(|&&b| b == ZPAD).count();
+            let (&zdle, &kind) = (buf.get(pos)?, buf.get(pos + 1)?);
+            if zdle != ZDLE || !matches!(kind, ZHEX | ZBIN | ZBIN32) {
+                self.rx_buffer.drain(..pos);
+                continue;
+            }
+            pos += 2;
+
+            let crc_len = if kind == ZBIN32 { 4 } else { 2 };
+            let mut raw = Vec::with_capacity(5 + crc_len);
+            if kind == ZHEX {
+                let hex = buf.get(pos..pos + 14)?;
+                pos += 14;
+                for pair in hex.chunks(2) {
+                    let byte = std::str::from_utf8(pair).ok().and_then(|p| u8::from_str_radix(p, 16).ok());
+                    match byte {
+                        Some(byte) => raw.push(byte),
+                        None => break,
+                    }
+                }
+            } else {
+                while raw.len() < 5 + crc_len {
+                    match read_zdle_unit(buf, &mut pos)? {
+                        ZdleUnit::Byte(b) => raw.push(b),
+                        ZdleUnit::FrameEnd(_) => break,
+                    }
+                }
+            }
+            self.rx_buffer.drain(..pos);
+
+            if raw.len() != 5 + crc_len || !Self::check_crc(&raw[..5], &raw[5..], kind == ZBIN32) {
+                return Some(ZmodemFrame::Garbled);
+            }
+            let frame_type = raw[0];
+            let flags = [raw[1], raw[2], raw[3], raw[4]];
+            if matches!(frame_type, ZSINIT | ZFILE | ZDATA | ZCOMMAND) {
+                self.subpacket_crc32 = Some(kind == ZBIN32);
+                self.data_header = frame_type;
+            }
+            return Some(ZmodemFrame::Header { frame_type, flags });
+        }
+    }
+
+    fn parse_subpacket(&mut self, crc32: bool) -> Option<ZmodemFrame> {
+        let buf = &self.rx_buffer;
+        let mut pos = 0;
+        let mut data = Vec::new();
+        let frame_end = loop {
+            match read_zdle_unit(buf, &mut pos) {
+                Some(ZdleUnit::Byte(b)) => data.push(b),
+                Some(ZdleUnit::FrameEnd(end)) => break end,
+                None if data.len() > ZMODEM_MAX_SUBPACKET => {
+                    self.rx_buffer.clear();
+                    self.subpacket_crc32 = None;
+                    return Some(ZmodemFrame::Garbled);
+                }
+                None => return None,
+            }
+        };
+
+        let mut crc = Vec::with_capacity(4);
+        while crc.len() < if crc32 { 4 } else { 2 } {
+            match read_zdle_unit(buf, &mut pos)? {
+                ZdleUnit::Byte(b) => crc.push(b),
+                ZdleUnit::FrameEnd(_) => break,
+            }
+        }
+        self.rx_buffer.drain(..pos);
+
+        // CRC covers the data and the frame end byte
+        data.push(frame_end);
+        let valid = crc.len() == if crc32 { 4 } else { 2 } && Self::check_crc(&data, &crc, crc32);
+        data.pop();
+        if !valid {
+            self.subpacket_crc32 = None;
+            return Some(ZmodemFrame::Garbled);
+        }
+        if matches!(frame_end, ZCRCE | ZCRCW) {
+            self.subpacket_crc32 = None;
+        }
+        Some(ZmodemFrame::Data { data, frame_end })
+    }
+
+    fn check_crc(data: &[u8], crc: &[u8], crc32: bool) -> bool {
+        if crc32 {
+            crc == crate::core::protocol::checksum::crc32(data).to_le_bytes()
+        } else {
+            crc == Self::crc16(data).to_be_bytes()
+        }
+    }
+
+    /// Handle one decoded frame as the receiver
+    ///
+    /// Any reply for the sender (ZRINIT, ZRPOS, ZACK, ZFIN) is appended to
+    /// `reply`. Garbled data makes the sender resume from the last good
+    /// position; too many garbled frames in a row fail the transfer.
+    pub fn receive_frame(&mut self, frame: ZmodemFrame, reply: &mut Vec<u8>) -> Result<ZmodemEvent, TransferError> {
+        let event = match frame {
+            ZmodemFrame::Garbled => {
+                self.progress.retry_count += 1;
+                if self.progress.retry_count > ZMODEM_MAX_RETRIES {
+                    return Err(TransferError::Checksum(ZMODEM_MAX_RETRIES));
+                }
+                if self.state == ZmodemState::WaitingZDATA {
+                    self.discard_data = true;
+                    reply.extend_from_slice(&Self::build_hex_header(ZRPOS, position_flags(self.file_offset)));
+                } else {
+                    reply.extend_from_slice(&Self::build_hex_header(ZNAK, [0; 4]));
+                }
+                ZmodemEvent::None
+            }
+            ZmodemFrame::Header { frame_type, flags } => match frame_type {
+                ZRQINIT => {
+                    self.send_zrinit(reply)?;
+                    ZmodemEvent::None
+                }
+                ZDATA => {
+                    // Data from anywhere but our position is stale: ask again
+                    self.discard_data = flags_position(flags) != self.file_offset;
+                    if self.discard_data {
+                        reply.extend_from_slice(&Self::build_hex_header(ZRPOS, position_flags(self.file_offset)));
+                    }
+                    ZmodemEvent::None
+                }
+                // A ZEOF for another position belongs to stale data; ignore it
+                ZEOF if self.state == ZmodemState::WaitingZDATA && flags_position(flags) == self.file_offset => {
+                    self.progress.state = TransferState::Complete;
+                    self.send_zrinit(reply)?;
+                    ZmodemEvent::FileEnd
+                }
+                ZFIN => {
+                    self.send_zfin(reply)?;
+                    ZmodemEvent::Finished
+                }
+                _ => ZmodemEvent::None,
+            },
+            ZmodemFrame::Data { data, frame_end } => {
+                self.progress.retry_count = 0;
+                match self.data_header {
+                    ZFILE => {
+                        let info = ZmodemFileInfo::parse(&data)
+                            .ok_or_else(|| TransferError::Protocol("Malformed ZFILE header".to_string()))?;
+                        self.file_offset = 0;
+                        self.discard_data = false;
+                        self.progress = TransferProgress {
+                            state: TransferState::InProgress,
+                            file_name: info.name.clone(),
+                            file_size: info.size,
+                            ..Default::default()
+                        };
+                        self.state = ZmodemState::WaitingZDATA;
+                        reply.extend_from_slice(&Self::build_hex_header(ZRPOS, position_flags(0)));
+                        ZmodemEvent::FileStart(info)
+                    }
+                    ZDATA if !self.discard_data && self.state == ZmodemState::WaitingZDATA => {
+                        self.file_offset += data.len() as u64;
+                        self.progress.bytes_transferred = self.file_offset;
+                        self.progress.block_number += 1;
+                        if matches!(frame_end, ZCRCQ | ZCRCW) {
+                            reply.extend_from_slice(&Self::build_hex_header(ZACK, position_flags(self.file_offset)));
+                        }
+                        ZmodemEvent::Data(data)
+                    }
+                    ZSINIT => {
+                        reply.extend_from_slice(&Self::build_hex_header(ZACK, [0; 4]));
+                        ZmodemEvent::None
+                    }
+                    ZCOMMAND => {
+                        return Err(TransferError::Protocol("Remote commands are not supported".to_string()));
+                    }
+                    _ => ZmodemEvent::None,
+                }
+            }
+        };
+        Ok(event)
+    }
+
+    /// Skip the file just offered by the sender
+    pub fn skip_file<W: Write>(&mut self, port: &mut W) -> Result<(), TransferError> {
+        port.write_all(&Self::build_hex_header(ZSKIP, [0; 4]))?;
+        self.state = ZmodemState::WaitingZFILE;
+        Ok(())
+    }
+
+    /// Read whatever the port has, treating timeouts as no data
+    fn read_chunk<P: Read>(port: &mut P, buf: &mut [u8]) -> Result<usize, TransferError> {
+        match port.read(buf) {
+            Ok(n) => Ok(n),
+            Err(ref e) if matches!(
+                e.kind(),
+                std::io::ErrorKind::TimedOut | std::io::ErrorKind::WouldBlock | std::io::ErrorKind::Interrupted
+            ) => Ok(0),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Wait for the receiver's next header, skipping anything else
+    fn read_header<P: Read>(&mut self, port: &mut P) -> Result<(u8, [u8; 4]), TransferError> {
+        let mut buf = [0u8; 256];
+        let mut last_rx = Instant::now();
+        loop {
+            while let Some(frame) = self.next_frame()? {
+                if let ZmodemFrame::Header { frame_type, flags } = frame {
+                    return Ok((frame_type, flags));
+                }
+            }
+
+            let n = Self::read_chunk(port, &mut buf)?;
+            if n == 0 {
+                if last_rx.elapsed() >= ZMODEM_TIMEOUT {
+                    return Err(TransferError::Timeout("waiting for ZMODEM receiver"));
+                }
+                std::thread::sleep(Duration::from_millis(5));
+                continue;
+            }
+            last_rx = Instant::now();
+            self.process_data(&buf[..n]);
+        }
     }
 
     /// Send file via ZMODEM
-    pub fn send_file<R: Read, W: Write>(
+    ///
+    /// The file is buffered so the receiver can resume from any position it
+    /// asks for with ZRPOS. With a non-zero `window_size` the data goes out
+    /// one window at a time, each acknowledged by a ZACK.
+    pub fn send_file<R: Read, P: Read + Write>(
         &mut self,
         file: &mut R,
-        port: &mut W,
+        port: &mut P,
         file_name: &str,
         file_size: u64,
-    ) -> Result<(), String> {
+    ) -> Result<(), TransferError> {
         self.progress.file_name = file_name.to_string();
         self.progress.file_size = file_size;
         self.progress.state = TransferState::WaitingForStart;
-        
-        // Send ZRQINIT to initiate transfer
+
+        let mut data = Vec::with_capacity(usize::try_from(file_size).unwrap_or(0));
+        file.read_to_end(&mut data)?;
+        let len = data.len() as u64;
+        let info = ZmodemFileInfo { name: file_name.to_string(), size: len, mtime: None, mode: None };
+
         self.send_zrqinit(port)?;
-        
-        self.progress.state = TransferState::InProgress;
-        
-        // In a full implementation, we would:
-        // 1. Wait for ZRINIT
-        // 2. Send ZFILE with filename
-        // 3. Wait for ZRPOS
-        // 4. Send ZDATA frames
-        // 5. Send ZEOF
-        // 6. Wait for ZRINIT
-        // 7. Send ZFIN
-        
+        loop {
+            match self.read_header(port)? {
+                (ZRINIT, _) => break,
+                (ZABORT | ZFERR, _) => return Err(TransferError::Cancelled("receiver")),
+                // Still waiting: the receiver may have missed our request
+                (ZNAK, _) => self.send_zrqinit(port)?,
+                _ => {}
+            }
+        }
+
+        port.write_all(&self.build_zfile(&info))?;
+        self.state = ZmodemState::WaitingZACK;
+        let mut sent_eof = false;
+        loop {
+            match self.read_header(port)? {
+                (ZRPOS, flags) => {
+                    self.progress.state = TransferState::InProgress;
+                    self.state = ZmodemState::SendingData;
+                    self.file_offset = flags_position(flags).min(len);
+                    self.send_data(port, &data)?;
+                    port.write_all(&self.build_zeof(len))?;
+                    self.state = ZmodemState::WaitingZACK;
+                    sent_eof = true;
+                }
+                (ZRINIT, _) if sent_eof => break,
+                // The ZFILE was garbled; a repeated ZRINIT just answers our ZRQINIT
+                (ZNAK, _) => port.write_all(&self.build_zfile(&info))?,
+                (ZSKIP, _) => break,
+                (ZABORT | ZFERR | ZFIN, _) => return Err(TransferError::Cancelled("receiver")),
+                _ => {}
+            }
+        }
+
+        // Over and out
+        self.send_zfin(port)?;
+        loop {
+            if let (ZFIN, _) = self.read_header(port)? {
+                break;
+            }
+        }
+        port.write_all(b"OO")?;
+
         self.progress.state = TransferState::Complete;
         self.state = ZmodemState::Complete;
         Ok(())
     }
 
+    /// Stream `data` from `file_offset` as ZDATA frames
+    ///
+    /// Restarts from the receiver's position if it sends a ZRPOS while a
+    /// window is being acknowledged.
+    fn send_data<P: Read + Write>(&mut self, port: &mut P, data: &[u8]) -> Result<(), TransferError> {
+        let window = self.config.window_size;
+        let mut start = usize::try_from(self.file_offset).unwrap_or(data.len()).min(data.len());
+        while start < data.len() {
+            let end = if window > 0 { (start + window).min(data.len()) } else { data.len() };
+            port.write_all(&self.build_zdata(start as u64, &data[start..end]))?;
+            let full_window = window > 0 && end - start == window;
+            start = end;
+            self.file_offset = end as u64;
+            self.progress.bytes_transferred = end as u64;
+            self.progress.block_number += 1;
+
+            // A full window ends in ZCRCW: wait for the receiver to catch up
+            if full_window {
+                loop {
+                    match self.read_header(port)? {
+                        (ZACK, _) => {
+                            self.progress.retry_count = 0;
+                            break;
+                        }
+                        (ZRPOS, flags) => {
+                            self.progress.retry_count += 1;
+                            if self.progress.retry_count > ZMODEM_MAX_RETRIES {
+                                return Err(TransferError::Checksum(ZMODEM_MAX_RETRIES));
+                            }
+                            start = usize::try_from(flags_position(flags)).unwrap_or(data.len()).min(data.len());
+                            break;
+                        }
+                        (ZABORT | ZFERR, _) => return Err(TransferError::Cancelled("receiver")),
+                        _ => {}
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
     /// Receive file via ZMODEM
-    pub fn receive_file<R: Read, W: Write>(
+    ///
+    /// Writes the first file of the batch to `file` and skips any others.
+    /// Returns the sender's file metadata; use [`ZmodemFileInfo::apply`]
+    /// on the written file to restore its timestamp and permissions.
+    pub fn receive_file<P: Read + Write, W: Write>(
         &mut self,
-        port: &mut R,
+        port: &mut P,
         file: &mut W,
-    ) -> Result<(String, u64), String> {
+    ) -> Result<ZmodemFileInfo, TransferError> {
         self.progress.state = TransferState::WaitingForStart;
-        
-        // In a full implementation, we would:
-        // 1. Send ZRINIT
-        // 2. Wait for ZFILE, parse filename
-        // 3. Send ZRPOS
-        // 4. Receive ZDATA frames
-        // 5. Write to file
-        // 6. Wait for ZEOF
-        // 7. Send ZRINIT (ready for next file)
-        // 8. Receive ZFIN
-        // 9. Send ZFIN
-        
-        self.progress.state = TransferState::Complete;
-        self.state = ZmodemState::Complete;
-        Ok((String::new(), 0))
+        self.send_zrinit(port)?;
+
+        let mut received = None;
+        let mut complete = false;
+        let mut reply = Vec::new();
+        let mut buf = [0u8; 1024];
+        let mut last_rx = Instant::now();
+        loop {
+            let n = Self::read_chunk(port, &mut buf)?;
+            if n == 0 {
+                if last_rx.elapsed() >= ZMODEM_TIMEOUT {
+                    return Err(TransferError::Timeout("waiting for ZMODEM data"));
+                }
+                std::thread::sleep(Duration::from_millis(5));
+                continue;
+            }
+            last_rx = Instant::now();
+            self.process_data(&buf[..n]);
+
+            while let Some(frame) = self.next_frame()? {
+                let event = self.receive_frame(frame, &mut reply)?;
+                match event {
+                    // A repeated offer of the first file restarts it at 0
+                    ZmodemEvent::FileStart(info) if !complete => received = Some(info),
+                    ZmodemEvent::FileStart(_) => {
+                        // Replace the ZRPOS with a ZSKIP: one file only
+                        reply.clear();
+                        self.skip_file(&mut reply)?;
+                    }
+                    ZmodemEvent::Data(data) => file.write_all(&data)?,
+                    ZmodemEvent::FileEnd => complete = true,
+                    ZmodemEvent::Finished => {
+                        port.write_all(&reply)?;
+                        self.progress.state = TransferState::Complete;
+                        return received.ok_or_else(|| TransferError::Protocol("Sender finished without a file".to_string()));
+                    }
+                    ZmodemEvent::None => {}
+                }
+                port.write_all(&reply)?;
+                reply.clear();
+            }
+        }
     }
 
     /// Cancel transfer
-    pub fn cancel<W: Write>(&mut self, port: &mut W) -> Result<(), String> {
+    pub fn cancel<W: Write>(&mut self, port: &mut W) -> Result<(), TransferError> {
         // Send 5 CAN characters
         let cancel = [0x18u8; 5];
-        port.write_all(&cancel).map_err(|e| e.to_string())?;
+        port.write_all(&cancel)?;
         self.state = ZmodemState::Error;
         self.progress.state = TransferState::Cancelled;
         Ok(())
     }
 }
 
+/// Cancels the transfers of a [`TransferManager`] from another thread
+///
+/// Once cancelled, the running transfer stops at its next port access and
+/// later transfers of the same manager fail at once.
+#[derive(Debug, Clone, Default)]
+pub struct TransferCancel(Arc<AtomicBool>);
+
+impl TransferCancel {
+    /// Request cancellation
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Check if cancellation was requested
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Port that fails every read and write once its transfer is cancelled
+struct CancellablePort<P> {
+    inner: P,
+    cancel: TransferCancel,
+}
+
+impl<P> CancellablePort<P> {
+    fn check(&self) -> std::io::Result<()> {
+        if self.cancel.is_cancelled() {
+            Err(std::io::Error::other("transfer cancelled"))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl<P: Read> Read for CancellablePort<P> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.check()?;
+        self.inner.read(buf)
+    }
+}
+
+impl<P: Write> Write for CancellablePort<P> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.check()?;
+        self.inner.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
 /// File transfer manager
 pub struct TransferManager {
     protocol: TransferProtocol,
+    zmodem_config: ZmodemConfig,
     xmodem: Option<XmodemTransfer>,
     zmodem: Option<ZmodemTransfer>,
+    cancel: TransferCancel,
 }
 
 impl TransferManager {
@@ -493,49 +1458,81 @@ impl TransferManager {
     pub fn new() -> Self {
         Self {
             protocol: TransferProtocol::Zmodem,
+            zmodem_config: ZmodemConfig::default(),
             xmodem: None,
             zmodem: None,
+            cancel: TransferCancel::default(),
         }
     }
 
+    /// Handle that cancels this manager's transfers, e.g. for
+    /// [`Session::track_transfer`](crate::core::session::Session::track_transfer)
+    pub fn cancel_handle(&self) -> TransferCancel {
+        self.cancel.clone()
+    }
+
     /// Set protocol
     pub fn set_protocol(&mut self, protocol: TransferProtocol) {
         self.protocol = protocol;
     }
 
+    /// Set ZMODEM tuning used for subsequent transfers
+    pub fn set_zmodem_config(&mut self, config: ZmodemConfig) {
+        self.zmodem_config = config;
+    }
+
     /// Start sending file
-    pub fn send_file<R: Read + 'static, W: Write + 'static>(
+    pub fn send_file<R: Read + 'static, W: Read + Write + 'static>(
         &mut self,
         file: R,
         port: W,
         file_name: &str,
         file_size: u64,
-    ) -> Result<(), String> {
-        match self.protocol {
+    ) -> Result<(), TransferError> {
+        let mut file = file;
+        let mut port = CancellablePort {
+            inner: port,
+            cancel: self.cancel.clone(),
+        };
+        let result = match self.protocol {
             TransferProtocol::Xmodem | TransferProtocol::Xmodem1K => {
                 let mut transfer = XmodemTransfer::new(self.protocol, TransferDirection::Send);
-                let mut file = file;
-                let mut port = port;
-                transfer.send_file(&mut file, &mut port, file_name, file_size)?;
+                let result = transfer.send_file(&mut file, &mut port, file_name, file_size);
                 self.xmodem = Some(transfer);
+                result
             }
             TransferProtocol::Ymodem | TransferProtocol::YmodemG => {
                 let mut transfer = XmodemTransfer::new(self.protocol, TransferDirection::Send);
-                let mut file = file;
-                let mut port = port;
                 // YMODEM sends file info block first
-                transfer.send_file(&mut file, &mut port, file_name, file_size)?;
+                let result = transfer.send_file(&mut file, &mut port, file_name, file_size);
                 self.xmodem = Some(transfer);
+                result
             }
             TransferProtocol::Zmodem => {
-                let mut transfer = ZmodemTransfer::new(TransferDirection::Send);
-                let mut file = file;
-                let mut port = port;
-                transfer.send_file(&mut file, &mut port, file_name, file_size)?;
+                let mut transfer = ZmodemTransfer::new(TransferDirection::Send, self.zmodem_config);
+                let result = transfer.send_file(&mut file, &mut port, file_name, file_size);
                 self.zmodem = Some(transfer);
+                result
+            }
+        };
+
+        if result.is_err() && self.cancel.is_cancelled() {
+            // Tell the receiver, bypassing the cancelled wrapper
+            let _ = port.inner.write_all(&[CAN; 5]);
+            if let Some(progress) = self.progress_mut() {
+                progress.state = TransferState::Cancelled;
             }
+            return Err(TransferError::Cancelled("local"));
+        }
+        result
+    }
+
+    fn progress_mut(&mut self) -> Option<&mut TransferProgress> {
+        match (self.xmodem.as_mut(), self.zmodem.as_mut()) {
+            (Some(xmodem), _) => Some(&mut xmodem.progress),
+            (None, Some(zmodem)) => Some(&mut zmodem.progress),
+            (None, None) => None,
         }
-        Ok(())
     }
 
     /// Get current progress
@@ -579,10 +1576,348 @@ mod tests {
         assert_eq!(sum, 10);
     }
 
+    /// In-memory port: reads from a scripted input, records writes
+    struct MockPort {
+        input: std::collections::VecDeque<u8>,
+        output: Vec<u8>,
+        /// Input stays silent until this many bytes were written
+        respond_after: usize,
+    }
+
+    impl MockPort {
+        fn new(input: &[u8]) -> Self {
+            Self { input: input.iter().copied().collect(), output: Vec::new(), respond_after: 0 }
+        }
+    }
+
+    impl Read for MockPort {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            if self.output.len() < self.respond_after {
+                return Ok(0);
+            }
+            let n = buf.len().min(self.input.len());
+            for slot in buf.iter_mut().take(n) {
+                *slot = self.input.pop_front().unwrap();
+            }
+            Ok(n)
+        }
+    }
+
+    impl Write for MockPort {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.output.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Port whose writes stay visible after it is moved into a transfer
+    struct SharedPort(Arc<parking_lot::Mutex<Vec<u8>>>);
+
+    impl Read for SharedPort {
+        fn read(&mut self, _buf: &mut [u8]) -> std::io::Result<usize> {
+            Ok(0)
+        }
+    }
+
+    impl Write for SharedPort {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_transfer_manager_cancel() {
+        let mut manager = TransferManager::new();
+        manager.set_protocol(TransferProtocol::Xmodem);
+        manager.cancel_handle().cancel();
+
+        let written = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let file: &'static [u8] = b"hello";
+        let err = manager.send_file(file, SharedPort(written.clone()), "hello.txt", 5).unwrap_err();
+        assert!(matches!(err, TransferError::Cancelled("local")));
+        assert_eq!(*written.lock(), [CAN; 5]);
+        assert_eq!(manager.progress().unwrap().state, TransferState::Cancelled);
+    }
+
+    #[test]
+    fn test_xmodem_send_honors_checksum_request() {
+        let mut xfer = XmodemTransfer::new(TransferProtocol::Xmodem, TransferDirection::Send);
+        xfer.set_start_timeout(Duration::from_millis(20));
+        let mut port = MockPort::new(&[NAK]);
+        let mut file: &[u8] = b"hello";
+
+        xfer.send_file(&mut file, &mut port, "hello.txt", 5).unwrap();
+        assert!(!xfer.uses_crc());
+        // SOH + num + !num + 128 data + 1 checksum, then EOT
+        assert_eq!(port.output.len(), 3 + 128 + 1 + 1);
+    }
+
+    fn xmodem_block(data: &[u8], use_crc: bool) -> Vec<u8> {
+        let mut padded = data.to_vec();
+        padded.resize(128, SUB);
+        let mut block = vec![SOH, 1, !1u8];
+        block.extend_from_slice(&padded);
+        if use_crc {
+            block.extend_from_slice(&XmodemTransfer::crc16(&padded).to_be_bytes());
+        } else {
+            block.push(XmodemTransfer::checksum(&padded));
+        }
+        block.push(EOT);
+        block
+    }
+
+    #[test]
+    fn test_xmodem_receive_prefers_crc() {
+        let mut xfer = XmodemTransfer::new(TransferProtocol::Xmodem, TransferDirection::Receive);
+        xfer.set_start_timeout(Duration::from_millis(20));
+        let mut port = MockPort::new(&xmodem_block(b"hi", true));
+        let mut out = Vec::new();
+
+        assert_eq!(xfer.receive_file(&mut port, &mut out, "f").unwrap(), 2);
+        assert!(xfer.uses_crc());
+        assert_eq!(port.output, vec![CRC, ACK, ACK]);
+        assert_eq!(out, b"hi");
+    }
+
+    #[test]
+    fn test_xmodem_receive_falls_back_to_checksum() {
+        let mut xfer = XmodemTransfer::new(TransferProtocol::Xmodem, TransferDirection::Receive);
+        xfer.set_start_timeout(Duration::from_millis(20));
+        // Sender ignores the CRC requests and only answers the NAK
+        let mut port = MockPort::new(&xmodem_block(b"hi", false));
+        port.respond_after = XMODEM_CRC_ATTEMPTS as usize + 1;
+        let mut out = Vec::new();
+
+        assert_eq!(xfer.receive_file(&mut port, &mut out, "f").unwrap(), 2);
+        assert!(!xfer.uses_crc());
+        assert_eq!(port.output, vec![CRC, CRC, CRC, NAK, ACK, ACK]);
+        assert_eq!(out, b"hi");
+    }
+
+    #[test]
+    fn test_xmodem_errors_are_typed() {
+        let mut xfer = XmodemTransfer::new(TransferProtocol::Xmodem, TransferDirection::Receive);
+        xfer.set_start_timeout(Duration::from_millis(20));
+        let mut out = Vec::new();
+
+        let err = xfer.receive_file(&mut MockPort::new(&[CAN]), &mut out, "f").unwrap_err();
+        assert!(matches!(err, TransferError::Cancelled("sender")));
+        assert_eq!(xfer.progress().error_message.as_deref(), Some("Transfer cancelled by sender"));
+
+        let err = xfer.receive_file(&mut MockPort::new(&[]), &mut out, "f").unwrap_err();
+        assert!(matches!(err, TransferError::Timeout(_)));
+    }
+
+    #[test]
+    fn test_zmodem_zdata_subpackets() {
+        let config = ZmodemConfig {
+            subpacket_size: 4,
+            window_size: 8,
+            escape_control_chars: false,
+            use_crc32: false,
+        };
+        let zm = ZmodemTransfer::new(TransferDirection::Send, config);
+        let frame = zm.build_zdata(0, b"abcdefghijkl");
+
+        // Frame ends: window boundary after 8 bytes, frame end after 12
+        let ends: Vec<u8> = frame
+            .windows(2)
+            .filter(|w| w[0] == ZDLE && matches!(w[1], ZCRCE | ZCRCG | ZCRCQ | ZCRCW))
+            .map(|w| w[1])
+            .collect();
+        assert_eq!(ends, vec![ZCRCG, ZCRCW, ZCRCE]);
+    }
+
+    #[test]
+    fn test_zmodem_control_char_escaping() {
+        let minimal = ZmodemTransfer::new(TransferDirection::Send, ZmodemConfig::default());
+        let mut out = Vec::new();
+        minimal.push_escaped(&mut out, 0x03);
+        minimal.push_escaped(&mut out, 0x11);
+        assert_eq!(out, vec![0x03, ZDLE, 0x51]);
+
+        let full = ZmodemTransfer::new(
+            TransferDirection::Send,
+            ZmodemConfig { escape_control_chars: true, ..ZmodemConfig::default() },
+        );
+        let mut out = Vec::new();
+        full.push_escaped(&mut out, 0x03);
+        full.push_escaped(&mut out, b'A');
+        assert_eq!(out, vec![ZDLE, 0x43, b'A']);
+    }
+
+    #[test]
+    fn test_zmodem_file_info_parse() {
+        let info = ZmodemFileInfo::parse(b"fw.bin\x001024 14755607010 100755 0 1 1024\x00").unwrap();
+        assert_eq!(info.name, "fw.bin");
+        assert_eq!(info.size, 1024);
+        assert_eq!(info.mtime, Some(UNIX_EPOCH + Duration::from_secs(0o14755607010)));
+        assert_eq!(info.mode, Some(0o100755));
+        assert_eq!(ZmodemFileInfo::parse(&info.to_bytes()), Some(info));
+
+        // Senders may omit everything but the name
+        let bare = ZmodemFileInfo::parse(b"log.txt\x00\x00").unwrap();
+        assert_eq!((bare.size, bare.mtime, bare.mode), (0, None, None));
+    }
+
+    #[test]
+    fn test_zmodem_file_info_apply() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("received.bin");
+        let file = std::fs::File::create(&path).unwrap();
+
+        let mtime = UNIX_EPOCH + Duration::from_secs(1_600_000_000);
+        let info = ZmodemFileInfo { name: "received.bin".into(), size: 0, mtime: Some(mtime), mode: Some(0o100600) };
+        info.apply(&file).unwrap();
+
+        let meta = std::fs::metadata(&path).unwrap();
+        assert_eq!(meta.modified().unwrap(), mtime);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(meta.permissions().mode() & 0o7777, 0o600);
+
+            // Special bits, group/other write and group/other execute never come from the peer
+            let info = ZmodemFileInfo { mode: Some(0o106777), ..info };
+            info.apply(&file).unwrap();
+            assert_eq!(std::fs::metadata(&path).unwrap().permissions().mode() & 0o7777, 0o744);
+        }
+    }
+
+    /// Feed `stream` to a receiver, collecting file data and replies
+    fn zmodem_receive(stream: &[u8]) -> (Vec<u8>, Vec<u8>, Vec<ZmodemEvent>) {
+        let mut rx = ZmodemTransfer::new(TransferDirection::Receive, ZmodemConfig::default());
+        let (mut data, mut reply, mut events) = (Vec::new(), Vec::new(), Vec::new());
+        for chunk in stream.chunks(5) {
+            rx.process_data(chunk);
+            while let Some(frame) = rx.next_frame().unwrap() {
+                match rx.receive_frame(frame, &mut reply).unwrap() {
+                    ZmodemEvent::Data(d) => data.extend_from_slice(&d),
+                    event => events.push(event),
+                }
+            }
+        }
+        (data, reply, events)
+    }
+
+    #[test]
+    fn test_zmodem_receiver_resumes_after_crc_error() {
+        let tx = ZmodemTransfer::new(TransferDirection::Send, ZmodemConfig { subpacket_size: 16, ..Default::default() });
+        let info = ZmodemFileInfo { name: "a.bin".into(), size: 32, mtime: None, mode: None };
+        let payload: Vec<u8> = (0..32).collect();
+
+        let mut corrupt = tx.build_zdata(0, &payload);
+        let last = corrupt.len() - 1;
+        corrupt[last] ^= 0x01;
+        let mut stream = tx.build_zfile(&info);
+        stream.extend_from_slice(&corrupt);
+        // The sender resumes at 16 after the ZRPOS; data at other offsets is dropped
+        stream.extend_from_slice(&tx.build_zdata(0, &payload));
+        stream.extend_from_slice(&tx.build_zdata(16, &payload[16..]));
+        stream.extend_from_slice(&tx.build_zeof(32));
+
+        let (data, reply, events) = zmodem_receive(&stream);
+        assert_eq!(data, payload);
+        assert_eq!(events.last(), Some(&ZmodemEvent::FileEnd));
+        let rpos: Vec<&[u8]> = reply.windows(16).filter(|w| w.starts_with(b"**\x18B09")).map(|w| &w[6..14]).collect();
+        assert_eq!(rpos, [&b"00000000"[..], b"10000000", b"10000000"]);
+    }
+
+    #[test]
+    fn test_zmodem_receiver_reports_cancel() {
+        let mut rx = ZmodemTransfer::new(TransferDirection::Receive, ZmodemConfig::default());
+        rx.process_data(b"noise\x18\x18\x18");
+        assert!(rx.next_frame().unwrap().is_none());
+        rx.process_data(&[CAN, CAN]);
+        assert!(matches!(rx.next_frame(), Err(TransferError::Cancelled("sender"))));
+    }
+
+    /// One end of an in-memory full-duplex link
+    struct PipeEnd {
+        rx: std::sync::mpsc::Receiver<Vec<u8>>,
+        tx: std::sync::mpsc::Sender<Vec<u8>>,
+        pending: std::collections::VecDeque<u8>,
+    }
+
+    fn pipe() -> (PipeEnd, PipeEnd) {
+        let (a_tx, b_rx) = std::sync::mpsc::channel();
+        let (b_tx, a_rx) = std::sync::mpsc::channel();
+        let end = |rx, tx| PipeEnd { rx, tx, pending: std::collections::VecDeque::new() };
+        (end(a_rx, a_tx), end(b_rx, b_tx))
+    }
+
+    impl Read for PipeEnd {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            if self.pending.is_empty() {
+                if let Ok(chunk) = self.rx.recv_timeout(Duration::from_millis(5)) {
+                    self.pending.extend(chunk);
+                }
+            }
+            let n = buf.len().min(self.pending.len());
+            for slot in buf.iter_mut().take(n) {
+                *slot = self.pending.pop_front().unwrap();
+            }
+            Ok(n)
+        }
+    }
+
+    impl Write for PipeEnd {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            let _ = self.tx.send(buf.to_vec());
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_zmodem_send_and_receive_round_trip() {
+        let payload: Vec<u8> = (0..5000u32).map(|i| (i * 13 % 256) as u8).collect();
+        for window_size in [0, 1024] {
+            let (mut sender_port, mut receiver_port) = pipe();
+            let config = ZmodemConfig { subpacket_size: 256, window_size, ..ZmodemConfig::default() };
+            let file = payload.clone();
+            let sender = std::thread::spawn(move || {
+                let mut tx = ZmodemTransfer::new(TransferDirection::Send, config);
+                tx.send_file(&mut file.as_slice(), &mut sender_port, "fw.bin", file.len() as u64)
+                    .map(|()| tx.progress().clone())
+            });
+
+            let mut rx = ZmodemTransfer::new(TransferDirection::Receive, ZmodemConfig::default());
+            let mut received = Vec::new();
+            let info = rx.receive_file(&mut receiver_port, &mut received).unwrap();
+            let progress = sender.join().unwrap().unwrap();
+
+            assert!(received == payload, "window {}: got {} bytes", window_size, received.len());
+            assert_eq!((info.name.as_str(), info.size), ("fw.bin", payload.len() as u64));
+            assert_eq!(progress.state, TransferState::Complete);
+            assert_eq!(progress.bytes_transferred, payload.len() as u64);
+        }
+    }
+
     #[test]
     fn test_zmodem_auto_start() {
         assert!(ZmodemTransfer::is_auto_start(b"rz\r*"));
         assert!(!ZmodemTransfer::is_auto_start(b"hello"));
+
+        let mut zfin = Vec::new();
+        ZmodemTransfer::new(TransferDirection::Send, ZmodemConfig::default())
+            .send_zfin(&mut zfin)
+            .unwrap();
+        assert!(ZmodemTransfer::is_finish(&zfin));
+        assert!(!ZmodemTransfer::is_finish(b"rz\r**\x18B00"));
+        assert!(ZmodemTransfer::is_cancel(&[0x18; 5]));
     }
 }
 
@@ -0,0 +1,434 @@
+//! Receive-path transforms
+//!
+//! A [`TransformChain`] rewrites received bytes before they reach triggers,
+//! the log and the display. Transforms are applied in the order added and
+//! may keep state across chunks.
+
+use crate::core::codec::{Codec, HexCodec};
+use crate::core::logger::TimestampConfig;
+use crate::core::terminal::{AnsiEvent, AnsiParser};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine as _;
+use chrono::Local;
+use regex::bytes::Regex;
+use std::time::Instant;
+
+/// A stage in the receive path
+pub trait DataTransform: Send {
+    /// Short name for display
+    fn name(&self) -> &str;
+
+    /// Transform one received chunk
+    fn transform(&mut self, data: &[u8]) -> Vec<u8>;
+}
+
+/// Ordered list of transforms applied to received data
+#[derive(Default)]
+pub struct TransformChain {
+    stages: Vec<Box<dyn DataTransform>>,
+}
+
+impl TransformChain {
+    /// Create an empty chain
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a transform (builder)
+    pub fn with(mut self, transform: impl DataTransform + 'static) -> Self {
+        self.push(Box::new(transform));
+        self
+    }
+
+    /// Append a transform
+    pub fn push(&mut self, transform: Box<dyn DataTransform>) {
+        self.stages.push(transform);
+    }
+
+    /// Remove all transforms
+    pub fn clear(&mut self) {
+        self.stages.clear();
+    }
+
+    /// Check if the chain passes data through unchanged
+    pub fn is_empty(&self) -> bool {
+        self.stages.is_empty()
+    }
+
+    /// Names of the transforms, in order
+    pub fn names(&self) -> Vec<&str> {
+        self.stages.iter().map(|stage| stage.name()).collect()
+    }
+
+    /// Run `data` through every transform
+    pub fn apply(&mut self, data: &[u8]) -> Vec<u8> {
+        let mut data = data.to_vec();
+        for stage in &mut self.stages {
+            if data.is_empty() {
+                break;
+            }
+            data = stage.transform(&data);
+        }
+        data
+    }
+}
+
+impl std::fmt::Debug for TransformChain {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.names()).finish()
+    }
+}
+
+/// Remove escape sequences, keeping text and control characters
+pub struct StripAnsi {
+    parser: AnsiParser,
+}
+
+impl StripAnsi {
+    /// Create the transform
+    pub fn new() -> Self {
+        Self { parser: AnsiParser::new() }
+    }
+}
+
+impl Default for StripAnsi {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DataTransform for StripAnsi {
+    fn name(&self) -> &str {
+        "strip-ansi"
+    }
+
+    fn transform(&mut self, data: &[u8]) -> Vec<u8> {
+        let mut out = Vec::with_capacity(data.len());
+        for event in self.parser.parse(data) {
+            match event {
+                AnsiEvent::Print(c) => {
+                    let mut buf = [0u8; 4];
+                    out.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+                }
+                AnsiEvent::Execute(byte) => out.push(byte),
+                _ => {}
+            }
+        }
+        out
+    }
+}
+
+/// Render bytes outside printable ASCII as `\xNN`, keeping tab, CR and LF
+#[derive(Debug, Default)]
+pub struct HexEscape;
+
+impl DataTransform for HexEscape {
+    fn name(&self) -> &str {
+        "hex-escape"
+    }
+
+    fn transform(&mut self, data: &[u8]) -> Vec<u8> {
+        let mut out = Vec::with_capacity(data.len());
+        for &byte in data {
+            match byte {
+                b'\t' | b'\r' | b'\n' | 0x20..=0x7E => out.push(byte),
+                _ => out.extend_from_slice(format!("\\x{:02x}", byte).as_bytes()),
+            }
+        }
+        out
+    }
+}
+
+/// Prefix each line with `[timestamp] `
+pub struct LineTimestamp {
+    config: TimestampConfig,
+    started: Instant,
+    at_line_start: bool,
+}
+
+impl LineTimestamp {
+    /// Stamp lines using `config`
+    pub fn new(config: TimestampConfig) -> Self {
+        Self {
+            config,
+            started: Instant::now(),
+            at_line_start: true,
+        }
+    }
+}
+
+impl Default for LineTimestamp {
+    fn default() -> Self {
+        Self::new(TimestampConfig::default())
+    }
+}
+
+impl DataTransform for LineTimestamp {
+    fn name(&self) -> &str {
+        "line-timestamp"
+    }
+
+    fn transform(&mut self, data: &[u8]) -> Vec<u8> {
+        let stamp = format!("[{}] ", self.config.render(&Local::now(), self.started.elapsed()));
+        let mut out = Vec::with_capacity(data.len() + stamp.len());
+        for &byte in data {
+            if self.at_line_start {
+                out.extend_from_slice(stamp.as_bytes());
+                self.at_line_start = false;
+            }
+            out.push(byte);
+            self.at_line_start = byte == b'\n';
+        }
+        out
+    }
+}
+
+/// Encode each chunk as one line of space-separated hex pairs
+///
+/// [`HexDecode`] turns the output back into the original bytes; see
+/// [`HexDump`] for a readable dump.
+#[derive(Debug, Default)]
+pub struct HexEncode;
+
+impl HexEncode {
+    /// Create the transform
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl DataTransform for HexEncode {
+    fn name(&self) -> &str {
+        "hex-encode"
+    }
+
+    fn transform(&mut self, data: &[u8]) -> Vec<u8> {
+        let mut out = Vec::with_capacity(data.len() * 3);
+        for (i, byte) in data.iter().enumerate() {
+            if i > 0 {
+                out.push(b' ');
+            }
+            out.extend_from_slice(format!("{:02X}", byte).as_bytes());
+        }
+        out.push(b'\n');
+        out
+    }
+}
+
+/// Render bytes as hexdump lines with an ASCII column
+pub struct HexDump {
+    codec: HexCodec,
+}
+
+impl HexDump {
+    /// Create the transform
+    pub fn new() -> Self {
+        Self {
+            codec: HexCodec::new().show_ascii(true),
+        }
+    }
+}
+
+impl Default for HexDump {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DataTransform for HexDump {
+    fn name(&self) -> &str {
+        "hex-dump"
+    }
+
+    fn transform(&mut self, data: &[u8]) -> Vec<u8> {
+        self.codec.encode(data).into_bytes()
+    }
+}
+
+/// Turn hex text into bytes, ignoring separators and `0x` prefixes
+///
+/// An odd digit, or a `0` that may start a `0x` prefix, at the end of a
+/// chunk is kept for the next one.
+#[derive(Debug, Default)]
+pub struct HexDecode {
+    /// High nibble waiting for its low nibble
+    high: Option<u8>,
+    /// A `0` at the start of a token, until the next byte shows whether it is `0x`
+    zero: bool,
+    /// The previous byte was a hex digit (so a `0` is not a token start)
+    in_token: bool,
+}
+
+/// Value of an ASCII hex digit
+fn hex_digit(byte: u8) -> Option<u8> {
+    match byte {
+        b'0'..=b'9' => Some(byte - b'0'),
+        b'a'..=b'f' => Some(byte - b'a' + 10),
+        b'A'..=b'F' => Some(byte - b'A' + 10),
+        _ => None,
+    }
+}
+
+impl HexDecode {
+    fn push_nibble(&mut self, nibble: u8, out: &mut Vec<u8>) {
+        match self.high.take() {
+            Some(high) => out.push(high << 4 | nibble),
+            None => self.high = Some(nibble),
+        }
+        self.in_token = true;
+    }
+}
+
+impl DataTransform for HexDecode {
+    fn name(&self) -> &str {
+        "hex-decode"
+    }
+
+    fn transform(&mut self, data: &[u8]) -> Vec<u8> {
+        let mut out = Vec::with_capacity(data.len() / 2);
+        for &byte in data {
+            if std::mem::take(&mut self.zero) {
+                if matches!(byte, b'x' | b'X') {
+                    self.in_token = false;
+                    continue;
+                }
+                self.push_nibble(0, &mut out);
+            }
+            match hex_digit(byte) {
+                Some(0) if !self.in_token => self.zero = true,
+                Some(nibble) => self.push_nibble(nibble, &mut out),
+                None => self.in_token = false,
+            }
+        }
+        out
+    }
+}
+
+/// Encode each chunk as one line of Base64
+#[derive(Debug, Default)]
+pub struct Base64Encode;
+
+impl DataTransform for Base64Encode {
+    fn name(&self) -> &str {
+        "base64-encode"
+    }
+
+    fn transform(&mut self, data: &[u8]) -> Vec<u8> {
+        let mut out = BASE64.encode(data).into_bytes();
+        out.push(b'\n');
+        out
+    }
+}
+
+/// Decode lines of Base64 (as written by [`Base64Encode`])
+///
+/// Lines that are not valid Base64 are dropped.
+#[derive(Debug, Default)]
+pub struct Base64Decode {
+    line: Vec<u8>,
+}
+
+impl DataTransform for Base64Decode {
+    fn name(&self) -> &str {
+        "base64-decode"
+    }
+
+    fn transform(&mut self, data: &[u8]) -> Vec<u8> {
+        let mut out = Vec::new();
+        for &byte in data {
+            if byte != b'\n' {
+                self.line.push(byte);
+                continue;
+            }
+            let line = std::mem::take(&mut self.line);
+            match BASE64.decode(String::from_utf8_lossy(&line).trim()) {
+                Ok(decoded) => out.extend(decoded),
+                Err(e) => tracing::debug!("Dropping invalid Base64 line: {}", e),
+            }
+        }
+        out
+    }
+}
+
+/// Pass only complete lines matching a regex
+pub struct LineFilter {
+    pattern: Regex,
+    line: Vec<u8>,
+}
+
+impl LineFilter {
+    /// Keep lines matching `pattern`
+    pub fn new(pattern: Regex) -> Self {
+        Self { pattern, line: Vec::new() }
+    }
+}
+
+impl DataTransform for LineFilter {
+    fn name(&self) -> &str {
+        "line-filter"
+    }
+
+    fn transform(&mut self, data: &[u8]) -> Vec<u8> {
+        let mut out = Vec::new();
+        for &byte in data {
+            self.line.push(byte);
+            if byte == b'\n' {
+                let line = std::mem::take(&mut self.line);
+                if self.pattern.is_match(&line) {
+                    out.extend(line);
+                }
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chain_applies_in_order() {
+        let mut chain = TransformChain::new().with(StripAnsi::new()).with(HexEscape);
+        assert_eq!(chain.names(), vec!["strip-ansi", "hex-escape"]);
+
+        // The escape sequence is split across chunks
+        assert_eq!(chain.apply(b"\x1b[1;3"), b"");
+        assert_eq!(chain.apply(b"1mred\x1b[0m\x00\r\n"), b"red\\x00\r\n");
+    }
+
+    #[test]
+    fn test_codec_transforms_span_chunks() {
+        let mut decode = HexDecode::default();
+        assert_eq!(decode.transform(b"0x41 4"), b"A");
+        assert_eq!(decode.transform(b"2:43\n"), b"BC");
+        // A `0x` prefix split across chunks
+        assert_eq!(decode.transform(b"0x44 0"), b"D");
+        assert_eq!(decode.transform(b"x45 00 0"), b"E\x00");
+        assert_eq!(decode.transform(b"A"), b"\n");
+
+        let mut chain = TransformChain::new().with(HexEncode::new()).with(HexDecode::default());
+        assert_eq!(chain.apply(b"\x00\xff0x"), b"\x00\xff0x");
+        assert_eq!(HexEncode::new().transform(b"AB\n"), b"41 42 0A\n");
+
+        let mut chain = TransformChain::new().with(Base64Encode).with(Base64Decode::default());
+        assert_eq!(chain.apply(b"\x00\xffbin"), b"\x00\xffbin");
+        assert_eq!(Base64Encode.transform(b"hi"), b"aGk=\n");
+
+        let dump = String::from_utf8(HexDump::new().transform(b"AB\n")).unwrap();
+        assert!(dump.starts_with("41 42 0A "));
+        assert!(dump.ends_with("|AB.|\n"));
+
+        let mut filter = LineFilter::new(Regex::new("^ERR").unwrap());
+        assert_eq!(filter.transform(b"ok\nERR 1\nER"), b"ERR 1\n");
+        assert_eq!(filter.transform(b"R 2\nok\n"), b"ERR 2\n");
+    }
+
+    #[test]
+    fn test_line_timestamp_spans_chunks() {
+        let mut stamp = LineTimestamp::new(TimestampConfig::custom("T"));
+        assert_eq!(stamp.transform(b"a\nb"), b"[T] a\n[T] b");
+        assert_eq!(stamp.transform(b"c\n"), b"c\n");
+        assert_eq!(stamp.transform(b"d"), b"[T] d");
+    }
+}
@@ -11,7 +11,9 @@ use btleplug::platform::{Adapter, Manager, Peripheral};
 use bytes::Bytes;
 use futures::stream::StreamExt;
 use parking_lot::RwLock;
+use crate::core::bluetooth::manufacturer_name;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::{broadcast, mpsc};
@@ -22,7 +24,7 @@ use uuid::Uuid;
 pub enum BluetoothType {
     /// BLE (Bluetooth Low Energy)
     Ble,
-    /// Classic Bluetooth SPP (Serial Port Profile)
+    /// Classic Bluetooth SPP (Serial Port Profile) over RFCOMM
     Spp,
 }
 
@@ -63,6 +65,9 @@ pub struct BluetoothConfig {
     pub auto_reconnect: bool,
     /// MTU size (for BLE)
     pub mtu: u16,
+    /// RFCOMM channel (for SPP; 0 = discover)
+    #[serde(default)]
+    pub channel: u8,
 }
 
 impl Default for BluetoothConfig {
@@ -74,6 +79,19 @@ impl Default for BluetoothConfig {
             timeout_secs: 10,
             auto_reconnect: false,
             mtu: 512,
+            channel: 0,
+        }
+    }
+}
+
+impl BluetoothConfig {
+    /// Classic SPP connection to `address` on RFCOMM `channel` (0 = discover)
+    pub fn rfcomm(address: &str, channel: u8) -> Self {
+        Self {
+            device: address.to_string(),
+            bt_type: BluetoothType::Spp,
+            channel,
+            ..Self::default()
         }
     }
 }
@@ -93,6 +111,25 @@ pub struct BluetoothDevice {
     pub bt_type: BluetoothType,
     /// Advertised services
     pub services: Vec<String>,
+    /// Manufacturer-specific advertising data by company ID
+    #[serde(default)]
+    pub manufacturer_data: HashMap<u16, Vec<u8>>,
+    /// Advertised service data by service UUID
+    #[serde(default)]
+    pub service_data: HashMap<Uuid, Vec<u8>>,
+    /// Advertised TX power level in dBm
+    #[serde(default)]
+    pub tx_power: Option<i16>,
+    /// GAP appearance value, where the platform reports it
+    #[serde(default)]
+    pub appearance: Option<u16>,
+}
+
+impl BluetoothDevice {
+    /// Name of the manufacturer in the advertising data
+    pub fn manufacturer_name(&self) -> Option<&'static str> {
+        manufacturer_name(self.manufacturer_data.keys())
+    }
 }
 
 /// Bluetooth scanner for discovering devices
@@ -156,6 +193,11 @@ impl BluetoothScanner {
                     connectable: true,
                     bt_type: BluetoothType::Ble,
                     services,
+                    manufacturer_data: props.manufacturer_data,
+                    service_data: props.service_data,
+                    tx_power: props.tx_power_level,
+                    // btleplug does not expose the appearance AD field
+                    appearance: None,
                 });
             }
         }
@@ -520,6 +562,7 @@ mod tests {
         assert_eq!(config.bt_type, BluetoothType::Ble);
         assert_eq!(config.timeout_secs, 10);
         assert_eq!(config.mtu, 512);
+        assert_eq!(config.channel, 0);
     }
 
     #[test]
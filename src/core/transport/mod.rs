@@ -4,24 +4,39 @@
 //! - Serial ports (RS-232, RS-485, USB-Serial)
 //! - Raw TCP connections
 //! - Telnet protocol
+//! - TLS around raw TCP and Telnet (stunnel, telnets)
 //! - SSH-2 protocol
-//! - Bluetooth (BLE and SPP)
+//! - Bluetooth (BLE and SPP over BlueZ RFCOMM)
+//! - Throttled decorator for simulating slow or noisy links
 
 mod bluetooth;
+mod ports;
 mod serial;
 mod ssh;
+mod reset;
 mod tcp;
 mod telnet;
+mod throttle;
+mod tls;
+mod url;
 
 pub use bluetooth::{
     BleServiceConfig, BluetoothConfig, BluetoothDevice, BluetoothScanner, BluetoothTransport,
     BluetoothType, GattBrowser, GattCharacteristic, GattService,
 };
-pub use serial::{SerialConfig, SerialFlowControl, SerialParity, SerialTransport};
-pub use ssh::{PortForward, PortForwardType, SftpClient, SshAuth, SshConfig, SshTransport};
+pub use ports::{enumerate_ports, PortEvent, PortInfo, PortKind, PortWatcher};
+pub use reset::{LineStep, ResetProfile};
+pub use serial::{Rs485Config, SerialConfig, DEFAULT_RX_BUFFER_SIZE, SerialFlowControl, SerialParams, SerialParity, SerialTransport};
+pub use ssh::{
+    default_known_hosts_path, AuthPrompt, HostKeyPolicy, PortForward, PortForwardType, PromptHandler, SftpClient,
+    SshAuth, SshConfig, SshError, SshTransport,
+};
 pub use tcp::{TcpConfig, TcpTransport};
 pub use telnet::{TelnetConfig, TelnetTransport};
+pub use throttle::{ThrottleConfig, ThrottledTransport};
+pub use tls::{TlsConfig, TlsInfo, TlsVerify, TELNETS_PORT};
 
+use crate::core::bluetooth::{RfcommConfig, RfcommTransport};
 use async_trait::async_trait;
 use bytes::Bytes;
 use std::fmt;
@@ -44,7 +59,7 @@ pub enum Transport {
 }
 
 /// Transport type identifier
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 pub enum TransportType {
     /// Serial port
     Serial,
@@ -85,6 +100,14 @@ pub enum TransportError {
     #[error("Port not found: {0}")]
     PortNotFound(String),
 
+    /// Authentication rejected by the remote
+    #[error("Authentication failed: {0}")]
+    AuthFailed(String),
+
+    /// SSH host verification error
+    #[error(transparent)]
+    Ssh(#[from] SshError),
+
     /// Permission denied
     #[error("Permission denied: {0}")]
     PermissionDenied(String),
@@ -120,6 +143,10 @@ pub enum TransportError {
     /// Receive error
     #[error("Receive error: {0}")]
     ReceiveError(String),
+
+    /// The transport is not available on this platform
+    #[error("Not supported: {0}")]
+    Unsupported(String),
 }
 
 /// Transport statistics
@@ -139,6 +166,27 @@ pub struct TransportStats {
     pub uptime_secs: u64,
 }
 
+/// Fill level of a transport's receive buffer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BufferLevel {
+    /// Bytes waiting to be received
+    pub used: usize,
+    /// Buffer size
+    pub capacity: usize,
+    /// Highest `used` seen since connecting (high-water mark)
+    pub peak: usize,
+}
+
+impl BufferLevel {
+    /// Share of the buffer in use (0.0 - 1.0)
+    pub fn fill(&self) -> f32 {
+        if self.capacity == 0 {
+            return 0.0;
+        }
+        self.used as f32 / self.capacity as f32
+    }
+}
+
 /// Transport trait for all connection types
 #[async_trait]
 pub trait TransportTrait: Send + Sync {
@@ -185,10 +233,35 @@ pub trait TransportTrait: Send + Sync {
         Ok(())
     }
 
+    /// Change parity on the open port (for serial)
+    async fn set_parity(&mut self, _parity: SerialParity) -> Result<(), TransportError> {
+        Err(TransportError::ConfigError("Parity is only supported on serial ports".to_string()))
+    }
+
+    /// Change baud rate, framing and flow control without closing the port (for serial)
+    async fn reconfigure(&mut self, _params: SerialParams) -> Result<(), TransportError> {
+        Err(TransportError::ConfigError("Reconfiguring is only supported on serial ports".to_string()))
+    }
+
     /// Get modem lines state (for serial)
     fn modem_lines(&self) -> Option<ModemLines> {
         None
     }
+
+    /// Receive buffer fill level, for transports that read ahead
+    fn receive_buffer(&self) -> Option<BufferLevel> {
+        None
+    }
+
+    /// Take the count of received bytes dropped because the buffer was full
+    fn take_dropped(&mut self) -> u64 {
+        0
+    }
+
+    /// Negotiated TLS parameters, for connections wrapped in TLS
+    fn tls_info(&self) -> Option<TlsInfo> {
+        None
+    }
 }
 
 /// Modem control lines state
@@ -227,6 +300,10 @@ pub async fn create_transport(config: Transport) -> Result<Box<dyn TransportTrai
             let transport = SshTransport::new(cfg);
             Ok(Box::new(transport))
         }
+        Transport::Bluetooth(cfg) if cfg.bt_type == BluetoothType::Spp => {
+            let transport = RfcommTransport::new(RfcommConfig::from(&cfg));
+            Ok(Box::new(transport))
+        }
         Transport::Bluetooth(cfg) => {
             let transport = BluetoothTransport::new(cfg).await?;
             Ok(Box::new(transport))
@@ -0,0 +1,196 @@
+//! Serial port enumeration and hot-plug detection
+
+use serialport::{SerialPortInfo, SerialPortType};
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// Kind of device behind a serial port
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PortKind {
+    Usb,
+    Pci,
+    Bluetooth,
+    Unknown,
+}
+
+/// A serial port with whatever device details the OS reports
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PortInfo {
+    /// Port name (`/dev/ttyUSB0`, `COM3`)
+    pub name: String,
+    pub kind: PortKind,
+    /// USB vendor ID
+    pub vid: Option<u16>,
+    /// USB product ID
+    pub pid: Option<u16>,
+    pub serial_number: Option<String>,
+    pub manufacturer: Option<String>,
+    pub product: Option<String>,
+}
+
+impl PortInfo {
+    /// Port without device details
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            kind: PortKind::Unknown,
+            vid: None,
+            pid: None,
+            serial_number: None,
+            manufacturer: None,
+            product: None,
+        }
+    }
+
+    /// Human-readable device description, e.g. "CP2102 USB to UART"
+    pub fn description(&self) -> Option<String> {
+        self.product
+            .clone()
+            .or_else(|| self.manufacturer.clone())
+            .or_else(|| match (self.vid, self.pid) {
+                (Some(vid), Some(pid)) => Some(format!("USB {:04X}:{:04X}", vid, pid)),
+                _ => None,
+            })
+    }
+
+    /// Label for port pickers: description followed by the port name
+    pub fn label(&self) -> String {
+        match self.description() {
+            Some(description) => format!("{} ({})", description, self.name),
+            None => self.name.clone(),
+        }
+    }
+}
+
+impl From<SerialPortInfo> for PortInfo {
+    fn from(info: SerialPortInfo) -> Self {
+        let mut port = Self::new(&info.port_name);
+        port.kind = match info.port_type {
+            SerialPortType::UsbPort(usb) => {
+                port.vid = Some(usb.vid);
+                port.pid = Some(usb.pid);
+                port.serial_number = usb.serial_number;
+                port.manufacturer = usb.manufacturer;
+                port.product = usb.product;
+                PortKind::Usb
+            }
+            SerialPortType::PciPort => PortKind::Pci,
+            SerialPortType::BluetoothPort => PortKind::Bluetooth,
+            SerialPortType::Unknown => PortKind::Unknown,
+        };
+        port
+    }
+}
+
+/// List serial ports with device details (empty if enumeration fails)
+pub fn enumerate_ports() -> Vec<PortInfo> {
+    serialport::available_ports()
+        .map(|ports| ports.into_iter().map(PortInfo::from).collect())
+        .unwrap_or_default()
+}
+
+/// Port plug/unplug event
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PortEvent {
+    Added(PortInfo),
+    Removed(PortInfo),
+}
+
+/// Detects ports appearing and disappearing by comparing enumerations
+#[derive(Debug, Default)]
+pub struct PortWatcher {
+    known: Vec<PortInfo>,
+}
+
+impl PortWatcher {
+    /// Watcher that treats the currently present ports as known
+    pub fn new() -> Self {
+        Self { known: enumerate_ports() }
+    }
+
+    /// Ports seen by the last poll
+    pub fn ports(&self) -> &[PortInfo] {
+        &self.known
+    }
+
+    /// Enumerate ports and report changes since the last poll
+    pub fn poll(&mut self) -> Vec<PortEvent> {
+        self.update(enumerate_ports())
+    }
+
+    /// Report changes between the known ports and `current`
+    pub fn update(&mut self, current: Vec<PortInfo>) -> Vec<PortEvent> {
+        let mut events: Vec<PortEvent> = self
+            .known
+            .iter()
+            .filter(|old| !current.iter().any(|p| p.name == old.name))
+            .cloned()
+            .map(PortEvent::Removed)
+            .collect();
+        events.extend(
+            current
+                .iter()
+                .filter(|new| !self.known.iter().any(|p| p.name == new.name))
+                .cloned()
+                .map(PortEvent::Added),
+        );
+        self.known = current;
+        events
+    }
+
+    /// Poll every `interval` in a background task; stops when the receiver is dropped
+    pub fn watch(mut self, interval: Duration) -> mpsc::Receiver<PortEvent> {
+        let (tx, rx) = mpsc::channel(32);
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(interval).await;
+                let current = match tokio::task::spawn_blocking(enumerate_ports).await {
+                    Ok(current) => current,
+                    Err(_) => break,
+                };
+                for event in self.update(current) {
+                    if tx.send(event).await.is_err() {
+                        return;
+                    }
+                }
+                if tx.is_closed() {
+                    break;
+                }
+            }
+        });
+        rx
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_port_label() {
+        let mut port = PortInfo::new("/dev/ttyUSB0");
+        assert_eq!(port.label(), "/dev/ttyUSB0");
+
+        port.vid = Some(0x10C4);
+        port.pid = Some(0xEA60);
+        assert_eq!(port.label(), "USB 10C4:EA60 (/dev/ttyUSB0)");
+
+        port.product = Some("CP2102 USB to UART".to_string());
+        assert_eq!(port.label(), "CP2102 USB to UART (/dev/ttyUSB0)");
+    }
+
+    #[test]
+    fn test_watcher_reports_added_and_removed() {
+        let mut watcher = PortWatcher::default();
+        let a = PortInfo::new("COM1");
+        let b = PortInfo::new("COM3");
+
+        assert_eq!(watcher.update(vec![a.clone()]), vec![PortEvent::Added(a.clone())]);
+        assert!(watcher.update(vec![a.clone()]).is_empty());
+        assert_eq!(
+            watcher.update(vec![b.clone()]),
+            vec![PortEvent::Removed(a), PortEvent::Added(b)]
+        );
+        assert_eq!(watcher.ports().len(), 1);
+    }
+}
@@ -0,0 +1,97 @@
+//! DTR/RTS reset sequences
+//!
+//! Development boards wire DTR/RTS to reset and boot-mode pins through
+//! an "auto-reset" circuit. These sequences reproduce the toggling done
+//! by esptool and avrdude.
+
+use std::time::Duration;
+
+/// One step of a modem line sequence
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineStep {
+    /// Set DTR
+    Dtr(bool),
+    /// Set RTS
+    Rts(bool),
+    /// Wait before the next step
+    Delay(Duration),
+}
+
+/// Built-in and custom reset sequences
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResetProfile {
+    /// ESP32/ESP8266 into the ROM bootloader (esptool classic reset)
+    Esp32Classic,
+    /// Hard reset into the firmware (NodeMCU and other ESP boards)
+    NodeMcu,
+    /// Arduino (AVR) bootloader via a DTR/RTS pulse
+    Arduino,
+    /// User-defined sequence
+    Custom(Vec<LineStep>),
+}
+
+impl ResetProfile {
+    /// Steps to perform, in order
+    pub fn steps(&self) -> Vec<LineStep> {
+        use LineStep::{Delay, Dtr, Rts};
+
+        match self {
+            // EN low, IO0 high; then EN high with IO0 low; then release IO0
+            Self::Esp32Classic => vec![
+                Dtr(false),
+                Rts(true),
+                Delay(Duration::from_millis(100)),
+                Dtr(true),
+                Rts(false),
+                Delay(Duration::from_millis(50)),
+                Dtr(false),
+            ],
+            Self::NodeMcu => vec![
+                Dtr(false),
+                Rts(true),
+                Delay(Duration::from_millis(100)),
+                Rts(false),
+            ],
+            // Same timing as avrdude's arduino programmer
+            Self::Arduino => vec![
+                Dtr(false),
+                Rts(false),
+                Delay(Duration::from_millis(250)),
+                Dtr(true),
+                Rts(true),
+                Delay(Duration::from_millis(50)),
+            ],
+            Self::Custom(steps) => steps.clone(),
+        }
+    }
+
+    /// Display name
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Esp32Classic => "ESP32 (classic)",
+            Self::NodeMcu => "NodeMCU",
+            Self::Arduino => "Arduino",
+            Self::Custom(_) => "Custom",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_esp32_classic_ends_with_lines_released() {
+        let steps = ResetProfile::Esp32Classic.steps();
+        assert_eq!(steps.last(), Some(&LineStep::Dtr(false)));
+
+        let total: Duration = steps
+            .iter()
+            .filter_map(|s| match s {
+                LineStep::Delay(d) => Some(*d),
+                _ => None,
+            })
+            .sum();
+        assert_eq!(total, Duration::from_millis(150));
+    }
+}
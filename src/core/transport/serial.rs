@@ -1,15 +1,33 @@
 //! Serial port transport implementation
 
-use super::{ModemLines, TransportError, TransportStats, TransportTrait, TransportType};
+use super::{BufferLevel, ModemLines, TransportError, TransportStats, TransportTrait, TransportType};
+use crate::core::arbitration::{PortLock, PortLockError};
 use async_trait::async_trait;
 use bytes::Bytes;
 use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
 use serialport::{DataBits, FlowControl, Parity, SerialPort, StopBits};
+use std::collections::VecDeque;
 use std::io::{Read, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::thread::JoinHandle;
 use std::time::{Duration, Instant};
-use tokio::sync::broadcast;
+use tokio::sync::{broadcast, Notify};
+
+/// Default size of the buffer between the port reader and `receive`
+pub const DEFAULT_RX_BUFFER_SIZE: usize = 1024 * 1024;
+
+/// Read timeout of the port; also how long `receive` waits for data
+const READ_TIMEOUT: Duration = Duration::from_millis(100);
+
+fn default_rx_buffer_size() -> usize {
+    DEFAULT_RX_BUFFER_SIZE
+}
+
+fn default_exclusive() -> bool {
+    true
+}
 
 /// Serial port flow control type
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
@@ -23,7 +41,31 @@ pub enum SerialFlowControl {
     Software,
 }
 
+impl std::str::FromStr for SerialFlowControl {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "hardware" | "rts/cts" | "rtscts" => Ok(Self::Hardware),
+            "software" | "xon/xoff" | "xonxoff" => Ok(Self::Software),
+            "none" | "off" => Ok(Self::None),
+            _ => Err(()),
+        }
+    }
+}
+
 /// Serial port parity
+///
+/// `Mark` and `Space` ("stick" parity) send a constant 1 or 0 in the parity
+/// slot. Multidrop and 9-bit protocols use it as an address bit: the
+/// address byte goes out with `Mark`, the rest of the frame with `Space`,
+/// and slaves wake only on a set ninth bit. Switch between the two with
+/// [`TransportTrait::set_parity`].
+///
+/// Linux supports stick parity natively (`CMSPAR`). Elsewhere it is
+/// emulated by picking odd or even parity per byte and reconfiguring the
+/// port between runs of bytes; that drains the output queue at every
+/// switch, so throughput drops sharply, and received parity is not checked.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum SerialParity {
     /// No parity
@@ -33,6 +75,28 @@ pub enum SerialParity {
     Odd,
     /// Even parity
     Even,
+    /// Parity bit always 1
+    Mark,
+    /// Parity bit always 0
+    Space,
+}
+
+impl SerialParity {
+    /// Short form used in `8N1`-style descriptions
+    pub fn letter(&self) -> &'static str {
+        match self {
+            Self::None => "N",
+            Self::Odd => "O",
+            Self::Even => "E",
+            Self::Mark => "M",
+            Self::Space => "S",
+        }
+    }
+
+    /// Whether this is mark or space parity
+    pub fn is_stick(&self) -> bool {
+        matches!(self, Self::Mark | Self::Space)
+    }
 }
 
 impl std::str::FromStr for SerialParity {
@@ -43,11 +107,75 @@ impl std::str::FromStr for SerialParity {
             "none" | "n" => Ok(Self::None),
             "odd" | "o" => Ok(Self::Odd),
             "even" | "e" => Ok(Self::Even),
-            _ => Ok(Self::None),
+            "mark" | "m" => Ok(Self::Mark),
+            "space" | "s" => Ok(Self::Space),
+            _ => Err(()),
+        }
+    }
+}
+
+/// RS-485 half-duplex direction control
+///
+/// The driver enable pin of most RS-485 transceivers is wired to RTS. While
+/// a frame is being sent RTS is driven to `rts_on_send`, and once the UART
+/// has drained it is returned to `rts_after_send`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Rs485Config {
+    /// RTS level while transmitting (the transceiver's active level)
+    pub rts_on_send: bool,
+    /// RTS level after transmission
+    pub rts_after_send: bool,
+    /// Delay between asserting RTS and the first byte (ms)
+    pub delay_before_send_ms: u32,
+    /// Delay between the last byte draining and releasing RTS (ms)
+    pub delay_after_send_ms: u32,
+    /// Let the kernel toggle RTS via `TIOCSRS485` (Linux only)
+    #[serde(default)]
+    pub kernel: bool,
+}
+
+impl Default for Rs485Config {
+    fn default() -> Self {
+        Self {
+            rts_on_send: true,
+            rts_after_send: false,
+            delay_before_send_ms: 0,
+            delay_after_send_ms: 0,
+            kernel: false,
         }
     }
 }
 
+impl Rs485Config {
+    /// Set the RTS level used while transmitting
+    #[must_use]
+    pub fn active_high(mut self, high: bool) -> Self {
+        self.rts_on_send = high;
+        self.rts_after_send = !high;
+        self
+    }
+
+    /// Set the pre- and post-transmission delays
+    #[must_use]
+    pub fn delays(mut self, before: Duration, after: Duration) -> Self {
+        self.delay_before_send_ms = u32::try_from(before.as_millis()).unwrap_or(u32::MAX);
+        self.delay_after_send_ms = u32::try_from(after.as_millis()).unwrap_or(u32::MAX);
+        self
+    }
+
+    /// Use the kernel's RS-485 mode instead of toggling RTS from user space
+    #[must_use]
+    pub fn kernel(mut self, enable: bool) -> Self {
+        self.kernel = enable;
+        self
+    }
+
+    /// Whether RTS is toggled by `SerialTransport::send`
+    fn manual(&self) -> bool {
+        !(self.kernel && cfg!(target_os = "linux"))
+    }
+}
+
 /// Serial port configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SerialConfig {
@@ -65,6 +193,26 @@ pub struct SerialConfig {
     pub flow_control: SerialFlowControl,
     /// Auto-reconnect on disconnect
     pub auto_reconnect: bool,
+    /// RS-485 direction control
+    #[serde(default)]
+    pub rs485: Option<Rs485Config>,
+    /// DTR state set right after the port opens (None = leave as opened)
+    #[serde(default)]
+    pub dtr_on_open: Option<bool>,
+    /// RTS state set right after the port opens (None = leave as opened)
+    #[serde(default)]
+    pub rts_on_open: Option<bool>,
+    /// Received bytes buffered while the consumer is busy
+    ///
+    /// Once it is full, reading pauses if flow control is on, so the sender
+    /// is throttled; without flow control the oldest bytes are dropped (and
+    /// reported).
+    #[serde(default = "default_rx_buffer_size")]
+    pub rx_buffer_size: usize,
+    /// Lock the port against other processes (lock file, plus `flock` on
+    /// Linux); turn off only when sharing the port is known to be safe
+    #[serde(default = "default_exclusive")]
+    pub exclusive: bool,
 }
 
 impl SerialConfig {
@@ -78,6 +226,11 @@ impl SerialConfig {
             parity: SerialParity::None,
             flow_control: SerialFlowControl::None,
             auto_reconnect: false,
+            rs485: None,
+            dtr_on_open: None,
+            rts_on_open: None,
+            rx_buffer_size: DEFAULT_RX_BUFFER_SIZE,
+            exclusive: true,
         }
     }
 
@@ -115,6 +268,41 @@ impl SerialConfig {
         self.auto_reconnect = enable;
         self
     }
+
+    /// Enable RS-485 direction control
+    #[must_use]
+    pub fn rs485(mut self, rs485: Rs485Config) -> Self {
+        self.rs485 = Some(rs485);
+        self
+    }
+
+    /// Pin DTR as soon as the port opens (e.g. `false` to avoid auto-reset)
+    #[must_use]
+    pub fn dtr_on_open(mut self, state: bool) -> Self {
+        self.dtr_on_open = Some(state);
+        self
+    }
+
+    /// Pin RTS as soon as the port opens (e.g. to keep a board out of its bootloader)
+    #[must_use]
+    pub fn rts_on_open(mut self, state: bool) -> Self {
+        self.rts_on_open = Some(state);
+        self
+    }
+
+    /// Set the receive buffer size
+    #[must_use]
+    pub fn rx_buffer_size(mut self, bytes: usize) -> Self {
+        self.rx_buffer_size = bytes;
+        self
+    }
+
+    /// Lock the port against other processes
+    #[must_use]
+    pub fn exclusive(mut self, exclusive: bool) -> Self {
+        self.exclusive = exclusive;
+        self
+    }
 }
 
 impl Default for SerialConfig {
@@ -123,7 +311,247 @@ impl Default for SerialConfig {
     }
 }
 
+impl SerialConfig {
+    /// Current line settings
+    pub fn params(&self) -> SerialParams {
+        SerialParams {
+            baud_rate: self.baud_rate,
+            data_bits: self.data_bits,
+            stop_bits: self.stop_bits,
+            parity: self.parity,
+            flow_control: self.flow_control,
+        }
+    }
+
+    fn set_params(&mut self, params: SerialParams) {
+        self.baud_rate = params.baud_rate;
+        self.data_bits = params.data_bits;
+        self.stop_bits = params.stop_bits;
+        self.parity = params.parity;
+        self.flow_control = params.flow_control;
+    }
+}
+
+/// Line settings that can change while the port stays open
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SerialParams {
+    /// Baud rate
+    pub baud_rate: u32,
+    /// Data bits (5, 6, 7, 8)
+    pub data_bits: u8,
+    /// Stop bits (1, 2)
+    pub stop_bits: u8,
+    /// Parity
+    pub parity: SerialParity,
+    /// Flow control
+    pub flow_control: SerialFlowControl,
+}
+
+impl SerialParams {
+    /// Same settings at a different baud rate
+    #[must_use]
+    pub fn with_baud(mut self, baud_rate: u32) -> Self {
+        self.baud_rate = baud_rate;
+        self
+    }
+
+    /// Reject settings no UART supports
+    pub fn validate(&self) -> Result<(), TransportError> {
+        if self.baud_rate == 0 {
+            return Err(TransportError::ConfigError("Baud rate must be non-zero".to_string()));
+        }
+        if !(5..=8).contains(&self.data_bits) {
+            return Err(TransportError::ConfigError(format!("Unsupported data bits: {}", self.data_bits)));
+        }
+        if !(1..=2).contains(&self.stop_bits) {
+            return Err(TransportError::ConfigError(format!("Unsupported stop bits: {}", self.stop_bits)));
+        }
+        Ok(())
+    }
+}
+
+fn data_bits(bits: u8) -> DataBits {
+    match bits {
+        5 => DataBits::Five,
+        6 => DataBits::Six,
+        7 => DataBits::Seven,
+        _ => DataBits::Eight,
+    }
+}
+
+fn stop_bits(bits: u8) -> StopBits {
+    match bits {
+        2 => StopBits::Two,
+        _ => StopBits::One,
+    }
+}
+
+fn flow_control(flow: SerialFlowControl) -> FlowControl {
+    match flow {
+        SerialFlowControl::Hardware => FlowControl::Hardware,
+        SerialFlowControl::Software => FlowControl::Software,
+        SerialFlowControl::None => FlowControl::None,
+    }
+}
+
+/// Bytes read from the port and not yet taken by `receive`
+#[derive(Debug)]
+struct RxBuffer {
+    data: VecDeque<u8>,
+    capacity: usize,
+    /// High-water mark
+    peak: usize,
+    /// Bytes dropped since the last `take_dropped`
+    dropped: u64,
+    /// The reader stopped; `Some` error unless the port reported end of file
+    stopped: Option<Option<std::io::Error>>,
+}
+
+impl RxBuffer {
+    fn new(capacity: usize) -> Self {
+        Self {
+            data: VecDeque::new(),
+            capacity: capacity.max(1),
+            peak: 0,
+            dropped: 0,
+            stopped: None,
+        }
+    }
+
+    /// Free space before the buffer is full
+    fn room(&self) -> usize {
+        self.capacity.saturating_sub(self.data.len())
+    }
+
+    /// Append `bytes`, dropping the oldest bytes that do not fit
+    fn push(&mut self, bytes: &[u8]) {
+        self.data.extend(bytes);
+        let excess = self.data.len().saturating_sub(self.capacity);
+        if excess > 0 {
+            self.data.drain(..excess);
+            self.dropped += excess as u64;
+        }
+        self.peak = self.peak.max(self.data.len());
+    }
+
+    fn level(&self) -> BufferLevel {
+        BufferLevel {
+            used: self.data.len(),
+            capacity: self.capacity,
+            peak: self.peak,
+        }
+    }
+}
+
+/// How long the reader sleeps on a full buffer before rechecking `stop`
+const RX_FULL_WAIT: Duration = Duration::from_millis(100);
+
+/// Receive buffer shared with the reader thread
+struct RxShared {
+    buffer: parking_lot::Mutex<RxBuffer>,
+    data_ready: Notify,
+    space_ready: parking_lot::Condvar,
+    stop: AtomicBool,
+    /// Pause reading while the buffer is full (flow control is on)
+    throttle: AtomicBool,
+}
+
+impl RxShared {
+    /// How many bytes the reader may read next, or `None` when stopping
+    ///
+    /// When throttling, waits until `take` frees space: leaving the data in
+    /// the driver lets RTS/CTS or XON/XOFF hold off the sender.
+    fn wait_for_room(&self, chunk: usize) -> Option<usize> {
+        let mut buffer = self.buffer.lock();
+        loop {
+            if self.stop.load(Ordering::Relaxed) {
+                return None;
+            }
+            if !self.throttle.load(Ordering::Relaxed) {
+                return Some(chunk);
+            }
+            let room = buffer.room();
+            if room > 0 {
+                return Some(room.min(chunk));
+            }
+            self.space_ready.wait_for(&mut buffer, RX_FULL_WAIT);
+        }
+    }
+
+    /// Ask the reader to exit and wake it if it waits for space
+    fn request_stop(&self) {
+        self.stop.store(true, Ordering::Relaxed);
+        self.space_ready.notify_one();
+    }
+
+    /// Record that the reader stopped and wake `receive`
+    fn finish(&self, error: Option<std::io::Error>) {
+        self.buffer.lock().stopped = Some(error);
+        self.data_ready.notify_one();
+    }
+}
+
+/// Thread that reads the port into the receive buffer
+struct RxReader {
+    shared: Arc<RxShared>,
+    thread: JoinHandle<()>,
+}
+
+impl RxReader {
+    fn spawn(mut port: Box<dyn SerialPort>, capacity: usize, throttle: bool) -> std::io::Result<Self> {
+        let shared = Arc::new(RxShared {
+            buffer: parking_lot::Mutex::new(RxBuffer::new(capacity)),
+            data_ready: Notify::new(),
+            space_ready: parking_lot::Condvar::new(),
+            stop: AtomicBool::new(false),
+            throttle: AtomicBool::new(throttle),
+        });
+        let reader = shared.clone();
+        let thread = std::thread::Builder::new().name("serial-rx".to_string()).spawn(move || {
+            let mut chunk = vec![0u8; 4096];
+            while let Some(len) = reader.wait_for_room(chunk.len()) {
+                match port.read(&mut chunk[..len]) {
+                    Ok(0) => return reader.finish(None),
+                    Ok(n) => reader.buffer.lock().push(&chunk[..n]),
+                    Err(e) if e.kind() == std::io::ErrorKind::TimedOut => continue,
+                    Err(e) => return reader.finish(Some(e)),
+                }
+                reader.data_ready.notify_one();
+            }
+        })?;
+        Ok(Self { shared, thread })
+    }
+
+    /// Stop the thread and wait for it to release its port handle
+    ///
+    /// Blocks for up to one read timeout; call from a blocking context.
+    fn stop(self) {
+        self.shared.request_stop();
+        let _ = self.thread.join();
+    }
+
+    /// Take everything buffered; fails once the buffer is drained after the reader stopped
+    fn take(&self) -> Result<Bytes, TransportError> {
+        let mut buffer = self.shared.buffer.lock();
+        if buffer.data.is_empty() {
+            return match buffer.stopped.as_mut() {
+                Some(error) => Err(error.take().map_or(TransportError::Disconnected, TransportError::IoError)),
+                None => Ok(Bytes::new()),
+            };
+        }
+        let data: Vec<u8> = buffer.data.drain(..).collect();
+        self.shared.space_ready.notify_one();
+        Ok(Bytes::from(data))
+    }
+}
+
 /// Serial port transport
+///
+/// A reader thread moves received bytes into a bounded buffer. When a slow
+/// consumer lets it fill up, the reader pauses if flow control is on so the
+/// sender is held off; without flow control nothing can hold the sender
+/// off, so the oldest bytes are dropped and counted (see `take_dropped`)
+/// rather than lost unseen in the driver.
 pub struct SerialTransport {
     config: SerialConfig,
     port: Arc<parking_lot::Mutex<Option<Box<dyn SerialPort + Send>>>>,
@@ -131,12 +559,22 @@ pub struct SerialTransport {
     connected_at: Option<Instant>,
     tx: broadcast::Sender<Bytes>,
     modem_lines: Arc<RwLock<ModemLines>>,
+    /// Stick parity emulated per byte (no native support)
+    emulated_parity: Option<SerialParity>,
+    /// Descriptor of the open port, for termios settings serialport lacks
+    #[cfg(target_os = "linux")]
+    raw_fd: Option<std::os::unix::io::RawFd>,
+    /// Reader thread while connected
+    reader: Option<RxReader>,
+    /// Cross-process lock, held for the transport's lifetime
+    lock: Option<PortLock>,
 }
 
 impl SerialTransport {
     /// Create a new serial transport
     pub fn new(config: SerialConfig) -> Result<Self, TransportError> {
         let (tx, _) = broadcast::channel(1024);
+        let lock = if config.exclusive { lock_port(&config.port)? } else { None };
 
         Ok(Self {
             config,
@@ -145,9 +583,19 @@ impl SerialTransport {
             connected_at: None,
             tx,
             modem_lines: Arc::new(RwLock::new(ModemLines::default())),
+            emulated_parity: None,
+            #[cfg(target_os = "linux")]
+            raw_fd: None,
+            reader: None,
+            lock,
         })
     }
 
+    /// Lock file held for the port, if any
+    pub fn lock_path(&self) -> Option<&std::path::Path> {
+        self.lock.as_ref().map(PortLock::path)
+    }
+
     fn update_modem_lines(&self) {
         let mut port_guard = self.port.lock();
         if let Some(ref mut port) = *port_guard {
@@ -165,42 +613,272 @@ impl SerialTransport {
             }
         }
     }
+
+    fn write_dtr(&self, state: bool) -> Result<(), TransportError> {
+        let mut port_guard = self.port.lock();
+        let port = port_guard.as_mut().ok_or(TransportError::Disconnected)?;
+        port.write_data_terminal_ready(state)
+            .map_err(|e| TransportError::IoError(std::io::Error::new(std::io::ErrorKind::Other, e)))?;
+        self.modem_lines.write().dtr = state;
+        Ok(())
+    }
+
+    fn write_rts(&self, state: bool) -> Result<(), TransportError> {
+        let mut port_guard = self.port.lock();
+        let port = port_guard.as_mut().ok_or(TransportError::Disconnected)?;
+        port.write_request_to_send(state)
+            .map_err(|e| TransportError::IoError(std::io::Error::new(std::io::ErrorKind::Other, e)))?;
+        self.modem_lines.write().rts = state;
+        Ok(())
+    }
+
+    fn write_all(&self, data: &[u8]) -> Result<usize, TransportError> {
+        let mut port_guard = self.port.lock();
+        let port = port_guard.as_mut().ok_or(TransportError::Disconnected)?;
+
+        if let Some(parity) = self.emulated_parity {
+            return write_emulated_parity(port.as_mut(), data, parity, self.config.data_bits);
+        }
+
+        let written = port.write(data).map_err(TransportError::IoError)?;
+        // flush() waits for the output queue to drain (tcdrain on unix)
+        port.flush().map_err(TransportError::IoError)?;
+        Ok(written)
+    }
+
+    /// Apply `params` to the open port without closing it
+    ///
+    /// Bytes still in the output queue are drained at the old settings first.
+    fn apply_params(&mut self, params: SerialParams) -> Result<(), TransportError> {
+        params.validate()?;
+        {
+            let mut port_guard = self.port.lock();
+            let port = port_guard.as_mut().ok_or(TransportError::Disconnected)?;
+            let to_io = |e: serialport::Error| TransportError::IoError(std::io::Error::new(std::io::ErrorKind::Other, e));
+
+            port.flush().map_err(TransportError::IoError)?;
+            port.set_baud_rate(params.baud_rate).map_err(to_io)?;
+            port.set_data_bits(data_bits(params.data_bits)).map_err(to_io)?;
+            port.set_stop_bits(stop_bits(params.stop_bits)).map_err(to_io)?;
+            port.set_flow_control(flow_control(params.flow_control)).map_err(to_io)?;
+        }
+        self.apply_parity(params.parity)
+    }
+
+    /// Apply `parity` to the open port
+    fn apply_parity(&mut self, parity: SerialParity) -> Result<(), TransportError> {
+        let mut port_guard = self.port.lock();
+        let port = port_guard.as_mut().ok_or(TransportError::Disconnected)?;
+        let to_io = |e: serialport::Error| TransportError::IoError(std::io::Error::new(std::io::ErrorKind::Other, e));
+
+        self.emulated_parity = None;
+        match parity {
+            SerialParity::None | SerialParity::Odd | SerialParity::Even => {
+                let parity = match parity {
+                    SerialParity::Odd => Parity::Odd,
+                    SerialParity::Even => Parity::Even,
+                    _ => Parity::None,
+                };
+                port.set_parity(parity).map_err(to_io)?;
+                // set_parity leaves CMSPAR alone; a previous Mark/Space would stick
+                #[cfg(target_os = "linux")]
+                if let Some(fd) = self.raw_fd {
+                    set_stick_parity(fd, SerialParity::None)?;
+                }
+            }
+            SerialParity::Mark | SerialParity::Space => {
+                #[cfg(target_os = "linux")]
+                {
+                    let fd = self.raw_fd.ok_or(TransportError::NotConnected)?;
+                    set_stick_parity(fd, parity)?;
+                }
+                #[cfg(not(target_os = "linux"))]
+                {
+                    tracing::warn!(
+                        "{:?} parity is emulated on {}; writes will be slow",
+                        parity,
+                        self.config.port
+                    );
+                    self.emulated_parity = Some(parity);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Parity that makes the hardware send `parity`'s stick bit after `byte`
+fn emulated_parity(byte: u8, parity: SerialParity, data_bits: u8) -> Parity {
+    let mask = if data_bits >= 8 { 0xFF } else { (1u8 << data_bits) - 1 };
+    let odd_ones = (byte & mask).count_ones() % 2 == 1;
+    // Even parity sets the bit exactly when the data has an odd number of ones
+    match (parity, odd_ones) {
+        (SerialParity::Mark, true) | (SerialParity::Space, false) => Parity::Even,
+        _ => Parity::Odd,
+    }
+}
+
+/// Write with emulated stick parity, reconfiguring the port between runs
+fn write_emulated_parity(
+    port: &mut dyn SerialPort,
+    data: &[u8],
+    parity: SerialParity,
+    data_bits: u8,
+) -> Result<usize, TransportError> {
+    let mut start = 0;
+    while start < data.len() {
+        let wanted = emulated_parity(data[start], parity, data_bits);
+        let end = data[start..]
+            .iter()
+            .position(|&b| emulated_parity(b, parity, data_bits) != wanted)
+            .map_or(data.len(), |n| start + n);
+
+        port.set_parity(wanted)
+            .map_err(|e| TransportError::IoError(std::io::Error::new(std::io::ErrorKind::Other, e)))?;
+        port.write_all(&data[start..end]).map_err(TransportError::IoError)?;
+        // The run must leave the UART before the parity changes again
+        port.flush().map_err(TransportError::IoError)?;
+        start = end;
+    }
+    Ok(data.len())
+}
+
+/// `cflag` with termios `CMSPAR` stick parity set for `Mark`/`Space`
+///
+/// Any other parity clears `CMSPAR` and leaves `PARENB`/`PARODD` as they are.
+#[cfg(target_os = "linux")]
+fn stick_parity_cflag(cflag: libc::tcflag_t, parity: SerialParity) -> libc::tcflag_t {
+    match parity {
+        SerialParity::Mark => cflag | libc::PARENB | libc::CMSPAR | libc::PARODD,
+        SerialParity::Space => (cflag | libc::PARENB | libc::CMSPAR) & !libc::PARODD,
+        _ => cflag & !libc::CMSPAR,
+    }
+}
+
+/// Turn mark or space stick parity on, or off for any other parity
+#[cfg(target_os = "linux")]
+fn set_stick_parity(fd: std::os::unix::io::RawFd, parity: SerialParity) -> std::io::Result<()> {
+    // SAFETY: fd is an open tty; termios is plain data filled by tcgetattr.
+    unsafe {
+        let mut termios: libc::termios = std::mem::zeroed();
+        if libc::tcgetattr(fd, &mut termios) < 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        let cflag = stick_parity_cflag(termios.c_cflag, parity);
+        if cflag == termios.c_cflag {
+            return Ok(());
+        }
+        termios.c_cflag = cflag;
+        if libc::tcsetattr(fd, libc::TCSANOW, &termios) < 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+    }
+    Ok(())
+}
+
+/// Take the cross-process lock on `port`
+///
+/// A lock held by another process is an error; a lock file that cannot be
+/// created only logs a warning. Windows opens COM ports exclusively anyway.
+fn lock_port(port: &str) -> Result<Option<PortLock>, TransportError> {
+    if cfg!(windows) {
+        return Ok(None);
+    }
+    match PortLock::acquire(port) {
+        Ok(lock) => Ok(Some(lock)),
+        Err(e @ (PortLockError::Held { .. } | PortLockError::Unreadable { .. })) => Err(TransportError::PortInUse(e.to_string())),
+        Err(PortLockError::Io(e)) => {
+            tracing::warn!("Cannot lock {}: {}", port, e);
+            Ok(None)
+        }
+    }
+}
+
+/// Hold an exclusive `flock` on the open port; fails with `WouldBlock`
+/// while another process holds one
+#[cfg(target_os = "linux")]
+fn flock_exclusive(fd: std::os::unix::io::RawFd) -> std::io::Result<()> {
+    // SAFETY: `fd` is the open descriptor of the port
+    if unsafe { libc::flock(fd, libc::LOCK_EX | libc::LOCK_NB) } != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn enable_kernel_rs485(fd: std::os::unix::io::RawFd, config: &Rs485Config) -> std::io::Result<()> {
+    const SER_RS485_ENABLED: u32 = 1 << 0;
+    const SER_RS485_RTS_ON_SEND: u32 = 1 << 1;
+    const SER_RS485_RTS_AFTER_SEND: u32 = 1 << 2;
+
+    /// `struct serial_rs485` from linux/serial.h
+    #[repr(C)]
+    struct SerialRs485 {
+        flags: u32,
+        delay_rts_before_send: u32,
+        delay_rts_after_send: u32,
+        padding: [u32; 5],
+    }
+
+    let mut flags = SER_RS485_ENABLED;
+    if config.rts_on_send {
+        flags |= SER_RS485_RTS_ON_SEND;
+    }
+    if config.rts_after_send {
+        flags |= SER_RS485_RTS_AFTER_SEND;
+    }
+    let rs485 = SerialRs485 {
+        flags,
+        delay_rts_before_send: config.delay_before_send_ms,
+        delay_rts_after_send: config.delay_after_send_ms,
+        padding: [0; 5],
+    };
+
+    // SAFETY: fd is an open tty and rs485 matches the kernel's layout.
+    let ret = unsafe { libc::ioctl(fd, libc::TIOCSRS485 as _, &rs485 as *const SerialRs485) };
+    if ret < 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(())
 }
 
 #[async_trait]
 impl TransportTrait for SerialTransport {
     async fn connect(&mut self) -> Result<(), TransportError> {
-        let data_bits = match self.config.data_bits {
-            5 => DataBits::Five,
-            6 => DataBits::Six,
-            7 => DataBits::Seven,
-            _ => DataBits::Eight,
-        };
-
-        let stop_bits = match self.config.stop_bits {
-            2 => StopBits::Two,
-            _ => StopBits::One,
-        };
+        // A reader left over from a dropped connection still holds the port
+        if let Some(reader) = self.reader.take() {
+            reader.stop();
+        }
 
+        // Stick parity is applied once the port is open
         let parity = match self.config.parity {
             SerialParity::Odd => Parity::Odd,
             SerialParity::Even => Parity::Even,
-            SerialParity::None => Parity::None,
+            SerialParity::None | SerialParity::Mark | SerialParity::Space => Parity::None,
         };
 
-        let flow_control = match self.config.flow_control {
-            SerialFlowControl::Hardware => FlowControl::Hardware,
-            SerialFlowControl::Software => FlowControl::Software,
-            SerialFlowControl::None => FlowControl::None,
-        };
-
-        let port = serialport::new(&self.config.port, self.config.baud_rate)
-            .data_bits(data_bits)
-            .stop_bits(stop_bits)
+        let builder = serialport::new(&self.config.port, self.config.baud_rate)
+            .data_bits(data_bits(self.config.data_bits))
+            .stop_bits(stop_bits(self.config.stop_bits))
             .parity(parity)
-            .flow_control(flow_control)
-            .timeout(Duration::from_millis(100))
-            .open()
+            .flow_control(flow_control(self.config.flow_control))
+            .timeout(READ_TIMEOUT);
+
+        #[cfg(target_os = "linux")]
+        let opened = builder.open_native().and_then(|port| {
+            use std::os::unix::io::AsRawFd;
+            if self.config.exclusive {
+                flock_exclusive(port.as_raw_fd())?;
+            }
+            if let Some(rs485) = self.config.rs485.filter(|rs485| rs485.kernel) {
+                enable_kernel_rs485(port.as_raw_fd(), &rs485)?;
+            }
+            Ok(port)
+        });
+        #[cfg(not(target_os = "linux"))]
+        let opened = builder.open();
+
+        let port = opened
             .map_err(|e| match e.kind() {
                 serialport::ErrorKind::NoDevice => {
                     TransportError::PortNotFound(self.config.port.clone())
@@ -209,12 +887,58 @@ impl TransportTrait for SerialTransport {
                     std::io::ErrorKind::PermissionDenied => {
                         TransportError::PermissionDenied(self.config.port.clone())
                     }
+                    std::io::ErrorKind::WouldBlock => {
+                        TransportError::PortInUse(format!("{} is locked by another process", self.config.port))
+                    }
                     _ => TransportError::ConnectionFailed(e.to_string()),
                 },
                 _ => TransportError::ConnectionFailed(e.to_string()),
             })?;
+        #[cfg(target_os = "linux")]
+        let port = {
+            use std::os::unix::io::AsRawFd;
+            self.raw_fd = Some(port.as_raw_fd());
+            Box::new(port) as Box<dyn SerialPort>
+        };
 
         *self.port.lock() = Some(port);
+        // Pin the modem lines before anything else touches the port
+        let pinned = self
+            .config
+            .dtr_on_open
+            .map_or(Ok(()), |state| self.write_dtr(state))
+            .and_then(|()| self.config.rts_on_open.map_or(Ok(()), |state| self.write_rts(state)));
+        if let Err(e) = pinned {
+            *self.port.lock() = None;
+            return Err(e);
+        }
+        // Also clears stick parity left behind by an earlier user of the tty
+        if let Err(e) = self.apply_parity(self.config.parity) {
+            *self.port.lock() = None;
+            return Err(e);
+        }
+        if let Some(rs485) = self.config.rs485.filter(Rs485Config::manual) {
+            self.write_rts(rs485.rts_after_send)?;
+        }
+        let reader = self
+            .port
+            .lock()
+            .as_ref()
+            .map(|port| port.try_clone())
+            .transpose()
+            .map_err(|e| TransportError::ConnectionFailed(e.to_string()))
+            .and_then(|port| {
+                let port = port.ok_or(TransportError::Disconnected)?;
+                let throttle = self.config.flow_control != SerialFlowControl::None;
+                RxReader::spawn(port, self.config.rx_buffer_size, throttle).map_err(TransportError::IoError)
+            });
+        match reader {
+            Ok(reader) => self.reader = Some(reader),
+            Err(e) => {
+                *self.port.lock() = None;
+                return Err(e);
+            }
+        }
         self.connected_at = Some(Instant::now());
         *self.stats.write() = TransportStats::default();
 
@@ -225,6 +949,14 @@ impl TransportTrait for SerialTransport {
 
     async fn disconnect(&mut self) -> Result<(), TransportError> {
         *self.port.lock() = None;
+        if let Some(reader) = self.reader.take() {
+            // Joining waits out a read timeout; keep it off the executor
+            let _ = tokio::task::spawn_blocking(move || reader.stop()).await;
+        }
+        #[cfg(target_os = "linux")]
+        {
+            self.raw_fd = None;
+        }
         self.connected_at = None;
         Ok(())
     }
@@ -234,11 +966,22 @@ impl TransportTrait for SerialTransport {
     }
 
     async fn send(&mut self, data: &[u8]) -> Result<usize, TransportError> {
-        let mut port_guard = self.port.lock();
-        let port = port_guard.as_mut().ok_or(TransportError::Disconnected)?;
-
-        let written = port.write(data).map_err(TransportError::IoError)?;
-        port.flush().map_err(TransportError::IoError)?;
+        let written = match self.config.rs485.filter(Rs485Config::manual) {
+            Some(rs485) => {
+                self.write_rts(rs485.rts_on_send)?;
+                if rs485.delay_before_send_ms > 0 {
+                    tokio::time::sleep(Duration::from_millis(rs485.delay_before_send_ms.into())).await;
+                }
+                let result = self.write_all(data);
+                if rs485.delay_after_send_ms > 0 {
+                    tokio::time::sleep(Duration::from_millis(rs485.delay_after_send_ms.into())).await;
+                }
+                // Always hand the bus back, even if the write failed
+                self.write_rts(rs485.rts_after_send)?;
+                result?
+            }
+            None => self.write_all(data)?,
+        };
 
         let mut stats = self.stats.write();
         stats.bytes_sent += written as u64;
@@ -248,35 +991,28 @@ impl TransportTrait for SerialTransport {
     }
 
     async fn receive(&mut self) -> Result<Bytes, TransportError> {
-        let mut port_guard = self.port.lock();
-        let port = port_guard.as_mut().ok_or(TransportError::Disconnected)?;
-
-        let mut buffer = vec![0u8; 4096];
-
-        match port.read(&mut buffer) {
-            Ok(0) => Err(TransportError::Disconnected),
-            Ok(n) => {
-                buffer.truncate(n);
-                let bytes = Bytes::from(buffer);
-                drop(port_guard); // Release lock before stats
+        let reader = self.reader.as_ref().ok_or(TransportError::Disconnected)?;
+        let mut bytes = reader.take()?;
+        if bytes.is_empty() {
+            let _ = tokio::time::timeout(READ_TIMEOUT, reader.shared.data_ready.notified()).await;
+            bytes = reader.take()?;
+            if bytes.is_empty() {
+                return Ok(bytes);
+            }
+        }
 
-                let mut stats = self.stats.write();
-                stats.bytes_received += n as u64;
-                stats.packets_received += 1;
+        {
+            let mut stats = self.stats.write();
+            stats.bytes_received += bytes.len() as u64;
+            stats.packets_received += 1;
+        }
 
-                // Broadcast to subscribers
-                let _ = self.tx.send(bytes.clone());
+        // Broadcast to subscribers
+        let _ = self.tx.send(bytes.clone());
 
-                self.update_modem_lines();
+        self.update_modem_lines();
 
-                Ok(bytes)
-            }
-            Err(ref e) if e.kind() == std::io::ErrorKind::TimedOut => {
-                // No data available, return empty
-                Ok(Bytes::new())
-            }
-            Err(e) => Err(TransportError::IoError(e)),
-        }
+        Ok(bytes)
     }
 
     fn transport_type(&self) -> TransportType {
@@ -289,11 +1025,7 @@ impl TransportTrait for SerialTransport {
             self.config.port,
             self.config.baud_rate,
             self.config.data_bits,
-            match self.config.parity {
-                SerialParity::None => "N",
-                SerialParity::Odd => "O",
-                SerialParity::Even => "E",
-            },
+            self.config.parity.letter(),
             self.config.stop_bits,
             match self.config.flow_control {
                 SerialFlowControl::None => "No FC",
@@ -335,13 +1067,10 @@ impl TransportTrait for SerialTransport {
     }
 
     async fn set_dtr(&mut self, state: bool) -> Result<(), TransportError> {
-        let mut port_guard = self.port.lock();
-        if let Some(ref mut port) = *port_guard {
-            port.write_data_terminal_ready(state)
-                .map_err(|e| TransportError::IoError(std::io::Error::new(std::io::ErrorKind::Other, e)))?;
-            self.modem_lines.write().dtr = state;
+        if !self.is_connected() {
+            return Ok(());
         }
-        Ok(())
+        self.write_dtr(state)
     }
 
     async fn set_rts(&mut self, state: bool) -> Result<(), TransportError> {
@@ -354,9 +1083,50 @@ impl TransportTrait for SerialTransport {
         Ok(())
     }
 
+    async fn set_parity(&mut self, parity: SerialParity) -> Result<(), TransportError> {
+        self.apply_parity(parity)?;
+        self.config.parity = parity;
+        Ok(())
+    }
+
+    async fn reconfigure(&mut self, params: SerialParams) -> Result<(), TransportError> {
+        let previous = self.config.params();
+        if let Err(e) = self.apply_params(params) {
+            // Best effort: leave the port as it was rather than half-changed
+            if self.is_connected() && params.validate().is_ok() {
+                let _ = self.apply_params(previous);
+            }
+            return Err(e);
+        }
+        self.config.set_params(params);
+        if let Some(reader) = &self.reader {
+            let throttle = params.flow_control != SerialFlowControl::None;
+            reader.shared.throttle.store(throttle, Ordering::Relaxed);
+        }
+        Ok(())
+    }
+
     fn modem_lines(&self) -> Option<ModemLines> {
         Some(*self.modem_lines.read())
     }
+
+    fn receive_buffer(&self) -> Option<BufferLevel> {
+        self.reader.as_ref().map(|reader| reader.shared.buffer.lock().level())
+    }
+
+    fn take_dropped(&mut self) -> u64 {
+        self.reader
+            .as_ref()
+            .map_or(0, |reader| std::mem::take(&mut reader.shared.buffer.lock().dropped))
+    }
+}
+
+impl Drop for SerialTransport {
+    fn drop(&mut self) {
+        if let Some(reader) = &self.reader {
+            reader.shared.request_stop();
+        }
+    }
 }
 
 /// List available serial ports
@@ -365,3 +1135,128 @@ pub fn list_ports() -> Result<Vec<serialport::SerialPortInfo>, TransportError> {
 }
 
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rs485_builder() {
+        let config = SerialConfig::new("/dev/ttyUSB0", 9600).rs485(
+            Rs485Config::default()
+                .active_high(false)
+                .delays(Duration::from_millis(2), Duration::from_millis(5)),
+        );
+        let rs485 = config.rs485.unwrap();
+        assert!(!rs485.rts_on_send);
+        assert!(rs485.rts_after_send);
+        assert_eq!(rs485.delay_before_send_ms, 2);
+        assert_eq!(rs485.delay_after_send_ms, 5);
+        assert!(rs485.manual());
+    }
+
+    #[test]
+    fn test_line_states_on_open_default_untouched() {
+        let json = r#"{"port":"/dev/ttyUSB0","baud_rate":115200,"data_bits":8,"stop_bits":1,
+            "parity":"None","flow_control":"None","auto_reconnect":false}"#;
+        let config: SerialConfig = serde_json::from_str(json).unwrap();
+        assert_eq!((config.dtr_on_open, config.rts_on_open), (None, None));
+
+        let config = config.dtr_on_open(false).rts_on_open(true);
+        assert_eq!((config.dtr_on_open, config.rts_on_open), (Some(false), Some(true)));
+    }
+
+    #[test]
+    fn test_rx_buffer_drops_oldest() {
+        let mut buffer = RxBuffer::new(8);
+        buffer.push(b"hello");
+        buffer.push(b"world!");
+        assert_eq!(buffer.data.iter().copied().collect::<Vec<u8>>(), b"loworld!");
+        assert_eq!(buffer.dropped, 3);
+        assert_eq!(buffer.level(), BufferLevel { used: 8, capacity: 8, peak: 8 });
+
+        buffer.data.clear();
+        assert_eq!(buffer.level().peak, 8);
+        assert_eq!(buffer.level().fill(), 0.0);
+    }
+
+    #[test]
+    fn test_rx_reader_waits_for_room_when_throttled() {
+        let shared = Arc::new(RxShared {
+            buffer: parking_lot::Mutex::new(RxBuffer::new(8)),
+            data_ready: Notify::new(),
+            space_ready: parking_lot::Condvar::new(),
+            stop: AtomicBool::new(false),
+            throttle: AtomicBool::new(true),
+        });
+        assert_eq!(shared.wait_for_room(4096), Some(8));
+        shared.buffer.lock().push(b"abcdef");
+        assert_eq!(shared.wait_for_room(4096), Some(2));
+        shared.buffer.lock().push(b"gh");
+
+        // Full: the reader waits until the consumer takes data
+        let waiter = {
+            let shared = shared.clone();
+            std::thread::spawn(move || shared.wait_for_room(4096))
+        };
+        std::thread::sleep(Duration::from_millis(20));
+        assert!(!waiter.is_finished());
+        shared.buffer.lock().data.drain(..5);
+        shared.space_ready.notify_one();
+        assert_eq!(waiter.join().unwrap(), Some(5));
+        assert_eq!(shared.buffer.lock().dropped, 0);
+
+        // Stopping wakes a waiting reader
+        shared.buffer.lock().push(b"12345");
+        let waiter = {
+            let shared = shared.clone();
+            std::thread::spawn(move || shared.wait_for_room(4096))
+        };
+        shared.request_stop();
+        assert_eq!(waiter.join().unwrap(), None);
+    }
+
+    #[test]
+    fn test_serial_params() {
+        let config = SerialConfig::new("/dev/ttyUSB0", 9600).parity(SerialParity::Even);
+        let params = config.params().with_baud(115200);
+        assert_eq!(params.baud_rate, 115200);
+        assert_eq!(params.parity, SerialParity::Even);
+        assert!(params.validate().is_ok());
+
+        assert!(matches!(params.with_baud(0).validate(), Err(TransportError::ConfigError(_))));
+        assert!(SerialParams { data_bits: 9, ..params }.validate().is_err());
+        assert!(SerialParams { stop_bits: 3, ..params }.validate().is_err());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_stick_parity_cflag_is_cleared() {
+        let odd = libc::CS8 | libc::PARENB | libc::PARODD;
+        let mark = stick_parity_cflag(odd, SerialParity::Mark);
+        assert_eq!(mark, odd | libc::CMSPAR);
+        let space = stick_parity_cflag(mark, SerialParity::Space);
+        assert_eq!(space, libc::CS8 | libc::PARENB | libc::CMSPAR);
+        // Back to odd/even parity: only CMSPAR goes, set_parity owns the rest
+        assert_eq!(stick_parity_cflag(mark, SerialParity::Odd), odd);
+        assert_eq!(stick_parity_cflag(space, SerialParity::Even), libc::CS8 | libc::PARENB);
+    }
+
+    #[test]
+    fn test_emulated_stick_parity() {
+        // 0x03 has two ones, 0x01 one
+        assert_eq!(emulated_parity(0x03, SerialParity::Mark, 8), Parity::Odd);
+        assert_eq!(emulated_parity(0x01, SerialParity::Mark, 8), Parity::Even);
+        assert_eq!(emulated_parity(0x03, SerialParity::Space, 8), Parity::Even);
+        assert_eq!(emulated_parity(0x01, SerialParity::Space, 8), Parity::Odd);
+        // Bit 7 is not sent with 7 data bits
+        assert_eq!(emulated_parity(0x81, SerialParity::Space, 7), Parity::Odd);
+
+        assert_eq!("mark".parse::<SerialParity>(), Ok(SerialParity::Mark));
+        assert!("bogus".parse::<SerialParity>().is_err());
+        assert_eq!("RTS/CTS".parse::<SerialFlowControl>(), Ok(SerialFlowControl::Hardware));
+        assert_eq!("none".parse::<SerialFlowControl>(), Ok(SerialFlowControl::None));
+        assert!("bogus".parse::<SerialFlowControl>().is_err());
+        assert_eq!(SerialParity::Space.letter(), "S");
+    }
+}
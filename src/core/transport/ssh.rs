@@ -1,7 +1,8 @@
 //! SSH transport implementation
 //!
 //! Provides SSH-2 protocol support with:
-//! - Password and public key authentication
+//! - Password, public key and keyboard-interactive authentication
+//! - Host key verification against an OpenSSH known_hosts file
 //! - Interactive shell and command execution
 //! - Port forwarding (local, remote, dynamic/SOCKS)
 //! - SFTP file transfer
@@ -10,7 +11,7 @@
 use super::{TransportError, TransportStats, TransportTrait, TransportType};
 use async_trait::async_trait;
 use bytes::Bytes;
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 use std::net::TcpStream;
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -37,12 +38,118 @@ pub enum SshAuth {
     None,
 }
 
+/// A single keyboard-interactive prompt from the server
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthPrompt {
+    /// Prompt text, e.g. "Verification code: "
+    pub text: String,
+    /// Whether the answer may be echoed (false for secrets)
+    pub echo: bool,
+}
+
+/// Callback answering keyboard-interactive prompts
+///
+/// Called with the server's instructions and prompts; must return one
+/// answer per prompt. May be called several times per login (e.g. password
+/// then OTP).
+#[derive(Clone)]
+pub struct PromptHandler(Arc<Mutex<Box<dyn FnMut(&str, &[AuthPrompt]) -> Vec<String> + Send>>>);
+
+impl PromptHandler {
+    /// Wrap a prompt callback
+    pub fn new(handler: impl FnMut(&str, &[AuthPrompt]) -> Vec<String> + Send + 'static) -> Self {
+        Self(Arc::new(Mutex::new(Box::new(handler))))
+    }
+
+    /// Answer a set of prompts
+    pub fn answer(&self, instructions: &str, prompts: &[AuthPrompt]) -> Vec<String> {
+        (self.0.lock())(instructions, prompts)
+    }
+}
+
+impl std::fmt::Debug for PromptHandler {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("PromptHandler")
+    }
+}
+
+impl ssh2::KeyboardInteractivePrompt for PromptHandler {
+    fn prompt<'a>(
+        &mut self,
+        _username: &str,
+        instructions: &str,
+        prompts: &[ssh2::Prompt<'a>],
+    ) -> Vec<String> {
+        let prompts: Vec<AuthPrompt> = prompts
+            .iter()
+            .map(|p| AuthPrompt { text: p.text.to_string(), echo: p.echo })
+            .collect();
+        let mut answers = self.answer(instructions, &prompts);
+        answers.resize(prompts.len(), String::new());
+        answers
+    }
+}
+
 impl Default for SshAuth {
     fn default() -> Self {
         Self::Agent
     }
 }
 
+/// How unknown or changed server host keys are handled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HostKeyPolicy {
+    /// Only connect to hosts already in known_hosts
+    Strict,
+    /// Reject unknown hosts with `SshError::UnknownHost` so the user can
+    /// confirm the fingerprint, then reconnect with `AcceptNew`
+    #[default]
+    Tofu,
+    /// Record unknown host keys automatically (OpenSSH `accept-new`)
+    AcceptNew,
+}
+
+/// SSH-specific connection errors
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum SshError {
+    /// The host is not in known_hosts
+    #[error("Unknown host {host}, key fingerprint {fingerprint}")]
+    UnknownHost {
+        /// Host as written to known_hosts
+        host: String,
+        /// Server key fingerprint (`SHA256:...`)
+        fingerprint: String,
+    },
+    /// The host's key differs from the one in known_hosts
+    #[error("Host key for {host} has changed (now {fingerprint}), possible MITM attack")]
+    HostKeyMismatch {
+        /// Host as written to known_hosts
+        host: String,
+        /// Server key fingerprint (`SHA256:...`)
+        fingerprint: String,
+    },
+}
+
+/// Default known_hosts file in the config directory
+pub fn default_known_hosts_path() -> Option<PathBuf> {
+    crate::config::config_dir().map(|dir| dir.join("known_hosts"))
+}
+
+/// Host name as OpenSSH writes it: `host` on port 22, `[host]:port` otherwise
+fn known_hosts_name(host: &str, port: u16) -> String {
+    if port == 22 {
+        host.to_string()
+    } else {
+        format!("[{}]:{}", host, port)
+    }
+}
+
+/// OpenSSH-style fingerprint of a raw SHA-256 host key hash
+fn format_fingerprint(sha256: &[u8]) -> String {
+    use base64::Engine;
+    format!("SHA256:{}", base64::engine::general_purpose::STANDARD_NO_PAD.encode(sha256))
+}
+
 /// SSH port forward configuration
 #[derive(Debug, Clone)]
 pub struct PortForward {
@@ -100,10 +207,12 @@ pub struct SshConfig {
     pub proxy_jump: Option<Box<SshConfig>>,
     /// Auto-reconnect on disconnect
     pub auto_reconnect: bool,
+    /// Keyboard-interactive prompt callback (empty answers if unset)
+    pub prompt_handler: Option<PromptHandler>,
     /// Known hosts file path (None = don't verify)
     pub known_hosts: Option<PathBuf>,
-    /// Strict host key checking
-    pub strict_host_key: bool,
+    /// Host key checking policy
+    pub host_key_policy: HostKeyPolicy,
 }
 
 impl SshConfig {
@@ -124,8 +233,9 @@ impl SshConfig {
             compression: false,
             proxy_jump: None,
             auto_reconnect: false,
-            known_hosts: None,
-            strict_host_key: false,
+            prompt_handler: None,
+            known_hosts: default_known_hosts_path(),
+            host_key_policy: HostKeyPolicy::default(),
         }
     }
 
@@ -153,6 +263,14 @@ impl SshConfig {
         self
     }
 
+    /// Set keyboard-interactive authentication answered by `handler`
+    #[must_use]
+    pub fn keyboard_interactive(mut self, handler: PromptHandler) -> Self {
+        self.auth = SshAuth::KeyboardInteractive;
+        self.prompt_handler = Some(handler);
+        self
+    }
+
     /// Add a local port forward
     #[must_use]
     pub fn local_forward(mut self, local_port: u16, remote_host: &str, remote_port: u16) -> Self {
@@ -239,13 +357,68 @@ impl SshTransport {
         }
     }
 
+    /// Register the callback answering keyboard-interactive prompts
+    pub fn set_prompt_handler(&mut self, handler: PromptHandler) {
+        self.config.prompt_handler = Some(handler);
+    }
+
+    /// Set the host key checking policy
+    pub fn set_host_key_policy(&mut self, policy: HostKeyPolicy) {
+        self.config.host_key_policy = policy;
+    }
+
+    /// Check the server's host key against known_hosts
+    fn verify_host_key(&self, session: &ssh2::Session) -> Result<(), TransportError> {
+        let Some(path) = &self.config.known_hosts else {
+            return Ok(());
+        };
+
+        let (key, key_type) = session
+            .host_key()
+            .ok_or_else(|| TransportError::ConnectionFailed("Server sent no host key".to_string()))?;
+        let fingerprint = session
+            .host_key_hash(ssh2::HashType::Sha256)
+            .map(format_fingerprint)
+            .unwrap_or_default();
+        let host = known_hosts_name(&self.config.host, self.config.port);
+
+        let mut known_hosts = session
+            .known_hosts()
+            .map_err(|e| TransportError::ConnectionFailed(format!("Known hosts init failed: {}", e)))?;
+        if path.exists() {
+            known_hosts
+                .read_file(path, ssh2::KnownHostFileKind::OpenSSH)
+                .map_err(|e| TransportError::ConfigError(format!("Failed to read {}: {}", path.display(), e)))?;
+        }
+
+        match known_hosts.check_port(&self.config.host, self.config.port, key) {
+            ssh2::CheckResult::Match => Ok(()),
+            ssh2::CheckResult::Mismatch => Err(SshError::HostKeyMismatch { host, fingerprint }.into()),
+            ssh2::CheckResult::NotFound if self.config.host_key_policy == HostKeyPolicy::AcceptNew => {
+                known_hosts
+                    .add(&host, key, "", key_type.into())
+                    .map_err(|e| TransportError::ConnectionFailed(format!("Failed to add host key: {}", e)))?;
+                if let Some(dir) = path.parent() {
+                    std::fs::create_dir_all(dir)?;
+                }
+                known_hosts
+                    .write_file(path, ssh2::KnownHostFileKind::OpenSSH)
+                    .map_err(|e| TransportError::ConfigError(format!("Failed to write {}: {}", path.display(), e)))?;
+                tracing::info!("Added host key for {} ({}) to {}", host, fingerprint, path.display());
+                Ok(())
+            }
+            ssh2::CheckResult::NotFound => Err(SshError::UnknownHost { host, fingerprint }.into()),
+            ssh2::CheckResult::Failure => Err(TransportError::ConnectionFailed("Host key check failed".to_string())),
+        }
+    }
+
     /// Authenticate with the configured method
     fn authenticate(&self, session: &ssh2::Session) -> Result<(), TransportError> {
         match &self.config.auth {
             SshAuth::Password(password) => {
                 session
                     .userauth_password(&self.config.username, password)
-                    .map_err(|e| TransportError::ConnectionFailed(format!("Password auth failed: {}", e)))?;
+                    .map_err(|e| TransportError::AuthFailed(format!("Password auth failed: {}", e)))?;
             }
             SshAuth::PublicKey { private_key, passphrase } => {
                 session
@@ -255,7 +428,7 @@ impl SshTransport {
                         private_key,
                         passphrase.as_deref(),
                     )
-                    .map_err(|e| TransportError::ConnectionFailed(format!("Key auth failed: {}", e)))?;
+                    .map_err(|e| TransportError::AuthFailed(format!("Key auth failed: {}", e)))?;
             }
             SshAuth::Agent => {
                 let mut agent = session.agent()
@@ -278,25 +451,19 @@ impl SshTransport {
                 }
                 
                 if !authenticated {
-                    return Err(TransportError::ConnectionFailed("No valid agent identity".to_string()));
+                    return Err(TransportError::AuthFailed("No valid agent identity".to_string()));
                 }
             }
             SshAuth::KeyboardInteractive => {
-                // Simplified keyboard-interactive (just try empty response)
-                struct EmptyPromptHandler;
-                impl ssh2::KeyboardInteractivePrompt for EmptyPromptHandler {
-                    fn prompt<'a>(
-                        &mut self,
-                        _username: &str,
-                        _instructions: &str,
-                        prompts: &[ssh2::Prompt<'a>],
-                    ) -> Vec<String> {
-                        prompts.iter().map(|_| String::new()).collect()
-                    }
-                }
+                // Without a handler, answer every prompt with an empty string
+                let mut handler = self
+                    .config
+                    .prompt_handler
+                    .clone()
+                    .unwrap_or_else(|| PromptHandler::new(|_, prompts| vec![String::new(); prompts.len()]));
                 session
-                    .userauth_keyboard_interactive(&self.config.username, &mut EmptyPromptHandler)
-                    .map_err(|e| TransportError::ConnectionFailed(format!("KB-interactive auth failed: {}", e)))?;
+                    .userauth_keyboard_interactive(&self.config.username, &mut handler)
+                    .map_err(|e| TransportError::AuthFailed(format!("KB-interactive auth failed: {}", e)))?;
             }
             SshAuth::None => {
                 // No authentication
@@ -304,7 +471,7 @@ impl SshTransport {
         }
 
         if !session.authenticated() {
-            return Err(TransportError::ConnectionFailed("Authentication failed".to_string()));
+            return Err(TransportError::AuthFailed(self.config.username.clone()));
         }
 
         Ok(())
@@ -404,6 +571,8 @@ impl TransportTrait for SshTransport {
         session.handshake()
             .map_err(|e| TransportError::ConnectionFailed(format!("SSH handshake failed: {}", e)))?;
 
+        self.verify_host_key(&session)?;
+
         // Authenticate
         self.authenticate(&session)?;
 
@@ -606,3 +775,32 @@ impl SftpClient {
 }
 
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ssh2::KeyboardInteractivePrompt;
+
+    #[test]
+    fn test_known_hosts_name_and_fingerprint() {
+        assert_eq!(known_hosts_name("router.lan", 22), "router.lan");
+        assert_eq!(known_hosts_name("10.0.0.1", 2222), "[10.0.0.1]:2222");
+        assert_eq!(format_fingerprint(&[0u8; 32]), "SHA256:AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA");
+    }
+
+    #[test]
+    fn test_prompt_handler_answers_each_prompt() {
+        let mut handler = PromptHandler::new(|instructions, prompts| {
+            assert_eq!(instructions, "Duo two-factor login");
+            assert!(!prompts[0].echo);
+            vec!["123456".to_string()]
+        });
+
+        let prompts = [
+            ssh2::Prompt { text: "Passcode: ".into(), echo: false },
+            ssh2::Prompt { text: "Remember device? ".into(), echo: true },
+        ];
+        let answers = handler.prompt("alice", "Duo two-factor login", &prompts);
+        assert_eq!(answers, vec!["123456".to_string(), String::new()]);
+    }
+}
@@ -1,6 +1,7 @@
 //! TCP transport implementation
 
-use super::{TransportError, TransportStats, TransportTrait, TransportType};
+use super::tls::{self, NetStream};
+use super::{TlsConfig, TlsInfo, TransportError, TransportStats, TransportTrait, TransportType};
 use async_trait::async_trait;
 use bytes::Bytes;
 use parking_lot::RwLock;
@@ -20,6 +21,9 @@ pub struct TcpConfig {
     pub port: u16,
     /// Connection timeout in seconds
     pub timeout_secs: u64,
+    /// Wrap the connection in TLS
+    #[serde(default)]
+    pub tls: Option<TlsConfig>,
 }
 
 impl TcpConfig {
@@ -29,6 +33,7 @@ impl TcpConfig {
             host: host.to_string(),
             port,
             timeout_secs: 10,
+            tls: None,
         }
     }
 
@@ -38,6 +43,13 @@ impl TcpConfig {
         self.timeout_secs = secs;
         self
     }
+
+    /// Connect over TLS
+    #[must_use]
+    pub fn tls(mut self, tls: TlsConfig) -> Self {
+        self.tls = Some(tls);
+        self
+    }
 }
 
 impl Default for TcpConfig {
@@ -49,7 +61,8 @@ impl Default for TcpConfig {
 /// TCP transport
 pub struct TcpTransport {
     config: TcpConfig,
-    stream: Option<TcpStream>,
+    stream: Option<NetStream>,
+    tls_info: Option<TlsInfo>,
     stats: Arc<RwLock<TransportStats>>,
     connected_at: Option<Instant>,
     tx: broadcast::Sender<Bytes>,
@@ -63,6 +76,7 @@ impl TcpTransport {
         Self {
             config,
             stream: None,
+            tls_info: None,
             stats: Arc::new(RwLock::new(TransportStats::default())),
             connected_at: None,
             tx,
@@ -88,6 +102,15 @@ impl TransportTrait for TcpTransport {
             .set_nodelay(true)
             .map_err(TransportError::IoError)?;
 
+        let stream = match &self.config.tls {
+            Some(config) => {
+                let (stream, info) = tls::connect(stream, &self.config.host, config).await?;
+                self.tls_info = Some(info);
+                stream
+            }
+            None => NetStream::Plain(stream),
+        };
+
         self.stream = Some(stream);
         self.connected_at = Some(Instant::now());
         *self.stats.write() = TransportStats::default();
@@ -100,6 +123,7 @@ impl TransportTrait for TcpTransport {
             stream.shutdown().await.ok();
         }
         self.connected_at = None;
+        self.tls_info = None;
         Ok(())
     }
 
@@ -162,7 +186,10 @@ impl TransportTrait for TcpTransport {
     }
 
     fn connection_info(&self) -> String {
-        format!("{}:{}", self.config.host, self.config.port)
+        match self.tls_info {
+            Some(ref info) => format!("{}:{} ({})", self.config.host, self.config.port, info.protocol),
+            None => format!("{}:{}", self.config.host, self.config.port),
+        }
     }
 
     fn stats(&self) -> TransportStats {
@@ -176,6 +203,10 @@ impl TransportTrait for TcpTransport {
     fn subscribe(&self) -> broadcast::Receiver<Bytes> {
         self.tx.subscribe()
     }
+
+    fn tls_info(&self) -> Option<TlsInfo> {
+        self.tls_info.clone()
+    }
 }
 
 
@@ -2,7 +2,8 @@
 //!
 //! Implements the Telnet protocol (RFC 854) with option negotiation
 
-use super::{TransportError, TransportStats, TransportTrait, TransportType};
+use super::tls::{self, NetStream};
+use super::{TlsConfig, TlsInfo, TransportError, TransportStats, TransportTrait, TransportType};
 use async_trait::async_trait;
 use bytes::{Bytes, BytesMut};
 use parking_lot::RwLock;
@@ -23,6 +24,7 @@ const SB: u8 = 250; // Subnegotiation Begin
 const SE: u8 = 240; // Subnegotiation End
 
 // Common Telnet options
+const OPT_BINARY: u8 = 0; // Transmit Binary (RFC 856)
 #[allow(dead_code)]
 const OPT_ECHO: u8 = 1;
 const OPT_SUPPRESS_GO_AHEAD: u8 = 3;
@@ -38,6 +40,12 @@ pub struct TelnetConfig {
     pub port: u16,
     /// Terminal type to announce
     pub terminal_type: String,
+    /// Negotiate TRANSMIT-BINARY both ways for 8-bit clean transfers (e.g. ZMODEM)
+    #[serde(default)]
+    pub binary_mode: bool,
+    /// Wrap the connection in TLS (telnets)
+    #[serde(default)]
+    pub tls: Option<TlsConfig>,
 }
 
 impl TelnetConfig {
@@ -47,6 +55,8 @@ impl TelnetConfig {
             host: host.to_string(),
             port: 23,
             terminal_type: "xterm".to_string(),
+            binary_mode: false,
+            tls: None,
         }
     }
 
@@ -63,6 +73,20 @@ impl TelnetConfig {
         self.terminal_type = term_type.to_string();
         self
     }
+
+    /// Request and accept binary transmission in both directions
+    #[must_use]
+    pub fn binary_mode(mut self, enable: bool) -> Self {
+        self.binary_mode = enable;
+        self
+    }
+
+    /// Connect over TLS (telnets)
+    #[must_use]
+    pub fn tls(mut self, tls: TlsConfig) -> Self {
+        self.tls = Some(tls);
+        self
+    }
 }
 
 impl Default for TelnetConfig {
@@ -71,15 +95,29 @@ impl Default for TelnetConfig {
     }
 }
 
+/// Negotiation state of one side of an option
+#[derive(Debug, Clone, Copy, Default)]
+struct OptionState {
+    /// Option is in effect
+    enabled: bool,
+    /// We asked for it and are waiting for the answer
+    pending: bool,
+}
+
 /// Telnet transport
 pub struct TelnetTransport {
     config: TelnetConfig,
-    stream: Option<TcpStream>,
+    stream: Option<NetStream>,
+    tls_info: Option<TlsInfo>,
     stats: Arc<RwLock<TransportStats>>,
     connected_at: Option<Instant>,
     tx: broadcast::Sender<Bytes>,
     /// Buffer for incomplete Telnet sequences
     pending_data: BytesMut,
+    /// We send binary (we WILL BINARY)
+    binary_local: OptionState,
+    /// Peer sends binary (peer WILL BINARY)
+    binary_remote: OptionState,
 }
 
 impl TelnetTransport {
@@ -90,16 +128,52 @@ impl TelnetTransport {
         Self {
             config,
             stream: None,
+            tls_info: None,
             stats: Arc::new(RwLock::new(TransportStats::default())),
             connected_at: None,
             tx,
             pending_data: BytesMut::new(),
+            binary_local: OptionState::default(),
+            binary_remote: OptionState::default(),
         }
     }
 
+    /// Whether binary transmission is in effect in both directions
+    pub fn is_binary(&self) -> bool {
+        self.binary_local.enabled && self.binary_remote.enabled
+    }
+
+    /// Answer a BINARY negotiation
+    ///
+    /// Replies only to refuse a request or to acknowledge a change we did
+    /// not ask for, so answers to our own requests do not loop (RFC 1143).
+    async fn negotiate_binary(&mut self, command: u8) -> Result<(), TransportError> {
+        let requested = matches!(command, DO | WILL);
+        let accept = requested && self.config.binary_mode;
+        let (state, reply) = match command {
+            DO | DONT => (&mut self.binary_local, if accept { WILL } else { WONT }),
+            _ => (&mut self.binary_remote, if accept { DO } else { DONT }),
+        };
+        let answer = !state.pending && (accept != state.enabled || accept != requested);
+        *state = OptionState {
+            enabled: accept,
+            pending: false,
+        };
+
+        if answer {
+            if let Some(ref mut stream) = self.stream {
+                stream
+                    .write_all(&[IAC, reply, OPT_BINARY])
+                    .await
+                    .map_err(TransportError::IoError)?;
+            }
+        }
+        Ok(())
+    }
+
     /// Handle Telnet option negotiation
     async fn handle_telnet_option(
-        stream: &mut TcpStream,
+        stream: &mut NetStream,
         command: u8,
         option: u8,
         terminal_type: &str,
@@ -172,7 +246,9 @@ impl TelnetTransport {
                         let option = raw_data[i + 2];
                         
                         // Handle option negotiation
-                        if let Some(ref mut stream) = self.stream {
+                        if option == OPT_BINARY {
+                            self.negotiate_binary(command).await?;
+                        } else if let Some(ref mut stream) = self.stream {
                             Self::handle_telnet_option(
                                 stream,
                                 command,
@@ -228,6 +304,26 @@ impl TransportTrait for TelnetTransport {
 
         stream.set_nodelay(true).map_err(TransportError::IoError)?;
 
+        let mut stream = match &self.config.tls {
+            Some(config) => {
+                let (stream, info) = tls::connect(stream, &self.config.host, config).await?;
+                self.tls_info = Some(info);
+                stream
+            }
+            None => NetStream::Plain(stream),
+        };
+
+        self.binary_local = OptionState::default();
+        self.binary_remote = OptionState::default();
+        if self.config.binary_mode {
+            stream
+                .write_all(&[IAC, WILL, OPT_BINARY, IAC, DO, OPT_BINARY])
+                .await
+                .map_err(TransportError::IoError)?;
+            self.binary_local.pending = true;
+            self.binary_remote.pending = true;
+        }
+
         self.stream = Some(stream);
         self.connected_at = Some(Instant::now());
         self.pending_data.clear();
@@ -241,7 +337,10 @@ impl TransportTrait for TelnetTransport {
             stream.shutdown().await.ok();
         }
         self.connected_at = None;
+        self.tls_info = None;
         self.pending_data.clear();
+        self.binary_local = OptionState::default();
+        self.binary_remote = OptionState::default();
         Ok(())
     }
 
@@ -327,7 +426,8 @@ impl TransportTrait for TelnetTransport {
     }
 
     fn connection_info(&self) -> String {
-        format!("telnet://{}:{}", self.config.host, self.config.port)
+        let scheme = if self.config.tls.is_some() { "telnets" } else { "telnet" };
+        format!("{}://{}:{}", scheme, self.config.host, self.config.port)
     }
 
     fn stats(&self) -> TransportStats {
@@ -341,6 +441,52 @@ impl TransportTrait for TelnetTransport {
     fn subscribe(&self) -> broadcast::Receiver<Bytes> {
         self.tx.subscribe()
     }
-}
 
+    fn tls_info(&self) -> Option<TlsInfo> {
+        self.tls_info.clone()
+    }
+}
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::AsyncReadExt;
+    use tokio::net::TcpListener;
+
+    #[tokio::test]
+    async fn test_binary_mode_negotiation() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let mut transport = TelnetTransport::new(TelnetConfig::new("127.0.0.1").port(port).binary_mode(true));
+        transport.connect().await.unwrap();
+        let (mut peer, _) = listener.accept().await.unwrap();
+
+        let mut request = [0u8; 6];
+        peer.read_exact(&mut request).await.unwrap();
+        assert_eq!(request, [IAC, WILL, OPT_BINARY, IAC, DO, OPT_BINARY]);
+
+        // Acknowledge both requests, then send an escaped 0xFF and a bare CR
+        peer.write_all(&[IAC, DO, OPT_BINARY, IAC, WILL, OPT_BINARY, IAC, IAC, b'\r'])
+            .await
+            .unwrap();
+        let data = tokio::time::timeout(Duration::from_secs(2), async {
+            loop {
+                let data = transport.receive().await.unwrap();
+                if !data.is_empty() {
+                    break data;
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(&data[..], [0xFF, b'\r']);
+        assert!(transport.is_binary());
+
+        // Acknowledgements are not answered, so the next bytes are the data
+        transport.send(b"\r\xff").await.unwrap();
+        let mut sent = [0u8; 3];
+        peer.read_exact(&mut sent).await.unwrap();
+        assert_eq!(sent, [b'\r', IAC, IAC]);
+    }
+}
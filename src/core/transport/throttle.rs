@@ -0,0 +1,349 @@
+//! Throttled transport decorator
+//!
+//! Wraps another transport and slows down or corrupts received data to
+//! reproduce flaky links without hardware.
+
+use super::{
+    BufferLevel, ModemLines, SerialParams, SerialParity, TlsInfo, TransportError, TransportStats, TransportTrait,
+    TransportType,
+};
+use async_trait::async_trait;
+use bytes::Bytes;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::time::Duration;
+use tokio::sync::broadcast;
+
+/// Link impairments applied on receive
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ThrottleConfig {
+    /// Delay per received byte (e.g. ~1ms per byte emulates 9600 baud)
+    pub per_byte_delay: Duration,
+    /// Maximum extra random delay per received chunk
+    pub jitter: Duration,
+    /// Probability of flipping each received bit (0.0 = never)
+    pub bit_error_rate: f64,
+    /// RNG seed for reproducible runs (None = random)
+    pub seed: Option<u64>,
+}
+
+impl ThrottleConfig {
+    /// Delay matching the byte time of a serial link (8N1)
+    pub fn baud(baud_rate: u32) -> Self {
+        Self {
+            per_byte_delay: Duration::from_secs_f64(10.0 / f64::from(baud_rate.max(1))),
+            ..Self::default()
+        }
+    }
+
+    /// Set jitter
+    #[must_use]
+    pub fn jitter(mut self, jitter: Duration) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Set bit error rate; clamped to 0.0..=1.0, NaN disables errors
+    #[must_use]
+    pub fn bit_error_rate(mut self, rate: f64) -> Self {
+        self.bit_error_rate = valid_rate(rate);
+        self
+    }
+
+    /// Set RNG seed
+    #[must_use]
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+}
+
+/// Bit error rate usable as a probability
+fn valid_rate(rate: f64) -> f64 {
+    if rate.is_nan() {
+        0.0
+    } else {
+        rate.clamp(0.0, 1.0)
+    }
+}
+
+/// Transport decorator injecting latency, jitter and bit errors on receive
+pub struct ThrottledTransport {
+    inner: Box<dyn TransportTrait>,
+    config: ThrottleConfig,
+    rng: StdRng,
+    bits_flipped: u64,
+}
+
+impl ThrottledTransport {
+    /// Wrap a transport
+    pub fn new(inner: Box<dyn TransportTrait>, config: ThrottleConfig) -> Self {
+        let rng = match config.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        Self {
+            inner,
+            config,
+            rng,
+            bits_flipped: 0,
+        }
+    }
+
+    /// Current impairments
+    pub fn config(&self) -> &ThrottleConfig {
+        &self.config
+    }
+
+    /// Change impairments on the fly
+    pub fn set_config(&mut self, config: ThrottleConfig) {
+        self.config = config;
+    }
+
+    /// Number of bits corrupted so far
+    pub fn bits_flipped(&self) -> u64 {
+        self.bits_flipped
+    }
+
+    /// Unwrap the inner transport
+    pub fn into_inner(self) -> Box<dyn TransportTrait> {
+        self.inner
+    }
+
+    /// Delay for a chunk of `len` bytes
+    fn chunk_delay(&mut self, len: usize) -> Duration {
+        let mut delay = self.config.per_byte_delay * u32::try_from(len).unwrap_or(u32::MAX);
+        if !self.config.jitter.is_zero() {
+            delay += self.config.jitter.mul_f64(self.rng.gen::<f64>());
+        }
+        delay
+    }
+
+    /// Flip random bits according to the bit error rate
+    fn corrupt(&mut self, data: &mut [u8]) {
+        // The field is public, so it may hold anything
+        let rate = valid_rate(self.config.bit_error_rate);
+        if rate <= 0.0 {
+            return;
+        }
+        for byte in data.iter_mut() {
+            for bit in 0..8 {
+                if self.rng.gen_bool(rate) {
+                    *byte ^= 1 << bit;
+                    self.bits_flipped += 1;
+                }
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl TransportTrait for ThrottledTransport {
+    async fn connect(&mut self) -> Result<(), TransportError> {
+        self.inner.connect().await
+    }
+
+    async fn disconnect(&mut self) -> Result<(), TransportError> {
+        self.inner.disconnect().await
+    }
+
+    fn is_connected(&self) -> bool {
+        self.inner.is_connected()
+    }
+
+    async fn send(&mut self, data: &[u8]) -> Result<usize, TransportError> {
+        self.inner.send(data).await
+    }
+
+    async fn receive(&mut self) -> Result<Bytes, TransportError> {
+        let data = self.inner.receive().await?;
+        if data.is_empty() {
+            return Ok(data);
+        }
+
+        let delay = self.chunk_delay(data.len());
+        if !delay.is_zero() {
+            tokio::time::sleep(delay).await;
+        }
+
+        if valid_rate(self.config.bit_error_rate) > 0.0 {
+            let mut data = data.to_vec();
+            self.corrupt(&mut data);
+            return Ok(Bytes::from(data));
+        }
+        Ok(data)
+    }
+
+    fn transport_type(&self) -> TransportType {
+        self.inner.transport_type()
+    }
+
+    fn connection_info(&self) -> String {
+        format!("{} (throttled)", self.inner.connection_info())
+    }
+
+    fn stats(&self) -> TransportStats {
+        self.inner.stats()
+    }
+
+    fn subscribe(&self) -> broadcast::Receiver<Bytes> {
+        self.inner.subscribe()
+    }
+
+    async fn send_break(&mut self) -> Result<(), TransportError> {
+        self.inner.send_break().await
+    }
+
+    async fn set_dtr(&mut self, state: bool) -> Result<(), TransportError> {
+        self.inner.set_dtr(state).await
+    }
+
+    async fn set_rts(&mut self, state: bool) -> Result<(), TransportError> {
+        self.inner.set_rts(state).await
+    }
+
+    async fn set_parity(&mut self, parity: SerialParity) -> Result<(), TransportError> {
+        self.inner.set_parity(parity).await
+    }
+
+    async fn reconfigure(&mut self, params: SerialParams) -> Result<(), TransportError> {
+        self.inner.reconfigure(params).await
+    }
+
+    fn modem_lines(&self) -> Option<ModemLines> {
+        self.inner.modem_lines()
+    }
+
+    fn tls_info(&self) -> Option<TlsInfo> {
+        self.inner.tls_info()
+    }
+
+    fn receive_buffer(&self) -> Option<BufferLevel> {
+        self.inner.receive_buffer()
+    }
+
+    fn take_dropped(&mut self) -> u64 {
+        self.inner.take_dropped()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+
+    /// Returns queued chunks, then empty reads
+    struct ScriptedTransport {
+        chunks: VecDeque<Bytes>,
+        /// Reported once by `take_dropped`
+        dropped: u64,
+        tx: broadcast::Sender<Bytes>,
+    }
+
+    impl ScriptedTransport {
+        fn boxed(chunks: &[&[u8]]) -> Box<dyn TransportTrait> {
+            Box::new(Self {
+                chunks: chunks.iter().map(|c| Bytes::copy_from_slice(c)).collect(),
+                dropped: 0,
+                tx: broadcast::channel(1).0,
+            })
+        }
+    }
+
+    #[async_trait]
+    impl TransportTrait for ScriptedTransport {
+        async fn connect(&mut self) -> Result<(), TransportError> {
+            Ok(())
+        }
+        async fn disconnect(&mut self) -> Result<(), TransportError> {
+            Ok(())
+        }
+        fn is_connected(&self) -> bool {
+            true
+        }
+        async fn send(&mut self, data: &[u8]) -> Result<usize, TransportError> {
+            Ok(data.len())
+        }
+        async fn receive(&mut self) -> Result<Bytes, TransportError> {
+            Ok(self.chunks.pop_front().unwrap_or_default())
+        }
+        fn transport_type(&self) -> TransportType {
+            TransportType::Serial
+        }
+        fn connection_info(&self) -> String {
+            "scripted".to_string()
+        }
+        fn stats(&self) -> TransportStats {
+            TransportStats::default()
+        }
+        fn subscribe(&self) -> broadcast::Receiver<Bytes> {
+            self.tx.subscribe()
+        }
+        fn receive_buffer(&self) -> Option<BufferLevel> {
+            let used = self.chunks.iter().map(Bytes::len).sum();
+            Some(BufferLevel { used, capacity: 64, peak: used })
+        }
+        fn take_dropped(&mut self) -> u64 {
+            std::mem::take(&mut self.dropped)
+        }
+    }
+
+    #[test]
+    fn test_forwards_receive_buffer() {
+        let inner = Box::new(ScriptedTransport {
+            chunks: VecDeque::from([Bytes::from_static(b"abc")]),
+            dropped: 5,
+            tx: broadcast::channel(1).0,
+        });
+        let mut transport = ThrottledTransport::new(inner, ThrottleConfig::default());
+        assert_eq!(transport.receive_buffer().map(|level| level.used), Some(3));
+        assert_eq!(transport.take_dropped(), 5);
+        assert_eq!(transport.take_dropped(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_per_byte_delay() {
+        let config = ThrottleConfig { per_byte_delay: Duration::from_millis(10), ..Default::default() };
+        let mut transport = ThrottledTransport::new(ScriptedTransport::boxed(&[b"hello"]), config);
+
+        let start = std::time::Instant::now();
+        assert_eq!(&transport.receive().await.unwrap()[..], b"hello");
+        assert!(start.elapsed() >= Duration::from_millis(50));
+
+        // Empty reads are not delayed
+        let start = std::time::Instant::now();
+        assert!(transport.receive().await.unwrap().is_empty());
+        assert!(start.elapsed() < Duration::from_millis(50));
+    }
+
+    #[tokio::test]
+    async fn test_bit_errors_are_reproducible() {
+        let config = ThrottleConfig::default().bit_error_rate(0.1).seed(42);
+        let data: &[u8] = &[0u8; 64];
+
+        let mut a = ThrottledTransport::new(ScriptedTransport::boxed(&[data]), config);
+        let mut b = ThrottledTransport::new(ScriptedTransport::boxed(&[data]), config);
+        let (ra, rb) = (a.receive().await.unwrap(), b.receive().await.unwrap());
+
+        assert_eq!(ra, rb);
+        assert_ne!(&ra[..], data);
+        assert_eq!(a.bits_flipped(), ra.iter().map(|b| u64::from(b.count_ones())).sum::<u64>());
+    }
+
+    #[tokio::test]
+    async fn test_out_of_range_bit_error_rate() {
+        assert_eq!(ThrottleConfig::default().bit_error_rate(f64::NAN).bit_error_rate, 0.0);
+        assert_eq!(ThrottleConfig::default().bit_error_rate(-1.0).bit_error_rate, 0.0);
+        assert_eq!(ThrottleConfig::default().bit_error_rate(2.0).bit_error_rate, 1.0);
+
+        // Invalid rates set directly do not panic
+        let data: &[u8] = &[0u8; 4];
+        let config = ThrottleConfig { bit_error_rate: f64::NAN, ..Default::default() };
+        let mut transport = ThrottledTransport::new(ScriptedTransport::boxed(&[data]), config);
+        assert_eq!(&transport.receive().await.unwrap()[..], data);
+
+        let config = ThrottleConfig { bit_error_rate: 5.0, ..Default::default() };
+        let mut transport = ThrottledTransport::new(ScriptedTransport::boxed(&[data]), config);
+        assert_eq!(&transport.receive().await.unwrap()[..], &[0xFF; 4]);
+    }
+}
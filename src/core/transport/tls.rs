@@ -0,0 +1,319 @@
+//! TLS for TCP-based transports
+//!
+//! Wraps raw TCP and Telnet connections in TLS for devices behind stunnel
+//! or serving telnets, using rustls. The same transport talks plaintext
+//! when no [`TlsConfig`] is set.
+
+use super::TransportError;
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::crypto::{verify_tls12_signature, verify_tls13_signature, CryptoProvider};
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, ServerName, UnixTime};
+use rustls::{ClientConfig, DigitallySignedStruct, RootCertStore, SignatureScheme};
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio::net::TcpStream;
+use tokio_rustls::client::TlsStream;
+use tokio_rustls::TlsConnector;
+
+/// Default port for telnets (RFC 4217 assignment)
+pub const TELNETS_PORT: u16 = 992;
+
+/// How the server certificate is checked
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum TlsVerify {
+    /// Chain must lead to a trusted root and match the server name
+    #[default]
+    Full,
+    /// Accept any certificate (self-signed lab equipment)
+    None,
+}
+
+/// TLS settings for a TCP or Telnet connection
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TlsConfig {
+    /// PEM file with CA certificates trusted in addition to the built-in roots
+    #[serde(default)]
+    pub ca_path: Option<PathBuf>,
+    /// Certificate check
+    #[serde(default)]
+    pub verify: TlsVerify,
+    /// Server name to send and verify (defaults to the host)
+    #[serde(default)]
+    pub sni: Option<String>,
+    /// PEM client certificate chain for mutual TLS
+    #[serde(default)]
+    pub client_cert: Option<PathBuf>,
+    /// PEM private key of the client certificate
+    #[serde(default)]
+    pub client_key: Option<PathBuf>,
+}
+
+impl TlsConfig {
+    /// Verified TLS with the built-in roots
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Trust the CA certificates in a PEM file
+    #[must_use]
+    pub fn ca_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.ca_path = Some(path.into());
+        self
+    }
+
+    /// Set the certificate check
+    #[must_use]
+    pub fn verify(mut self, verify: TlsVerify) -> Self {
+        self.verify = verify;
+        self
+    }
+
+    /// Send and verify this server name instead of the host
+    #[must_use]
+    pub fn sni(mut self, name: &str) -> Self {
+        self.sni = Some(name.to_string());
+        self
+    }
+
+    /// Present a client certificate
+    #[must_use]
+    pub fn client_cert(mut self, cert: impl Into<PathBuf>, key: impl Into<PathBuf>) -> Self {
+        self.client_cert = Some(cert.into());
+        self.client_key = Some(key.into());
+        self
+    }
+
+    /// Build the rustls client configuration
+    fn client_config(&self) -> Result<ClientConfig, TransportError> {
+        let provider = Arc::new(rustls::crypto::ring::default_provider());
+        let builder = ClientConfig::builder_with_provider(provider.clone())
+            .with_safe_default_protocol_versions()
+            .map_err(|e| TransportError::ConfigError(format!("TLS: {}", e)))?;
+
+        let builder = match self.verify {
+            TlsVerify::Full => {
+                let mut roots = RootCertStore::empty();
+                roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+                if let Some(path) = &self.ca_path {
+                    for cert in read_certs(path)? {
+                        roots
+                            .add(cert)
+                            .map_err(|e| TransportError::ConfigError(format!("CA file {}: {}", path.display(), e)))?;
+                    }
+                }
+                builder.with_root_certificates(roots)
+            }
+            TlsVerify::None => builder
+                .dangerous()
+                .with_custom_certificate_verifier(Arc::new(AcceptAnyCert(provider))),
+        };
+
+        match (&self.client_cert, &self.client_key) {
+            (Some(cert), Some(key)) => {
+                let key = PrivateKeyDer::from_pem_file(key)
+                    .map_err(|e| TransportError::ConfigError(format!("Client key {}: {}", key.display(), e)))?;
+                builder
+                    .with_client_auth_cert(read_certs(cert)?, key)
+                    .map_err(|e| TransportError::ConfigError(format!("Client certificate: {}", e)))
+            }
+            (None, None) => Ok(builder.with_no_client_auth()),
+            _ => Err(TransportError::ConfigError(
+                "Client certificate and key must be set together".to_string(),
+            )),
+        }
+    }
+}
+
+/// Certificates in a PEM file
+fn read_certs(path: &Path) -> Result<Vec<CertificateDer<'static>>, TransportError> {
+    let invalid = |e: rustls::pki_types::pem::Error| TransportError::ConfigError(format!("{}: {}", path.display(), e));
+    let certs = CertificateDer::pem_file_iter(path)
+        .map_err(invalid)?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(invalid)?;
+    if certs.is_empty() {
+        return Err(TransportError::ConfigError(format!("No certificates in {}", path.display())));
+    }
+    Ok(certs)
+}
+
+/// Negotiated TLS parameters
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TlsInfo {
+    /// Protocol version, e.g. `TLSv1_3`
+    pub protocol: String,
+    /// Cipher suite, e.g. `TLS13_AES_256_GCM_SHA384`
+    pub cipher_suite: String,
+    /// Server name that was sent
+    pub server_name: String,
+    /// DER certificates presented by the server, leaf first
+    pub peer_certificates: Vec<Vec<u8>>,
+}
+
+/// Run the TLS handshake over a connected stream
+pub(crate) async fn connect(
+    stream: TcpStream,
+    host: &str,
+    config: &TlsConfig,
+) -> Result<(NetStream, TlsInfo), TransportError> {
+    let name = config.sni.as_deref().unwrap_or(host).to_string();
+    let server_name = ServerName::try_from(name.clone())
+        .map_err(|e| TransportError::ConfigError(format!("Invalid TLS server name '{}': {}", name, e)))?;
+    let connector = TlsConnector::from(Arc::new(config.client_config()?));
+    let stream = connector
+        .connect(server_name, stream)
+        .await
+        .map_err(|e| TransportError::ConnectionFailed(format!("TLS handshake failed: {}", e)))?;
+
+    let (_, connection) = stream.get_ref();
+    let info = TlsInfo {
+        protocol: connection
+            .protocol_version()
+            .map_or_else(String::new, |version| format!("{:?}", version)),
+        cipher_suite: connection
+            .negotiated_cipher_suite()
+            .map_or_else(String::new, |suite| format!("{:?}", suite.suite())),
+        server_name: name,
+        peer_certificates: connection
+            .peer_certificates()
+            .map(|certs| certs.iter().map(|cert| cert.as_ref().to_vec()).collect())
+            .unwrap_or_default(),
+    };
+    Ok((NetStream::Tls(Box::new(stream)), info))
+}
+
+/// Verifier for [`TlsVerify::None`]; still checks handshake signatures
+#[derive(Debug)]
+struct AcceptAnyCert(Arc<CryptoProvider>);
+
+impl ServerCertVerifier for AcceptAnyCert {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls12_signature(message, cert, dss, &self.0.signature_verification_algorithms)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls13_signature(message, cert, dss, &self.0.signature_verification_algorithms)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.0.signature_verification_algorithms.supported_schemes()
+    }
+}
+
+/// Plain or TLS-wrapped TCP stream
+pub(crate) enum NetStream {
+    Plain(TcpStream),
+    Tls(Box<TlsStream<TcpStream>>),
+}
+
+impl NetStream {
+    /// Read what is available without waiting, like [`TcpStream::try_read`]
+    pub(crate) fn try_read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Self::Plain(stream) => stream.try_read(buf),
+            Self::Tls(stream) => {
+                let mut read_buf = ReadBuf::new(buf);
+                let mut cx = Context::from_waker(futures::task::noop_waker_ref());
+                match Pin::new(stream.as_mut()).poll_read(&mut cx, &mut read_buf) {
+                    Poll::Ready(Ok(())) => Ok(read_buf.filled().len()),
+                    Poll::Ready(Err(e)) => Err(e),
+                    Poll::Pending => Err(io::ErrorKind::WouldBlock.into()),
+                }
+            }
+        }
+    }
+}
+
+impl AsyncRead for NetStream {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Self::Plain(stream) => Pin::new(stream).poll_read(cx, buf),
+            Self::Tls(stream) => Pin::new(stream.as_mut()).poll_read(cx, buf),
+        }
+    }
+}
+
+impl AsyncWrite for NetStream {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        match self.get_mut() {
+            Self::Plain(stream) => Pin::new(stream).poll_write(cx, buf),
+            Self::Tls(stream) => Pin::new(stream.as_mut()).poll_write(cx, buf),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Self::Plain(stream) => Pin::new(stream).poll_flush(cx),
+            Self::Tls(stream) => Pin::new(stream.as_mut()).poll_flush(cx),
+        }
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Self::Plain(stream) => Pin::new(stream).poll_shutdown(cx),
+            Self::Tls(stream) => Pin::new(stream.as_mut()).poll_shutdown(cx),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_client_config_errors() {
+        assert!(TlsConfig::new().client_config().is_ok());
+        assert!(TlsConfig::new().verify(TlsVerify::None).client_config().is_ok());
+
+        let missing = TlsConfig::new().ca_path("/nonexistent/ca.pem");
+        assert!(matches!(missing.client_config(), Err(TransportError::ConfigError(_))));
+
+        let half = TlsConfig {
+            client_cert: Some(PathBuf::from("client.pem")),
+            ..TlsConfig::default()
+        };
+        assert!(matches!(half.client_config(), Err(TransportError::ConfigError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_handshake_failure_is_reported() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            // Not a TLS server: answer the ClientHello with plain text
+            let (mut peer, _) = listener.accept().await.unwrap();
+            tokio::io::AsyncWriteExt::write_all(&mut peer, b"hello\r\n").await.ok();
+        });
+
+        let stream = TcpStream::connect(addr).await.unwrap();
+        let result = connect(stream, "127.0.0.1", &TlsConfig::new()).await;
+        assert!(matches!(result, Err(TransportError::ConnectionFailed(_))));
+    }
+}
@@ -8,8 +8,9 @@
 
 pub mod advanced;
 
-use regex::Regex;
+use crate::core::storage::{self, Migration, StorageError};
 use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
 use uuid::Uuid;
 
 // Re-export advanced types
@@ -32,43 +33,73 @@ pub enum TriggerCondition {
     Regex(String),
     /// Match hex pattern (e.g., "FF 00 *" where * is wildcard)
     HexPattern(String),
+    /// All conditions match
+    All(Vec<TriggerCondition>),
+    /// Any condition matches
+    Any(Vec<TriggerCondition>),
+    /// The condition does not match
+    Not(Box<TriggerCondition>),
 }
 
 impl TriggerCondition {
     /// Check if the condition matches the data
+    ///
+    /// Returns the matched text. `All` reports the first non-empty match of
+    /// its conditions, `Any` the first satisfied condition's match, and
+    /// `Not` an empty string. Combinators stop at the first deciding condition.
+    #[must_use]
     pub fn matches(&self, data: &[u8]) -> Option<String> {
+        self.find(data).map(|(matched, _)| matched)
+    }
+
+    /// Like [`TriggerCondition::matches`], also returning where the match ends
+    ///
+    /// The offset is just past the matched bytes, so callers can consume
+    /// the data up to it. `All` ends at its last-ending match; `Not` matches
+    /// no bytes and ends at 0.
+    pub fn find(&self, data: &[u8]) -> Option<(String, usize)> {
         match self {
-            Self::Exact(pattern) => {
-                if data.windows(pattern.len()).any(|w| w == pattern.as_slice()) {
-                    Some(hex::encode(pattern))
-                } else {
-                    None
+            Self::All(conditions) => {
+                let mut matched: Option<String> = None;
+                let mut end = 0;
+                for condition in conditions {
+                    let (m, e) = condition.find(data)?;
+                    end = end.max(e);
+                    if matched.as_deref().map_or(true, str::is_empty) {
+                        matched = Some(m);
+                    }
                 }
+                Some((matched.unwrap_or_default(), end))
+            }
+            Self::Any(conditions) => conditions.iter().find_map(|c| c.find(data)),
+            Self::Not(condition) => match condition.find(data) {
+                Some(_) => None,
+                None => Some((String::new(), 0)),
+            },
+            Self::Exact(pattern) => {
+                let start = memchr::memmem::find(data, pattern)?;
+                Some((hex::encode(pattern), start + pattern.len()))
             }
             Self::Text(text) => {
-                let data_str = String::from_utf8_lossy(data);
-                if data_str.contains(text) {
-                    Some(text.clone())
-                } else {
-                    None
-                }
+                memchr::memmem::find(data, text.as_bytes()).map(|start| (text.clone(), start + text.len()))
             }
             Self::TextIgnoreCase(text) => {
-                let data_str = String::from_utf8_lossy(data).to_lowercase();
-                let text_lower = text.to_lowercase();
-                if data_str.contains(&text_lower) {
-                    Some(text.clone())
+                let end = if text.is_ascii() {
+                    find_ascii_ignore_case(data, text.as_bytes()).map(|start| start + text.len())
                 } else {
-                    None
-                }
+                    // Non-ASCII case folding needs full Unicode case rules
+                    regex::bytes::RegexBuilder::new(&regex::escape(text))
+                        .case_insensitive(true)
+                        .build()
+                        .ok()?
+                        .find(data)
+                        .map(|m| m.end())
+                };
+                end.map(|end| (text.clone(), end))
             }
             Self::Regex(pattern) => {
-                if let Ok(re) = Regex::new(pattern) {
-                    let data_str = String::from_utf8_lossy(data);
-                    re.find(&data_str).map(|m| m.as_str().to_string())
-                } else {
-                    None
-                }
+                let re = regex::bytes::Regex::new(pattern).ok()?;
+                re.find(data).map(|m| (String::from_utf8_lossy(m.as_bytes()).into_owned(), m.end()))
             }
             Self::HexPattern(pattern) => {
                 // Parse pattern: "FF 00 * 01" where * matches any byte
@@ -88,7 +119,7 @@ impl TriggerCondition {
                 }
 
                 // Search for pattern in data
-                'outer: for window in data.windows(pattern_bytes.len()) {
+                'outer: for (start, window) in data.windows(pattern_bytes.len()).enumerate() {
                     for (i, &expected) in pattern_bytes.iter().enumerate() {
                         if let Some(exp) = expected {
                             if window[i] != exp {
@@ -97,7 +128,7 @@ impl TriggerCondition {
                         }
                         // None (wildcard) matches anything
                     }
-                    return Some(hex::encode(window));
+                    return Some((hex::encode(window), start + window.len()));
                 }
 
                 None
@@ -106,6 +137,19 @@ impl TriggerCondition {
     }
 }
 
+/// Substring search folding ASCII case on the fly; returns the match start
+fn find_ascii_ignore_case(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    let Some((&first, rest)) = needle.split_first() else {
+        return Some(0);
+    };
+    let (lower, upper) = (first.to_ascii_lowercase(), first.to_ascii_uppercase());
+    let last_start = haystack.len().checked_sub(needle.len())?;
+
+    memchr::memchr2_iter(lower, upper, &haystack[..=last_start]).find(|&pos| {
+        haystack[pos + 1..pos + needle.len()].eq_ignore_ascii_case(rest)
+    })
+}
+
 /// Action to perform when trigger matches
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum TriggerAction {
@@ -123,6 +167,16 @@ pub enum TriggerAction {
     Highlight(String),
     /// Stop/pause connection
     StopConnection,
+    /// Save the session's recent RX history to a file (`{time}` is replaced by the fire time)
+    SaveHistory(String),
+    /// Start logging the session to a file (`{time}` is replaced by the fire time)
+    StartLogging(String),
+    /// Stop session logging
+    StopLogging,
+    /// Resume recording into the session's chart
+    StartChartRecording,
+    /// Pause recording into the session's chart
+    StopChartRecording,
 }
 
 /// Trigger definition
@@ -143,6 +197,12 @@ pub struct Trigger {
     /// Has fired (for one-shot triggers)
     #[serde(skip)]
     pub fired: bool,
+    /// Minimum time between firings in milliseconds (0 = none)
+    #[serde(default)]
+    pub cooldown_ms: u64,
+    /// When the trigger last fired
+    #[serde(skip)]
+    pub last_fired: Option<Instant>,
 }
 
 impl Trigger {
@@ -156,6 +216,8 @@ impl Trigger {
             enabled: true,
             one_shot: false,
             fired: false,
+            cooldown_ms: 0,
+            last_fired: None,
         }
     }
 
@@ -173,7 +235,22 @@ impl Trigger {
         self
     }
 
+    /// Set the minimum time between firings
+    #[must_use]
+    pub fn cooldown(mut self, cooldown: Duration) -> Self {
+        self.cooldown_ms = cooldown.as_millis() as u64;
+        self
+    }
+
+    /// Check if the trigger fired less than its cooldown before `now`
+    pub fn is_cooling_down(&self, now: Instant) -> bool {
+        self.last_fired
+            .is_some_and(|last| now.saturating_duration_since(last) < Duration::from_millis(self.cooldown_ms))
+    }
+
     /// Check if trigger matches and return matched string
+    ///
+    /// Does not consider the cooldown or change state; see [`Trigger::fire`].
     pub fn check(&self, data: &[u8]) -> Option<String> {
         if !self.enabled {
             return None;
@@ -186,17 +263,60 @@ impl Trigger {
         self.condition.matches(data)
     }
 
+    /// Like [`Trigger::check`], also returning where the match ends in `data`
+    #[must_use]
+    pub fn find(&self, data: &[u8]) -> Option<(String, usize)> {
+        if !self.enabled || (self.one_shot && self.fired) {
+            return None;
+        }
+
+        self.condition.find(data)
+    }
+
     /// Mark trigger as fired
     pub fn mark_fired(&mut self) {
         self.fired = true;
     }
 
+    /// Check the trigger at `now` and, if it fires, record the firing
+    ///
+    /// A trigger in its cooldown does not fire.
+    pub fn fire(&mut self, data: &[u8], now: Instant) -> Option<String> {
+        if self.is_cooling_down(now) {
+            return None;
+        }
+        let matched = self.check(data)?;
+        self.record_firing(now);
+        Some(matched)
+    }
+
+    /// Record that the trigger fired at `now`, for callers matching with
+    /// [`Trigger::find`]
+    pub fn record_firing(&mut self, now: Instant) {
+        self.mark_fired();
+        self.last_fired = Some(now);
+    }
+
     /// Reset trigger state
     pub fn reset(&mut self) {
         self.fired = false;
+        self.last_fired = None;
     }
 }
 
+/// A trigger that fired in [`TriggerManager::check_all_mut`]
+#[derive(Debug, Clone)]
+pub struct FiredTrigger {
+    /// Trigger ID
+    pub id: Uuid,
+    /// Trigger name
+    pub name: String,
+    /// Matched text
+    pub matched: String,
+    /// Actions to perform
+    pub actions: Vec<TriggerAction>,
+}
+
 /// Trigger manager for storing and managing triggers
 pub struct TriggerManager {
     triggers: std::collections::HashMap<Uuid, Trigger>,
@@ -211,7 +331,9 @@ impl TriggerManager {
             triggers: std::collections::HashMap::new(),
             config_path,
         };
-        manager.load().ok();
+        if let Err(e) = manager.load() {
+            tracing::error!("Failed to load triggers: {}", e);
+        }
         manager
     }
 
@@ -226,19 +348,13 @@ impl TriggerManager {
         }
     }
 
-    /// Load triggers from disk
-    pub fn load(&mut self) -> Result<(), String> {
-        if !self.config_path.exists() {
+    /// Load triggers from disk, upgrading older formats
+    pub fn load(&mut self) -> Result<(), StorageError> {
+        let Some(data) = storage::read_versioned::<TriggerData>(&self.config_path, TRIGGER_MIGRATIONS)? else {
             return Ok(());
-        }
-
-        let content = std::fs::read_to_string(&self.config_path)
-            .map_err(|e| format!("Failed to read triggers: {}", e))?;
-
-        let triggers: Vec<Trigger> = serde_json::from_str(&content)
-            .map_err(|e| format!("Failed to parse triggers: {}", e))?;
+        };
 
-        self.triggers = triggers.into_iter()
+        self.triggers = data.triggers.into_iter()
             .map(|t| (t.id, t))
             .collect();
 
@@ -246,16 +362,13 @@ impl TriggerManager {
     }
 
     /// Save triggers to disk
-    pub fn save(&self) -> Result<(), String> {
-        let triggers: Vec<&Trigger> = self.triggers.values().collect();
-
-        let content = serde_json::to_string_pretty(&triggers)
-            .map_err(|e| format!("Failed to serialize triggers: {}", e))?;
-
-        std::fs::write(&self.config_path, content)
-            .map_err(|e| format!("Failed to write triggers: {}", e))?;
+    pub fn save(&self) -> Result<(), StorageError> {
+        let data = TriggerData {
+            version: TRIGGER_MIGRATIONS.len() as u32,
+            triggers: self.triggers.values().cloned().collect(),
+        };
 
-        Ok(())
+        storage::write_json(&self.config_path, &data)
     }
 
     /// Add a trigger
@@ -295,7 +408,9 @@ impl TriggerManager {
             .collect()
     }
 
-    /// Check data against all enabled triggers
+    /// Check data against all enabled triggers without changing their state
+    ///
+    /// For previews; use [`TriggerManager::check_all_mut`] when acting on matches.
     pub fn check_all(&self, data: &[u8]) -> Vec<(Uuid, String, Vec<TriggerAction>)> {
         let mut matches = Vec::new();
         
@@ -308,6 +423,29 @@ impl TriggerManager {
         matches
     }
 
+    /// Fire all enabled triggers matching `data`
+    ///
+    /// One-shot triggers are marked fired and cooldowns start, so only the
+    /// triggers that actually fire this call are returned.
+    pub fn check_all_mut(&mut self, data: &[u8]) -> Vec<FiredTrigger> {
+        self.fire_all(data, Instant::now())
+    }
+
+    fn fire_all(&mut self, data: &[u8], now: Instant) -> Vec<FiredTrigger> {
+        self.triggers
+            .values_mut()
+            .filter_map(|trigger| {
+                let matched = trigger.fire(data, now)?;
+                Some(FiredTrigger {
+                    id: trigger.id,
+                    name: trigger.name.clone(),
+                    matched,
+                    actions: trigger.actions.clone(),
+                })
+            })
+            .collect()
+    }
+
     /// Count triggers
     pub fn count(&self) -> usize {
         self.triggers.len()
@@ -343,6 +481,24 @@ impl Default for TriggerManager {
     }
 }
 
+/// v0 stored a bare list of triggers
+fn wrap_trigger_list(value: serde_json::Value) -> Result<serde_json::Value, String> {
+    if !value.is_array() {
+        return Err("expected a list of triggers".to_string());
+    }
+    Ok(serde_json::json!({ "triggers": value }))
+}
+
+/// Upgrades of `triggers.json`
+const TRIGGER_MIGRATIONS: &[Migration] = &[wrap_trigger_list];
+
+/// Serialized trigger data
+#[derive(Debug, Serialize, Deserialize)]
+struct TriggerData {
+    version: u32,
+    triggers: Vec<Trigger>,
+}
+
 /// Alias for pattern group (trigger group)
 pub type TriggerGroup = PatternGroup;
 
@@ -368,6 +524,50 @@ pub type ConditionalTrigger = advanced::PatternGroup;
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_load_migrates_unversioned_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_path = dir.path().join("triggers.json");
+        let trigger = Trigger::new("boot", TriggerCondition::Text("READY".to_string()));
+        std::fs::write(&config_path, serde_json::to_string(&vec![&trigger]).unwrap()).unwrap();
+
+        let mut manager = TriggerManager {
+            triggers: std::collections::HashMap::new(),
+            config_path: config_path.clone(),
+        };
+        manager.load().unwrap();
+        assert_eq!(manager.get(trigger.id).unwrap().name, "boot");
+        assert!(dir.path().join("triggers.json.v0.bak").exists());
+
+        manager.save().unwrap();
+        let saved: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&config_path).unwrap()).unwrap();
+        assert_eq!(saved["version"], 1);
+    }
+
+    #[test]
+    fn test_check_all_mut_fires_once_and_cools_down() {
+        let mut manager = TriggerManager {
+            triggers: std::collections::HashMap::new(),
+            config_path: std::path::PathBuf::new(),
+        };
+        let once = Trigger::new("once", TriggerCondition::Text("READY".to_string())).one_shot(true);
+        let slow = Trigger::new("slow", TriggerCondition::Text("READY".to_string())).cooldown(Duration::from_secs(1));
+        let (once_id, slow_id) = (once.id, slow.id);
+        manager.triggers.insert(once_id, once);
+        manager.triggers.insert(slow_id, slow);
+
+        let start = Instant::now();
+        assert_eq!(manager.fire_all(b"READY", start).len(), 2);
+        assert!(manager.fire_all(b"READY", start + Duration::from_millis(500)).is_empty());
+        let fired = manager.fire_all(b"READY", start + Duration::from_secs(2));
+        assert_eq!(fired.iter().map(|f| f.id).collect::<Vec<_>>(), [slow_id]);
+
+        // The immutable check still previews the match
+        assert_eq!(manager.check_all(b"READY").len(), 1);
+        manager.reset_all();
+        assert_eq!(manager.check_all_mut(b"READY").len(), 2);
+    }
+
     #[test]
     fn test_text_match() {
         let cond = TriggerCondition::Text("ERROR".to_string());
@@ -382,6 +582,18 @@ mod tests {
         assert!(cond.matches(b"An Error occurred").is_some());
     }
 
+    #[test]
+    fn test_text_match_raw_bytes() {
+        // Invalid UTF-8 around the match must not get in the way
+        let data = b"\xff\xfeboot: READY\x80";
+        assert!(TriggerCondition::Text("READY".to_string()).matches(data).is_some());
+        assert!(TriggerCondition::TextIgnoreCase("ready".to_string()).matches(data).is_some());
+        assert!(TriggerCondition::TextIgnoreCase("READY!".to_string()).matches(data).is_none());
+        assert!(TriggerCondition::TextIgnoreCase("ÄRGER".to_string())
+            .matches("kein ärger".as_bytes())
+            .is_some());
+    }
+
     #[test]
     fn test_exact_match() {
         let cond = TriggerCondition::Exact(vec![0xFF, 0x00, 0x01]);
@@ -397,6 +609,40 @@ mod tests {
         assert!(cond.matches(&[0xFF, 0x00, 0x02]).is_none());
     }
 
+    #[test]
+    fn test_combined_conditions() {
+        let cond = TriggerCondition::All(vec![
+            TriggerCondition::Not(Box::new(TriggerCondition::Text("IGNORE".to_string()))),
+            TriggerCondition::Text("ERROR".to_string()),
+        ]);
+        assert_eq!(cond.matches(b"ERROR: disk full"), Some("ERROR".to_string()));
+        assert!(cond.matches(b"ERROR: IGNORE this").is_none());
+        assert!(cond.matches(b"all good").is_none());
+
+        let any = TriggerCondition::Any(vec![
+            TriggerCondition::Text("FAIL".to_string()),
+            TriggerCondition::Regex(r"ERR\d+".to_string()),
+        ]);
+        assert_eq!(any.matches(b"code ERR42"), Some("ERR42".to_string()));
+        assert!(any.matches(b"ok").is_none());
+        assert!(TriggerCondition::Any(Vec::new()).matches(b"x").is_none());
+    }
+
+    #[test]
+    fn test_find_reports_match_end() {
+        let data = b"boot\nERR42 stop\n";
+        assert_eq!(TriggerCondition::Text("boot".to_string()).find(data), Some(("boot".to_string(), 4)));
+        assert_eq!(TriggerCondition::Regex(r"ERR\d+".to_string()).find(data), Some(("ERR42".to_string(), 10)));
+        assert_eq!(TriggerCondition::HexPattern("0A".to_string()).find(data), Some(("0a".to_string(), 5)));
+        let all = TriggerCondition::All(vec![
+            TriggerCondition::TextIgnoreCase("STOP".to_string()),
+            TriggerCondition::Text("boot".to_string()),
+        ]);
+        assert_eq!(all.find(data), Some(("STOP".to_string(), 15)));
+        let not = TriggerCondition::Not(Box::new(TriggerCondition::Text("FAIL".to_string())));
+        assert_eq!(not.find(data), Some((String::new(), 0)));
+    }
+
     #[test]
     fn test_regex_match() {
         let cond = TriggerCondition::Regex(r"ERROR:\s+\d+".to_string());
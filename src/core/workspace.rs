@@ -92,6 +92,19 @@ impl Workspace {
         }
     }
     
+    /// Record the split layout (None = single pane)
+    pub fn set_split(&mut self, split: Option<SplitLayout>) {
+        self.layout.split = split;
+        self.touch();
+    }
+    
+    /// Split layout to restore, with panes of sessions missing from this workspace emptied
+    pub fn restore_split(&self) -> Option<SplitLayout> {
+        let mut split = self.layout.split.clone()?;
+        split.retain_sessions(|id| self.sessions.iter().any(|s| s.id == id));
+        Some(split)
+    }
+    
     /// Save to file
     pub fn save(&self, path: &Path) -> std::io::Result<()> {
         let json = serde_json::to_string_pretty(self)
@@ -221,7 +234,7 @@ impl Default for WindowLayout {
 }
 
 /// Split layout configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SplitLayout {
     /// Split orientation (horizontal or vertical)
     pub orientation: SplitOrientation,
@@ -233,15 +246,52 @@ pub struct SplitLayout {
     pub second: SplitContent,
 }
 
+impl SplitLayout {
+    /// Split two panes; the ratio is the first pane's share
+    pub fn new(orientation: SplitOrientation, ratio: f32, first: SplitContent, second: SplitContent) -> Self {
+        Self {
+            orientation,
+            ratio: ratio.clamp(0.1, 0.9),
+            first,
+            second,
+        }
+    }
+    
+    /// Two sessions side by side, evenly split
+    pub fn side_by_side(left: &str, right: &str) -> Self {
+        Self::new(
+            SplitOrientation::Horizontal,
+            0.5,
+            SplitContent::Session(left.to_string()),
+            SplitContent::Session(right.to_string()),
+        )
+    }
+    
+    /// Session IDs shown in the panes, first to last
+    pub fn sessions(&self) -> Vec<&str> {
+        let mut ids = self.first.sessions();
+        ids.extend(self.second.sessions());
+        ids
+    }
+    
+    /// Empty every pane whose session fails `keep`
+    pub fn retain_sessions(&mut self, keep: impl Fn(&str) -> bool + Copy) {
+        self.first.retain_sessions(keep);
+        self.second.retain_sessions(keep);
+    }
+}
+
 /// Split orientation
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum SplitOrientation {
+    /// Side by side
     Horizontal,
+    /// Top and bottom
     Vertical,
 }
 
 /// Content of a split pane
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum SplitContent {
     /// Session by ID
     Session(String),
@@ -251,6 +301,24 @@ pub enum SplitContent {
     Empty,
 }
 
+impl SplitContent {
+    fn sessions(&self) -> Vec<&str> {
+        match self {
+            Self::Session(id) => vec![id.as_str()],
+            Self::Split(split) => split.sessions(),
+            Self::Empty => Vec::new(),
+        }
+    }
+    
+    fn retain_sessions(&mut self, keep: impl Fn(&str) -> bool + Copy) {
+        match self {
+            Self::Session(id) if !keep(id) => *self = Self::Empty,
+            Self::Split(split) => split.retain_sessions(keep),
+            _ => {}
+        }
+    }
+}
+
 /// Side panel state
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SidePanelState {
@@ -512,6 +580,50 @@ mod tests {
         assert_eq!(imported.variables.get("key"), Some(&"value".to_string()));
     }
     
+    fn tcp_session(id: &str) -> SessionState {
+        SessionState {
+            id: id.to_string(),
+            name: id.to_string(),
+            connection_type: ConnectionType::Tcp,
+            connection_params: ConnectionParams::Tcp {
+                host: "localhost".to_string(),
+                port: 23,
+            },
+            was_connected: true,
+            auto_reconnect: true,
+            scroll_buffer: None,
+            command_history: Vec::new(),
+            tab_index: 0,
+        }
+    }
+    
+    #[test]
+    fn test_split_layout_round_trip() {
+        let mut workspace = Workspace::new("Monitor");
+        workspace.add_session(tcp_session("gps"));
+        workspace.add_session(tcp_session("modem"));
+        workspace.set_split(Some(SplitLayout::new(
+            SplitOrientation::Horizontal,
+            0.6,
+            SplitContent::Session("gps".to_string()),
+            SplitContent::Split(Box::new(SplitLayout::new(
+                SplitOrientation::Vertical,
+                0.5,
+                SplitContent::Session("modem".to_string()),
+                SplitContent::Session("closed".to_string()),
+            ))),
+        )));
+        
+        let imported = Workspace::import(&workspace.export()).unwrap();
+        assert_eq!(imported.layout.split, workspace.layout.split);
+        
+        let restored = imported.restore_split().unwrap();
+        assert_eq!(restored.orientation, SplitOrientation::Horizontal);
+        assert_eq!(restored.ratio, 0.6);
+        assert_eq!(restored.sessions(), ["gps", "modem"]);
+        assert_eq!(SplitLayout::side_by_side("a", "b").sessions(), ["a", "b"]);
+    }
+    
     #[test]
     fn test_base64() {
         let data = b"Hello, World!";
@@ -2,14 +2,22 @@
 
 use eframe::egui::{self, Color32, CornerRadius, FontId, RichText, Vec2, Margin, Stroke};
 use std::collections::VecDeque;
+use std::sync::Arc;
 use std::sync::mpsc::{self, Receiver, Sender};
 use std::thread;
 use rust_i18n::t;
 
-use super::ansi_parser::parse_ansi;
 use super::profiles::{Profile, ProfileManager, ProfileType, ProfileSnippet, SerialProfileSettings, TcpProfileSettings, SshProfileSettings, BluetoothProfileSettings};
+use super::scrollback::{OutputRetention, OLDER_LINES_PAGE};
 use super::session_tab::{SessionTab, TabManager};
+use termicon_core::core::history::HistoryStore;
+use termicon_core::core::terminal::{Color, NewlineTranslation};
+use termicon_core::core::profile::UsbSelector;
+use termicon_core::core::transport::{enumerate_ports, PortInfo};
+use termicon_core::{ControlCharEcho, EchoPolicy};
+use termicon_core::config::{AppConfig, ConfigEvent, ConfigWatcher};
 use termicon_core::i18n::{set_locale, Locale};
+use termicon_core::{CodecType, Notifier};
 
 /// Connection type
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -197,6 +205,8 @@ impl Default for BluetoothSettings {
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ViewMode {
     Text,
+    /// Text with control characters as `^C`/`\x1b`
+    TextSafe,
     Hex,
     Mixed,
 }
@@ -270,9 +280,7 @@ pub struct TermiconApp {
     /// Status message
     status_message: String,
     /// Available serial ports
-    available_ports: Vec<String>,
-    /// View mode
-    view_mode: ViewMode,
+    available_ports: Vec<PortInfo>,
     /// Show side panel
     show_side_panel: bool,
     /// Current theme
@@ -328,6 +336,10 @@ pub struct TermiconApp {
     new_trigger_response: String,
     /// SFTP remote path
     sftp_remote_path: String,
+    /// Persisted application config
+    config: AppConfig,
+    /// Reloads `config` when the file changes on disk
+    config_watcher: Option<ConfigWatcher>,
 }
 
 impl Default for TermiconApp {
@@ -361,8 +373,15 @@ impl Default for TermiconApp {
             },
         ];
 
+        let mut tabs = TabManager::new();
+        // Bell notifications stay off until the config enables them
+        let notifier = Notifier::desktop();
+        notifier.set_enabled(false);
+        tabs.notifier = Some(Arc::new(notifier));
+        tabs.history = Some(HistoryStore::new());
+
         Self {
-            tabs: TabManager::new(),
+            tabs,
             current_dialog: DialogType::None,
             serial_settings: SerialSettings::default(),
             tcp_settings: TcpSettings::default(),
@@ -370,7 +389,6 @@ impl Default for TermiconApp {
             bluetooth_settings: BluetoothSettings::default(),
             status_message: "Ready".to_string(),
             available_ports: Vec::new(),
-            view_mode: ViewMode::Text,
             show_side_panel: true,
             theme: AppTheme::Dark,
             side_panel_mode: SidePanelMode::Profiles,  // Start with profiles view
@@ -403,6 +421,8 @@ impl Default for TermiconApp {
             new_trigger_pattern: String::new(),
             new_trigger_response: String::new(),
             sftp_remote_path: "/".to_string(),
+            config: AppConfig::default(),
+            config_watcher: None,
         }
     }
 }
@@ -471,6 +491,17 @@ impl TermiconApp {
         let mut app = Self::default();
         app.refresh_serial_ports();
 
+        match AppConfig::load() {
+            Ok(config) => {
+                app.apply_config(config, &cc.egui_ctx);
+            }
+            Err(e) => tracing::warn!("Failed to load config: {}", e),
+        }
+        match AppConfig::watch() {
+            Ok(watcher) => app.config_watcher = Some(watcher),
+            Err(e) => tracing::warn!("Config live reload unavailable: {}", e),
+        }
+
         // Create welcome tab
         let mut welcome_tab = SessionTab::new("Welcome", ConnectionType::Serial);
         welcome_tab.add_line("╔════════════════════════════════════════════════════════════╗", false);
@@ -490,14 +521,90 @@ impl TermiconApp {
         app
     }
 
+    /// Apply a (re)loaded config, touching only the settings that changed
+    ///
+    /// Reloads caused by our own saves then leave the UI state alone. Returns
+    /// whether theme, language or default view changed.
+    fn apply_config(&mut self, config: AppConfig, ctx: &egui::Context) -> bool {
+        let previous = std::mem::replace(&mut self.config, config);
+        let mut changed = false;
+
+        if self.config.theme != previous.theme {
+            self.theme = if self.config.is_dark_theme() { AppTheme::Dark } else { AppTheme::Light };
+            self.apply_theme(ctx);
+            changed = true;
+        }
+
+        if self.config.locale != previous.locale {
+            let locale = self.config.locale();
+            self.language = match locale {
+                Locale::English => Language::English,
+                Locale::Hungarian => Language::Hungarian,
+            };
+            set_locale(locale);
+            self.language_changed = true;
+            changed = true;
+        }
+
+        if self.config.default_codec != previous.default_codec {
+            self.tabs.view_mode = match self.config.default_codec {
+                CodecType::Text => ViewMode::Text,
+                CodecType::TextSafe => ViewMode::TextSafe,
+                CodecType::Hex | CodecType::Binary => ViewMode::Hex,
+                CodecType::Mixed => ViewMode::Mixed,
+            };
+            changed = true;
+        }
+
+        self.tabs.set_retention(OutputRetention::from_settings(
+            self.config.terminal.scroll_buffer,
+            self.config.terminal.spill_to_disk,
+        ));
+        if let Some(ref notifier) = self.tabs.notifier {
+            notifier.set_enabled(self.config.terminal.bell_notifications);
+            notifier.set_rate_limit(std::time::Duration::from_secs(self.config.terminal.bell_interval_secs));
+        }
+
+        changed
+    }
+
+    /// Pick up external config edits and persist theme/locale changes made in the UI
+    fn sync_config(&mut self, ctx: &egui::Context) {
+        if let Some(event) = self.config_watcher.as_ref().and_then(ConfigWatcher::latest) {
+            match event {
+                ConfigEvent::Changed(config) => {
+                    if self.apply_config(*config, ctx) {
+                        self.status_message = "Settings reloaded".to_string();
+                    }
+                }
+                ConfigEvent::Error(e) => self.status_message = e,
+            }
+            return;
+        }
+
+        let theme = match self.theme {
+            AppTheme::Dark => "dark",
+            AppTheme::Light => "light",
+        };
+        let locale = match self.language {
+            Language::English => Locale::English,
+            Language::Hungarian => Locale::Hungarian,
+        };
+        if self.config.theme != theme || self.config.locale() != locale {
+            self.config.theme = theme.to_string();
+            self.config.set_locale(locale);
+            if let Err(e) = self.config.save() {
+                tracing::warn!("Failed to save config: {}", e);
+            }
+        }
+    }
+
     /// Refresh available serial ports
     fn refresh_serial_ports(&mut self) {
-        self.available_ports = serialport::available_ports()
-            .map(|ports| ports.into_iter().map(|p| p.port_name).collect())
-            .unwrap_or_default();
+        self.available_ports = enumerate_ports();
 
         if !self.available_ports.is_empty() && self.serial_settings.port.is_empty() {
-            self.serial_settings.port = self.available_ports[0].clone();
+            self.serial_settings.port = self.available_ports[0].name.clone();
         }
     }
 
@@ -625,13 +732,54 @@ impl TermiconApp {
         let show_hex = tab.show_hex;
         let is_dark = self.theme == AppTheme::Dark;
 
+        let mut load_older = false;
+        let mut hide_older = false;
+
         egui::ScrollArea::vertical()
             .auto_shrink([false, false])
             .stick_to_bottom(true)
             .show(ui, |ui| {
                 ui.add_space(8.0);
 
-                for line in &tab.output {
+                if tab.scrollback.has_older() {
+                    let remaining = tab.scrollback.spilled() - tab.scrollback.older().len();
+                    ui.horizontal(|ui| {
+                        ui.add_space(8.0);
+                        if ui.small_button(format!("Load older lines ({} on disk)", remaining)).clicked() {
+                            load_older = true;
+                        }
+                    });
+                }
+                if !tab.scrollback.older().is_empty() {
+                    ui.horizontal(|ui| {
+                        ui.add_space(8.0);
+                        if ui.small_button("Hide older lines").clicked() {
+                            hide_older = true;
+                        }
+                    });
+                }
+
+                let bookmark_color = if is_dark {
+                    Color32::from_rgb(230, 190, 80)
+                } else {
+                    Color32::from_rgb(160, 110, 0)
+                };
+                let show_bookmarks = |ui: &mut egui::Ui, line: u64| {
+                    for bookmark in tab.bookmarks_before(line) {
+                        ui.horizontal(|ui| {
+                            ui.add_space(8.0);
+                            ui.label(RichText::new(format!("-- {} --", bookmark.label))
+                                .monospace()
+                                .size(12.0)
+                                .color(bookmark_color));
+                        });
+                    }
+                };
+                // A marker sits after the last line there was when it was added
+                let mut line_number = tab.first_shown_line();
+                for line in tab.scrollback.older().iter().chain(&tab.output) {
+                    show_bookmarks(ui, line_number);
+                    line_number += 1;
                     ui.horizontal_wrapped(|ui| {
                         ui.add_space(8.0);
 
@@ -676,13 +824,17 @@ impl TermiconApp {
                                 .size(12.0)
                                 .color(Color32::from_rgb(100, 200, 255)));
                         } else {
-                            // Output lines - parse ANSI codes
-                            let spans = parse_ansi(&line.text);
-                            for span in spans {
+                            // Output lines - styled by the core terminal emulator on arrival
+                            for span in &line.styled.spans {
+                                let fg = match span.style.effective_fg() {
+                                    Color::Default if span.style.dim => Color32::from_rgb(140, 140, 140),
+                                    Color::Default => Color32::from_rgb(200, 200, 200),
+                                    color => color.to_egui(),
+                                };
                                 let mut text = RichText::new(&span.text)
                                     .monospace()
                                     .size(12.0)
-                                    .color(span.style.get_fg());
+                                    .color(fg);
 
                                 if span.style.bold {
                                     text = text.strong();
@@ -698,18 +850,31 @@ impl TermiconApp {
                                 }
 
                                 // Background color if set
-                                if span.style.bg_color.is_some() {
-                                    ui.label(text.background_color(span.style.get_bg()));
-                                } else {
-                                    ui.label(text);
+                                match span.style.effective_bg() {
+                                    Color::Default => {
+                                        ui.label(text);
+                                    }
+                                    bg => {
+                                        ui.label(text.background_color(bg.to_egui()));
+                                    }
                                 }
                             }
                         }
                     });
                 }
+                show_bookmarks(ui, line_number);
 
                 ui.add_space(8.0);
             });
+
+        if let Some(tab) = self.tabs.active_tab_mut() {
+            if load_older {
+                tab.load_older(OLDER_LINES_PAGE);
+            }
+            if hide_older {
+                tab.scrollback.release_older();
+            }
+        }
     }
 
     /// Render side panel with snippets, history, settings
@@ -912,6 +1077,8 @@ impl TermiconApp {
         } else {
             let mut snippet_to_insert: Option<String> = None;
             let mut snippet_to_delete: Option<usize> = None;
+            let mut on_connect_toggle: Option<String> = None;
+            let on_connect = self.profile_manager.get(&pid).and_then(|p| p.on_connect_snippet.clone());
 
             egui::ScrollArea::vertical()
                 .auto_shrink([false, false])
@@ -930,6 +1097,10 @@ impl TermiconApp {
                                     if ui.small_button("X").on_hover_text("Delete").clicked() {
                                         snippet_to_delete = Some(idx);
                                     }
+                                    let is_on_connect = on_connect.as_ref() == Some(&snippet.name);
+                                    if ui.selectable_label(is_on_connect, "auto").on_hover_text("Send after connecting").clicked() {
+                                        on_connect_toggle = Some(snippet.name.clone());
+                                    }
                                 });
                             });
                             ui.label(RichText::new(snippet.content.trim()).monospace().size(10.0).color(
@@ -958,6 +1129,14 @@ impl TermiconApp {
                 ui.ctx().request_repaint();
             }
 
+            // Toggle the on-connect snippet
+            if let Some(name) = on_connect_toggle {
+                if let Some(profile) = self.profile_manager.get_mut(&pid) {
+                    profile.on_connect_snippet = if on_connect.as_ref() == Some(&name) { None } else { Some(name) };
+                    self.profile_manager.save();
+                }
+            }
+
             // Delete snippet
             if let Some(idx) = snippet_to_delete {
                 if let Some(profile) = self.profile_manager.get_mut(&pid) {
@@ -979,6 +1158,13 @@ impl TermiconApp {
     /// Render history panel
     fn render_history_panel(&mut self, ui: &mut egui::Ui) {
         ui.heading(RichText::new("History").size(14.0));
+        let mut excluded = self.tabs.history_excluded();
+        if ui.checkbox(&mut excluded, "Don't save this session's history")
+            .on_hover_text("For sessions where secrets are typed inline")
+            .changed()
+        {
+            self.tabs.set_history_excluded(excluded);
+        }
         ui.add_space(8.0);
 
         // First collect the history items
@@ -1115,7 +1301,25 @@ impl TermiconApp {
                 ui.label(RichText::new("Terminal").strong());
                 ui.checkbox(&mut tab.show_timestamps, "Show Timestamps");
                 ui.checkbox(&mut tab.show_hex, "Hex View");
+                ui.checkbox(&mut tab.control_safe, "Show Control Characters");
                 ui.checkbox(&mut tab.local_echo, "Local Echo");
+
+                let label = |translation: NewlineTranslation| match translation {
+                    NewlineTranslation::None => "As received",
+                    NewlineTranslation::CrToCrLf => "CR → CR LF",
+                    NewlineTranslation::LfToCrLf => "LF → CR LF",
+                };
+                let mut translation = tab.newline_translation();
+                egui::ComboBox::from_label("Newlines")
+                    .selected_text(label(translation))
+                    .show_ui(ui, |ui| {
+                        for option in [NewlineTranslation::None, NewlineTranslation::CrToCrLf, NewlineTranslation::LfToCrLf] {
+                            ui.selectable_value(&mut translation, option, label(option));
+                        }
+                    });
+                if translation != tab.newline_translation() {
+                    tab.set_newline_translation(translation);
+                }
             });
         }
 
@@ -1283,8 +1487,21 @@ impl TermiconApp {
             match profile.profile_type {
                 ProfileType::Serial => {
                     if let Some(ref settings) = profile.serial {
+                        let port = match &settings.usb {
+                            Some(usb) => {
+                                self.available_ports = enumerate_ports();
+                                let Some(port) = usb.find_port(&self.available_ports, &settings.port) else {
+                                    self.status_message =
+                                        format!("USB adapter {:04X}:{:04X} not found", usb.vid, usb.pid);
+                                    self.active_profile_id = None;
+                                    return;
+                                };
+                                port
+                            }
+                            None => settings.port.clone(),
+                        };
                         self.serial_settings = SerialSettings {
-                            port: settings.port.clone(),
+                            port,
                             baud_rate: settings.baud_rate.to_string(),
                             data_bits: settings.data_bits.to_string(),
                             parity: settings.parity.clone(),
@@ -1363,6 +1580,14 @@ impl TermiconApp {
         }
     }
 
+    /// On-connect snippet of the profile being connected, if any
+    fn on_connect_payload(&self) -> Option<Vec<u8>> {
+        self.active_profile_id
+            .as_deref()
+            .and_then(|id| self.profile_manager.get(id))
+            .and_then(Profile::on_connect_payload)
+    }
+
     /// Show save profile dialog
     fn show_save_profile_dialog(&mut self, ctx: &egui::Context) {
         egui::Window::new("Save Profile")
@@ -1420,6 +1645,9 @@ impl TermiconApp {
                     parity: self.serial_settings.parity.clone(),
                     stop_bits: self.serial_settings.stop_bits.clone(),
                     flow_control: self.serial_settings.flow_control.clone(),
+                    usb: self.available_ports.iter()
+                        .find(|p| p.name == self.serial_settings.port)
+                        .and_then(UsbSelector::from_port),
                 })
             }
             Some(ProfileType::Tcp) | Some(ProfileType::Telnet) => {
@@ -1506,13 +1734,25 @@ impl TermiconApp {
                     response.request_focus();
                 }
 
-                // Handle Up/Down for history
+                // Keys for the remote are encoded per its cursor/keypad modes;
+                // otherwise Up/Down walk the history
                 if response.has_focus() {
-                    if ui.input(|i| i.key_pressed(egui::Key::ArrowUp)) {
-                        tab.history_up();
-                    }
-                    if ui.input(|i| i.key_pressed(egui::Key::ArrowDown)) {
-                        tab.history_down();
+                    let keys: Vec<(egui::Key, egui::Modifiers)> = ui.input(|i| {
+                        i.events.iter().filter_map(|e| match e {
+                            egui::Event::Key { key, pressed: true, modifiers, .. } => Some((*key, *modifiers)),
+                            _ => None,
+                        }).collect()
+                    });
+                    for (key, modifiers) in keys {
+                        if key == egui::Key::R && modifiers.ctrl {
+                            tab.history_reverse_search();
+                        } else if tab.passes_through(key) {
+                            tab.send_key(key, modifiers);
+                        } else if key == egui::Key::ArrowUp {
+                            tab.history_up();
+                        } else if key == egui::Key::ArrowDown {
+                            tab.history_down();
+                        }
                     }
                 }
 
@@ -1525,6 +1765,10 @@ impl TermiconApp {
             });
         }
         
+        if let Some(ref cmd) = command_sent {
+            self.tabs.record_history(cmd);
+        }
+
         // Record command to profile if connected from one
         if let (Some(pid), Some(cmd)) = (profile_id, command_sent) {
             self.profile_manager.record_command(&pid, &cmd);
@@ -1552,7 +1796,7 @@ impl TermiconApp {
                                 .width(160.0)
                                 .show_ui(ui, |ui| {
                                     for port in &self.available_ports {
-                                        ui.selectable_value(&mut self.serial_settings.port, port.clone(), port);
+                                        ui.selectable_value(&mut self.serial_settings.port, port.name.clone(), port.label());
                                     }
                                 });
                             if ui.small_button("R").on_hover_text("Refresh ports").clicked() {
@@ -3296,6 +3540,7 @@ fields:
         tab.add_line(&format!("Connecting to {} @ {} baud...", port, baud), false);
         // Set profile_id if connecting from a profile
         tab.profile_id = self.active_profile_id.clone();
+        tab.on_connect = self.on_connect_payload();
 
         let (tx_to_gui, rx_from_conn) = mpsc::channel::<ConnectionMessage>();
         let (tx_to_conn, rx_from_gui) = mpsc::channel::<ConnectionCommand>();
@@ -3371,6 +3616,7 @@ fields:
         tab.connection_info = format!("{}:{}", host, port);
         tab.add_line(&format!("Connecting to {}:{}...", host, port), false);
         tab.profile_id = self.active_profile_id.clone();
+        tab.on_connect = self.on_connect_payload();
 
         let (tx_to_gui, rx_from_conn) = mpsc::channel::<ConnectionMessage>();
         let (tx_to_conn, rx_from_gui) = mpsc::channel::<ConnectionCommand>();
@@ -3464,6 +3710,7 @@ fields:
         tab.connection_info = format!("ssh://{}@{}:{}", username, host, port);
         tab.add_line(&format!("Connecting to {}@{}:{} (SSH)...", username, host, port), false);
         tab.profile_id = self.active_profile_id.clone();
+        tab.on_connect = self.on_connect_payload();
 
         let (tx_to_gui, rx_from_conn) = mpsc::channel::<ConnectionMessage>();
         let (tx_to_conn, rx_from_gui) = mpsc::channel::<ConnectionCommand>();
@@ -3625,6 +3872,7 @@ fields:
         tab.add_line(&format!("Connecting to BLE device: {}...", device), false);
         tab.add_line(&format!("Service: {}", service_uuid), false);
         tab.profile_id = self.active_profile_id.clone();
+        tab.on_connect = self.on_connect_payload();
 
         let (tx_to_gui, rx_from_conn) = mpsc::channel::<ConnectionMessage>();
         let (tx_to_conn, rx_from_gui) = mpsc::channel::<ConnectionCommand>();
@@ -3656,6 +3904,7 @@ fields:
                     timeout_secs: 10,
                     auto_reconnect: false,
                     mtu: 512,
+                    channel: 0,
                 };
 
                 let mut transport = match BluetoothTransport::new(config).await {
@@ -3729,6 +3978,9 @@ impl eframe::App for TermiconApp {
             ctx.request_repaint();
         }
         
+        // Apply config file changes
+        self.sync_config(ctx);
+
         // Process all tabs
         self.tabs.process_all();
 
@@ -3801,6 +4053,18 @@ impl eframe::App for TermiconApp {
                             }
                             ui.close_menu();
                         }
+                        if ui.button(t!("menu.add_bookmark")).clicked() {
+                            if let Some(tab) = self.tabs.active_tab_mut() {
+                                let label = format!(
+                                    "{} {} ({})",
+                                    t!("menu.bookmark"),
+                                    tab.bookmarks.len() + 1,
+                                    chrono::Local::now().format("%H:%M:%S")
+                                );
+                                tab.add_bookmark(&label);
+                            }
+                            ui.close_menu();
+                        }
                     });
 
                     ui.menu_button(t!("menu.view"), |ui| {
@@ -4287,9 +4551,9 @@ impl eframe::App for TermiconApp {
                                     if let Some(ref tx) = tab.tx {
                                         let _ = tx.send(ConnectionCommand::Send(data.clone()));
                                         if tab.local_echo {
-                                            if let Ok(text) = String::from_utf8(data) {
-                                                tab.add_line(text.trim(), true);
-                                            }
+                                            // Show control bytes as ^X instead of dropping binary macros
+                                            let echoed = EchoPolicy::new(ControlCharEcho::Caret).render(&data);
+                                            tab.add_line(String::from_utf8_lossy(&echoed).trim(), true);
                                         }
                                     }
                                 }
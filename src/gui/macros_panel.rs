@@ -3,6 +3,14 @@
 use eframe::egui::{self, Color32, RichText, Ui};
 use termicon_core::core::macros::{MacroManager, MacroSlot, MacroContent, parse_hex_string, format_hex_bytes};
 
+/// How the edit dialog interprets the content field
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EditMode {
+    Text,
+    Hex,
+    Keys,
+}
+
 /// Macros panel state
 pub struct MacrosPanel {
     /// Macro manager
@@ -15,7 +23,7 @@ pub struct MacrosPanel {
     edit_name: String,
     edit_content: String,
     edit_description: String,
-    edit_hex_mode: bool,
+    edit_mode: EditMode,
     edit_append_crlf: bool,
     /// Is panel expanded
     pub expanded: bool,
@@ -30,7 +38,7 @@ impl Default for MacrosPanel {
             edit_name: String::new(),
             edit_content: String::new(),
             edit_description: String::new(),
-            edit_hex_mode: false,
+            edit_mode: EditMode::Text,
             edit_append_crlf: true,
             expanded: false,
         }
@@ -119,7 +127,7 @@ impl MacrosPanel {
                             ui.label(&desc);
                         }
                         match &content {
-                            MacroContent::Text(s) => {
+                            MacroContent::Text(s) | MacroContent::KeySequence(s) => {
                                 ui.label(RichText::new(s).monospace().size(10.0));
                             }
                             MacroContent::Hex(b) => {
@@ -174,7 +182,7 @@ impl MacrosPanel {
                             ui.label(&desc);
                         }
                         match &content {
-                            MacroContent::Text(s) => {
+                            MacroContent::Text(s) | MacroContent::KeySequence(s) => {
                                 ui.label(RichText::new(s).monospace().size(10.0));
                             }
                             MacroContent::Hex(b) => {
@@ -228,15 +236,19 @@ impl MacrosPanel {
         match &data.content {
             MacroContent::Text(s) => {
                 self.edit_content = s.clone();
-                self.edit_hex_mode = false;
+                self.edit_mode = EditMode::Text;
             }
             MacroContent::Hex(b) => {
                 self.edit_content = format_hex_bytes(b);
-                self.edit_hex_mode = true;
+                self.edit_mode = EditMode::Hex;
+            }
+            MacroContent::KeySequence(s) => {
+                self.edit_content = s.clone();
+                self.edit_mode = EditMode::Keys;
             }
             _ => {
                 self.edit_content = String::new();
-                self.edit_hex_mode = false;
+                self.edit_mode = EditMode::Text;
             }
         }
         
@@ -259,11 +271,17 @@ impl MacrosPanel {
                 });
 
                 ui.horizontal(|ui| {
-                    ui.checkbox(&mut self.edit_hex_mode, "Hex mode");
+                    ui.radio_value(&mut self.edit_mode, EditMode::Text, "Text");
+                    ui.radio_value(&mut self.edit_mode, EditMode::Hex, "Hex");
+                    ui.radio_value(&mut self.edit_mode, EditMode::Keys, "Keys");
                     ui.checkbox(&mut self.edit_append_crlf, "Append CR+LF");
                 });
 
-                ui.label(if self.edit_hex_mode { "Hex data:" } else { "Command:" });
+                ui.label(match self.edit_mode {
+                    EditMode::Text => "Command:",
+                    EditMode::Hex => "Hex data:",
+                    EditMode::Keys => "Key sequence:",
+                });
                 ui.add(
                     egui::TextEdit::multiline(&mut self.edit_content)
                         .desired_width(300.0)
@@ -271,8 +289,14 @@ impl MacrosPanel {
                         .font(egui::TextStyle::Monospace)
                 );
 
-                if self.edit_hex_mode {
-                    ui.label(RichText::new("Format: FF 00 A5 or FF00A5").size(10.0).color(Color32::GRAY));
+                match self.edit_mode {
+                    EditMode::Hex => {
+                        ui.label(RichText::new("Format: FF 00 A5 or FF00A5").size(10.0).color(Color32::GRAY));
+                    }
+                    EditMode::Keys => {
+                        ui.label(RichText::new("Format: {0x02}payload{0x03}, {ENTER}, {ESC}").size(10.0).color(Color32::GRAY));
+                    }
+                    EditMode::Text => {}
                 }
 
                 ui.add_space(10.0);
@@ -294,13 +318,13 @@ impl MacrosPanel {
     }
 
     fn save_macro(&mut self, profile_id: Option<&str>) {
-        let content = if self.edit_hex_mode {
-            match parse_hex_string(&self.edit_content) {
+        let content = match self.edit_mode {
+            EditMode::Hex => match parse_hex_string(&self.edit_content) {
                 Ok(bytes) => MacroContent::Hex(bytes),
                 Err(_) => MacroContent::Text(self.edit_content.clone()),
-            }
-        } else {
-            MacroContent::Text(self.edit_content.clone())
+            },
+            EditMode::Keys => MacroContent::KeySequence(self.edit_content.clone()),
+            EditMode::Text => MacroContent::Text(self.edit_content.clone()),
         };
 
         let set = self.manager.get_set_mut(profile_id);
@@ -1,7 +1,6 @@
 //! GUI module for the graphical user interface
 
 mod accessibility;
-mod ansi_parser;
 mod app;
 mod ble_panel;
 mod chart_panel;
@@ -10,6 +9,7 @@ mod font_config;
 mod keyboard;
 mod macros_panel;
 mod profiles;
+mod scrollback;
 mod session_tab;
 mod sftp_panel;
 mod split_view;
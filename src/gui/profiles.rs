@@ -8,8 +8,10 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use chrono::{DateTime, Utc};
+use termicon_core::core::profile::UsbSelector;
+use termicon_core::core::storage;
 
 /// Connection type for profiles
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Hash)]
@@ -89,6 +91,9 @@ pub struct SerialProfileSettings {
     pub parity: String,
     pub stop_bits: String,
     pub flow_control: String,
+    /// USB adapter to look the port up by, so the profile follows it across ports
+    #[serde(default)]
+    pub usb: Option<UsbSelector>,
 }
 
 /// TCP-specific settings
@@ -157,6 +162,9 @@ pub struct Profile {
     pub last_used: Option<DateTime<Utc>>,
     pub use_count: u32,
     pub snippets: Vec<ProfileSnippet>,
+    /// Name of the snippet sent right after connecting
+    #[serde(default)]
+    pub on_connect_snippet: Option<String>,
     pub favorite: bool,
     // Type-specific settings
     pub serial: Option<SerialProfileSettings>,
@@ -175,6 +183,7 @@ impl Profile {
             last_used: None,
             use_count: 0,
             snippets: Vec::new(),
+            on_connect_snippet: None,
             favorite: false,
             serial: None,
             tcp: None,
@@ -241,6 +250,19 @@ impl Profile {
         }
     }
 
+    /// Data to send once connected: each line of the on-connect snippet,
+    /// terminated like typed input
+    pub fn on_connect_payload(&self) -> Option<Vec<u8>> {
+        let name = self.on_connect_snippet.as_ref()?;
+        let snippet = self.snippets.iter().find(|s| &s.name == name)?;
+        let mut data = Vec::new();
+        for line in snippet.content.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            data.extend_from_slice(line.as_bytes());
+            data.push(b'\n');
+        }
+        Some(data).filter(|data| !data.is_empty())
+    }
+
     /// Get snippets sorted by usage count (most used first)
     pub fn sorted_snippets(&self) -> Vec<&ProfileSnippet> {
         let mut snippets: Vec<_> = self.snippets.iter().collect();
@@ -289,6 +311,17 @@ impl Profile {
     }
 }
 
+/// Upgrades of `gui_profiles.json`; v1 added the version field
+const GUI_PROFILE_MIGRATIONS: &[storage::Migration] = &[storage::add_version];
+
+/// Serialized form of [`ProfileManager`]
+#[derive(Serialize)]
+struct GuiProfileData<'a> {
+    /// Format version; files from before versioning are version 0
+    version: u32,
+    profiles: &'a [Profile],
+}
+
 /// Profile manager - handles saving/loading profiles
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct ProfileManager {
@@ -322,19 +355,40 @@ impl ProfileManager {
     }
 
     /// Get profiles file path
+    ///
+    /// `profiles.json` belongs to the core profile store, whose format
+    /// differs; this store keeps its own file.
     fn profiles_path() -> Option<PathBuf> {
-        Self::config_dir().map(|d| d.join("profiles.json"))
+        Self::config_dir().map(|d| d.join("gui_profiles.json"))
+    }
+
+    /// Move profiles that older versions saved as `profiles.json`
+    ///
+    /// Only a file in this store's format is moved, so the core store's
+    /// file stays where it is.
+    fn adopt_legacy_file(path: &Path) {
+        let legacy = path.with_file_name("profiles.json");
+        if path.exists() || !matches!(storage::read_json::<ProfileManager>(&legacy), Ok(Some(_))) {
+            return;
+        }
+        match fs::rename(&legacy, path) {
+            Ok(()) => tracing::info!("Moved GUI profiles from {} to {}", legacy.display(), path.display()),
+            Err(e) => tracing::warn!("Failed to move {}: {}", legacy.display(), e),
+        }
     }
 
     /// Load profiles from disk
+    ///
+    /// Older formats are upgraded (see [`storage::read_versioned`]). A file
+    /// that does not parse is kept as `gui_profiles.json.corrupt` instead of
+    /// being overwritten by the next save.
     pub fn load() -> Self {
         if let Some(path) = Self::profiles_path() {
-            if path.exists() {
-                if let Ok(content) = fs::read_to_string(&path) {
-                    if let Ok(manager) = serde_json::from_str::<ProfileManager>(&content) {
-                        return manager;
-                    }
-                }
+            Self::adopt_legacy_file(&path);
+            match storage::read_versioned::<ProfileManager>(&path, GUI_PROFILE_MIGRATIONS) {
+                Ok(Some(manager)) => return manager,
+                Ok(None) => {}
+                Err(e) => tracing::error!("Failed to load profiles: {}", e),
             }
         }
         Self::new()
@@ -343,8 +397,12 @@ impl ProfileManager {
     /// Save profiles to disk
     pub fn save(&self) {
         if let Some(path) = Self::profiles_path() {
-            if let Ok(content) = serde_json::to_string_pretty(self) {
-                let _ = fs::write(path, content);
+            let data = GuiProfileData {
+                version: GUI_PROFILE_MIGRATIONS.len() as u32,
+                profiles: &self.profiles,
+            };
+            if let Err(e) = storage::write_json(&path, &data) {
+                tracing::error!("Failed to save profiles: {}", e);
             }
         }
     }
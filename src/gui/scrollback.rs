@@ -0,0 +1,270 @@
+//! Output retention for session tabs
+//!
+//! Bounds how many output lines a tab keeps in memory. Lines past the limit
+//! are either dropped or, with spill-to-disk, appended to a temp file and
+//! paged back in when the user scrolls up.
+
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{self, BufWriter, Read, Seek, SeekFrom, Write};
+use std::ops::Range;
+
+use termicon_core::core::terminal::LineStyler;
+
+use super::session_tab::TerminalLine;
+
+/// Lines kept in memory when nothing else is configured
+pub const DEFAULT_RETAINED_LINES: usize = 50_000;
+
+/// Spilled lines paged in per request
+pub const OLDER_LINES_PAGE: usize = 1_000;
+
+/// What happens to lines past the in-memory limit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputRetention {
+    /// Keep at most this many lines; older lines are dropped
+    Limit(usize),
+    /// Keep `in_memory` lines and move older ones to a temp file
+    SpillToDisk { in_memory: usize },
+}
+
+impl Default for OutputRetention {
+    fn default() -> Self {
+        Self::Limit(DEFAULT_RETAINED_LINES)
+    }
+}
+
+impl OutputRetention {
+    /// Retention for the terminal settings
+    pub fn from_settings(lines: usize, spill_to_disk: bool) -> Self {
+        if spill_to_disk {
+            Self::SpillToDisk { in_memory: lines }
+        } else {
+            Self::Limit(lines)
+        }
+    }
+
+    /// Lines kept in memory
+    pub fn in_memory(&self) -> usize {
+        match *self {
+            Self::Limit(lines) | Self::SpillToDisk { in_memory: lines } => lines.max(1),
+        }
+    }
+}
+
+/// Spilled lines, one JSON object per line
+///
+/// The file is unlinked from the start and only readable by us, since it
+/// holds whatever the terminal showed, credentials included; the OS frees
+/// it once dropped.
+struct SpillFile {
+    writer: BufWriter<File>,
+    /// Start of each line in the file
+    offsets: Vec<u64>,
+    len: u64,
+}
+
+impl SpillFile {
+    fn create() -> io::Result<Self> {
+        let file = tempfile::tempfile()?;
+        Ok(Self {
+            writer: BufWriter::new(file),
+            offsets: Vec::new(),
+            len: 0,
+        })
+    }
+
+    fn append(&mut self, lines: &[TerminalLine]) -> io::Result<()> {
+        for line in lines {
+            let mut record = serde_json::to_vec(line)?;
+            record.push(b'\n');
+            self.writer.write_all(&record)?;
+            self.offsets.push(self.len);
+            self.len += record.len() as u64;
+        }
+        Ok(())
+    }
+
+    fn read(&mut self, range: Range<usize>) -> io::Result<Vec<TerminalLine>> {
+        if range.is_empty() {
+            return Ok(Vec::new());
+        }
+        let start = self.offsets[range.start];
+        let end = self.offsets.get(range.end).copied().unwrap_or(self.len);
+
+        self.writer.flush()?;
+        let file = self.writer.get_mut();
+        file.seek(SeekFrom::Start(start))?;
+        let mut data = vec![0; (end - start) as usize];
+        let read = file.read_exact(&mut data);
+        // Appends continue at the end whether or not the read worked
+        file.seek(SeekFrom::End(0))?;
+        read?;
+
+        data.split(|&b| b == b'\n')
+            .filter(|record| !record.is_empty())
+            .map(|record| serde_json::from_slice(record).map_err(io::Error::from))
+            .collect()
+    }
+}
+
+/// Lines a tab no longer keeps in its output buffer
+pub struct Scrollback {
+    retention: OutputRetention,
+    spill: Option<SpillFile>,
+    /// Spilled lines paged back in; they end where the output starts
+    older: Vec<TerminalLine>,
+    /// Lines ever evicted from the output, spilled or not
+    evicted: u64,
+}
+
+impl Scrollback {
+    /// Empty scrollback; nothing is written to disk until lines spill
+    pub fn new() -> Self {
+        Self {
+            retention: OutputRetention::default(),
+            spill: None,
+            older: Vec::new(),
+            evicted: 0,
+        }
+    }
+
+    /// Change the retention; lines already spilled stay retrievable
+    pub fn set_retention(&mut self, retention: OutputRetention, output: &mut VecDeque<TerminalLine>) {
+        self.retention = retention;
+        self.trim(output);
+    }
+
+    /// Evict the oldest lines of `output` past the in-memory limit
+    pub fn trim(&mut self, output: &mut VecDeque<TerminalLine>) {
+        let excess = output.len().saturating_sub(self.retention.in_memory());
+        if excess == 0 {
+            return;
+        }
+        let evicted: Vec<TerminalLine> = output.drain(..excess).collect();
+        self.evicted += evicted.len() as u64;
+        if let OutputRetention::Limit(_) = self.retention {
+            return;
+        }
+        if let Err(e) = self.spill(&evicted) {
+            tracing::warn!("Failed to spill output to disk: {}", e);
+            return;
+        }
+        if !self.older.is_empty() {
+            self.older.extend(evicted);
+        }
+    }
+
+    fn spill(&mut self, lines: &[TerminalLine]) -> io::Result<()> {
+        if self.spill.is_none() {
+            self.spill = Some(SpillFile::create()?);
+        }
+        self.spill.as_mut().map_or(Ok(()), |spill| spill.append(lines))
+    }
+
+    /// Lines ever evicted from the output; the output's first line has this number
+    pub fn evicted(&self) -> u64 {
+        self.evicted
+    }
+
+    /// Lines written to the spill file
+    pub fn spilled(&self) -> usize {
+        self.spill.as_ref().map_or(0, |spill| spill.offsets.len())
+    }
+
+    /// Spilled lines paged back in, oldest first
+    pub fn older(&self) -> &[TerminalLine] {
+        &self.older
+    }
+
+    /// Check if spilled lines remain that are not paged in
+    pub fn has_older(&self) -> bool {
+        self.spilled() > self.older.len()
+    }
+
+    /// Page in up to `count` more spilled lines; returns how many were read
+    pub fn load_older(&mut self, count: usize) -> io::Result<usize> {
+        let loaded = self.older.len();
+        let Some(spill) = self.spill.as_mut() else {
+            return Ok(0);
+        };
+        let end = spill.offsets.len() - loaded;
+        let start = end.saturating_sub(count);
+        let mut lines = spill.read(start..end)?;
+        let mut styler = LineStyler::new();
+        for line in &mut lines {
+            line.restyle(&mut styler);
+        }
+        let read = lines.len();
+        lines.append(&mut self.older);
+        self.older = lines;
+        Ok(read)
+    }
+
+    /// Drop paged-in lines from memory; they stay on disk
+    pub fn release_older(&mut self) {
+        self.older = Vec::new();
+    }
+
+    /// Forget everything, deleting the spill file
+    pub fn clear(&mut self) {
+        self.spill = None;
+        self.older = Vec::new();
+        self.evicted = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(n: usize) -> TerminalLine {
+        TerminalLine {
+            text: format!("line {}", n),
+            timestamp: String::new(),
+            is_input: false,
+            raw_bytes: None,
+            styled: Default::default(),
+        }
+    }
+
+    fn texts(lines: &[TerminalLine]) -> Vec<&str> {
+        lines.iter().map(|line| line.text.as_str()).collect()
+    }
+
+    #[test]
+    fn test_limit_drops_oldest() {
+        let mut scrollback = Scrollback::new();
+        let mut output: VecDeque<TerminalLine> = (0..10).map(line).collect();
+        scrollback.set_retention(OutputRetention::Limit(4), &mut output);
+        assert_eq!(output.len(), 4);
+        assert_eq!(output[0].text, "line 6");
+        assert_eq!(scrollback.evicted(), 6);
+        assert_eq!(scrollback.spilled(), 0);
+        assert!(!scrollback.has_older());
+    }
+
+    #[test]
+    fn test_spill_pages_back_in() {
+        let mut scrollback = Scrollback::new();
+        let mut output: VecDeque<TerminalLine> = (0..10).map(line).collect();
+        scrollback.set_retention(OutputRetention::SpillToDisk { in_memory: 3 }, &mut output);
+        assert_eq!(scrollback.spilled(), 7);
+
+        assert_eq!(scrollback.load_older(2).unwrap(), 2);
+        assert_eq!(texts(scrollback.older()), ["line 5", "line 6"]);
+        assert_eq!(scrollback.load_older(3).unwrap(), 3);
+        assert_eq!(texts(scrollback.older()), ["line 2", "line 3", "line 4", "line 5", "line 6"]);
+
+        // Lines spilled while older ones are shown stay contiguous
+        output.push_back(line(10));
+        scrollback.trim(&mut output);
+        assert_eq!(scrollback.older().last().unwrap().text, "line 7");
+        assert_eq!(scrollback.load_older(100).unwrap(), 2);
+        assert!(!scrollback.has_older());
+
+        scrollback.clear();
+        assert_eq!(scrollback.spilled(), 0);
+        assert_eq!(scrollback.load_older(10).unwrap(), 0);
+    }
+}
@@ -1,11 +1,18 @@
 //! Session tab management for multiple connections
 
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
 use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
 use std::thread;
+use termicon_core::core::codec::{split_long_line, BinaryDetector, Codec, TextCodec, Utf8Stream, DEFAULT_MAX_LINE_LEN};
+use termicon_core::core::history::HistoryStore;
+use termicon_core::core::terminal::{Key, KeyModifiers, LineStyler, NewlineTranslation, StyledLine, Terminal};
+use termicon_core::Notifier;
 use uuid::Uuid;
 
-use super::app::{ConnectionCommand, ConnectionMessage, ConnectionState, ConnectionType};
+use super::app::{ConnectionCommand, ConnectionMessage, ConnectionState, ConnectionType, ViewMode};
+use super::scrollback::{OutputRetention, Scrollback};
 
 /// A single session/tab
 pub struct SessionTab {
@@ -17,14 +24,20 @@ pub struct SessionTab {
     pub conn_type: ConnectionType,
     /// Connection state
     pub state: ConnectionState,
-    /// Terminal output lines
-    pub output: Vec<TerminalLine>,
+    /// Terminal output lines held in memory
+    pub output: VecDeque<TerminalLine>,
+    /// Lines evicted from `output`
+    pub scrollback: Scrollback,
     /// Input history
     pub input_history: Vec<String>,
     /// Current input
     pub current_input: String,
     /// History index
     pub history_index: Option<usize>,
+    /// Prefix of an active reverse-i-search (Ctrl-R)
+    pub history_search: Option<String>,
+    /// Persistent history was merged into `input_history`
+    pub history_loaded: bool,
     /// Channel to send commands
     pub tx: Option<Sender<ConnectionCommand>>,
     /// Channel to receive messages
@@ -41,6 +54,12 @@ pub struct SessionTab {
     pub show_timestamps: bool,
     /// Show hex
     pub show_hex: bool,
+    /// Show control characters as `^C`/`\x1b` instead of interpreting them
+    pub control_safe: bool,
+    /// Received bytes held back until a split UTF-8 character is complete
+    utf8: Utf8Stream,
+    /// Received text ended in a CR shown as a line break
+    after_cr: bool,
     /// Search query
     pub search_query: String,
     /// Search results (line indices)
@@ -55,28 +74,65 @@ pub struct SessionTab {
     pub just_connected: bool,
     /// Associated profile ID (if connected from a profile)
     pub profile_id: Option<String>,
+    /// Sent once the connection is up (the profile's on-connect snippet)
+    pub on_connect: Option<Vec<u8>>,
+    /// Emulator fed with received data, tracks the remote's keyboard modes
+    pub terminal: Terminal,
+    /// Styles output lines as they arrive
+    pub styler: LineStyler,
+    /// Longest line shown before it is broken (0 = no limit)
+    pub max_line_len: usize,
+    /// Flags binary data arriving while the text view is shown
+    pub binary_detector: BinaryDetector,
+    /// Markers in the output, ordered by line
+    pub bookmarks: Vec<OutputBookmark>,
+}
+
+/// Named marker between two output lines
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputBookmark {
+    /// Label shown in the output
+    pub label: String,
+    /// Lines (counted since the output was last cleared) before the marker
+    pub line: u64,
 }
 
 /// Terminal line with metadata
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TerminalLine {
     pub text: String,
     pub timestamp: String,
     pub is_input: bool,
     pub raw_bytes: Option<Vec<u8>>,
+    /// Output text styled on arrival; not kept in spill files
+    #[serde(skip)]
+    pub styled: StyledLine,
+}
+
+impl TerminalLine {
+    /// Style the text of an output line; input lines are drawn plain
+    pub fn restyle(&mut self, styler: &mut LineStyler) {
+        if !self.is_input {
+            self.styled = styler.style(&self.text);
+        }
+    }
 }
 
 impl Default for SessionTab {
     fn default() -> Self {
+        let id = Uuid::new_v4().to_string();
         Self {
-            id: Uuid::new_v4().to_string(),
+            scrollback: Scrollback::new(),
+            id,
             name: "New Tab".to_string(),
             conn_type: ConnectionType::Serial,
             state: ConnectionState::Disconnected,
-            output: Vec::new(),
+            output: VecDeque::new(),
             input_history: Vec::new(),
             current_input: String::new(),
             history_index: None,
+            history_search: None,
+            history_loaded: false,
             tx: None,
             rx: None,
             connection_info: String::new(),
@@ -85,6 +141,9 @@ impl Default for SessionTab {
             local_echo: true,
             show_timestamps: true,
             show_hex: false,
+            control_safe: false,
+            utf8: Utf8Stream::new(),
+            after_cr: false,
             search_query: String::new(),
             search_results: Vec::new(),
             search_index: 0,
@@ -92,6 +151,12 @@ impl Default for SessionTab {
             search_case_sensitive: false,
             just_connected: false,
             profile_id: None,
+            on_connect: None,
+            terminal: Terminal::new(),
+            styler: LineStyler::new(),
+            max_line_len: DEFAULT_MAX_LINE_LEN,
+            binary_detector: BinaryDetector::default(),
+            bookmarks: Vec::new(),
         }
     }
 }
@@ -109,42 +174,107 @@ impl SessionTab {
     /// Add a line to output
     pub fn add_line(&mut self, text: &str, is_input: bool) {
         let timestamp = chrono::Local::now().format("%H:%M:%S%.3f").to_string();
-        self.output.push(TerminalLine {
+        let mut line = TerminalLine {
             text: text.to_string(),
             timestamp,
             is_input,
             raw_bytes: None,
-        });
-        
-        // Limit buffer
-        while self.output.len() > 50000 {
-            self.output.remove(0);
-        }
+            styled: StyledLine::default(),
+        };
+        line.restyle(&mut self.styler);
+        self.output.push_back(line);
+        self.scrollback.trim(&mut self.output);
     }
 
     /// Add raw bytes to output
     pub fn add_bytes(&mut self, data: &[u8], is_input: bool) {
         let timestamp = chrono::Local::now().format("%H:%M:%S%.3f").to_string();
-        let text = String::from_utf8_lossy(data).to_string();
+        let complete = if is_input { data.to_vec() } else { self.utf8.push(data) };
+        let mut text = if self.control_safe && !is_input {
+            TextCodec::safe().encode(&complete)
+        } else {
+            String::from_utf8_lossy(&complete).to_string()
+        };
+        if self.terminal.newline_translation() == NewlineTranslation::CrToCrLf {
+            // The LF of a CR LF split across reads was already applied
+            if !is_input && !text.is_empty() {
+                if std::mem::take(&mut self.after_cr) && text.starts_with('\n') {
+                    text.remove(0);
+                }
+                self.after_cr = text.ends_with('\r');
+            }
+            text = text.replace("\r\n", "\n").replace('\r', "\n");
+        }
+        if !is_input && !self.show_hex {
+            if let Some(ratio) = self.binary_detector.check(data) {
+                self.add_line(
+                    &format!("[Binary data detected ({:.0}% printable); consider Hex View]", ratio * 100.0),
+                    false,
+                );
+            }
+        }
         
-        // Split by newlines
+        // Split by newlines, breaking runaway lines so rendering stays fast
         for line in text.lines() {
-            if !line.is_empty() {
-                self.output.push(TerminalLine {
-                    text: line.to_string(),
-                    timestamp: timestamp.clone(),
-                    is_input,
-                    raw_bytes: Some(line.as_bytes().to_vec()),
-                });
+            for piece in split_long_line(line, self.max_line_len) {
+                if !piece.is_empty() {
+                    let mut line = TerminalLine {
+                        text: piece.to_string(),
+                        timestamp: timestamp.clone(),
+                        is_input,
+                        raw_bytes: Some(piece.as_bytes().to_vec()),
+                        styled: StyledLine::default(),
+                    };
+                    line.restyle(&mut self.styler);
+                    self.output.push_back(line);
+                }
             }
         }
-        
-        // Limit buffer
-        while self.output.len() > 50000 {
-            self.output.remove(0);
+        self.scrollback.trim(&mut self.output);
+    }
+
+    /// Show bytes still held back for an incomplete character
+    fn flush_pending(&mut self) {
+        let rest = self.utf8.flush();
+        if !rest.is_empty() {
+            let text = if self.control_safe {
+                TextCodec::safe().encode(&rest)
+            } else {
+                String::from_utf8_lossy(&rest).to_string()
+            };
+            self.add_line(&text, false);
+        }
+    }
+
+    /// Apply a view mode: hex for Hex/Mixed, control notation for TextSafe
+    pub fn set_view_mode(&mut self, mode: ViewMode) {
+        self.show_hex = matches!(mode, ViewMode::Hex | ViewMode::Mixed);
+        self.control_safe = mode == ViewMode::TextSafe;
+    }
+
+    /// Set how many lines are kept in memory and what happens to older ones
+    pub fn set_retention(&mut self, retention: OutputRetention) {
+        self.scrollback.set_retention(retention, &mut self.output);
+    }
+
+    /// Page in up to `count` older lines from the spill file
+    pub fn load_older(&mut self, count: usize) {
+        if let Err(e) = self.scrollback.load_older(count) {
+            self.add_line(&format!("[Failed to read older lines: {}]", e), false);
         }
     }
 
+    /// Newline normalization applied to received data on screen
+    pub fn newline_translation(&self) -> NewlineTranslation {
+        self.terminal.newline_translation()
+    }
+
+    /// Set the display newline normalization; logs keep the raw bytes
+    pub fn set_newline_translation(&mut self, translation: NewlineTranslation) {
+        self.terminal.set_newline_translation(translation);
+        self.after_cr = false;
+    }
+
     /// Send data to connection
     pub fn send(&mut self, data: &[u8]) {
         if let Some(ref tx) = self.tx {
@@ -152,6 +282,29 @@ impl SessionTab {
         }
     }
 
+    /// Whether a key press goes straight to the remote instead of the input line
+    pub fn passes_through(&self, key: egui::Key) -> bool {
+        match Key::from_egui(key) {
+            Some(Key::F(_)) => true,
+            // A full-screen application owns navigation keys
+            Some(Key::Up | Key::Down | Key::Left | Key::Right | Key::Home | Key::End
+                | Key::PageUp | Key::PageDown | Key::Escape) => {
+                self.terminal.app_cursor_keys() || self.terminal.is_alt_screen()
+            }
+            _ => false,
+        }
+    }
+
+    /// Send a key press encoded for the remote's cursor/keypad modes
+    pub fn send_key(&mut self, key: egui::Key, modifiers: egui::Modifiers) {
+        let Some(key) = Key::from_egui(key) else {
+            return;
+        };
+        if let Some(bytes) = self.terminal.encode_key(key, KeyModifiers::from(modifiers)) {
+            self.send(&bytes);
+        }
+    }
+
     /// Send input with newline
     pub fn send_input(&mut self) {
         if self.current_input.is_empty() {
@@ -166,6 +319,7 @@ impl SessionTab {
             self.input_history.push(text.clone());
         }
         self.history_index = None;
+        self.history_search = None;
 
         // Local echo
         if self.local_echo {
@@ -213,6 +367,25 @@ impl SessionTab {
         }
     }
 
+    /// Key for the persistent history: the profile, else the connection
+    pub fn history_key(&self) -> String {
+        self.profile_id.clone().unwrap_or_else(|| self.connection_info.clone())
+    }
+
+    /// Reverse-i-search: replace the input with the next older command
+    /// starting with the text typed before the first Ctrl-R
+    pub fn history_reverse_search(&mut self) {
+        let prefix = self
+            .history_search
+            .get_or_insert_with(|| self.current_input.clone())
+            .clone();
+        let start = self.history_index.unwrap_or(self.input_history.len());
+        if let Some(idx) = (0..start).rev().find(|&i| self.input_history[i].starts_with(&prefix)) {
+            self.history_index = Some(idx);
+            self.current_input = self.input_history[idx].clone();
+        }
+    }
+
     /// Process incoming messages
     pub fn process_messages(&mut self) {
         let messages: Vec<ConnectionMessage> = if let Some(ref rx) = self.rx {
@@ -236,18 +409,28 @@ impl SessionTab {
                     if self.profile_id.is_none() {
                         self.just_connected = true;
                     }
+                    if let Some(data) = self.on_connect.take() {
+                        self.send(&data);
+                    }
                 }
                 ConnectionMessage::Disconnected => {
+                    self.flush_pending();
                     self.state = ConnectionState::Disconnected;
                     self.add_line("Disconnected.", false);
                     should_clear = true;
                 }
                 ConnectionMessage::Error(e) => {
+                    self.flush_pending();
                     self.add_line(&format!("Error: {}", e), false);
                     self.state = ConnectionState::Disconnected;
                     should_clear = true;
                 }
                 ConnectionMessage::Data(data) => {
+                    self.terminal.process(&data);
+                    let responses = self.terminal.take_responses();
+                    if !responses.is_empty() {
+                        self.send(&responses);
+                    }
                     self.add_bytes(&data, false);
                     self.has_unread = true;
                 }
@@ -271,6 +454,28 @@ impl SessionTab {
     /// Clear output
     pub fn clear(&mut self) {
         self.output.clear();
+        self.scrollback.clear();
+        self.bookmarks.clear();
+    }
+
+    /// Mark the end of the output with `label`
+    ///
+    /// The marker stays after the current last line as more output arrives.
+    pub fn add_bookmark(&mut self, label: &str) {
+        self.bookmarks.push(OutputBookmark {
+            label: label.to_string(),
+            line: self.scrollback.evicted() + self.output.len() as u64,
+        });
+    }
+
+    /// Line number of the first shown row (paged-in older lines, then the output)
+    pub fn first_shown_line(&self) -> u64 {
+        self.scrollback.evicted().saturating_sub(self.scrollback.older().len() as u64)
+    }
+
+    /// Bookmarks shown before the row with line number `line`
+    pub fn bookmarks_before(&self, line: u64) -> impl Iterator<Item = &OutputBookmark> {
+        self.bookmarks.iter().filter(move |bookmark| bookmark.line == line)
     }
 
     /// Get hex view of a line
@@ -399,6 +604,14 @@ pub struct TabManager {
     pub tabs: Vec<SessionTab>,
     /// Active tab index
     pub active_index: usize,
+    /// Where terminal bells are reported
+    pub notifier: Option<Arc<Notifier>>,
+    /// Persistent command history shared by all tabs
+    pub history: Option<HistoryStore>,
+    /// Output retention applied to every tab
+    pub retention: OutputRetention,
+    /// View mode new tabs start in
+    pub view_mode: ViewMode,
 }
 
 impl Default for TabManager {
@@ -406,6 +619,10 @@ impl Default for TabManager {
         Self {
             tabs: Vec::new(),
             active_index: 0,
+            notifier: None,
+            history: None,
+            retention: OutputRetention::default(),
+            view_mode: ViewMode::Text,
         }
     }
 }
@@ -417,11 +634,21 @@ impl TabManager {
     }
 
     /// Add a new tab
-    pub fn add_tab(&mut self, tab: SessionTab) -> usize {
+    pub fn add_tab(&mut self, mut tab: SessionTab) -> usize {
+        tab.set_retention(self.retention);
+        tab.set_view_mode(self.view_mode);
         self.tabs.push(tab);
         self.tabs.len() - 1
     }
 
+    /// Change the output retention of all tabs
+    pub fn set_retention(&mut self, retention: OutputRetention) {
+        self.retention = retention;
+        for tab in &mut self.tabs {
+            tab.set_retention(retention);
+        }
+    }
+
     /// Remove tab by index
     pub fn remove_tab(&mut self, index: usize) {
         if index < self.tabs.len() {
@@ -463,6 +690,50 @@ impl TabManager {
     pub fn process_all(&mut self) {
         for tab in &mut self.tabs {
             tab.process_messages();
+            if !tab.history_loaded && tab.state == ConnectionState::Connected {
+                tab.history_loaded = true;
+                if let Some(ref history) = self.history {
+                    let mut saved = history.entries(&tab.history_key());
+                    saved.append(&mut tab.input_history);
+                    tab.input_history = saved;
+                }
+            }
+            if tab.terminal.take_bell() {
+                if let Some(ref notifier) = self.notifier {
+                    notifier.notify(&tab.name, "Bell");
+                }
+            }
+        }
+    }
+
+    /// Record a command sent from the active tab in the persistent history
+    pub fn record_history(&mut self, command: &str) {
+        let Some(key) = self.active_tab().map(|t| t.history_key()) else {
+            return;
+        };
+        if let Some(ref mut history) = self.history {
+            if let Err(e) = history.append(&key, command) {
+                tracing::warn!("Failed to save history: {}", e);
+            }
+        }
+    }
+
+    /// Whether the active tab's commands are kept out of the history
+    pub fn history_excluded(&self) -> bool {
+        match (self.active_tab(), &self.history) {
+            (Some(tab), Some(history)) => history.is_excluded(&tab.history_key()),
+            _ => false,
+        }
+    }
+
+    /// Keep (or stop keeping) the active tab's commands out of the history
+    pub fn set_history_excluded(&mut self, excluded: bool) {
+        let Some(key) = self.active_tab().map(|t| t.history_key()) else {
+            return;
+        };
+        if let Some(ref mut history) = self.history {
+            history.set_excluded(&key, excluded);
+            let _ = history.save();
         }
     }
 
@@ -5,6 +5,7 @@
 
 use egui::{self, Rect, Pos2, Color32, Stroke, CursorIcon, StrokeKind};
 use std::collections::HashMap;
+use termicon_core::core::workspace::{self, SplitContent, SplitLayout};
 
 /// Split orientation
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -505,6 +506,83 @@ impl SplitView {
         }
         map
     }
+    
+    /// Capture the layout for a workspace (None = single pane)
+    ///
+    /// `session_key` maps a pane's session index to the workspace session ID.
+    pub fn to_layout(&self, session_key: impl Fn(usize) -> Option<String> + Copy) -> Option<SplitLayout> {
+        fn content(node: &SplitNode, session_key: impl Fn(usize) -> Option<String> + Copy) -> SplitContent {
+            match node {
+                SplitNode::Leaf(pane) => pane
+                    .session_id
+                    .and_then(session_key)
+                    .map_or(SplitContent::Empty, SplitContent::Session),
+                SplitNode::Split { .. } => match layout(node, session_key) {
+                    Some(split) => SplitContent::Split(Box::new(split)),
+                    None => SplitContent::Empty,
+                },
+            }
+        }
+        
+        fn layout(node: &SplitNode, session_key: impl Fn(usize) -> Option<String> + Copy) -> Option<SplitLayout> {
+            let SplitNode::Split { orientation, ratio, first, second } = node else {
+                return None;
+            };
+            let orientation = match orientation {
+                SplitOrientation::Horizontal => workspace::SplitOrientation::Horizontal,
+                SplitOrientation::Vertical => workspace::SplitOrientation::Vertical,
+            };
+            Some(SplitLayout::new(
+                orientation,
+                *ratio,
+                content(first, session_key),
+                content(second, session_key),
+            ))
+        }
+        
+        layout(&self.root, session_key)
+    }
+    
+    /// Rebuild a split view from a saved workspace layout
+    ///
+    /// `session_index` maps a workspace session ID to the open session index;
+    /// panes whose session is gone come back empty.
+    pub fn from_layout(layout: Option<&SplitLayout>, session_index: impl Fn(&str) -> Option<usize> + Copy) -> Self {
+        let mut view = Self::new();
+        let Some(layout) = layout else {
+            return view;
+        };
+        
+        fn build(
+            view: &mut SplitView,
+            layout: &SplitLayout,
+            session_index: impl Fn(&str) -> Option<usize> + Copy,
+        ) -> SplitNode {
+            let mut child = |content: &SplitContent| match content {
+                SplitContent::Split(split) => build(view, split, session_index),
+                SplitContent::Session(id) => {
+                    let mut pane = Pane::new(view.allocate_pane_id());
+                    pane.session_id = session_index(id);
+                    SplitNode::leaf(pane)
+                }
+                SplitContent::Empty => SplitNode::leaf(Pane::new(view.allocate_pane_id())),
+            };
+            let first = child(&layout.first);
+            let second = child(&layout.second);
+            match layout.orientation {
+                workspace::SplitOrientation::Horizontal => SplitNode::horizontal(first, second, layout.ratio),
+                workspace::SplitOrientation::Vertical => SplitNode::vertical(first, second, layout.ratio),
+            }
+        }
+        
+        view.next_pane_id = 0;
+        let root = build(&mut view, layout, session_index);
+        view.root = root;
+        if let Some(id) = view.root.get_panes().first().map(|pane| pane.id) {
+            view.root.set_focus(id);
+        }
+        view
+    }
 }
 
 /// Preset split layouts
@@ -614,5 +692,24 @@ mod tests {
         let grid = layouts::grid_2x2();
         assert_eq!(grid.root.pane_count(), 4);
     }
+    
+    #[test]
+    fn test_workspace_layout_round_trip() {
+        let mut view = layouts::two_horizontal();
+        view.assign_session(0, 3);
+        view.assign_session(1, 7);
+        
+        let key = |index: usize| Some(format!("session-{}", index));
+        let layout = view.to_layout(key).unwrap();
+        assert_eq!(layout.sessions(), ["session-3", "session-7"]);
+        
+        let restored = SplitView::from_layout(Some(&layout), |id: &str| id.strip_prefix("session-")?.parse().ok());
+        assert_eq!(restored.root.pane_count(), 2);
+        let sessions: Vec<_> = restored.root.get_panes().iter().map(|pane| pane.session_id).collect();
+        assert_eq!(sessions, [Some(3), Some(7)]);
+        assert_eq!(restored.to_layout(key), Some(layout));
+        
+        assert!(layouts::single().to_layout(key).is_none());
+    }
 }
 
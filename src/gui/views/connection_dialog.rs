@@ -1,7 +1,7 @@
 //! Connection dialog component
 
 use crate::core::transport::{
-    SerialConfig, SerialFlowControl, SerialParity, TcpConfig, TelnetConfig, Transport,
+    enumerate_ports, PortInfo, SerialConfig, SerialFlowControl, SerialParity, TcpConfig, TelnetConfig, Transport,
 };
 use crate::i18n::t;
 use egui::{ComboBox, Grid, Ui};
@@ -26,7 +26,7 @@ pub struct ConnectionDialog {
     /// Telnet settings
     telnet: TelnetDialogState,
     /// Available serial ports
-    available_ports: Vec<String>,
+    available_ports: Vec<PortInfo>,
 }
 
 /// Serial port dialog state
@@ -57,11 +57,9 @@ impl ConnectionDialog {
     /// Create a new connection dialog
     pub fn new() -> Self {
         // Get available serial ports
-        let available_ports = serialport::available_ports()
-            .map(|ports| ports.into_iter().map(|p| p.port_name).collect())
-            .unwrap_or_default();
+        let available_ports = enumerate_ports();
 
-        let default_port = available_ports.first().cloned().unwrap_or_default();
+        let default_port = available_ports.first().map(|p| p.name.clone()).unwrap_or_default();
 
         Self {
             connection_type: ConnectionType::Serial,
@@ -89,9 +87,7 @@ impl ConnectionDialog {
 
     /// Refresh available serial ports
     pub fn refresh_ports(&mut self) {
-        self.available_ports = serialport::available_ports()
-            .map(|ports| ports.into_iter().map(|p| p.port_name).collect())
-            .unwrap_or_default();
+        self.available_ports = enumerate_ports();
     }
 
     /// Show the dialog UI
@@ -148,7 +144,7 @@ impl ConnectionDialog {
                         .selected_text(&self.serial.port)
                         .show_ui(ui, |ui| {
                             for port in &self.available_ports {
-                                ui.selectable_value(&mut self.serial.port, port.clone(), port);
+                                ui.selectable_value(&mut self.serial.port, port.name.clone(), port.label());
                             }
                         });
 
@@ -208,6 +204,8 @@ impl ConnectionDialog {
                         SerialParity::None => t("serial.parity_none"),
                         SerialParity::Odd => t("serial.parity_odd"),
                         SerialParity::Even => t("serial.parity_even"),
+                        SerialParity::Mark => t("serial.parity_mark"),
+                        SerialParity::Space => t("serial.parity_space"),
                     })
                     .show_ui(ui, |ui| {
                         ui.selectable_value(
@@ -225,6 +223,16 @@ impl ConnectionDialog {
                             SerialParity::Even,
                             t("serial.parity_even"),
                         );
+                        ui.selectable_value(
+                            &mut self.serial.parity,
+                            SerialParity::Mark,
+                            t("serial.parity_mark"),
+                        );
+                        ui.selectable_value(
+                            &mut self.serial.parity,
+                            SerialParity::Space,
+                            t("serial.parity_space"),
+                        );
                     });
                 ui.end_row();
 
@@ -312,6 +320,7 @@ impl ConnectionDialog {
                     parity: self.serial.parity,
                     flow_control: self.serial.flow_control,
                     auto_reconnect: self.serial.auto_reconnect,
+                    ..SerialConfig::default()
                 }))
             }
             ConnectionType::Tcp => {
@@ -322,6 +331,7 @@ impl ConnectionDialog {
                     host: self.tcp.host.clone(),
                     port,
                     timeout_secs: timeout,
+                    tls: None,
                 }))
             }
             ConnectionType::Telnet => {
@@ -331,6 +341,8 @@ impl ConnectionDialog {
                     host: self.telnet.host.clone(),
                     port,
                     terminal_type: "xterm".to_string(),
+                    binary_mode: false,
+                    tls: None,
                 }))
             }
         }
@@ -77,12 +77,12 @@ impl SettingsView {
                 // Theme
                 ui.label("Theme");
                 ComboBox::from_id_salt("theme")
-                    .selected_text(&config.window.theme)
+                    .selected_text(&config.theme)
                     .show_ui(ui, |ui| {
-                        ui.selectable_value(&mut config.window.theme, "dark".to_string(), "Dark");
-                        ui.selectable_value(&mut config.window.theme, "light".to_string(), "Light");
+                        ui.selectable_value(&mut config.theme, "dark".to_string(), "Dark");
+                        ui.selectable_value(&mut config.theme, "light".to_string(), "Light");
                         ui.selectable_value(
-                            &mut config.window.theme,
+                            &mut config.theme,
                             "high_contrast".to_string(),
                             "High Contrast",
                         );
@@ -125,24 +125,24 @@ impl SettingsView {
                 // Line ending
                 ui.label(t("terminal.line_ending"));
                 ComboBox::from_id_salt("line_ending")
-                    .selected_text(match config.terminal.line_ending {
+                    .selected_text(match config.default_line_ending {
                         LineEnding::Cr => t("terminal.line_ending_cr"),
                         LineEnding::Lf => t("terminal.line_ending_lf"),
                         LineEnding::CrLf => t("terminal.line_ending_crlf"),
                     })
                     .show_ui(ui, |ui| {
                         ui.selectable_value(
-                            &mut config.terminal.line_ending,
+                            &mut config.default_line_ending,
                             LineEnding::Cr,
                             t("terminal.line_ending_cr"),
                         );
                         ui.selectable_value(
-                            &mut config.terminal.line_ending,
+                            &mut config.default_line_ending,
                             LineEnding::Lf,
                             t("terminal.line_ending_lf"),
                         );
                         ui.selectable_value(
-                            &mut config.terminal.line_ending,
+                            &mut config.default_line_ending,
                             LineEnding::CrLf,
                             t("terminal.line_ending_crlf"),
                         );
@@ -152,25 +152,31 @@ impl SettingsView {
                 // Display mode
                 ui.label("Default View");
                 ComboBox::from_id_salt("display_mode")
-                    .selected_text(match config.terminal.display_mode {
+                    .selected_text(match config.default_codec {
                         CodecType::Text => "Text",
+                        CodecType::TextSafe => "Text (safe)",
                         CodecType::Hex => "Hex",
                         CodecType::Mixed => "Mixed",
                         CodecType::Binary => "Binary",
                     })
                     .show_ui(ui, |ui| {
                         ui.selectable_value(
-                            &mut config.terminal.display_mode,
+                            &mut config.default_codec,
                             CodecType::Text,
                             "Text",
                         );
                         ui.selectable_value(
-                            &mut config.terminal.display_mode,
+                            &mut config.default_codec,
+                            CodecType::TextSafe,
+                            "Text (safe)",
+                        );
+                        ui.selectable_value(
+                            &mut config.default_codec,
                             CodecType::Hex,
                             "Hex",
                         );
                         ui.selectable_value(
-                            &mut config.terminal.display_mode,
+                            &mut config.default_codec,
                             CodecType::Mixed,
                             "Mixed",
                         );
@@ -182,6 +188,11 @@ impl SettingsView {
                 ui.add(Slider::new(&mut config.terminal.scroll_buffer, 1000..=100000));
                 ui.end_row();
 
+                // Spill older lines to disk
+                ui.label("Keep older lines on disk");
+                ui.checkbox(&mut config.terminal.spill_to_disk, "");
+                ui.end_row();
+
                 // Scroll on output
                 ui.label(t("terminal.scroll_on_output"));
                 ui.checkbox(&mut config.terminal.scroll_on_output, "");
@@ -191,6 +202,18 @@ impl SettingsView {
                 ui.label(t("log.timestamps"));
                 ui.checkbox(&mut config.terminal.show_timestamps, "");
                 ui.end_row();
+
+                // Bell notifications
+                ui.label("Notify on bell");
+                ui.checkbox(&mut config.terminal.bell_notifications, "");
+                ui.end_row();
+
+                ui.label("Bell notification interval (s)");
+                ui.add_enabled(
+                    config.terminal.bell_notifications,
+                    Slider::new(&mut config.terminal.bell_interval_secs, 0..=60),
+                );
+                ui.end_row();
             });
     }
 
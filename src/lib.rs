@@ -30,8 +30,8 @@
 //!     
 //!     session.send(b"AT\r\n").await?;
 //!     
-//!     let mut rx = session.subscribe();
-//!     while let Ok(event) = rx.recv().await {
+//!     let mut rx = session.subscribe_events();
+//!     while let Some(event) = rx.recv().await {
 //!         if let termicon_core::SessionEvent::DataReceived(data) = event {
 //!             println!("Received: {:?}", data);
 //!         }
@@ -59,15 +59,16 @@ pub mod utils;
 pub use crate::cli::{CliResult, ExitCodes, PipeMode, OutputFormat};
 pub use crate::config::{AppConfig, ConnectionProfile};
 pub use crate::core::codec::{Codec, CodecType};
-pub use crate::core::logger::{LogEntry, Logger, LogFormat};
-pub use crate::core::session::{Session, SessionEvent, SessionState};
+pub use crate::core::logger::{LogEntry, LogRedactor, Logger, LogFormat, TimestampConfig};
+pub use crate::core::session::{ConnectDiagnostics, ConnectFailure, ConnectPhase, ControlCharEcho, DataFilter, DisconnectReason, EventReceiver, EchoPolicy, IdleAction, SendPacing, Session, SessionError, SessionEvent, SessionState};
 pub use crate::core::transport::{
-    SerialConfig, SerialFlowControl, SshAuth, SshConfig, TcpConfig, TelnetConfig, Transport,
-    TransportType,
+    Rs485Config, SerialConfig, SerialFlowControl, SshAuth, SshConfig, TcpConfig, TelnetConfig, TlsConfig, TlsVerify,
+    Transport, TransportType,
 };
 pub use crate::core::trigger::{Trigger, TriggerAction, TriggerCondition, TriggerManager, TriggerScope};
 pub use crate::i18n::{get_locale, set_locale, t, Locale};
 pub use crate::utils::autoconnect::{AutoConnect, AutoConnectConfig};
+pub use crate::utils::notifier::Notifier;
 
 /// Library version
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
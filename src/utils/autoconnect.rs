@@ -3,7 +3,7 @@
 //! Monitors connection state and automatically reconnects when disconnected.
 //! Also handles USB device hot-plug detection.
 
-use crate::core::session::{Session, SessionState};
+use crate::core::session::{DisconnectReason, Session, SessionState};
 use crate::core::transport::Transport;
 use parking_lot::RwLock;
 use std::sync::Arc;
@@ -84,6 +84,16 @@ impl AutoConnect {
         }
     }
 
+    /// React to a session ending: reconnect on transient failures, give up otherwise
+    pub fn on_disconnect(&mut self, reason: &DisconnectReason) {
+        if reason.is_retryable() {
+            self.start();
+        } else {
+            self.state.write().last_error = Some(reason.to_string());
+            let _ = self.event_tx.try_send(AutoConnectEvent::GaveUp);
+        }
+    }
+
     /// Start monitoring for reconnection
     pub fn start(&mut self) {
         if !self.config.enabled {
@@ -147,6 +157,11 @@ impl AutoConnect {
                             .send(AutoConnectEvent::ReconnectFailed { error })
                             .await;
                         warn!("Auto-reconnect failed (attempt {}): {}", attempt, e);
+
+                        if !DisconnectReason::from(&e).is_retryable() {
+                            let _ = event_tx.send(AutoConnectEvent::GaveUp).await;
+                            break;
+                        }
                     }
                 }
             }
@@ -1,6 +1,7 @@
 //! Utility modules
 
 pub mod autoconnect;
+pub mod notifier;
 
 /// Format bytes to human-readable string (e.g., "1.5 KB")
 pub fn format_bytes(bytes: u64) -> String {
@@ -0,0 +1,148 @@
+//! Desktop notifications
+//!
+//! Surfaces trigger `Notify` actions and terminal bells outside the app
+//! window, either as a native notification or through a callback.
+
+use parking_lot::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+/// Callback receiving `(title, body)`
+pub type NotifyCallback = Arc<dyn Fn(&str, &str) + Send + Sync>;
+
+/// Default minimum interval between two notifications
+pub const DEFAULT_NOTIFY_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Where notifications go
+#[derive(Clone)]
+pub enum NotifyBackend {
+    /// Native desktop notification
+    Desktop,
+    /// Application callback
+    Callback(NotifyCallback),
+}
+
+impl std::fmt::Debug for NotifyBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Desktop => f.write_str("Desktop"),
+            Self::Callback(_) => f.write_str("Callback"),
+        }
+    }
+}
+
+/// Rate-limited notification sink shared by sessions and the GUI
+#[derive(Debug)]
+pub struct Notifier {
+    backend: NotifyBackend,
+    enabled: AtomicBool,
+    min_interval: Mutex<Duration>,
+    last_sent: Mutex<Option<Instant>>,
+}
+
+impl Notifier {
+    /// Create a notifier with the given backend
+    pub fn new(backend: NotifyBackend) -> Self {
+        Self {
+            backend,
+            enabled: AtomicBool::new(true),
+            min_interval: Mutex::new(DEFAULT_NOTIFY_INTERVAL),
+            last_sent: Mutex::new(None),
+        }
+    }
+
+    /// Notifier posting native desktop notifications
+    pub fn desktop() -> Self {
+        Self::new(NotifyBackend::Desktop)
+    }
+
+    /// Notifier invoking a callback
+    pub fn with_callback(callback: impl Fn(&str, &str) + Send + Sync + 'static) -> Self {
+        Self::new(NotifyBackend::Callback(Arc::new(callback)))
+    }
+
+    /// Enable or disable all notifications
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Check if notifications are enabled
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    /// Set the minimum interval between notifications (zero = no limit)
+    pub fn set_rate_limit(&self, interval: Duration) {
+        *self.min_interval.lock() = interval;
+    }
+
+    /// Post a notification
+    /// Returns false if it was suppressed by the enable flag or rate limit
+    pub fn notify(&self, title: &str, body: &str) -> bool {
+        if !self.is_enabled() {
+            return false;
+        }
+
+        {
+            let now = Instant::now();
+            let mut last_sent = self.last_sent.lock();
+            if let Some(last) = *last_sent {
+                if now.duration_since(last) < *self.min_interval.lock() {
+                    return false;
+                }
+            }
+            *last_sent = Some(now);
+        }
+
+        match &self.backend {
+            NotifyBackend::Desktop => {
+                // Posting goes over D-Bus and may block; keep it off the caller's thread
+                let mut notification = notify_rust::Notification::new();
+                notification.appname("Termicon").summary(title).body(body);
+                std::thread::spawn(move || {
+                    if let Err(e) = notification.show() {
+                        warn!("Desktop notification failed: {}", e);
+                    }
+                });
+            }
+            NotifyBackend::Callback(callback) => callback(title, body),
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counting_notifier() -> (Notifier, Arc<Mutex<Vec<String>>>) {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let sink = seen.clone();
+        let notifier = Notifier::with_callback(move |title, body| {
+            sink.lock().push(format!("{}: {}", title, body));
+        });
+        (notifier, seen)
+    }
+
+    #[test]
+    fn test_rate_limit_suppresses_bursts() {
+        let (notifier, seen) = counting_notifier();
+        assert!(notifier.notify("Build", "done"));
+        assert!(!notifier.notify("Build", "done again"));
+        assert_eq!(*seen.lock(), vec!["Build: done".to_string()]);
+
+        notifier.set_rate_limit(Duration::ZERO);
+        assert!(notifier.notify("Build", "third"));
+        assert_eq!(seen.lock().len(), 2);
+    }
+
+    #[test]
+    fn test_disabled_notifier_is_silent() {
+        let (notifier, seen) = counting_notifier();
+        notifier.set_enabled(false);
+        assert!(!notifier.notify("Error", "boom"));
+        assert!(seen.lock().is_empty());
+    }
+}
@@ -0,0 +1,6 @@
+//! Integration tests for Termicon
+//!
+//! End-to-end tests that drive a full `Session` against an in-process peer
+
+mod session_pipeline;
+mod session_shutdown;
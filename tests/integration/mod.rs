@@ -1,19 +0,0 @@
-//! Integration tests for Termicon
-
-// This module will contain integration tests once the core functionality is complete.
-// 
-// Example tests to implement:
-// - Serial port connection/disconnection
-// - TCP connection handling
-// - Telnet protocol negotiation
-// - File transfer protocols
-// - Trigger matching
-// - Logging functionality
-
-
-
-
-
-
-
-
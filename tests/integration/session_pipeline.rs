@@ -0,0 +1,69 @@
+use std::time::Duration;
+use termicon_core::core::session::SessionConfig;
+use termicon_core::core::transport::TcpConfig;
+use termicon_core::{Session, SessionEvent, Transport, Trigger, TriggerCondition};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+/// Accept one connection and echo everything back until it closes
+async fn spawn_loopback() -> u16 {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut buf = [0u8; 1024];
+        loop {
+            match stream.read(&mut buf).await {
+                Ok(0) | Err(_) => break,
+                Ok(n) => {
+                    if stream.write_all(&buf[..n]).await.is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+    });
+    port
+}
+
+#[tokio::test]
+async fn trigger_logger_and_stats_over_loopback() {
+    let port = spawn_loopback().await;
+    let dir = tempfile::tempdir().unwrap();
+    let log_path = dir.path().join("pipeline.log");
+
+    let mut config = SessionConfig::new("Pipeline", Transport::Tcp(TcpConfig::new("127.0.0.1", port)));
+    config.logging_enabled = true;
+    config.log_path = Some(log_path.display().to_string());
+    let session = Session::connect_with_config(config).await.unwrap();
+
+    let trigger = Trigger::new("ready", TriggerCondition::Text("READY".to_string()));
+    let trigger_id = trigger.id;
+    session.add_trigger(trigger);
+    let mut events = session.subscribe();
+
+    session.send(b"boot READY\n").await.unwrap();
+
+    let pattern = tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            match events.recv().await {
+                Ok(SessionEvent::TriggerMatched { trigger_id: id, pattern }) if id == trigger_id => break pattern,
+                Ok(_) | Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => {}
+                Err(e) => panic!("event stream closed: {}", e),
+            }
+        }
+    })
+    .await
+    .expect("trigger did not fire");
+    assert_eq!(pattern, "READY");
+
+    let stats = session.stats().await;
+    assert_eq!(stats.bytes_sent, 11);
+    assert_eq!(stats.bytes_received, 11);
+
+    session.shutdown(Duration::from_secs(5)).await.unwrap();
+
+    let log = std::fs::read_to_string(&log_path).unwrap();
+    assert!(log.lines().any(|line| line.contains("TX boot READY")));
+    assert!(log.lines().any(|line| line.contains("RX boot READY")));
+}
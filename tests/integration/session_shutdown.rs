@@ -0,0 +1,48 @@
+use std::time::Duration;
+use termicon_core::core::session::SessionConfig;
+use termicon_core::core::transport::TcpConfig;
+use termicon_core::{Session, SessionState, Transport};
+use tokio::io::AsyncReadExt;
+use tokio::net::TcpListener;
+
+#[tokio::test]
+async fn shutdown_leaves_complete_log() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let server = tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut received = Vec::new();
+        let _ = stream.read_to_end(&mut received).await;
+        received
+    });
+
+    let dir = tempfile::tempdir().unwrap();
+    let log_path = dir.path().join("shutdown.txt");
+
+    let mut config = SessionConfig::new("Shutdown", Transport::Tcp(TcpConfig::new("127.0.0.1", port)));
+    config.logging_enabled = true;
+    config.log_path = Some(log_path.display().to_string());
+    let session = Session::connect_with_config(config).await.unwrap();
+
+    for i in 0..200 {
+        session.send(format!("line {}", i).as_bytes()).await.unwrap();
+    }
+    session.shutdown(Duration::from_secs(5)).await.unwrap();
+    assert_eq!(session.state(), SessionState::Disconnected);
+
+    // Every send reached the log, in order, whatever the entry layout
+    let log = std::fs::read_to_string(&log_path).unwrap();
+    let mut rest = log.as_str();
+    for i in 0..200 {
+        let entry = format!("line {}", i);
+        let at = rest.find(&entry).unwrap_or_else(|| panic!("'{}' missing from the log", entry));
+        rest = &rest[at + entry.len()..];
+    }
+
+    // Queued sends went out before the connection closed
+    let received = tokio::time::timeout(Duration::from_secs(5), server).await.unwrap().unwrap();
+    assert!(String::from_utf8_lossy(&received).ends_with("line 199"));
+
+    // A second shutdown is a no-op
+    session.shutdown(Duration::from_secs(1)).await.unwrap();
+}